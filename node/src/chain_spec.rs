@@ -2,9 +2,9 @@
 
 use hex_literal::hex;
 use predict_runtime::{
-    AccountId, AuraConfig, AutonomyConfig, BalancesConfig, CoupleConfig, GenesisConfig,
-    GrandpaConfig, ProposalsConfig, RulerConfig, Signature, SudoConfig, SystemConfig, TokensConfig,
-    WASM_BINARY,
+    AccountId, AuraConfig, AutonomyConfig, BalancesConfig, CategoricalConfig, CoupleConfig,
+    GenesisConfig, GrandpaConfig, LmsrConfig, ProposalsConfig, RulerConfig, Signature, SudoConfig,
+    SystemConfig, TokensConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use serde_json::{map::Map, value::Value};
@@ -391,6 +391,13 @@ fn predict_genesis(
             liquidity_provider_fee_rate: 9000,
             withdrawal_fee_rate: 50,
         }),
+        categorical: Some(CategoricalConfig {
+            liquidity_provider_fee_rate: 9000,
+            withdrawal_fee_rate: 50,
+        }),
+        lmsr: Some(LmsrConfig {
+            withdrawal_fee_rate: 50,
+        }),
         autonomy: Some(AutonomyConfig {
             minimal_stake_number: 1000 * 100000000,
             minimal_review_number: 100 * 100000000,
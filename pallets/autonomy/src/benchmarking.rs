@@ -0,0 +1,458 @@
+//! Benchmarks for the autonomy pallet
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use codec::Decode;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{IdentifyAccount, TrailingZeroInput};
+use sp_std::vec;
+
+const SEED: u32 = 0;
+const INITIAL_BALANCE: u32 = 1_000_000_000;
+const STAKE: u32 = 100_000_000;
+const VOTES: u32 = 50_000_000;
+const LOCK_RATIO: u32 = 50;
+const MINIMAL_REPORT: u32 = 1_000_000;
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+    let who: T::AccountId = account(name, index, SEED);
+    <TokensOf<T> as Tokens<T::AccountId>>::mint(
+        T::StakeCurrencyId::get(),
+        &who,
+        INITIAL_BALANCE.into(),
+    )
+    .expect("mint failed");
+    who
+}
+
+fn staked_node<T: Config>(who: &T::AccountId) -> Result<(), &'static str> {
+    <TokensOf<T> as Tokens<T::AccountId>>::mint(
+        T::StakeCurrencyId::get(),
+        who,
+        INITIAL_BALANCE.into(),
+    )?;
+    MinimalStakeNumber::<T>::put::<BalanceOf<T>>(STAKE.into());
+    Pallet::<T>::inner_stake(who, STAKE.into())?;
+    Ok(())
+}
+
+fn node_public<T: Config>() -> Result<(T::Public, T::AccountId), &'static str> {
+    let public =
+        T::Public::decode(&mut TrailingZeroInput::zeroes()).map_err(|_| "decode public failed")?;
+    let who = public.clone().into_account();
+    Ok((public, who))
+}
+
+fn zero_signature<T: Config>() -> Result<T::Signature, &'static str> {
+    T::Signature::decode(&mut TrailingZeroInput::zeroes()).map_err(|_| "decode signature failed")
+}
+
+fn set_state<T: Config>(
+    proposal_id: ProposalIdOf<T>,
+    state: ProposalStatus,
+) -> Result<(), &'static str> {
+    if T::Pool::get_proposal_state(proposal_id)? != state {
+        T::Pool::set_proposal_state(proposal_id, state)?;
+    }
+    Ok(())
+}
+
+fn waiting_proposal<T: Config>(
+    owner: &T::AccountId,
+    scalar: bool,
+) -> Result<ProposalIdOf<T>, &'static str> {
+    let proposal_id = T::CouplePool::new_benchmark_proposal(owner, scalar)?;
+    set_state::<T>(proposal_id, ProposalStatus::WaitingForResults)?;
+    LockRatio::<T>::put::<BalanceOf<T>>(LOCK_RATIO.into());
+    Ok(proposal_id)
+}
+
+/// A proposal whose result was uploaded by `node` and has been announced
+fn announced_proposal<T: Config>(
+    owner: &T::AccountId,
+    node: &T::AccountId,
+) -> Result<(ProposalIdOf<T>, CurrencyIdOf<T>), &'static str> {
+    let proposal_id = waiting_proposal::<T>(owner, false)?;
+    let (result, _) = T::CouplePool::proposal_pair(proposal_id)?;
+    Pallet::<T>::inner_upload_result(node, proposal_id, result, VOTES.into())?;
+    T::CouplePool::set_proposal_result(proposal_id, result)?;
+    set_state::<T>(proposal_id, ProposalStatus::ResultAnnouncement)?;
+    Ok((proposal_id, result))
+}
+
+/// A proposal whose announced result has been reported successfully, its dispute is open
+fn disputed_proposal<T: Config>(
+    owner: &T::AccountId,
+    node: &T::AccountId,
+    reporter: &T::AccountId,
+) -> Result<(ProposalIdOf<T>, CurrencyIdOf<T>), &'static str> {
+    let (proposal_id, result) = announced_proposal::<T>(owner, node)?;
+    MinimalReportNumber::<T>::put::<BalanceOf<T>>(MINIMAL_REPORT.into());
+    PublicityPeriod::<T>::put::<MomentOf<T>>(1_000u32.into());
+    Pallet::<T>::inner_report(reporter, proposal_id, MINIMAL_REPORT.into())?;
+    Ok((proposal_id, result))
+}
+
+/// A proposal whose announced result has been reported successfully and then overturned
+fn reported_proposal<T: Config>(
+    owner: &T::AccountId,
+    node: &T::AccountId,
+    reporter: &T::AccountId,
+) -> Result<ProposalIdOf<T>, &'static str> {
+    let (proposal_id, _) = disputed_proposal::<T>(owner, node, reporter)?;
+    let dispute = Disputes::<T>::get(proposal_id).ok_or("dispute not opened")?;
+    Pallet::<T>::settle_dispute(proposal_id, dispute.outcome)?;
+    Ok(proposal_id)
+}
+
+fn funded_caller<T: Config>() -> Result<T::AccountId, &'static str> {
+    let caller: T::AccountId = whitelisted_caller();
+    <TokensOf<T> as Tokens<T::AccountId>>::mint(
+        T::StakeCurrencyId::get(),
+        &caller,
+        INITIAL_BALANCE.into(),
+    )?;
+    Ok(caller)
+}
+
+benchmarks! {
+    stake {
+        let caller = funded_account::<T>("caller", 0);
+    }: _(RawOrigin::Signed(caller.clone()), STAKE.into())
+    verify {
+        assert!(StakedNode::<T>::get(&caller).is_some());
+    }
+
+    unstake {
+        let caller: T::AccountId = whitelisted_caller();
+        staked_node::<T>(&caller)?;
+    }: _(RawOrigin::Signed(caller.clone()), STAKE.into())
+    verify {
+        assert_eq!(StakedNode::<T>::get(&caller), Some((Zero::zero(), false)));
+    }
+
+    review {
+        let caller: T::AccountId = whitelisted_caller();
+        staked_node::<T>(&caller)?;
+        let owner = funded_account::<T>("owner", 0);
+        let proposal_id = T::CouplePool::new_benchmark_proposal(&owner, false)?;
+    }: _(RawOrigin::Signed(caller.clone()), VOTES.into(), proposal_id, true)
+    verify {
+        assert!(NodeReviewVotingStatus::<T>::get(proposal_id, &caller).is_some());
+    }
+
+    process_slashes {
+        let n in 1 .. 50;
+        let (_, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let reporter = funded_account::<T>("reporter", 0);
+        let proposal_id = reported_proposal::<T>(&owner, &node, &reporter)?;
+        let dispute = Disputes::<T>::get(proposal_id).ok_or("dispute not opened")?;
+        for i in 1 .. n {
+            let other: T::AccountId = account("node", i, SEED);
+            staked_node::<T>(&other)?;
+            Pallet::<T>::inner_upload_result(&other, proposal_id, dispute.announced, VOTES.into())?;
+            PendingSlashes::<T>::insert(proposal_id, &other, ());
+        }
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller), proposal_id, n)
+    verify {
+        assert!(AccountSlashNumber::<T>::get(proposal_id, &node).is_some());
+        assert_eq!(SlashFinishFlag::<T>::get(proposal_id), Some(()));
+    }
+
+    change_state {
+        let (_, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let proposal_id = waiting_proposal::<T>(&owner, false)?;
+        let (result, _) = T::CouplePool::proposal_pair(proposal_id)?;
+        Pallet::<T>::inner_upload_result(&node, proposal_id, result, VOTES.into())?;
+        let close_time = T::Pool::proposal_close_time(proposal_id)?;
+        let upload_time = ResultUploadCycle::<T>::get().unwrap_or_else(Zero::zero);
+        let now = close_time.saturating_add(upload_time);
+    }: {
+        <Pallet<T> as DeadlineScheduler>::change_state(proposal_id, now)?;
+    }
+    verify {
+        assert_eq!(
+            T::Pool::get_proposal_state(proposal_id)?,
+            ProposalStatus::ResultAnnouncement
+        );
+    }
+
+    upload_result {
+        let (public, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let proposal_id = waiting_proposal::<T>(&owner, false)?;
+        let (result, _) = T::CouplePool::proposal_pair(proposal_id)?;
+        let payload = Payload {
+            proposal_id,
+            result,
+            public,
+            vote_num: VOTES.into(),
+        };
+        let signature = zero_signature::<T>()?;
+    }: _(RawOrigin::None, payload, signature)
+    verify {
+        assert!(NodeResultVotingStatus::<T>::get(proposal_id, &node).is_some());
+    }
+
+    upload_scalar_result {
+        let (public, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let proposal_id = waiting_proposal::<T>(&owner, true)?;
+        let (min, _) = T::CouplePool::proposal_scalar_range(proposal_id)
+            .ok_or("proposal not scalar")?;
+        let payload = ScalarPayload {
+            proposal_id,
+            value: min,
+            public,
+            vote_num: VOTES.into(),
+        };
+        let signature = zero_signature::<T>()?;
+    }: _(RawOrigin::None, payload, signature)
+    verify {
+        assert!(NodeScalarResultVotingStatus::<T>::get(proposal_id, &node).is_some());
+    }
+
+    commit_result {
+        let (public, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let proposal_id = waiting_proposal::<T>(&owner, false)?;
+        let (result, _) = T::CouplePool::proposal_pair(proposal_id)?;
+        RevealPeriod::<T>::put::<MomentOf<T>>(1_000u32.into());
+        let payload = CommitPayload {
+            proposal_id,
+            commitment: Pallet::<T>::result_commitment(
+                proposal_id,
+                &node,
+                result,
+                VOTES.into(),
+                [0; 32],
+            ),
+            public,
+            vote_num: VOTES.into(),
+        };
+        let signature = zero_signature::<T>()?;
+    }: _(RawOrigin::None, payload, signature)
+    verify {
+        assert!(ResultCommitments::<T>::get(proposal_id, &node).is_some());
+    }
+
+    reveal_result {
+        let (public, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let proposal_id = waiting_proposal::<T>(&owner, false)?;
+        let (result, _) = T::CouplePool::proposal_pair(proposal_id)?;
+        RevealPeriod::<T>::put::<MomentOf<T>>(1_000u32.into());
+        let commitment =
+            Pallet::<T>::result_commitment(proposal_id, &node, result, VOTES.into(), [0; 32]);
+        Pallet::<T>::inner_commit_result(&node, proposal_id, commitment, VOTES.into())?;
+        let end = <TimeOf<T> as Time>::now().saturating_add(1_000u32.into());
+        RevealEnd::<T>::insert(proposal_id, end);
+        let payload = RevealPayload {
+            proposal_id,
+            result,
+            salt: [0; 32],
+            public,
+        };
+        let signature = zero_signature::<T>()?;
+    }: _(RawOrigin::None, payload, signature)
+    verify {
+        assert!(NodeResultVotingStatus::<T>::get(proposal_id, &node).is_some());
+    }
+
+    set_result_source {
+        let owner = funded_account::<T>("owner", 0);
+        let proposal_id = T::CouplePool::new_benchmark_proposal(&owner, false)?;
+        let source = ResultSource {
+            url: vec![b'u'; MAX_RESULT_SOURCE_LENGTH],
+            path: vec![b'p'; MAX_RESULT_SOURCE_LENGTH],
+            yes: vec![b'y'; MAX_RESULT_SOURCE_LENGTH],
+            no: vec![b'n'; MAX_RESULT_SOURCE_LENGTH],
+        };
+    }: _(RawOrigin::Signed(owner), proposal_id, source)
+    verify {
+        assert!(ResultSources::<T>::get(proposal_id).is_some());
+    }
+
+    report {
+        let (_, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let (proposal_id, _) = announced_proposal::<T>(&owner, &node)?;
+        MinimalReportNumber::<T>::put::<BalanceOf<T>>(MINIMAL_REPORT.into());
+        let reporter = funded_account::<T>("reporter", 0);
+        Pallet::<T>::inner_report(&reporter, proposal_id, One::one())?;
+        let caller = funded_caller::<T>()?;
+    }: _(RawOrigin::Signed(caller), proposal_id, MINIMAL_REPORT.into())
+    verify {
+        assert_eq!(ReportSuccessFlag::<T>::get(proposal_id), Some(()));
+    }
+
+    take_out {
+        let (_, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_caller::<T>()?;
+        let proposal_id = reported_proposal::<T>(&owner, &node, &caller)?;
+        Pallet::<T>::inner_process_slashes(proposal_id, 1)?;
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id)
+    verify {
+        assert!(AccountReportNumber::<T>::get(proposal_id, &caller).is_none());
+    }
+
+    unlock {
+        let caller: T::AccountId = whitelisted_caller();
+        staked_node::<T>(&caller)?;
+        let owner = funded_account::<T>("owner", 0);
+        let (proposal_id, _) = announced_proposal::<T>(&owner, &caller)?;
+        set_state::<T>(proposal_id, ProposalStatus::End)?;
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id)
+    verify {
+        assert_eq!(StakedNodeLockNum::<T>::get(proposal_id, &caller), Some(Zero::zero()));
+    }
+
+    dispute {
+        let (_, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let reporter = funded_account::<T>("reporter", 0);
+        let (proposal_id, result) = disputed_proposal::<T>(&owner, &node, &reporter)?;
+        let bond = Disputes::<T>::get(proposal_id).ok_or("dispute not opened")?.bond;
+        let caller = funded_caller::<T>()?;
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id, result, bond)
+    verify {
+        assert_eq!(DisputeBonds::<T>::get(proposal_id, &caller), Some((result, bond)));
+    }
+
+    resolve_dispute {
+        let (_, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let reporter = funded_account::<T>("reporter", 0);
+        let (proposal_id, result) = disputed_proposal::<T>(&owner, &node, &reporter)?;
+        Disputes::<T>::mutate(proposal_id, |optional| {
+            if let Some(dispute) = optional {
+                dispute.round = T::MaxDisputeRounds::get().saturating_add(1);
+            }
+        });
+        let origin = T::DisputeOrigin::successful_origin();
+    }: _<T::Origin>(origin, proposal_id, result)
+    verify {
+        assert_eq!(T::Pool::get_proposal_state(proposal_id), Ok(ProposalStatus::End));
+    }
+
+    claim_dispute_bond {
+        let (_, node) = node_public::<T>()?;
+        staked_node::<T>(&node)?;
+        let owner = funded_account::<T>("owner", 0);
+        let reporter = funded_account::<T>("reporter", 0);
+        let (proposal_id, result) = disputed_proposal::<T>(&owner, &node, &reporter)?;
+        let bond = Disputes::<T>::get(proposal_id).ok_or("dispute not opened")?.bond;
+        let caller = funded_caller::<T>()?;
+        Pallet::<T>::inner_dispute(&caller, proposal_id, result, bond)?;
+        Pallet::<T>::settle_dispute(proposal_id, result)?;
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id)
+    verify {
+        assert!(DisputeBonds::<T>::get(proposal_id, &caller).is_none());
+    }
+
+    set_minimal_review_number {
+    }: _(RawOrigin::Root, STAKE.into())
+    verify {
+        assert_eq!(MinimalReviewNumber::<T>::get(), Some(STAKE.into()));
+    }
+
+    set_minimal_stake_number {
+    }: _(RawOrigin::Root, STAKE.into())
+    verify {
+        assert_eq!(MinimalStakeNumber::<T>::get(), Some(STAKE.into()));
+    }
+
+    set_minimal_report_number {
+    }: _(RawOrigin::Root, MINIMAL_REPORT.into())
+    verify {
+        assert_eq!(MinimalReportNumber::<T>::get(), Some(MINIMAL_REPORT.into()));
+    }
+
+    set_lock_ratio {
+    }: _(RawOrigin::Root, LOCK_RATIO.into())
+    verify {
+        assert_eq!(LockRatio::<T>::get(), Some(LOCK_RATIO.into()));
+    }
+
+    set_review_cycle {
+        let interval: MomentOf<T> = 1_000u32.into();
+    }: _(RawOrigin::Root, interval)
+    verify {
+        assert_eq!(ReviewCycle::<T>::get(), Some(interval));
+    }
+
+    set_upload_cycle {
+        let interval: MomentOf<T> = 1_000u32.into();
+    }: _(RawOrigin::Root, interval)
+    verify {
+        assert_eq!(ResultUploadCycle::<T>::get(), Some(interval));
+    }
+
+    set_publicity_period {
+        let interval: MomentOf<T> = 1_000u32.into();
+    }: _(RawOrigin::Root, interval)
+    verify {
+        assert_eq!(PublicityPeriod::<T>::get(), Some(interval));
+    }
+
+    set_reveal_period {
+        let interval: MomentOf<T> = 1_000u32.into();
+    }: _(RawOrigin::Root, interval)
+    verify {
+        assert_eq!(RevealPeriod::<T>::get(), Some(interval));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext(|_| {
+            assert_ok!(test_benchmark_stake::<Test>());
+            assert_ok!(test_benchmark_unstake::<Test>());
+            assert_ok!(test_benchmark_review::<Test>());
+            assert_ok!(test_benchmark_process_slashes::<Test>());
+            assert_ok!(test_benchmark_change_state::<Test>());
+            assert_ok!(test_benchmark_upload_result::<Test>());
+            assert_ok!(test_benchmark_upload_scalar_result::<Test>());
+            assert_ok!(test_benchmark_set_result_source::<Test>());
+            assert_ok!(test_benchmark_report::<Test>());
+            assert_ok!(test_benchmark_take_out::<Test>());
+            assert_ok!(test_benchmark_unlock::<Test>());
+            assert_ok!(test_benchmark_dispute::<Test>());
+            assert_ok!(test_benchmark_resolve_dispute::<Test>());
+            assert_ok!(test_benchmark_claim_dispute_bond::<Test>());
+            assert_ok!(test_benchmark_set_minimal_review_number::<Test>());
+            assert_ok!(test_benchmark_set_minimal_stake_number::<Test>());
+            assert_ok!(test_benchmark_set_minimal_report_number::<Test>());
+            assert_ok!(test_benchmark_set_lock_ratio::<Test>());
+            assert_ok!(test_benchmark_set_review_cycle::<Test>());
+            assert_ok!(test_benchmark_set_upload_cycle::<Test>());
+            assert_ok!(test_benchmark_set_publicity_period::<Test>());
+            // the results are committed from here on
+            assert_ok!(test_benchmark_commit_result::<Test>());
+            assert_ok!(test_benchmark_reveal_result::<Test>());
+            assert_ok!(test_benchmark_set_reveal_period::<Test>());
+        });
+    }
+}
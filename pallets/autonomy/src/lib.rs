@@ -16,6 +16,18 @@
 //!
 //! Only the data provided by the officially signed node is valid.
 //!
+//! When `RevealPeriod` is set, the nodes commit to the hash of their result instead of
+//! uploading it, and reveal it in the reveal window after the upload cycle. Only the revealed
+//! results are counted, the locked votes of a commitment that is not revealed are slashed.
+//!
+//! A successful report on an announced result opens a dispute. Holders of governance tokens
+//! bond against its outcome in rounds whose bond doubles each time, and a dispute still
+//! contested after the last round is decided by `DisputeOrigin`. The bonds are paid out
+//! according to the final outcome.
+//!
+//! The nodes that uploaded an overturned result are slashed in batches at the beginning of the
+//! blocks, anyone can also slash them with `process_slashes`.
+//!
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
@@ -27,29 +39,45 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+
 pub(crate) mod macros;
 
 pub use pallet::*;
+pub use weights::WeightInfo;
 
 use frame_support::{
+    debug,
     dispatch::{DispatchError, Weight},
     ensure,
     traits::{Get, Time},
 };
-use frame_system::offchain::SignedPayload;
+use frame_system::offchain::{AppCrypto, SendUnsignedTransaction, SignedPayload, Signer};
+use lite_json::json::JsonValue;
 use sp_core::crypto::KeyTypeId;
 use sp_runtime::{
-    traits::{AccountIdConversion, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Zero},
+    offchain::{http, Duration},
+    traits::{
+        AccountIdConversion, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Hash, IdentifyAccount,
+        One, Saturating, Zero,
+    },
     transaction_validity::{
         InvalidTransaction, TransactionSource, TransactionValidity, ValidTransaction,
     },
+    RuntimeAppPublic,
 };
 use xpmrl_traits::{
-    autonomy::Autonomy, couple::LiquidityCouple, pool::LiquidityPool, tokens::Tokens,
+    autonomy::Autonomy,
+    couple::LiquidityCouple,
+    deadline::DeadlineScheduler,
+    pool::{LiquidityPool, ProposalArchive},
+    tokens::Tokens,
     ProposalStatus,
 };
 use xpmrl_utils::{with_transaction_result, storage_try_mutate};
-use sp_std::{collections::btree_map::BTreeMap, cmp::Ordering};
+use sp_std::{collections::btree_map::BTreeMap, cmp::{self, Ordering}, str, vec::Vec};
 
 /// Defines application identifier for crypto keys of this module.
 ///
@@ -98,7 +126,7 @@ use frame_support::traits::GenesisBuild;
 
 #[frame_support::pallet]
 pub mod pallet {
-    use sp_std::collections::btree_map::BTreeMap;
+    use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
     use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, traits::Time};
     use frame_system::{offchain::*, pallet_prelude::*};
@@ -108,6 +136,7 @@ pub mod pallet {
         ProposalStatus,
     };
     use xpmrl_utils::with_transaction_result;
+    use crate::weights::WeightInfo;
 
     pub(crate) type TokensOf<T> =
         <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::Tokens;
@@ -128,6 +157,8 @@ pub mod pallet {
         /// The asset id of the proposal result
         ///
         /// The proposal option is a token, so here only the id of the corresponding token needs to be uploaded
+        ///
+        /// The id of the invalid result of the proposal, if it has one, votes to void the proposal
         pub result: ResultId,
         /// Account for uploading results
         pub public: Public,
@@ -143,11 +174,101 @@ pub mod pallet {
         }
     }
 
+    /// The payload struct for uploading the numeric result of a scalar proposal
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct ScalarPayload<Public, ProposalId, Balance> {
+        /// The id of the scalar proposal that needs to upload the result
+        pub proposal_id: ProposalId,
+        /// The observed value of the proposal, within the range of the proposal
+        pub value: Balance,
+        /// Account for uploading results
+        pub public: Public,
+        /// Upload votes
+        pub vote_num: Balance,
+    }
+
+    impl<T: Config> SignedPayload<T> for ScalarPayload<T::Public, T::ProposalId, BalanceOf<T>> {
+        fn public(&self) -> T::Public {
+            self.public.clone()
+        }
+    }
+
+    /// The payload struct for committing to a result without publishing it
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct CommitPayload<Public, ProposalId, Hash, Balance> {
+        /// The id of the proposal that needs to upload the result
+        pub proposal_id: ProposalId,
+        /// The hash of the proposal, the node, the result, the votes and a salt, see
+        /// `Pallet::result_commitment`
+        pub commitment: Hash,
+        /// Account for uploading results
+        pub public: Public,
+        /// Upload votes, locked when committing
+        pub vote_num: Balance,
+    }
+
+    impl<T: Config> SignedPayload<T>
+        for CommitPayload<T::Public, T::ProposalId, T::Hash, BalanceOf<T>>
+    {
+        fn public(&self) -> T::Public {
+            self.public.clone()
+        }
+    }
+
+    /// The payload struct for revealing a committed result
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct RevealPayload<Public, ProposalId, ResultId> {
+        /// The id of the proposal the result was committed to
+        pub proposal_id: ProposalId,
+        /// The asset id of the committed result
+        pub result: ResultId,
+        /// The salt the commitment was hashed with
+        pub salt: [u8; 32],
+        /// Account for uploading results
+        pub public: Public,
+    }
+
+    impl<T: Config> SignedPayload<T> for RevealPayload<T::Public, T::ProposalId, CurrencyIdOf<T>> {
+        fn public(&self) -> T::Public {
+            self.public.clone()
+        }
+    }
+
+    /// Where the offchain workers of the nodes fetch the result of a proposal from
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct ResultSource {
+        /// The url answering the json document holding the result
+        pub url: Vec<u8>,
+        /// The keys leading to the result in the json document separated by `.`, the elements
+        /// of an array are selected by their index
+        pub path: Vec<u8>,
+        /// The value at the path meaning the first option of the proposal
+        pub yes: Vec<u8>,
+        /// The value at the path meaning the second option of the proposal
+        pub no: Vec<u8>,
+    }
+
+    /// The dispute opened by a successful report on the announced result of a proposal
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct Dispute<CurrencyId, Balance, Moment> {
+        /// The result announced by the nodes, which the report claims to be wrong
+        pub announced: CurrencyId,
+        /// The outcome the dispute stands on, final once a round ends without bonds against it
+        pub outcome: CurrencyId,
+        /// The current round, the round after `MaxDisputeRounds` is decided by `DisputeOrigin`
+        pub round: u32,
+        /// The smallest bond accepted in the current round, doubled by each round
+        pub bond: Balance,
+        /// The end of the current round
+        pub end: Moment,
+    }
+
     /// This is the pallet's configuration trait
     #[pallet::config]
     pub trait Config:
         frame_system::Config
         + SigningTypes
+        + SendTransactionTypes<Call<Self>>
         + ProposalSystem<<Self as frame_system::Config>::AccountId>
     {
         /// The overarching event type.
@@ -158,7 +279,8 @@ pub mod pallet {
         type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
         /// A trait used to obtain public information about the proposal
         type Pool: LiquidityPool<Self>;
-        /// A trait that operates on a proposal with two options
+        /// A trait that operates on the options of a proposal, whether it has two options or
+        /// more
         type CouplePool: LiquidityCouple<Self>;
 
         /// The asset id of the governance token
@@ -169,6 +291,22 @@ pub mod pallet {
 
         #[pallet::constant]
         type AutonomyId: Get<ModuleId>;
+
+        /// The length of time covered by one bucket of the deadline queue, usually the block time
+        #[pallet::constant]
+        type DeadlineBucket: Get<MomentOf<Self>>;
+
+        /// The origin deciding the outcome of a dispute still contested after its last round,
+        /// usually a council
+        type DisputeOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The number of bonded rounds of a dispute before its outcome is left to
+        /// `DisputeOrigin`
+        #[pallet::constant]
+        type MaxDisputeRounds: Get<u32>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
@@ -279,6 +417,18 @@ pub mod pallet {
         (CurrencyIdOf<T>, BalanceOf<T>),
         OptionQuery>;
 
+    /// Stored the value and the number of votes uploaded to a scalar proposal by the node
+    #[pallet::storage]
+    #[pallet::getter(fn node_scalar_result_voting_status)]
+    pub type NodeScalarResultVotingStatus<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::ProposalId,
+        Twox64Concat,
+        T::AccountId,
+        (BalanceOf<T>, BalanceOf<T>),
+        OptionQuery>;
+
     /// Stored the total number of votes for upload result of a proposal
     #[pallet::storage]
     #[pallet::getter(fn result_voting_status)]
@@ -291,6 +441,40 @@ pub mod pallet {
         BalanceOf<T>, 
         OptionQuery>;
 
+    /// Stored the commitment and the locked votes of the nodes that have not revealed their
+    /// result yet
+    #[pallet::storage]
+    #[pallet::getter(fn result_commitments)]
+    pub type ResultCommitments<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        (T::Hash, BalanceOf<T>),
+        OptionQuery,
+    >;
+
+    /// Stored the end of the reveal window of a proposal, no more results are committed until
+    /// it ends
+    #[pallet::storage]
+    #[pallet::getter(fn reveal_end)]
+    pub type RevealEnd<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, MomentOf<T>, OptionQuery>;
+
+    /// Stored the length of the reveal window of a proposal, taken from `RevealPeriod` when
+    /// the first result is committed to it
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_reveal_period)]
+    pub type ProposalRevealPeriod<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, MomentOf<T>, OptionQuery>;
+
+    /// Stored the source the offchain workers fetch the result of a proposal from
+    #[pallet::storage]
+    #[pallet::getter(fn result_sources)]
+    pub type ResultSources<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, ResultSource, OptionQuery>;
+
     /// Stored the number of report votes to a proposal by the account
     #[pallet::storage]
     #[pallet::getter(fn account_report_number)]
@@ -316,6 +500,25 @@ pub mod pallet {
     pub type SlashFinishFlag<T: Config> =
         StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, (), OptionQuery>;
 
+    /// Stored the proposals whose nodes are slashed at the beginning of the blocks
+    #[pallet::storage]
+    #[pallet::getter(fn slash_queue)]
+    pub type SlashQueue<T: Config> = StorageValue<_, Vec<ProposalIdOf<T>>, ValueQuery>;
+
+    /// Stored the nodes still to be slashed for a proposal, those that uploaded the result
+    /// overturned by its dispute, a node leaves it once it is slashed
+    #[pallet::storage]
+    #[pallet::getter(fn pending_slashes)]
+    pub type PendingSlashes<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        (),
+        OptionQuery,
+    >;
+
     /// Stored the number of votes that users reported on proposal
     #[pallet::storage]
     #[pallet::getter(fn account_slash_number )]
@@ -334,6 +537,56 @@ pub mod pallet {
     pub type ReportVotingStatus<T: Config> =
         StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>,OptionQuery>;
 
+    /// Stored the dispute opened by the successful report of a proposal
+    #[pallet::storage]
+    #[pallet::getter(fn disputes)]
+    pub type Disputes<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Dispute<CurrencyIdOf<T>, BalanceOf<T>, MomentOf<T>>,
+        OptionQuery,
+    >;
+
+    /// Stored the option an account bonded on in the dispute of a proposal and its total bond
+    #[pallet::storage]
+    #[pallet::getter(fn dispute_bonds)]
+    pub type DisputeBonds<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        (CurrencyIdOf<T>, BalanceOf<T>),
+        OptionQuery,
+    >;
+
+    /// Stored the total bond on each option of the dispute of a proposal
+    #[pallet::storage]
+    #[pallet::getter(fn dispute_voting_status)]
+    pub type DisputeVotingStatus<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        CurrencyIdOf<T>,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// Stored the bond on each option during the current round of the dispute of a proposal
+    #[pallet::storage]
+    #[pallet::getter(fn dispute_round_voting_status)]
+    pub type DisputeRoundVotingStatus<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        CurrencyIdOf<T>,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
     /// Stored the minimal number of review
     #[pallet::storage]
     #[pallet::getter(fn minimal_review_number)]
@@ -368,6 +621,12 @@ pub mod pallet {
     #[pallet::storage]
     #[pallet::getter(fn publicity_period)]
     pub type PublicityPeriod<T: Config> = StorageValue<_, MomentOf<T>, OptionQuery>;
+
+    /// Stored the length of the reveal window, the results are committed and revealed instead
+    /// of uploaded when it is set
+    #[pallet::storage]
+    #[pallet::getter(fn reveal_period)]
+    pub type RevealPeriod<T: Config> = StorageValue<_, MomentOf<T>, OptionQuery>;
     
     /// Stored the review delay times
     #[pallet::storage]
@@ -390,6 +649,53 @@ pub mod pallet {
     pub type ResultAnnouncementTime<T: Config> =
         StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, MomentOf<T>, OptionQuery>;
 
+    /// Stored the next time the review, the result upload or the publicity of the proposal can
+    /// end, the proposal is queued in the bucket of the deadline queue holding this time
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_deadline)]
+    pub type ProposalDeadline<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, MomentOf<T>, OptionQuery>;
+
+    /// Stored the deadline queue, the deadlines of the proposals grouped by bucket
+    ///
+    /// An entry whose deadline no longer matches `ProposalDeadline` was rescheduled and is skipped
+    #[pallet::storage]
+    #[pallet::getter(fn deadline_queue)]
+    pub type DeadlineQueue<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        MomentOf<T>,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        MomentOf<T>,
+        OptionQuery,
+    >;
+
+    /// Stored the first bucket of the deadline queue that has not been processed yet
+    #[pallet::storage]
+    #[pallet::getter(fn next_deadline_bucket)]
+    pub type NextDeadlineBucket<T: Config> = StorageValue<_, MomentOf<T>, OptionQuery>;
+
+    /// Stored how many times in a row `change_state` has failed on the proposal, the time before
+    /// it is retried doubles with each failure
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_retries)]
+    pub type ProposalRetries<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, u32, OptionQuery>;
+
+    /// Stored the first proposal id that has not been queued yet, proposals are created by the
+    /// pool and picked up by the following block
+    #[pallet::storage]
+    #[pallet::getter(fn next_scheduled_proposal_id)]
+    pub type NextScheduledProposalId<T: Config> = StorageValue<_, ProposalIdOf<T>, OptionQuery>;
+
+    /// Stored the queued proposals whose deadline is computed again after a cycle changed, from
+    /// the first one to the end of the range, a batch of them is rescheduled every block
+    #[pallet::storage]
+    #[pallet::getter(fn rescheduled_proposals)]
+    pub type RescheduledProposals<T: Config> =
+        StorageValue<_, (ProposalIdOf<T>, ProposalIdOf<T>), OptionQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub minimal_stake_number: BalanceOf<T>,
@@ -439,14 +745,38 @@ pub mod pallet {
         Slash(T::AccountId, T::ProposalId, BalanceOf<T>),
         /// Slash finish
         SlashFinsh(T::ProposalId),
+        /// The slashes of a queued proposal failed, it left the queue and its slashes can be
+        /// retried with `process_slashes`
+        SlashFailed(T::ProposalId),
         /// StakedNode uploaded result.
         UploadResult(T::AccountId, T::ProposalId, CurrencyIdOf<T>, BalanceOf<T>),
+        /// StakedNode uploaded the value of a scalar proposal.
+        UploadScalarResult(T::AccountId, T::ProposalId, BalanceOf<T>, BalanceOf<T>),
+        /// StakedNode committed to a result with the votes
+        CommitResult(T::AccountId, T::ProposalId, BalanceOf<T>),
+        /// StakedNode revealed the committed result
+        RevealResult(T::AccountId, T::ProposalId, CurrencyIdOf<T>, BalanceOf<T>),
+        /// The committed result was not revealed, the locked votes are slashed
+        CommitNotRevealed(T::AccountId, T::ProposalId, BalanceOf<T>),
+        /// Set the source the result of the proposal is fetched from
+        SetResultSource(T::ProposalId),
         /// Report proposal result
         Report(T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
         /// Take out the tokens pledged by the report
         TakeOut(T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
         /// The staked node unlocks the number of votes
         Unlock(T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
+        /// A successful report opened a dispute, the outcome it stands on and the bond of its
+        /// first round
+        DisputeOpened(ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        /// Bonded on an option against the outcome of a dispute
+        DisputeBond(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        /// A round of the dispute was contested, the new round, its outcome and its bond
+        DisputeEscalated(ProposalIdOf<T>, u32, CurrencyIdOf<T>, BalanceOf<T>),
+        /// The dispute is settled on its final outcome
+        DisputeSettled(ProposalIdOf<T>, CurrencyIdOf<T>),
+        /// Took out the bond and the winnings of a dispute
+        DisputeBondClaimed(T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
         /// Set the minimum review amount
         SetMinimalReviewNumber(BalanceOf<T>),
         /// Set the minimum stake amount
@@ -461,6 +791,8 @@ pub mod pallet {
         SetUploadCycle(MomentOf<T>),
         /// Set the publicity period
         SetPublicityPeriod(MomentOf<T>),
+        /// Set the reveal period
+        SetRevealPeriod(MomentOf<T>),
 
     }
 
@@ -536,19 +868,57 @@ pub mod pallet {
         /// Input ratio is too large
         InputRatioIsTooLarge,
         /// Final reprot asser pool num has set
-        FinalReportAssetPoolHasSet
+        FinalReportAssetPoolHasSet,
+        /// The proposal is a scalar proposal, its result is a value instead of an option
+        ProposalIsScalar,
+        /// The proposal is not a scalar proposal
+        ProposalNotScalar,
+        /// The proposal has no dispute
+        DisputeNotExist,
+        /// The current round of the dispute is over
+        DisputeRoundIsOver,
+        /// The dispute is left to `DisputeOrigin`, no more bonds are accepted
+        DisputeIsEscalated,
+        /// The dispute still has bonded rounds left
+        DisputeNotEscalated,
+        /// The bond is below the bond of the current round
+        DisputeBondTooLow,
+        /// The dispute already stands on the option, a bond has to be against the outcome
+        DisputeOutcomeNotChallenged,
+        /// The account has no bond in the dispute
+        DisputeBondNotExist,
+        /// The results are committed and revealed, they cannot be uploaded directly
+        ResultMustBeCommitted,
+        /// The reveal period is not set, the results are uploaded directly
+        CommitRevealDisabled,
+        /// The reveal window of the proposal has started, no more results are accepted
+        CommitPeriodIsOver,
+        /// The reveal window of the proposal has not started
+        RevealPeriodNotStarted,
+        /// The reveal window of the proposal is over
+        RevealPeriodIsOver,
+        /// The account has no result committed to the proposal
+        CommitmentNotExist,
+        /// The revealed result does not match the commitment
+        CommitmentMismatch,
+        /// Only the owner of the proposal can do this
+        NotProposalOwner,
+        /// The result of the proposal cannot be fetched, it does not have two options
+        ResultSourceNotSupported,
+        /// The url, the path or a value of the result source is too long
+        ResultSourceTooLong,
     }   
 
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
         /// Offchain Worker entry point.
         ///
-        /// The node can upload the result in its own way, or it can write another program to
-        /// upload the result by itself.
-        ///
-        /// This callback function does not have to be implemented.
+        /// The results of the proposals waiting for them are fetched from their result source
+        /// and uploaded by the local keys of the staked nodes. The node can also upload the
+        /// result in its own way, or write another program to upload the result by itself.
         fn offchain_worker(_block_number: T::BlockNumber) {
             debug::info!("Entering off-chain worker");
+            Self::offchain_upload_results();
         }
 
         /// When the block is encapsulated, execute the following hook function
@@ -568,7 +938,7 @@ pub mod pallet {
         /// tokens.
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 2))]
+        #[pallet::weight(T::WeightInfo::stake())]
         pub fn stake(origin: OriginFor<T>, stake_number: BalanceOf<T>) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
             let number = with_transaction_result(|| Self::inner_stake(&who, stake_number))?;
@@ -580,7 +950,7 @@ pub mod pallet {
         /// governance tokens by himself.
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(6, 3))]
+        #[pallet::weight(T::WeightInfo::unstake())]
         pub fn unstake(origin: OriginFor<T>, unstake_number: BalanceOf<T>) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
             let number = with_transaction_result(|| Self::inner_unstake(&who, unstake_number))?;
@@ -593,7 +963,7 @@ pub mod pallet {
         /// The governance node decides whether the proposal is approved by voting agree or against
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.       
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 2))]
+        #[pallet::weight(T::WeightInfo::review())]
         pub fn review(origin: OriginFor<T>, vote_number: BalanceOf<T>, proposal_id: ProposalIdOf<T>, vote_type: bool) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
             Self::ensure_proposal_status(proposal_id, ProposalStatus::OriginalPrediction)?;
@@ -609,52 +979,31 @@ pub mod pallet {
             Ok(().into())
         }
 
-        /// Slash the reserved of a given account.
+        /// Slash at most `max` of the nodes that uploaded the result overturned by the dispute.
         ///
-        /// If a node commits evil and uploads some false results, the official or the community
-        /// will directly punish him for the amount of votes he pledged in this proposal.
+        /// The nodes are also slashed in batches at the beginning of each block, the slashes are
+        /// finished once no node is left and the reporters can then take out their rewards.
         ///
-        /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(9, 2))]
-        pub fn slash(
-            origin: OriginFor<T>, 
-            who: T::AccountId,
+        /// The dispatch origin for this call must be `Signed`.
+        #[pallet::weight(T::WeightInfo::process_slashes(*max))]
+        pub fn process_slashes(
+            origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
+            max: u32,
         ) -> DispatchResultWithPostInfo {
-            let _ = ensure_root(origin)?;
+            let _ = ensure_signed(origin)?;
             Self::ensure_proposal_status(proposal_id, ProposalStatus::End)?;
             ensure!(
                 ReportSuccessFlag::<T>::get(proposal_id) == Some(()),
                 Error::<T>::ReportNotSuccess,
             );
-            ensure!(
-                AccountSlashNumber::<T>::get(proposal_id, &who) == None,
-                Error::<T>::AccountHasBeenSlashed,
-            );
             ensure!(
                 SlashFinishFlag::<T>::get(proposal_id) == None,
                 Error::<T>::SlashHasBeenCompleted,
             );
-            let number = with_transaction_result(|| Self::inner_slash(&who, proposal_id))?;
-            Self::deposit_event(Event::<T>::Slash(who, proposal_id, number));
-            Ok(().into())
-        }
-
-        /// Set slash completion flag
-        ///
-        /// When all malicious nodes are slashed, this flag is set to completed
-        ///
-        /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(9, 1))]
-        pub fn slash_finish(
-            origin: OriginFor<T>, 
-            proposal_id: ProposalIdOf<T>,
-        ) -> DispatchResultWithPostInfo {
-            let _ = ensure_root(origin)?;
-            Self::ensure_proposal_status(proposal_id, ProposalStatus::End)?;
-            with_transaction_result(|| Self::inner_slash_finish(proposal_id))?;
-            Self::deposit_event(Event::<T>::SlashFinsh(proposal_id));
-            Ok(().into())
+            let (slashed, _) =
+                with_transaction_result(|| Self::inner_process_slashes(proposal_id, max))?;
+            Ok(Some(T::WeightInfo::process_slashes(slashed)).into())
         }
 
         /// Upload proposal results
@@ -662,8 +1011,11 @@ pub mod pallet {
         /// The governance node uploads the final result of the proposal through an unsigned
         /// transaction with a signed payload
         ///
+        /// The result is one of the options of the proposal or its invalid result, which voids
+        /// the proposal and refunds both options at an equal share of the collateral
+        ///
         /// This transaction does not need to be signed, but the payload must be signed
-        #[pallet::weight(0)]
+        #[pallet::weight(T::WeightInfo::upload_result())]
         pub fn upload_result(
             origin: OriginFor<T>,
             payload: Payload<T::Public, T::ProposalId, CurrencyIdOf<T>, BalanceOf<T>>,
@@ -683,11 +1035,129 @@ pub mod pallet {
             Ok(().into())
         }
 
-        /// Users can report proposals with incorrect results. If successful, 
-        /// all nodes that cast incorrect results will be punished.
+        /// Upload the value of a scalar proposal
+        ///
+        /// Works like `upload_result`, the final value of the proposal is the median of the
+        /// uploaded values weighted by their votes
+        ///
+        /// This transaction does not need to be signed, but the payload must be signed
+        #[pallet::weight(T::WeightInfo::upload_scalar_result())]
+        pub fn upload_scalar_result(
+            origin: OriginFor<T>,
+            payload: ScalarPayload<T::Public, T::ProposalId, BalanceOf<T>>,
+            _signature: T::Signature,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_none(origin)?;
+            let ScalarPayload {
+                public,
+                proposal_id,
+                value,
+                vote_num,
+            } = payload;
+            Self::ensure_proposal_status(proposal_id, ProposalStatus::WaitingForResults)?;
+            let who = public.into_account();
+            with_transaction_result(|| Self::inner_upload_scalar_result(&who, proposal_id, value, vote_num))?;
+            Self::deposit_event(Event::<T>::UploadScalarResult(who, proposal_id, value, vote_num));
+            Ok(().into())
+        }
+
+        /// Commit to the result of a proposal without publishing it
+        ///
+        /// The votes are locked like `upload_result`, the result is counted once it is revealed
+        /// with `reveal_result` in the reveal window after the upload cycle. The locked votes
+        /// of a result that is not revealed are slashed.
+        ///
+        /// This transaction does not need to be signed, but the payload must be signed
+        #[pallet::weight(T::WeightInfo::commit_result())]
+        pub fn commit_result(
+            origin: OriginFor<T>,
+            payload: CommitPayload<T::Public, T::ProposalId, T::Hash, BalanceOf<T>>,
+            _signature: T::Signature,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_none(origin)?;
+            let CommitPayload {
+                public,
+                proposal_id,
+                commitment,
+                vote_num,
+            } = payload;
+            Self::ensure_proposal_status(proposal_id, ProposalStatus::WaitingForResults)?;
+            let who = public.into_account();
+            with_transaction_result(|| {
+                Self::inner_commit_result(&who, proposal_id, commitment, vote_num)
+            })?;
+            Self::deposit_event(Event::<T>::CommitResult(who, proposal_id, vote_num));
+            Ok(().into())
+        }
+
+        /// Reveal the result committed with `commit_result`
+        ///
+        /// This transaction does not need to be signed, but the payload must be signed
+        #[pallet::weight(T::WeightInfo::reveal_result())]
+        pub fn reveal_result(
+            origin: OriginFor<T>,
+            payload: RevealPayload<T::Public, T::ProposalId, CurrencyIdOf<T>>,
+            _signature: T::Signature,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_none(origin)?;
+            let RevealPayload {
+                public,
+                proposal_id,
+                result,
+                salt,
+            } = payload;
+            Self::ensure_proposal_status(proposal_id, ProposalStatus::WaitingForResults)?;
+            let who = public.into_account();
+            let vote_num = with_transaction_result(|| {
+                Self::inner_reveal_result(&who, proposal_id, result, salt)
+            })?;
+            Self::deposit_event(Event::<T>::RevealResult(who, proposal_id, result, vote_num));
+            Ok(().into())
+        }
+
+        /// Set the source the offchain workers of the nodes fetch the result of the proposal
+        /// from, until the proposal waits for its result
+        ///
+        /// The dispatch origin for this call must be `Signed` by the owner of the proposal.
+        #[pallet::weight(T::WeightInfo::set_result_source())]
+        pub fn set_result_source(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            source: ResultSource,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                T::Pool::proposal_owner(proposal_id)? == who,
+                Error::<T>::NotProposalOwner
+            );
+            let state = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                state == ProposalStatus::OriginalPrediction
+                    || state == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            ensure!(
+                T::CouplePool::proposal_scalar_range(proposal_id).is_none()
+                    && T::CouplePool::proposal_options(proposal_id)?.len() == 2,
+                Error::<T>::ResultSourceNotSupported
+            );
+            ensure!(
+                [&source.url, &source.path, &source.yes, &source.no]
+                    .iter()
+                    .all(|field| field.len() <= MAX_RESULT_SOURCE_LENGTH),
+                Error::<T>::ResultSourceTooLong
+            );
+            ResultSources::<T>::insert(proposal_id, source);
+            Self::deposit_event(Event::<T>::SetResultSource(proposal_id));
+            Ok(().into())
+        }
+
+        /// Users can report proposals with incorrect results. If successful, a dispute is
+        /// opened and all nodes that cast incorrect results will be punished when the dispute
+        /// overturns the result.
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 2))]
+        #[pallet::weight(T::WeightInfo::report())]
         pub fn report(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
@@ -699,6 +1169,14 @@ pub mod pallet {
                 state == ProposalStatus::ResultAnnouncement,
                 Error::<T>::ProposalAbnormalState,
             );
+            ensure!(
+                T::CouplePool::proposal_scalar_range(proposal_id).is_none(),
+                Error::<T>::ProposalIsScalar
+            );
+            ensure!(
+                !Disputes::<T>::contains_key(proposal_id),
+                Error::<T>::ProposalNotNeedSecond
+            );
             with_transaction_result(|| Self::inner_report(&who, proposal_id, vote_num))?;
             Self::deposit_event(Event::<T>::Report(who, proposal_id, vote_num));
             Ok(().into())
@@ -708,7 +1186,7 @@ pub mod pallet {
         /// out the staked tokens
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 1))]
+        #[pallet::weight(T::WeightInfo::take_out())]
         pub fn take_out(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>
@@ -724,7 +1202,7 @@ pub mod pallet {
         /// votes that were locked when uploading the results
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 2))]
+        #[pallet::weight(T::WeightInfo::unlock())]
         pub fn unlock(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>
@@ -736,38 +1214,98 @@ pub mod pallet {
             Ok(().into())
         }
 
-        /// Set the minimum number of reviews
+        /// Bond governance tokens on an option against the outcome of a dispute
         ///
-        /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
-        pub fn set_minimal_review_number(
+        /// Any holder of governance tokens can bond, at least the bond of the current round. An
+        /// account always bonds on the same option. A round with bonds against the outcome
+        /// moves the dispute to the most bonded option and doubles the bond of the next round.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::dispute())]
+        pub fn dispute(
             origin: OriginFor<T>,
-            number: BalanceOf<T>,
+            proposal_id: ProposalIdOf<T>,
+            option: CurrencyIdOf<T>,
+            bond: BalanceOf<T>,
         ) -> DispatchResultWithPostInfo {
-            let _ = ensure_root(origin)?;
-            MinimalReviewNumber::<T>::set(Some(number));
-            Self::deposit_event(Event::<T>::SetMinimalReviewNumber(number));
+            let who = ensure_signed(origin)?;
+            Self::ensure_proposal_status(proposal_id, ProposalStatus::ResultAnnouncement)?;
+            with_transaction_result(|| Self::inner_dispute(&who, proposal_id, option, bond))?;
+            Self::deposit_event(Event::<T>::DisputeBond(who, proposal_id, option, bond));
             Ok(().into())
         }
 
-        /// Set the minimum number of stake
+        /// Decide the outcome of a dispute still contested after its last bonded round
         ///
-        /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
-        pub fn set_minimal_stake_number(
+        /// The dispatch origin for this call is `DisputeOrigin`.
+        #[pallet::weight(T::WeightInfo::resolve_dispute())]
+        pub fn resolve_dispute(
             origin: OriginFor<T>,
-            number: BalanceOf<T>,
+            proposal_id: ProposalIdOf<T>,
+            outcome: CurrencyIdOf<T>,
         ) -> DispatchResultWithPostInfo {
-            let _ = ensure_root(origin)?;
-            MinimalStakeNumber::<T>::set(Some(number));
-            Self::deposit_event(Event::<T>::SetMinimalStakeNumber(number));
+            T::DisputeOrigin::ensure_origin(origin)?;
+            Self::ensure_proposal_status(proposal_id, ProposalStatus::ResultAnnouncement)?;
+            Self::ensure_proposal_optional_id(proposal_id, outcome)?;
+            let dispute = Disputes::<T>::get(proposal_id).ok_or(Error::<T>::DisputeNotExist)?;
+            ensure!(
+                dispute.round > T::MaxDisputeRounds::get(),
+                Error::<T>::DisputeNotEscalated
+            );
+            with_transaction_result(|| Self::settle_dispute(proposal_id, outcome))?;
+            Ok(().into())
+        }
+
+        /// After the dispute is settled, take out the bond on its outcome together with its
+        /// share of the bonds on the other options, the bonds on the other options are lost
+        /// unless no bond is on the outcome, then every bond is paid back
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::claim_dispute_bond())]
+        pub fn claim_dispute_bond(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::ensure_proposal_status(proposal_id, ProposalStatus::End)?;
+            let number =
+                with_transaction_result(|| Self::inner_claim_dispute_bond(&who, proposal_id))?;
+            Self::deposit_event(Event::<T>::DisputeBondClaimed(who, proposal_id, number));
+            Ok(().into())
+        }
+
+        /// Set the minimum number of reviews
+        ///
+        /// The dispatch origin for this call is `root`.
+        #[pallet::weight(T::WeightInfo::set_minimal_review_number())]
+        pub fn set_minimal_review_number(
+            origin: OriginFor<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_root(origin)?;
+            MinimalReviewNumber::<T>::set(Some(number));
+            Self::deposit_event(Event::<T>::SetMinimalReviewNumber(number));
+            Ok(().into())
+        }
+
+        /// Set the minimum number of stake
+        ///
+        /// The dispatch origin for this call is `root`.
+        #[pallet::weight(T::WeightInfo::set_minimal_stake_number())]
+        pub fn set_minimal_stake_number(
+            origin: OriginFor<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_root(origin)?;
+            MinimalStakeNumber::<T>::set(Some(number));
+            Self::deposit_event(Event::<T>::SetMinimalStakeNumber(number));
             Ok(().into())
         }
 
         /// Set the minimum number of report
         ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        #[pallet::weight(T::WeightInfo::set_minimal_report_number())]
         pub fn set_minimal_report_number(
             origin: OriginFor<T>,
             number: BalanceOf<T>,
@@ -781,7 +1319,7 @@ pub mod pallet {
         /// Set the minimum number of lockratio
         ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        #[pallet::weight(T::WeightInfo::set_lock_ratio())]
         pub fn set_lock_ratio(
             origin: OriginFor<T>,
             number: BalanceOf<T>,
@@ -796,13 +1334,14 @@ pub mod pallet {
         /// Set the publicity interval
         ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        #[pallet::weight(T::WeightInfo::set_review_cycle())]
         pub fn set_review_cycle(
             origin: OriginFor<T>,
             interval: MomentOf<T>,
         ) -> DispatchResultWithPostInfo {
             let _ = ensure_root(origin)?;
             ReviewCycle::<T>::set(Some(interval));
+            Self::reschedule_all_proposals();
             Self::deposit_event(Event::<T>::SetReviewCycle(interval));
             Ok(().into())
         }
@@ -810,13 +1349,14 @@ pub mod pallet {
         /// Set the upload cycle
         ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        #[pallet::weight(T::WeightInfo::set_upload_cycle())]
         pub fn set_upload_cycle(
             origin: OriginFor<T>,
             interval: MomentOf<T>,
         ) -> DispatchResultWithPostInfo {
             let _ = ensure_root(origin)?;
             ResultUploadCycle::<T>::set(Some(interval));
+            Self::reschedule_all_proposals();
             Self::deposit_event(Event::<T>::SetUploadCycle(interval));
             Ok(().into())
         }
@@ -824,16 +1364,34 @@ pub mod pallet {
         /// Set the publicity period
         ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        #[pallet::weight(T::WeightInfo::set_publicity_period())]
         pub fn set_publicity_period(
             origin: OriginFor<T>,
             interval: MomentOf<T>,
         ) -> DispatchResultWithPostInfo {
             let _ = ensure_root(origin)?;
             PublicityPeriod::<T>::set(Some(interval));
+            Self::reschedule_all_proposals();
             Self::deposit_event(Event::<T>::SetPublicityPeriod(interval));
             Ok(().into())
         }
+
+        /// Set the reveal period
+        ///
+        /// The results are committed and revealed instead of uploaded while it is not zero.
+        /// A proposal keeps the reveal period it had when the first result was committed to it.
+        ///
+        /// The dispatch origin for this call is `root`.
+        #[pallet::weight(T::WeightInfo::set_reveal_period())]
+        pub fn set_reveal_period(
+            origin: OriginFor<T>,
+            interval: MomentOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_root(origin)?;
+            RevealPeriod::<T>::set(Some(interval));
+            Self::deposit_event(Event::<T>::SetRevealPeriod(interval));
+            Ok(().into())
+        }
     }
 }
 
@@ -854,111 +1412,117 @@ impl<T: Config> GenesisConfig<T> {
     }
 }
 
+/// The most new proposals queued in one block
+const MAX_NEW_PROPOSALS_PER_BLOCK: u32 = 64;
+
+/// The most queued proposals rescheduled in one block after a cycle changed
+const MAX_RESCHEDULED_PROPOSALS_PER_BLOCK: u32 = 64;
+
+/// The most nodes slashed in one block, the remaining ones are slashed in the following blocks
+const MAX_SLASHES_PER_BLOCK: u32 = 16;
+
+/// The longest url, path or value of a result source
+const MAX_RESULT_SOURCE_LENGTH: usize = 256;
+
+/// How long the offchain worker waits for the result source to answer
+const FETCH_TIMEOUT_MILLIS: u64 = 3_000;
+
 impl<T: Config> Pallet<T> {
     fn module_account() -> T::AccountId {
         T::AutonomyId::get().into_account()
     }
 
     fn begin_block(_: T::BlockNumber) -> Result<Weight, DispatchError> {
-        let max_id = T::Pool::max_proposal_id();
-        let mut index: ProposalIdOf<T> = Zero::zero();
         let now = <TimeOf<T> as Time>::now();
-        loop {
-            
-            if index >= max_id {
-                break;
+        let mut weight = Self::schedule_new_proposals();
+        weight = weight.saturating_add(Self::reschedule_proposals());
+        weight = weight.saturating_add(Self::process_slash_queue());
+        let progress = <Self as DeadlineScheduler>::process_deadlines(now);
+        let change = T::WeightInfo::change_state().saturating_add(T::Pool::state_changed_weight());
+        Ok(weight.saturating_add(progress.weight(T::DbWeight::get(), change)))
+    }
+
+    /// Queue the proposals created by the pool since the last block
+    fn schedule_new_proposals() -> Weight {
+        let max_id = T::Pool::max_proposal_id();
+        let mut index = NextScheduledProposalId::<T>::get().unwrap_or_else(Zero::zero);
+        let mut weight = T::DbWeight::get().reads_writes(2, 1);
+        let mut scheduled: u32 = 0;
+        while index < max_id && scheduled < MAX_NEW_PROPOSALS_PER_BLOCK {
+            if let Err(e) = Self::schedule_deadline(index) {
+                sp_runtime::print(e);
             }
-            let result = with_transaction_result(|| {
-                
-                Self::change_state(index, now)
-            });
-            if result.is_ok() {}
-            index = index
-                .checked_add(&One::one())
-                .ok_or(Error::<T>::ProposalIdOverflow)?;
+            weight = weight.saturating_add(T::DbWeight::get().reads_writes(6, 2));
+            index = index.saturating_add(One::one());
+            scheduled += 1;
         }
-        Ok(0)
+        NextScheduledProposalId::<T>::put(index);
+        weight
     }
 
-    fn change_state(
-        index: ProposalIdOf<T>,
-        now: MomentOf<T>,
-    ) -> Result<(), DispatchError> {
-        let state = T::Pool::get_proposal_state(index)?;
-        let create_time = T::Pool::proposal_create_time(index)?;
-        let close_time = T::Pool::proposal_close_time(index)?;
-        match state{
-            ProposalStatus::OriginalPrediction => {
-                let review_time = ReviewCycle::<T>::get().unwrap_or_else(Zero::zero);
-                let diff = now.checked_sub(&create_time).unwrap_or_else(Zero::zero);
-                let delay_num = ReviewDelay::<T>::get(index).unwrap_or_else(Zero::zero);
-                let delay = delay_num.checked_mul(&review_time).ok_or(Error::<T>::Overflow)?;
-                let delay = delay.checked_add(&review_time).ok_or(Error::<T>::Overflow)?;
-                if now > close_time {
-                    T::Pool::set_proposal_state(index, ProposalStatus::End)?;
-                }
-                if diff >= delay{
-                    if ReviewEqualFlag::<T>::get(index).is_some(){
-                            let new_v = delay_num.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
-                            ReviewDelay::<T>::insert(index, new_v);
-                    }
-                    else{
-                        match ReviewFlag::<T>::get(index) {
-                                Some(_) =>{
-                                    T::Pool::set_proposal_state(index, ProposalStatus::FormalPrediction)?;
-                                }
-                                None =>{
-                                    T::Pool::set_proposal_state(index, ProposalStatus::End)?;
-                                }
-                           
-                                
-                            }
-                    }
+    /// Compute again the deadlines of all the queued proposals, in batches from the next block
+    /// on, a reschedule that is still running starts over
+    fn reschedule_all_proposals() {
+        let end = NextScheduledProposalId::<T>::get().unwrap_or_else(Zero::zero);
+        RescheduledProposals::<T>::put((Zero::zero(), end));
+    }
+
+    /// Reschedule a batch of the proposals marked by `reschedule_all_proposals`
+    fn reschedule_proposals() -> Weight {
+        let (mut index, end) = match RescheduledProposals::<T>::get() {
+            Some(range) => range,
+            None => return T::DbWeight::get().reads(1),
+        };
+        let mut weight = T::DbWeight::get().reads_writes(1, 1);
+        let mut rescheduled: u32 = 0;
+        while index < end && rescheduled < MAX_RESCHEDULED_PROPOSALS_PER_BLOCK {
+            if ProposalDeadline::<T>::contains_key(index) {
+                if let Err(e) = Self::schedule_deadline(index) {
+                    sp_runtime::print(e);
                 }
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(6, 2));
             }
-            ProposalStatus::WaitingForResults => {
-                let upload_time = ResultUploadCycle::<T>::get().unwrap_or_else(Zero::zero);
-                let diff = now.checked_sub(&close_time).unwrap_or_else(Zero::zero);
-                let delay_num = UploadDelay::<T>::get(index).unwrap_or_else(Zero::zero);
-                let delay = delay_num.checked_mul(&upload_time).ok_or(Error::<T>::Overflow)?;
-                let delay = delay.checked_add(&upload_time).ok_or(Error::<T>::Overflow)?;
-                if diff >= delay{
-                    let (p1, p2) = T::CouplePool::proposal_pair(index)?;
-                    let p1_balance = ResultVotingStatus::<T>::get(index, p1).unwrap_or_else(Zero::zero);
-                    let p2_balance = ResultVotingStatus::<T>::get(index, p2).unwrap_or_else(Zero::zero);
-                    match p1_balance.cmp(&p2_balance) {
-                        Ordering::Less => {
-                            T::CouplePool::set_proposal_result(index, p2)?;
-                            T::Pool::set_proposal_state(index, ProposalStatus::ResultAnnouncement)?;
-                            ResultAnnouncementTime::<T>::insert(index, now);
-                        }
-                        Ordering::Equal => {
-                            let new_v = delay_num.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
-                            UploadDelay::<T>::insert(index, new_v);
-                        }
-                        Ordering::Greater =>{
-                            T::CouplePool::set_proposal_result(index, p1)?;
-                            T::Pool::set_proposal_state(index, ProposalStatus::ResultAnnouncement)?;
-                            ResultAnnouncementTime::<T>::insert(index, now);
-                        }
-                    };
+            weight = weight.saturating_add(T::DbWeight::get().reads(1));
+            index = index.saturating_add(One::one());
+            rescheduled += 1;
+        }
+        if index < end {
+            RescheduledProposals::<T>::put((index, end));
+        } else {
+            RescheduledProposals::<T>::kill();
+        }
+        weight
+    }
+
+    /// Slash the nodes of the queued proposals in order, a proposal leaves the queue once its
+    /// slashes are finished
+    fn process_slash_queue() -> Weight {
+        let mut queue = SlashQueue::<T>::get();
+        let mut weight = T::DbWeight::get().reads(1);
+        if queue.is_empty() {
+            return weight;
+        }
+        let mut remaining = MAX_SLASHES_PER_BLOCK;
+        while !queue.is_empty() && remaining > 0 {
+            let proposal_id = queue[0];
+            match with_transaction_result(|| Self::inner_process_slashes(proposal_id, remaining)) {
+                Ok((slashed, finished)) => {
+                    weight = weight.saturating_add(T::WeightInfo::process_slashes(slashed));
+                    remaining = remaining.saturating_sub(slashed);
+                    if !finished {
+                        break;
+                    }
                 }
-            }
-            ProposalStatus::ResultAnnouncement => {
-                let report_time = PublicityPeriod::<T>::get().unwrap_or_else(Zero::zero);
-                let announcement_time = ResultAnnouncementTime::<T>::get(index)
-                    .ok_or(Error::<T>::ProposalHasNotEnteredThePublicityPeriod)?;
-                let diff = now.checked_sub(&announcement_time).unwrap_or_else(Zero::zero);
-                if diff >= report_time{
-                        T::Pool::set_proposal_state(index, ProposalStatus::End)?;
+                Err(e) => {
+                    sp_runtime::print(e);
+                    weight = weight.saturating_add(T::WeightInfo::process_slashes(remaining));
+                    Self::deposit_event(Event::<T>::SlashFailed(proposal_id));
                 }
-                
-            }
-            _ => {
-
             }
+            queue.remove(0);
         }
-        Ok(())
+        SlashQueue::<T>::put(queue);
+        weight.saturating_add(T::DbWeight::get().writes(1))
     }
 
     fn ensure_proposal_status(
@@ -974,14 +1538,63 @@ impl<T: Config> Pallet<T> {
         proposal_id: T::ProposalId,
         result: CurrencyIdOf<T>,
     ) -> Result<(), DispatchError> {
-        let (id1, id2) = T::CouplePool::proposal_pair(proposal_id)?;
+        let options = T::CouplePool::proposal_results(proposal_id)?;
         ensure!(
-            result == id1 || result == id2,
+            options.contains(&result),
             Error::<T>::ProposalOptionNotCorrect
         );
         Ok(())
     }
 
+    /// The option with the most result votes, `None` if several options share the most votes
+    fn most_voted_option(
+        proposal_id: T::ProposalId,
+        options: &[CurrencyIdOf<T>],
+    ) -> Option<CurrencyIdOf<T>> {
+        let mut result = None;
+        let mut max_votes: BalanceOf<T> = Zero::zero();
+        let mut is_equal = true;
+        for option in options {
+            let votes = ResultVotingStatus::<T>::get(proposal_id, option).unwrap_or_else(Zero::zero);
+            match votes.cmp(&max_votes) {
+                Ordering::Greater => {
+                    max_votes = votes;
+                    result = Some(*option);
+                    is_equal = false;
+                }
+                Ordering::Equal => is_equal = true,
+                Ordering::Less => {}
+            }
+        }
+        if is_equal {
+            None
+        } else {
+            result
+        }
+    }
+
+    /// The median of the values uploaded to a scalar proposal weighted by their votes,
+    /// `None` if nothing has been uploaded
+    fn weighted_median_value(proposal_id: T::ProposalId) -> Option<BalanceOf<T>> {
+        let mut values: Vec<(BalanceOf<T>, BalanceOf<T>)> =
+            NodeScalarResultVotingStatus::<T>::iter_prefix_values(proposal_id).collect();
+        values.sort_by(|a, b| a.0.cmp(&b.0));
+        let total = values
+            .iter()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, (_, votes)| acc.saturating_add(*votes));
+        if total.is_zero() {
+            return None;
+        }
+        let mut sum: BalanceOf<T> = Zero::zero();
+        for (value, votes) in values {
+            sum = sum.saturating_add(votes);
+            if sum.saturating_add(sum) >= total {
+                return Some(value);
+            }
+        }
+        None
+    }
+
     fn inner_review(
         who: &T::AccountId, 
         proposal_id: ProposalIdOf<T>, 
@@ -1179,127 +1792,92 @@ impl<T: Config> Pallet<T> {
         )
     }
 
-    fn inner_slash(who: &T::AccountId, proposal_id: ProposalIdOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+    /// Slash the votes the node locked on the proposal into the report asset pool
+    fn inner_slash(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
         let currency_stake_id = T::StakeCurrencyId::get();
-        let (currency_id, vote_number) = NodeResultVotingStatus::<T>::get(proposal_id, who).ok_or(Error::<T>::AccountDidNotUploadResult)?;
-        let result = T::CouplePool::get_proposal_result(proposal_id)?;
-        ensure!(result == currency_id, Error::<T>::SlashAccountError);
-        let lock_ratio = LockRatio::<T>::get().ok_or(Error::<T>::LockRatioNotSet)?;
-        let slash_number = vote_number.checked_mul(&lock_ratio).unwrap_or_else(Zero::zero);
-        let slash_number = slash_number.checked_div(&100u32.into()).unwrap_or_else(Zero::zero);
-        let unstake_number = with_transaction_result(|| Self::inner_unstake(who, slash_number))?;
+        let (currency_id, _) = NodeResultVotingStatus::<T>::get(proposal_id, who)
+            .ok_or(Error::<T>::AccountDidNotUploadResult)?;
+        let dispute = Disputes::<T>::get(proposal_id).ok_or(Error::<T>::DisputeNotExist)?;
+        ensure!(
+            dispute.announced == currency_id,
+            Error::<T>::SlashAccountError
+        );
+        let unstake_number = Self::slash_lock(who, proposal_id)?;
         let autonomy_account = Self::module_account();
         let _ = <TokensOf<T> as Tokens<T::AccountId>>::transfer(currency_stake_id, who, &autonomy_account, unstake_number)?;
-        ReportAssetPool::<T>::try_mutate_exists(
-            proposal_id,
-            |optional| -> Result<(), DispatchError> {
-                let num = match optional {
-                    Some(old_balance) => {
-                        old_balance.checked_add(&unstake_number).ok_or(Error::<T>::Overflow)?
-                    }
-                    None => {
-                        unstake_number
-                    }
-                };
-                *optional = Some(num);
-                Ok(())
-            }
-        )?;
-        AccountSlashNumber::<T>::try_mutate_exists(
-            proposal_id,
-            &who,
-            |optional| -> Result<(), DispatchError> {
-                let num = match optional {
-                    Some(old_balance) => {
-                        old_balance.checked_add(&unstake_number).ok_or(Error::<T>::Overflow)?
-                    }
-                    None => {
-                        unstake_number
-                    }
-                };
-                *optional = Some(num);
-                Ok(())
-            }
-        )?;
-        StakedNodeLockTotalNum::<T>::try_mutate(
-            &who,
-            |optional| -> Result<(), DispatchError> {
-                let new_lock_balance = match optional{
-                    Some(lock_balance) => {
-                        lock_balance.checked_sub(&unstake_number).unwrap_or_else(Zero::zero)
-                    }
-                    None => {
-                        unstake_number
-                    }
-                };
-                *optional = Some(new_lock_balance);
-                Ok(())
-            }
-        )?;
-        StakedNodeLockNum::<T>::try_mutate(
-            proposal_id,
-            &who,
-            |optional| -> Result<(), DispatchError> {
-                match optional{
-                    Some(_) => {
-                        *optional = None;
-                        Ok(())
-                    }
-                    None => {
-                        Err(Error::<T>::NoLockedQuantity.into())
-                    }
-                }
-            }
-        )?;
-        Ok(slash_number)
+        ReportAssetPool::<T>::try_mutate(proposal_id, |optional| -> Result<(), DispatchError> {
+            let num = optional.unwrap_or_else(Zero::zero);
+            *optional = Some(
+                num.checked_add(&unstake_number)
+                    .ok_or(Error::<T>::Overflow)?,
+            );
+            Ok(())
+        })?;
+        AccountSlashNumber::<T>::insert(proposal_id, who, unstake_number);
+        Ok(unstake_number)
     }
 
-    fn inner_slash_finish(proposal_id: ProposalIdOf<T>) -> Result<(), DispatchError> {
-        ensure!(
-            ReportSuccessFlag::<T>::get(proposal_id) == Some(()),
-            Error::<T>::ReportNotSuccess,
-        );
+    /// Release the votes the node locked on the proposal and unstake them, at most its
+    /// available stake
+    ///
+    /// Return the number unstaked, which is left in the free balance of the node.
+    fn slash_lock(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let lock_num = StakedNodeLockNum::<T>::take(proposal_id, who).unwrap_or_else(Zero::zero);
+        let lock_total = StakedNodeLockTotalNum::<T>::get(who)
+            .unwrap_or_else(Zero::zero)
+            .saturating_sub(lock_num);
+        StakedNodeLockTotalNum::<T>::insert(who, lock_total);
+        let (staked, _) = StakedNode::<T>::get(who).unwrap_or((Zero::zero(), false));
+        let slash_number = cmp::min(lock_num, staked.saturating_sub(lock_total));
+        if slash_number.is_zero() {
+            return Ok(slash_number);
+        }
+        Self::inner_unstake(who, slash_number)
+    }
+
+    /// Slash at most `max` of the nodes that uploaded the announced result and have not been
+    /// slashed yet, the slashes are finished once no such node is left
+    ///
+    /// Return the number of nodes slashed and whether the slashes are finished.
+    fn inner_process_slashes(
+        proposal_id: ProposalIdOf<T>,
+        max: u32,
+    ) -> Result<(u32, bool), DispatchError> {
         ensure!(
-            SlashFinishFlag::<T>::get(proposal_id) == None,
-            Error::<T>::SlashHasBeenCompleted,
+            Disputes::<T>::contains_key(proposal_id),
+            Error::<T>::DisputeNotExist
         );
-        let (id1, id2) = T::CouplePool::proposal_pair(proposal_id)?;
+        let nodes: Vec<T::AccountId> = PendingSlashes::<T>::iter_prefix(proposal_id)
+            .map(|(who, _)| who)
+            .take((max as usize).saturating_add(1))
+            .collect();
+        let finished = nodes.len() <= max as usize;
+        let mut slashed: u32 = 0;
+        for who in nodes.into_iter().take(max as usize) {
+            PendingSlashes::<T>::remove(proposal_id, &who);
+            let number = Self::inner_slash(&who, proposal_id)?;
+            Self::deposit_event(Event::<T>::Slash(who, proposal_id, number));
+            slashed += 1;
+        }
+        if finished {
+            Self::inner_slash_finish(proposal_id);
+        }
+        Ok((slashed, finished))
+    }
+
+    /// Set the slash completion flag, the rewards of the reporters come from the slashed votes
+    fn inner_slash_finish(proposal_id: ProposalIdOf<T>) {
         let pool_num = ReportAssetPool::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
-        let vote_num1 = ResultVotingStatus::<T>::get(proposal_id, id1).unwrap_or_else(Zero::zero);
-        let vote_num2 = ResultVotingStatus::<T>::get(proposal_id, id2).unwrap_or_else(Zero::zero);
-        ensure!(vote_num1 != vote_num2, Error::<T>::ResultIsEqual);
-        let result_id = T::CouplePool::get_proposal_result(proposal_id)?;
-        let result_num = ResultVotingStatus::<T>::get(proposal_id, result_id).unwrap_or_else(Zero::zero);
-        let lock_ratio = LockRatio::<T>::get().ok_or(Error::<T>::LockRatioNotSet)?;
-        let result_num = result_num.checked_mul(&lock_ratio).ok_or(Error::<T>::Overflow)?;
-        let result_num = result_num.checked_div(&100u32.into()).ok_or(Error::<T>::Overflow)?;
-        ensure!(result_num == pool_num, Error::<T>::SlashNumError);
-        SlashFinishFlag::<T>::try_mutate(
-            proposal_id,
-            |optional| -> Result<(), DispatchError> {
-                match optional {
-                    Some(_) => {
-                    },
-                    None => *optional = Some(()),
-                };
-                Ok(())
-            },
-        )?;
-        FinalReportAssetPool::<T>::try_mutate(
-            proposal_id,
-            |optional| -> Result<(), DispatchError> {
-                match optional {
-                    Some(_) => {
-                        Err(Error::<T>::FinalReportAssetPoolHasSet.into())
-                    },
-                    None => {
-                        *optional = Some(pool_num);
-                        Ok(())
-                    }
-                }
-            },
-        )?;
-        Self::inner_swap_result(proposal_id)
+        SlashFinishFlag::<T>::insert(proposal_id, ());
+        FinalReportAssetPool::<T>::insert(proposal_id, pool_num);
+        SlashQueue::<T>::mutate(|queue| queue.retain(|index| *index != proposal_id));
+        Self::deposit_event(Event::<T>::SlashFinsh(proposal_id));
     }
 
     fn inner_upload_result(
@@ -1308,22 +1886,197 @@ impl<T: Config> Pallet<T> {
         result: CurrencyIdOf<T>,
         vote_num: BalanceOf<T>,
     ) -> Result<(), DispatchError> {
-        let (_, node_flag) = StakedNode::<T>::get(&who).ok_or(Error::<T>::AccountNotStaked)?;
         ensure!(
-            node_flag,
-            Error::<T>::NotAStakingNode
+            T::CouplePool::proposal_scalar_range(proposal_id).is_none(),
+            Error::<T>::ProposalIsScalar
+        );
+        ensure!(
+            !Self::commit_reveal_enabled(),
+            Error::<T>::ResultMustBeCommitted
+        );
+        ensure!(
+            !RevealEnd::<T>::contains_key(proposal_id),
+            Error::<T>::CommitPeriodIsOver
         );
+        Self::ensure_staked_node(who)?;
         Self::ensure_proposal_optional_id(proposal_id, result)?;
-        let close_time = T::Pool::proposal_close_time(proposal_id)?;
-        let usable_balance = Self::inner_get_snapshot_usable_num(who, close_time)?;
-        ensure!(usable_balance >= vote_num, Error::<T>::InsufficientNumberOfVotes);
-        let lock_ratio = LockRatio::<T>::get().ok_or(Error::<T>::LockRatioNotSet)?;
-        let lock_num = vote_num.checked_mul(&lock_ratio).ok_or(Error::<T>::Overflow)?;
-        let lock_num = lock_num.checked_div(&100u32.into()).ok_or(Error::<T>::Overflow)?;
-        StakedNodeLockTotalNum::<T>::try_mutate(
+        Self::lock_upload_votes(who, proposal_id, vote_num)?;
+        Self::record_result_votes(who, proposal_id, result, vote_num)
+    }
+
+    /// Count the votes of the node for the result
+    fn record_result_votes(
+        who: &T::AccountId,
+        proposal_id: T::ProposalId,
+        result: CurrencyIdOf<T>,
+        vote_num: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        NodeResultVotingStatus::<T>::try_mutate(
+            proposal_id,
             &who,
             |optional| -> Result<(), DispatchError> {
-                let new_lock_balance = match optional{
+                match optional {
+                    Some(_) => Err(Error::<T>::AccountHasAlreadyUploaded.into()),
+                    None => {
+                        *optional = Some((result, vote_num));
+                        Ok(())
+                    }
+                }
+            },
+        )?;
+        ResultVotingStatus::<T>::try_mutate(
+            proposal_id,
+            result,
+            |optional| -> Result<(), DispatchError> {
+                let mut sum = optional.unwrap_or_else(Zero::zero);
+                sum = sum.checked_add(&vote_num).ok_or(Error::<T>::Overflow)?;
+                *optional = Some(sum);
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Whether the results are committed and revealed instead of uploaded
+    fn commit_reveal_enabled() -> bool {
+        !RevealPeriod::<T>::get()
+            .unwrap_or_else(Zero::zero)
+            .is_zero()
+    }
+
+    /// The commitment of a node to the result with the votes, hashed with its salt
+    ///
+    /// The proposal and the node are part of the preimage, so a commitment copied from another
+    /// node or another proposal can not be revealed.
+    pub fn result_commitment(
+        proposal_id: T::ProposalId,
+        who: &T::AccountId,
+        result: CurrencyIdOf<T>,
+        vote_num: BalanceOf<T>,
+        salt: [u8; 32],
+    ) -> T::Hash {
+        T::Hashing::hash_of(&(proposal_id, who, result, vote_num, salt))
+    }
+
+    fn inner_commit_result(
+        who: &T::AccountId,
+        proposal_id: T::ProposalId,
+        commitment: T::Hash,
+        vote_num: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        ensure!(
+            Self::commit_reveal_enabled(),
+            Error::<T>::CommitRevealDisabled
+        );
+        ensure!(
+            T::CouplePool::proposal_scalar_range(proposal_id).is_none(),
+            Error::<T>::ProposalIsScalar
+        );
+        ensure!(
+            !RevealEnd::<T>::contains_key(proposal_id),
+            Error::<T>::CommitPeriodIsOver
+        );
+        Self::ensure_staked_node(who)?;
+        Self::lock_upload_votes(who, proposal_id, vote_num)?;
+        if !ProposalRevealPeriod::<T>::contains_key(proposal_id) {
+            let reveal_period = RevealPeriod::<T>::get().unwrap_or_else(Zero::zero);
+            ProposalRevealPeriod::<T>::insert(proposal_id, reveal_period);
+        }
+        ResultCommitments::<T>::insert(proposal_id, who, (commitment, vote_num));
+        Ok(())
+    }
+
+    fn inner_reveal_result(
+        who: &T::AccountId,
+        proposal_id: T::ProposalId,
+        result: CurrencyIdOf<T>,
+        salt: [u8; 32],
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let end = RevealEnd::<T>::get(proposal_id).ok_or(Error::<T>::RevealPeriodNotStarted)?;
+        ensure!(
+            <TimeOf<T> as Time>::now() < end,
+            Error::<T>::RevealPeriodIsOver
+        );
+        let (commitment, vote_num) =
+            ResultCommitments::<T>::take(proposal_id, who).ok_or(Error::<T>::CommitmentNotExist)?;
+        ensure!(
+            Self::result_commitment(proposal_id, who, result, vote_num, salt) == commitment,
+            Error::<T>::CommitmentMismatch
+        );
+        Self::ensure_proposal_optional_id(proposal_id, result)?;
+        Self::record_result_votes(who, proposal_id, result, vote_num)?;
+        Ok(vote_num)
+    }
+
+    /// Burn the locked votes of the nodes that did not reveal their committed result
+    fn penalise_unrevealed(proposal_id: T::ProposalId) -> Result<(), DispatchError> {
+        let currency_id = T::StakeCurrencyId::get();
+        let commitments: Vec<(T::AccountId, (T::Hash, BalanceOf<T>))> =
+            ResultCommitments::<T>::drain_prefix(proposal_id).collect();
+        for (who, _) in commitments {
+            let number = Self::slash_lock(&who, proposal_id)?;
+            let _ = <TokensOf<T> as Tokens<T::AccountId>>::burn(currency_id, &who, number)?;
+            Self::deposit_event(Event::<T>::CommitNotRevealed(who, proposal_id, number));
+        }
+        Ok(())
+    }
+
+    fn inner_upload_scalar_result(
+        who: &T::AccountId,
+        proposal_id: T::ProposalId,
+        value: BalanceOf<T>,
+        vote_num: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        let (min, max) = T::CouplePool::proposal_scalar_range(proposal_id)
+            .ok_or(Error::<T>::ProposalNotScalar)?;
+        Self::ensure_staked_node(who)?;
+        ensure!(
+            value >= min && value <= max,
+            Error::<T>::ProposalOptionNotCorrect
+        );
+        Self::lock_upload_votes(who, proposal_id, vote_num)?;
+        NodeScalarResultVotingStatus::<T>::try_mutate(
+            proposal_id,
+            &who,
+            |optional| -> Result<(), DispatchError> {
+                match optional {
+                    Some(_) => Err(Error::<T>::AccountHasAlreadyUploaded.into()),
+                    None => {
+                        *optional = Some((value, vote_num));
+                        Ok(())
+                    }
+                }
+            },
+        )?;
+        Ok(())
+    }
+
+    fn ensure_staked_node(who: &T::AccountId) -> Result<(), DispatchError> {
+        let (_, node_flag) = StakedNode::<T>::get(&who).ok_or(Error::<T>::AccountNotStaked)?;
+        ensure!(
+            node_flag,
+            Error::<T>::NotAStakingNode
+        );
+        Ok(())
+    }
+
+    /// Lock part of the votes the node uploaded with, the votes come from the snapshot taken
+    /// at the close time of the proposal
+    fn lock_upload_votes(
+        who: &T::AccountId,
+        proposal_id: T::ProposalId,
+        vote_num: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        let close_time = T::Pool::proposal_close_time(proposal_id)?;
+        let usable_balance = Self::inner_get_snapshot_usable_num(who, close_time)?;
+        ensure!(usable_balance >= vote_num, Error::<T>::InsufficientNumberOfVotes);
+        let lock_ratio = LockRatio::<T>::get().ok_or(Error::<T>::LockRatioNotSet)?;
+        let lock_num = vote_num.checked_mul(&lock_ratio).ok_or(Error::<T>::Overflow)?;
+        let lock_num = lock_num.checked_div(&100u32.into()).ok_or(Error::<T>::Overflow)?;
+        StakedNodeLockTotalNum::<T>::try_mutate(
+            &who,
+            |optional| -> Result<(), DispatchError> {
+                let new_lock_balance = match optional{
                     Some(lock_balance) => {
                         lock_balance.checked_add(&lock_num).ok_or(Error::<T>::Overflow)?
                     }
@@ -1350,29 +2103,6 @@ impl<T: Config> Pallet<T> {
                 }
             }
         )?;
-        NodeResultVotingStatus::<T>::try_mutate(
-            proposal_id,
-            &who,
-            |optional| -> Result<(), DispatchError> {
-                match optional {
-                    Some(_) => Err(Error::<T>::AccountHasAlreadyUploaded.into()),
-                    None => {
-                        *optional = Some((result, vote_num));
-                        Ok(())
-                    }
-                }
-            },
-        )?;
-        ResultVotingStatus::<T>::try_mutate(
-            proposal_id,
-            result,
-            |optional| -> Result<(), DispatchError> {
-                let mut sum = optional.unwrap_or_else(Zero::zero);
-                sum = sum.checked_add(&vote_num).ok_or(Error::<T>::Overflow)?;
-                *optional = Some(sum);
-                Ok(())
-            },
-        )?;
         Ok(())
     }
 
@@ -1402,20 +2132,7 @@ impl<T: Config> Pallet<T> {
                 }
             },
         )?;
-        let reward_num = match ReportSuccessFlag::<T>::get(proposal_id){
-            Some(_) => {
-                let base: BalanceOf<T> = 100u32.into();
-                let report_pool_num = FinalReportAssetPool::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
-                let total_report_num = ReportVotingStatus::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
-                let report_number = report_number.checked_mul(&base).ok_or(Error::<T>::Overflow)?;
-                let reward_ratio = report_number.checked_div(&total_report_num).ok_or(Error::<T>::Overflow)?;
-                let reward_num = report_pool_num.checked_mul(&reward_ratio).ok_or(Error::<T>::Overflow)?;
-                reward_num.checked_div(&base).ok_or(Error::<T>::Overflow)?
-            }
-            None => {
-                Zero::zero()
-            }
-        };
+        let reward_num = Self::report_reward(proposal_id, report_number)?;
         ReportAssetPool::<T>::try_mutate(
             proposal_id,
             |optional| -> Result<(), DispatchError> {
@@ -1435,6 +2152,28 @@ impl<T: Config> Pallet<T> {
         <TokensOf<T> as Tokens<T::AccountId>>::transfer(currency_id, &autonomy_account, who, reward_num)
     }
 
+    /// The share of the slashed votes paid to a successful report of `report_number`
+    fn report_reward(
+        proposal_id: T::ProposalId,
+        report_number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let reward_num = match ReportSuccessFlag::<T>::get(proposal_id){
+            Some(_) => {
+                let base: BalanceOf<T> = 100u32.into();
+                let report_pool_num = FinalReportAssetPool::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+                let total_report_num = ReportVotingStatus::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+                let report_number = report_number.checked_mul(&base).ok_or(Error::<T>::Overflow)?;
+                let reward_ratio = report_number.checked_div(&total_report_num).ok_or(Error::<T>::Overflow)?;
+                let reward_num = report_pool_num.checked_mul(&reward_ratio).ok_or(Error::<T>::Overflow)?;
+                reward_num.checked_div(&base).ok_or(Error::<T>::Overflow)?
+            }
+            None => {
+                Zero::zero()
+            }
+        };
+        Ok(reward_num)
+    }
+
     fn inner_unlock(
         who: &T::AccountId,
         proposal_id: T::ProposalId
@@ -1474,13 +2213,23 @@ impl<T: Config> Pallet<T> {
         Ok(lock_num)
     }
 
-    fn inner_swap_result(
+    /// The option with the most result votes besides `result`, the invalid result of the
+    /// proposal being one of the candidates
+    fn best_alternative(
         proposal_id: T::ProposalId,
-    ) -> Result<(), DispatchError> {
-        let (id1, id2) = T::CouplePool::proposal_pair(proposal_id)?;
-        let result = T::CouplePool::get_proposal_result(proposal_id)?;
-        T::CouplePool::set_proposal_result_when_end(proposal_id, [id1, id2][(result == id1) as usize])?;
-        Ok(())
+        result: CurrencyIdOf<T>,
+    ) -> Result<CurrencyIdOf<T>, DispatchError> {
+        let options = T::CouplePool::proposal_results(proposal_id)?;
+        let mut new_result = None;
+        let mut max_votes: BalanceOf<T> = Zero::zero();
+        for option in options.into_iter().filter(|option| *option != result) {
+            let votes = ResultVotingStatus::<T>::get(proposal_id, option).unwrap_or_else(Zero::zero);
+            if new_result.is_none() || votes > max_votes {
+                new_result = Some(option);
+                max_votes = votes;
+            }
+        }
+        new_result.ok_or_else(|| Error::<T>::ProposalOptionNotCorrect.into())
     }
 
     fn inner_report(
@@ -1508,43 +2257,663 @@ impl<T: Config> Pallet<T> {
                 }
             },
         )?;
-        ReportVotingStatus::<T>::try_mutate(
+        let total = ReportVotingStatus::<T>::get(proposal_id)
+            .unwrap_or_else(Zero::zero)
+            .checked_add(&report_num)
+            .ok_or(Error::<T>::Overflow)?;
+        ReportVotingStatus::<T>::insert(proposal_id, total);
+        <TokensOf<T> as Tokens<T::AccountId>>::reserve(currency_id, who, report_num)?;
+        if total >= minimal_report_number && ReportSuccessFlag::<T>::get(proposal_id).is_none() {
+            ReportSuccessFlag::<T>::insert(proposal_id, ());
+            Self::open_dispute(proposal_id, minimal_report_number)?;
+        }
+        Ok(())
+    }
+
+    /// Open the dispute of a successfully reported proposal, it first stands on the option with
+    /// the most result votes besides the announced result
+    fn open_dispute(
+        proposal_id: ProposalIdOf<T>,
+        minimal_report_number: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        let announced = T::CouplePool::get_proposal_result(proposal_id)?;
+        let outcome = Self::best_alternative(proposal_id, announced)?;
+        let bond = minimal_report_number
+            .checked_add(&minimal_report_number)
+            .ok_or(Error::<T>::Overflow)?;
+        let period = PublicityPeriod::<T>::get().unwrap_or_else(Zero::zero);
+        let end = <TimeOf<T> as Time>::now().saturating_add(period);
+        Disputes::<T>::insert(
+            proposal_id,
+            Dispute {
+                announced,
+                outcome,
+                round: 1,
+                bond,
+                end,
+            },
+        );
+        Self::schedule_deadline(proposal_id)?;
+        Self::deposit_event(Event::<T>::DisputeOpened(proposal_id, outcome, bond));
+        Ok(())
+    }
+
+    fn inner_dispute(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        option: CurrencyIdOf<T>,
+        bond: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        let dispute = Disputes::<T>::get(proposal_id).ok_or(Error::<T>::DisputeNotExist)?;
+        ensure!(
+            dispute.round <= T::MaxDisputeRounds::get(),
+            Error::<T>::DisputeIsEscalated
+        );
+        ensure!(
+            <TimeOf<T> as Time>::now() < dispute.end,
+            Error::<T>::DisputeRoundIsOver
+        );
+        Self::ensure_proposal_optional_id(proposal_id, option)?;
+        ensure!(
+            option != dispute.outcome,
+            Error::<T>::DisputeOutcomeNotChallenged
+        );
+        ensure!(bond >= dispute.bond, Error::<T>::DisputeBondTooLow);
+        DisputeBonds::<T>::try_mutate(proposal_id, who, |optional| -> Result<(), DispatchError> {
+            let (bonded_option, total) = optional.unwrap_or((option, Zero::zero()));
+            ensure!(bonded_option == option, Error::<T>::AttitudeNeedSame);
+            let total = total.checked_add(&bond).ok_or(Error::<T>::Overflow)?;
+            *optional = Some((option, total));
+            Ok(())
+        })?;
+        DisputeVotingStatus::<T>::try_mutate(
             proposal_id,
+            option,
             |optional| -> Result<(), DispatchError> {
-                match optional {
-                    Some(old_balance) =>{
-                        let new_balance = old_balance.checked_add(&report_num).ok_or(Error::<T>::Overflow)?;
-                        if new_balance >= minimal_report_number{
-                            ReportSuccessFlag::<T>::try_mutate(
-                                proposal_id,
-                                |optional| -> Result<(), DispatchError> {
-                                    match optional {
-                                        Some(_) => {
-                                        }
-                                        None =>{
-                                            *optional = Some(());
-                                        }
-                                    }
-                                    Ok(())
-                                },
-                            )?;
-                        }
-                        *optional = Some(new_balance);
-                    } ,
-                    None => {
-                        *optional = Some(report_num);
-                    }
-                }
+                let sum = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(sum.checked_add(&bond).ok_or(Error::<T>::Overflow)?);
                 Ok(())
             },
         )?;
-        <TokensOf<T> as Tokens<T::AccountId>>::reserve(currency_id, who, report_num)?;
+        DisputeRoundVotingStatus::<T>::try_mutate(
+            proposal_id,
+            option,
+            |optional| -> Result<(), DispatchError> {
+                let sum = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(sum.checked_add(&bond).ok_or(Error::<T>::Overflow)?);
+                Ok(())
+            },
+        )?;
+        let autonomy_account = Self::module_account();
+        <TokensOf<T> as Tokens<T::AccountId>>::transfer(
+            T::StakeCurrencyId::get(),
+            who,
+            &autonomy_account,
+            bond,
+        )?;
         Ok(())
     }
 
+    /// End the current round of a dispute, a round without bonds against the outcome settles
+    /// the dispute, otherwise the most bonded option becomes the outcome of the next round
+    fn close_dispute_round(
+        proposal_id: ProposalIdOf<T>,
+        mut dispute: Dispute<CurrencyIdOf<T>, BalanceOf<T>, MomentOf<T>>,
+        now: MomentOf<T>,
+    ) -> Result<(), DispatchError> {
+        let options = T::CouplePool::proposal_results(proposal_id)?;
+        let mut challenger = None;
+        let mut max_bond: BalanceOf<T> = Zero::zero();
+        for option in options {
+            let bond =
+                DisputeRoundVotingStatus::<T>::take(proposal_id, option).unwrap_or_else(Zero::zero);
+            if bond > max_bond {
+                challenger = Some(option);
+                max_bond = bond;
+            }
+        }
+        match challenger {
+            Some(outcome) => {
+                let period = PublicityPeriod::<T>::get().unwrap_or_else(Zero::zero);
+                dispute.outcome = outcome;
+                dispute.round = dispute.round.saturating_add(1);
+                dispute.bond = dispute
+                    .bond
+                    .checked_add(&dispute.bond)
+                    .ok_or(Error::<T>::Overflow)?;
+                dispute.end = now.saturating_add(period);
+                Self::deposit_event(Event::<T>::DisputeEscalated(
+                    proposal_id,
+                    dispute.round,
+                    outcome,
+                    dispute.bond,
+                ));
+                Disputes::<T>::insert(proposal_id, dispute);
+                Ok(())
+            }
+            None => Self::settle_dispute(proposal_id, dispute.outcome),
+        }
+    }
+
+    /// End the proposal on the final outcome of its dispute, the report failed if the outcome
+    /// is the announced result
+    fn settle_dispute(
+        proposal_id: ProposalIdOf<T>,
+        outcome: CurrencyIdOf<T>,
+    ) -> Result<(), DispatchError> {
+        let mut dispute = Disputes::<T>::get(proposal_id).ok_or(Error::<T>::DisputeNotExist)?;
+        T::Pool::set_proposal_state(proposal_id, ProposalStatus::End)?;
+        if outcome == dispute.announced {
+            ReportSuccessFlag::<T>::remove(proposal_id);
+        } else {
+            T::CouplePool::set_proposal_result_when_end(proposal_id, outcome)?;
+            for (who, (currency_id, _)) in NodeResultVotingStatus::<T>::iter_prefix(proposal_id) {
+                if currency_id == dispute.announced {
+                    PendingSlashes::<T>::insert(proposal_id, who, ());
+                }
+            }
+            SlashQueue::<T>::append(proposal_id);
+        }
+        dispute.outcome = outcome;
+        Disputes::<T>::insert(proposal_id, dispute);
+        Self::deposit_event(Event::<T>::DisputeSettled(proposal_id, outcome));
+        Ok(())
+    }
+
+    fn inner_claim_dispute_bond(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let dispute = Disputes::<T>::get(proposal_id).ok_or(Error::<T>::DisputeNotExist)?;
+        let (option, bond) =
+            DisputeBonds::<T>::take(proposal_id, who).ok_or(Error::<T>::DisputeBondNotExist)?;
+        let number = Self::dispute_payout(proposal_id, dispute.outcome, option, bond)?;
+        let autonomy_account = Self::module_account();
+        <TokensOf<T> as Tokens<T::AccountId>>::transfer(
+            T::StakeCurrencyId::get(),
+            &autonomy_account,
+            who,
+            number,
+        )
+    }
+
+    /// A bond on the final outcome of a dispute is paid back with its share of the bonds on the
+    /// other options, a bond on any other option is lost
+    ///
+    /// If no bond is on the outcome, which the dispute origin may settle on, nobody wins the
+    /// other bonds and each of them is paid back.
+    fn dispute_payout(
+        proposal_id: ProposalIdOf<T>,
+        outcome: CurrencyIdOf<T>,
+        option: CurrencyIdOf<T>,
+        bond: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let winning =
+            DisputeVotingStatus::<T>::get(proposal_id, outcome).unwrap_or_else(Zero::zero);
+        if winning.is_zero() {
+            return Ok(bond);
+        }
+        if option != outcome {
+            return Ok(Zero::zero());
+        }
+        let total = DisputeVotingStatus::<T>::iter_prefix_values(proposal_id)
+            .fold(Zero::zero(), |acc: BalanceOf<T>, number| {
+                acc.saturating_add(number)
+            });
+        let losing = total.saturating_sub(winning);
+        let share = bond
+            .checked_mul(&losing)
+            .ok_or(Error::<T>::Overflow)?
+            .checked_div(&winning)
+            .unwrap_or_else(Zero::zero);
+        bond.checked_add(&share)
+            .ok_or_else(|| Error::<T>::Overflow.into())
+    }
+
+    /// Upload the results of the proposals waiting for them, fetched from their result source,
+    /// with the local keys of the staked nodes that have not uploaded them yet
+    ///
+    /// The sources are not asked before the time the resolution of the proposal expects the
+    /// result at. The committed results are revealed by the nodes themselves, nothing is
+    /// uploaded while the results are committed.
+    fn offchain_upload_results() {
+        if Self::commit_reveal_enabled() {
+            return;
+        }
+        let nodes = Self::local_staked_nodes();
+        if nodes.is_empty() {
+            return;
+        }
+        for (proposal_id, source) in ResultSources::<T>::iter() {
+            if T::Pool::get_proposal_state(proposal_id) != Ok(ProposalStatus::WaitingForResults) {
+                continue;
+            }
+            let close_time = match T::Pool::proposal_close_time(proposal_id) {
+                Ok(close_time) => close_time,
+                Err(_) => continue,
+            };
+            let resolution_time = T::Pool::proposal_resolution(proposal_id)
+                .map(|resolution| resolution.time)
+                .unwrap_or(close_time);
+            if <TimeOf<T> as Time>::now() < resolution_time {
+                continue;
+            }
+            let pending: Vec<T::Public> = nodes
+                .iter()
+                .filter(|public| {
+                    let who = (*public).clone().into_account();
+                    !NodeResultVotingStatus::<T>::contains_key(proposal_id, &who)
+                        && !Self::inner_get_snapshot_usable_num(&who, close_time)
+                            .unwrap_or_else(|_| Zero::zero())
+                            .is_zero()
+                })
+                .cloned()
+                .collect();
+            if pending.is_empty() {
+                continue;
+            }
+            let result = match Self::fetch_result(proposal_id, &source) {
+                Ok(result) => result,
+                Err(e) => {
+                    debug::warn!("Failed to fetch the result of {:?}: {}", proposal_id, e);
+                    continue;
+                }
+            };
+            let sent = Signer::<T, T::AuthorityId>::all_accounts()
+                .with_filter(pending)
+                .send_unsigned_transaction(
+                    |account| Payload {
+                        proposal_id,
+                        result,
+                        public: account.public.clone(),
+                        vote_num: Self::inner_get_snapshot_usable_num(&account.id, close_time)
+                            .unwrap_or_else(|_| Zero::zero()),
+                    },
+                    |payload, signature| Call::upload_result(payload, signature),
+                );
+            for (account, result) in sent {
+                if result.is_err() {
+                    debug::warn!(
+                        "[{:?}] Failed to upload the result of {:?}",
+                        account.id,
+                        proposal_id
+                    );
+                }
+            }
+        }
+    }
+
+    /// The local keys of the offchain worker that belong to staked nodes
+    fn local_staked_nodes() -> Vec<T::Public> {
+        <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::all()
+            .into_iter()
+            .map(|key| {
+                let generic =
+                    <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic::from(
+                        key,
+                    );
+                generic.into()
+            })
+            .filter(|public: &T::Public| {
+                Self::ensure_staked_node(&public.clone().into_account()).is_ok()
+            })
+            .collect()
+    }
+
+    /// Fetch the value at the path of the result source and map it to an option of the proposal
+    fn fetch_result(
+        proposal_id: ProposalIdOf<T>,
+        source: &ResultSource,
+    ) -> Result<CurrencyIdOf<T>, &'static str> {
+        let url = str::from_utf8(&source.url).map_err(|_| "url is not utf8")?;
+        let deadline =
+            sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT_MILLIS));
+        let pending = http::Request::get(url)
+            .deadline(deadline)
+            .send()
+            .map_err(|_| "request failed")?;
+        let response = pending
+            .try_wait(deadline)
+            .map_err(|_| "deadline reached")?
+            .map_err(|_| "request failed")?;
+        if response.code != 200 {
+            return Err("unexpected status code");
+        }
+        let body = response.body().collect::<Vec<u8>>();
+        let value = Self::json_value(&body, &source.path).ok_or("result not found")?;
+        let (yes, no) =
+            T::CouplePool::proposal_pair(proposal_id).map_err(|_| "proposal not found")?;
+        if value == source.yes {
+            Ok(yes)
+        } else if value == source.no {
+            Ok(no)
+        } else {
+            Err("result not mapped to an option")
+        }
+    }
+
+    /// The text of the value at `path` in the json document, `None` if there is no such value
+    /// or it is an object, an array or a fractional number
+    fn json_value(body: &[u8], path: &[u8]) -> Option<Vec<u8>> {
+        let document = str::from_utf8(body).ok()?;
+        let mut value = lite_json::parse_json(document).ok()?;
+        for key in path.split(|c| *c == b'.').filter(|key| !key.is_empty()) {
+            value = match value {
+                JsonValue::Object(fields) => {
+                    let key = str::from_utf8(key).ok()?;
+                    fields
+                        .into_iter()
+                        .find(|(name, _)| name.iter().copied().eq(key.chars()))?
+                        .1
+                }
+                JsonValue::Array(items) => {
+                    let index: usize = str::from_utf8(key).ok()?.parse().ok()?;
+                    items.into_iter().nth(index)?
+                }
+                _ => return None,
+            };
+        }
+        let mut text = Vec::new();
+        match value {
+            JsonValue::String(chars) => {
+                for c in chars {
+                    text.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+            }
+            JsonValue::Number(number) if number.fraction == 0 && number.exponent == 0 => {
+                if number.negative {
+                    text.push(b'-');
+                }
+                let digits = number.integer;
+                let mut divisor = 1;
+                while digits / divisor >= 10 {
+                    divisor *= 10;
+                }
+                while divisor > 0 {
+                    text.push(b'0' + (digits / divisor % 10) as u8);
+                    divisor /= 10;
+                }
+            }
+            JsonValue::Boolean(true) => text.extend_from_slice(b"true"),
+            JsonValue::Boolean(false) => text.extend_from_slice(b"false"),
+            JsonValue::Null => text.extend_from_slice(b"null"),
+            _ => return None,
+        }
+        Some(text)
+    }
+
+    /// Remove at most `limit` storage entries of a settled proposal, the votes still locked for
+    /// it and the stakes of the reports not taken out are released to their owners, the dispute
+    /// bonds not claimed are paid out
+    fn inner_archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32 {
+        let currency_id = T::StakeCurrencyId::get();
+        let mut removed: u32 = 0;
+        for (who, lock_num) in
+            StakedNodeLockNum::<T>::drain_prefix(proposal_id).take(limit as usize)
+        {
+            StakedNodeLockTotalNum::<T>::mutate(&who, |optional| {
+                if let Some(lock_balance) = optional {
+                    *lock_balance = lock_balance.saturating_sub(lock_num);
+                }
+            });
+            removed += 1;
+        }
+        for (who, report_number) in
+            AccountReportNumber::<T>::drain_prefix(proposal_id).take((limit - removed) as usize)
+        {
+            if let Err(e) =
+                <TokensOf<T> as Tokens<T::AccountId>>::unreserve(currency_id, &who, report_number)
+            {
+                sp_runtime::print(e);
+            }
+            let paid = Self::report_reward(proposal_id, report_number).and_then(|number| {
+                <TokensOf<T> as Tokens<T::AccountId>>::transfer(
+                    currency_id,
+                    &Self::module_account(),
+                    &who,
+                    number,
+                )
+            });
+            if let Err(e) = paid {
+                sp_runtime::print(e);
+            }
+            removed += 1;
+        }
+        if let Some(dispute) = Disputes::<T>::get(proposal_id) {
+            for (who, (option, bond)) in
+                DisputeBonds::<T>::drain_prefix(proposal_id).take((limit - removed) as usize)
+            {
+                let paid = Self::dispute_payout(proposal_id, dispute.outcome, option, bond)
+                    .and_then(|number| {
+                        <TokensOf<T> as Tokens<T::AccountId>>::transfer(
+                            currency_id,
+                            &Self::module_account(),
+                            &who,
+                            number,
+                        )
+                    });
+                if let Err(e) = paid {
+                    sp_runtime::print(e);
+                }
+                removed += 1;
+            }
+        }
+        removed += DisputeVotingStatus::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += DisputeRoundVotingStatus::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += NodeReviewVotingStatus::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += ReviewVotingStatus::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += NodeResultVotingStatus::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += NodeScalarResultVotingStatus::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += ResultCommitments::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += ResultVotingStatus::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += AccountSlashNumber::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += PendingSlashes::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        if removed < limit {
+            ReviewEqualFlag::<T>::remove(proposal_id);
+            ReviewFlag::<T>::remove(proposal_id);
+            ReportAssetPool::<T>::remove(proposal_id);
+            FinalReportAssetPool::<T>::remove(proposal_id);
+            ReportSuccessFlag::<T>::remove(proposal_id);
+            SlashFinishFlag::<T>::remove(proposal_id);
+            ReportVotingStatus::<T>::remove(proposal_id);
+            Disputes::<T>::remove(proposal_id);
+            ReviewDelay::<T>::remove(proposal_id);
+            UploadDelay::<T>::remove(proposal_id);
+            ReportDelay::<T>::remove(proposal_id);
+            ResultAnnouncementTime::<T>::remove(proposal_id);
+            RevealEnd::<T>::remove(proposal_id);
+            ProposalRevealPeriod::<T>::remove(proposal_id);
+            ResultSources::<T>::remove(proposal_id);
+            ProposalDeadline::<T>::remove(proposal_id);
+            ProposalRetries::<T>::remove(proposal_id);
+        }
+        removed
+    }
 }
 
 /// impl `ValidateUnsigned` trait with valudate unsigned transaction
+impl<T: Config> DeadlineScheduler for Pallet<T> {
+    type ProposalId = ProposalIdOf<T>;
+    type Moment = MomentOf<T>;
+    type Time = TimeOf<T>;
+    type BucketSize = T::DeadlineBucket;
+    type Deadlines = ProposalDeadline<T>;
+    type Buckets = DeadlineQueue<T>;
+    type NextBucket = NextDeadlineBucket<T>;
+    type Retries = ProposalRetries<T>;
+
+    /// The next time `change_state` can act on the proposal, `None` if it never will
+    fn next_deadline(index: ProposalIdOf<T>) -> Result<Option<MomentOf<T>>, DispatchError> {
+        let state = T::Pool::get_proposal_state(index)?;
+        let close_time = T::Pool::proposal_close_time(index)?;
+        let deadline = match state {
+            ProposalStatus::OriginalPrediction => {
+                let create_time = T::Pool::proposal_create_time(index)?;
+                let review_time = ReviewCycle::<T>::get().unwrap_or_else(Zero::zero);
+                let delay_num = ReviewDelay::<T>::get(index).unwrap_or_else(Zero::zero);
+                let delay = delay_num
+                    .saturating_add(One::one())
+                    .saturating_mul(review_time);
+                let close_deadline = close_time.saturating_add(One::one());
+                Some(cmp::min(create_time.saturating_add(delay), close_deadline))
+            }
+            ProposalStatus::WaitingForResults if RevealEnd::<T>::contains_key(index) => {
+                RevealEnd::<T>::get(index)
+            }
+            ProposalStatus::FormalPrediction | ProposalStatus::WaitingForResults => {
+                let upload_time = ResultUploadCycle::<T>::get().unwrap_or_else(Zero::zero);
+                let delay_num = UploadDelay::<T>::get(index).unwrap_or_else(Zero::zero);
+                let delay = delay_num
+                    .saturating_add(One::one())
+                    .saturating_mul(upload_time);
+                Some(close_time.saturating_add(delay))
+            }
+            ProposalStatus::ResultAnnouncement => match Disputes::<T>::get(index) {
+                Some(dispute) if dispute.round > T::MaxDisputeRounds::get() => None,
+                Some(dispute) => Some(dispute.end),
+                None => {
+                    let report_time = PublicityPeriod::<T>::get().unwrap_or_else(Zero::zero);
+                    ResultAnnouncementTime::<T>::get(index)
+                        .map(|time| time.saturating_add(report_time))
+                }
+            },
+            _ => None,
+        };
+        Ok(deadline)
+    }
+
+    fn change_state(
+        index: ProposalIdOf<T>,
+        now: MomentOf<T>,
+    ) -> Result<(), DispatchError> {
+        let state = T::Pool::get_proposal_state(index)?;
+        let create_time = T::Pool::proposal_create_time(index)?;
+        let close_time = T::Pool::proposal_close_time(index)?;
+        match state{
+            ProposalStatus::OriginalPrediction => {
+                let review_time = ReviewCycle::<T>::get().unwrap_or_else(Zero::zero);
+                let diff = now.checked_sub(&create_time).unwrap_or_else(Zero::zero);
+                let delay_num = ReviewDelay::<T>::get(index).unwrap_or_else(Zero::zero);
+                let delay = delay_num.checked_mul(&review_time).ok_or(Error::<T>::Overflow)?;
+                let delay = delay.checked_add(&review_time).ok_or(Error::<T>::Overflow)?;
+                if now > close_time {
+                    T::Pool::set_proposal_state(index, ProposalStatus::End)?;
+                }
+                if diff >= delay{
+                    if ReviewEqualFlag::<T>::get(index).is_some(){
+                            let new_v = delay_num.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
+                            ReviewDelay::<T>::insert(index, new_v);
+                    }
+                    else{
+                        match ReviewFlag::<T>::get(index) {
+                                Some(_) =>{
+                                    T::Pool::set_proposal_state(index, ProposalStatus::FormalPrediction)?;
+                                }
+                                None =>{
+                                    T::Pool::set_proposal_state(index, ProposalStatus::End)?;
+                                }
+                           
+                                
+                            }
+                    }
+                }
+            }
+            ProposalStatus::WaitingForResults => {
+                let upload_time = ResultUploadCycle::<T>::get().unwrap_or_else(Zero::zero);
+                let diff = now.checked_sub(&close_time).unwrap_or_else(Zero::zero);
+                let delay_num = UploadDelay::<T>::get(index).unwrap_or_else(Zero::zero);
+                let delay = delay_num.checked_mul(&upload_time).ok_or(Error::<T>::Overflow)?;
+                let delay = delay.checked_add(&upload_time).ok_or(Error::<T>::Overflow)?;
+                if diff >= delay{
+                    match RevealEnd::<T>::get(index) {
+                        Some(end) if now < end => return Ok(()),
+                        Some(_) => {
+                            RevealEnd::<T>::remove(index);
+                            ProposalRevealPeriod::<T>::remove(index);
+                            Self::penalise_unrevealed(index)?;
+                        }
+                        None if ResultCommitments::<T>::iter_prefix(index).next().is_some() => {
+                            let reveal_period =
+                                ProposalRevealPeriod::<T>::get(index).unwrap_or_else(Zero::zero);
+                            RevealEnd::<T>::insert(index, now.saturating_add(reveal_period));
+                            return Ok(());
+                        }
+                        None => {}
+                    }
+                    let announced = match T::CouplePool::proposal_scalar_range(index) {
+                        Some(_) => match Self::weighted_median_value(index) {
+                            Some(value) => {
+                                T::CouplePool::set_proposal_scalar_result(index, value)?;
+                                true
+                            }
+                            None => false,
+                        },
+                        None => {
+                            let options = T::CouplePool::proposal_results(index)?;
+                            match Self::most_voted_option(index, &options) {
+                                Some(result) => {
+                                    T::CouplePool::set_proposal_result(index, result)?;
+                                    true
+                                }
+                                None => false,
+                            }
+                        }
+                    };
+                    if announced {
+                        T::Pool::set_proposal_state(index, ProposalStatus::ResultAnnouncement)?;
+                        ResultAnnouncementTime::<T>::insert(index, now);
+                    } else {
+                        let new_v = delay_num.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
+                        UploadDelay::<T>::insert(index, new_v);
+                    }
+                }
+            }
+            ProposalStatus::ResultAnnouncement => match Disputes::<T>::get(index) {
+                Some(dispute) => {
+                    if dispute.round <= T::MaxDisputeRounds::get() && now >= dispute.end {
+                        Self::close_dispute_round(index, dispute, now)?;
+                    }
+                }
+                None => {
+                    let report_time = PublicityPeriod::<T>::get().unwrap_or_else(Zero::zero);
+                    let announcement_time = ResultAnnouncementTime::<T>::get(index)
+                        .ok_or(Error::<T>::ProposalHasNotEnteredThePublicityPeriod)?;
+                    let diff = now
+                        .checked_sub(&announcement_time)
+                        .unwrap_or_else(Zero::zero);
+                    if diff >= report_time {
+                        T::Pool::set_proposal_state(index, ProposalStatus::End)?;
+                    }
+                }
+            },
+            _ => {
+
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
     type Call = Call<T>;
 
@@ -1554,10 +2923,10 @@ impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
     /// here we make sure that some particular calls (the ones produced by offchain worker)
     /// are being whitelisted and marked as valid.
     fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-        let valid_tx = |provide| {
+        let valid_tx = |call: &[u8], proposal_id: ProposalIdOf<T>, public: &T::Public| {
             ValidTransaction::with_tag_prefix("autonomy")
                 .priority(UNSIGNED_TXS_PRIORITY)
-                .and_provides([&provide])
+                .and_provides((call, proposal_id, public))
                 .longevity(3)
                 .propagate(true)
                 .build()
@@ -1568,7 +2937,29 @@ impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
                 if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
                     return InvalidTransaction::BadProof.into();
                 }
-                valid_tx(b"upload_result".to_vec())
+                valid_tx(b"upload_result", payload.proposal_id, &payload.public)
+            }
+            Call::upload_scalar_result(ref payload, ref signature) => {
+                if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+                    return InvalidTransaction::BadProof.into();
+                }
+                valid_tx(
+                    b"upload_scalar_result",
+                    payload.proposal_id,
+                    &payload.public,
+                )
+            }
+            Call::commit_result(ref payload, ref signature) => {
+                if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+                    return InvalidTransaction::BadProof.into();
+                }
+                valid_tx(b"commit_result", payload.proposal_id, &payload.public)
+            }
+            Call::reveal_result(ref payload, ref signature) => {
+                if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+                    return InvalidTransaction::BadProof.into();
+                }
+                valid_tx(b"reveal_result", payload.proposal_id, &payload.public)
             }
             _ => InvalidTransaction::Call.into(),
         }
@@ -1597,3 +2988,15 @@ impl<T: Config> Autonomy<T> for Pallet<T> {
         ResultVotingStatus::<T>::get(proposal_id, currency_id).unwrap_or_else(Zero::zero)
     }
 }
+
+impl<T: Config> ProposalArchive<T> for Pallet<T> {
+    /// A successful report leaves the proposal to the slashes until they are finished
+    fn is_drained(proposal_id: ProposalIdOf<T>) -> bool {
+        ReportSuccessFlag::<T>::get(proposal_id).is_none()
+            || SlashFinishFlag::<T>::get(proposal_id).is_some()
+    }
+
+    fn archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32 {
+        Self::inner_archive(proposal_id, limit)
+    }
+}
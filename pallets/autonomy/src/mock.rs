@@ -9,19 +9,28 @@ use frame_support::{
 use frame_system::{
     limits, mocking,
     offchain::{SendTransactionTypes, SigningTypes},
+    EnsureRoot,
 };
+use parking_lot::RwLock;
 use sp_core::{
+    offchain::{
+        testing::{OffchainState, PoolState, TestOffchainExt, TestTransactionPoolExt},
+        OffchainExt, TransactionPoolExt,
+    },
     sr25519::{self, Signature},
     H256,
 };
-use sp_keystore::{testing::KeyStore, SyncCryptoStore};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
 use sp_runtime::{
     testing::{Header, TestXt},
     traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
     ModuleId,
 };
-use std::{cell::RefCell, collections::HashMap};
-use xpmrl_traits::{pool::LiquidityPool, system::ProposalSystem, tokens::Tokens};
+use std::{cell::RefCell, collections::HashMap, sync::Arc};
+use xpmrl_traits::{
+    couple::INVALID_RESULT_ID, pool::LiquidityPool, system::ProposalSystem, tokens::Tokens,
+    Resolution,
+};
 
 pub type Extrinsic = TestXt<Call, ()>;
 type UncheckedExtrinsic = mocking::MockUncheckedExtrinsic<Test>;
@@ -114,6 +123,10 @@ impl xpmrl_tokens::Config for Test {
     type Currency = PalletBalances;
     type NativeCurrencyId = NativeCurrencyId;
     type ModuleId = TokensModuleId;
+    type BeforeTransfer = ();
+    type PermitSignature = Signature;
+    type PermitSigner = Public;
+    type WeightInfo = ();
 }
 
 type ProposalId = u32;
@@ -158,13 +171,17 @@ pub struct ProposalsWrapper {
     pub interval_time: MomentOf<Test>,
     pub used_currency_id: HashMap<CurrencyIdOf<Test>, ()>,
     pub announcement_time: HashMap<ProposalIdOf<Test>, MomentOf<Test>>,
+    pub resolution: HashMap<ProposalIdOf<Test>, Resolution<MomentOf<Test>>>,
     pub create_time: HashMap<ProposalIdOf<Test>, MomentOf<Test>>,
     pub close_time: HashMap<ProposalIdOf<Test>, MomentOf<Test>>,
     pub proposal_state: HashMap<ProposalIdOf<Test>, ProposalStatus>,
     pub proposal_owner: HashMap<ProposalIdOf<Test>, AccountId>,
+    pub version_id: HashMap<ProposalIdOf<Test>, VersionIdOf<Test>>,
     pub proposal_pair: HashMap<ProposalIdOf<Test>, (CurrencyIdOf<Test>, CurrencyIdOf<Test>)>,
     pub proposal_result: HashMap<ProposalIdOf<Test>, CurrencyIdOf<Test>>,
     pub proposal_lp: HashMap<ProposalIdOf<Test>, CurrencyIdOf<Test>>,
+    pub scalar_range: HashMap<ProposalIdOf<Test>, (BalanceOf<Test>, BalanceOf<Test>)>,
+    pub scalar_result: HashMap<ProposalIdOf<Test>, BalanceOf<Test>>,
 }
 
 impl ProposalsWrapper {
@@ -174,14 +191,18 @@ impl ProposalsWrapper {
             interval_time: 5,
             used_currency_id: HashMap::<CurrencyIdOf<Test>, ()>::new(),
             announcement_time: HashMap::<ProposalIdOf<Test>, MomentOf<Test>>::new(),
+            resolution: HashMap::<ProposalIdOf<Test>, Resolution<MomentOf<Test>>>::new(),
             create_time: HashMap::<ProposalIdOf<Test>, MomentOf<Test>>::new(),
             close_time: HashMap::<ProposalIdOf<Test>, MomentOf<Test>>::new(),
             proposal_state: HashMap::<ProposalIdOf<Test>, ProposalStatus>::new(),
             proposal_owner: HashMap::<ProposalIdOf<Test>, AccountId>::new(),
+            version_id: HashMap::<ProposalIdOf<Test>, VersionIdOf<Test>>::new(),
             proposal_result: HashMap::<ProposalIdOf<Test>, CurrencyIdOf<Test>>::new(),
             proposal_pair:
                 HashMap::<ProposalIdOf<Test>, (CurrencyIdOf<Test>, CurrencyIdOf<Test>)>::new(),
             proposal_lp: HashMap::<ProposalIdOf<Test>, CurrencyIdOf<Test>>::new(),
+            scalar_range: HashMap::<ProposalIdOf<Test>, (BalanceOf<Test>, BalanceOf<Test>)>::new(),
+            scalar_result: HashMap::<ProposalIdOf<Test>, BalanceOf<Test>>::new(),
         }
     }
 }
@@ -215,6 +236,18 @@ impl Proposals {
         })
     }
 
+    pub fn remove_pair(proposal_id: ProposalIdOf<Test>) {
+        PROPOSALS_WRAPPER.with(|wrapper| {
+            wrapper.borrow_mut().proposal_pair.remove(&proposal_id);
+        })
+    }
+
+    pub fn remove_state(proposal_id: ProposalIdOf<Test>) {
+        PROPOSALS_WRAPPER.with(|wrapper| {
+            wrapper.borrow_mut().proposal_state.remove(&proposal_id);
+        })
+    }
+
     pub fn new_couple_proposal(
         who: AccountId,
         currency_id: CurrencyIdOf<Test>,
@@ -255,6 +288,24 @@ impl Proposals {
             Ok(())
         })
     }
+
+    pub fn new_scalar_proposal(
+        who: AccountId,
+        currency_id: CurrencyIdOf<Test>,
+        min: BalanceOf<Test>,
+        max: BalanceOf<Test>,
+    ) -> Result<(), DispatchError> {
+        let id = PROPOSALS_WRAPPER.with(|wrapper| wrapper.borrow().next_proposal_id);
+        Self::new_couple_proposal(who, currency_id)?;
+        PROPOSALS_WRAPPER.with(|wrapper| {
+            wrapper.borrow_mut().scalar_range.insert(id, (min, max));
+        });
+        Ok(())
+    }
+
+    pub fn scalar_result(proposal_id: ProposalIdOf<Test>) -> Option<BalanceOf<Test>> {
+        PROPOSALS_WRAPPER.with(|wrapper| wrapper.borrow().scalar_result.get(&proposal_id).copied())
+    }
 }
 
 impl LiquidityPool<Test> for Proposals {
@@ -283,9 +334,10 @@ impl LiquidityPool<Test> for Proposals {
         state: ProposalStatus,
         _create_time: MomentOf<Test>,
         _close_time: MomentOf<Test>,
-        _version: VersionIdOf<Test>,
+        version: VersionIdOf<Test>,
     ) {
         PROPOSALS_WRAPPER.with(|wrapper| -> () {
+            wrapper.borrow_mut().version_id.insert(proposal_id, version);
             wrapper
                 .borrow_mut()
                 .proposal_owner
@@ -370,6 +422,17 @@ impl LiquidityPool<Test> for Proposals {
         })
     }
 
+    fn proposal_liquidate_version_id(
+        proposal_id: ProposalIdOf<Test>,
+    ) -> Result<VersionIdOf<Test>, DispatchError> {
+        PROPOSALS_WRAPPER.with(|wrapper| -> Result<VersionIdOf<Test>, DispatchError> {
+            match wrapper.borrow().version_id.get(&proposal_id) {
+                Some(v) => Ok(*v),
+                None => Err("ProposalIdNotExist".into()),
+            }
+        })
+    }
+
     fn proposal_announcement_time(
         proposal_id: ProposalIdOf<Test>,
     ) -> Result<MomentOf<Test>, DispatchError> {
@@ -380,6 +443,32 @@ impl LiquidityPool<Test> for Proposals {
             }
         })
     }
+
+    fn set_proposal_resolution(
+        proposal_id: ProposalIdOf<Test>,
+        resolution: Resolution<MomentOf<Test>>,
+    ) -> Result<(), DispatchError> {
+        PROPOSALS_WRAPPER.with(|wrapper| -> Result<(), DispatchError> {
+            wrapper
+                .borrow_mut()
+                .resolution
+                .insert(proposal_id, resolution);
+            Ok(())
+        })
+    }
+
+    fn proposal_resolution(
+        proposal_id: ProposalIdOf<Test>,
+    ) -> Result<Resolution<MomentOf<Test>>, DispatchError> {
+        PROPOSALS_WRAPPER.with(
+            |wrapper| -> Result<Resolution<MomentOf<Test>>, DispatchError> {
+                match wrapper.borrow().resolution.get(&proposal_id) {
+                    Some(v) => Ok(v.clone()),
+                    None => Err("ProposalIdNotExist".into()),
+                }
+            },
+        )
+    }
 }
 
 impl LiquidityCouple<Test> for Proposals {
@@ -396,6 +485,18 @@ impl LiquidityCouple<Test> for Proposals {
         )
     }
 
+    fn proposal_invalid_result(proposal_id: ProposalIdOf<Test>) -> Option<CurrencyIdOf<Test>> {
+        PROPOSALS_WRAPPER.with(|wrapper| {
+            let wrapper = wrapper.borrow();
+            if !wrapper.proposal_pair.contains_key(&proposal_id)
+                || wrapper.scalar_range.contains_key(&proposal_id)
+            {
+                return None;
+            }
+            Some(INVALID_RESULT_ID)
+        })
+    }
+
     fn set_proposal_result(
         proposal_id: ProposalIdOf<Test>,
         result: CurrencyIdOf<Test>,
@@ -451,11 +552,64 @@ impl LiquidityCouple<Test> for Proposals {
             }
         })
     }
+
+    fn proposal_scalar_range(
+        proposal_id: ProposalIdOf<Test>,
+    ) -> Option<(BalanceOf<Test>, BalanceOf<Test>)> {
+        PROPOSALS_WRAPPER.with(|wrapper| wrapper.borrow().scalar_range.get(&proposal_id).copied())
+    }
+
+    fn set_proposal_scalar_result(
+        proposal_id: ProposalIdOf<Test>,
+        value: BalanceOf<Test>,
+    ) -> Result<(), DispatchError> {
+        PROPOSALS_WRAPPER.with(|wrapper| -> Result<(), DispatchError> {
+            wrapper
+                .borrow_mut()
+                .scalar_result
+                .insert(proposal_id, value);
+            wrapper
+                .borrow_mut()
+                .proposal_state
+                .insert(proposal_id, ProposalStatus::ResultAnnouncement);
+            Ok(())
+        })
+    }
+
+    fn get_proposal_scalar_result(
+        proposal_id: ProposalIdOf<Test>,
+    ) -> Result<BalanceOf<Test>, DispatchError> {
+        PROPOSALS_WRAPPER.with(|wrapper| -> Result<BalanceOf<Test>, DispatchError> {
+            match wrapper.borrow().scalar_result.get(&proposal_id) {
+                Some(val) => Ok(*val),
+                None => Err("ProposalIdNotExist".into()),
+            }
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn new_benchmark_proposal(
+        who: &AccountId,
+        scalar: bool,
+    ) -> Result<ProposalIdOf<Test>, DispatchError> {
+        let id = PROPOSALS_WRAPPER.with(|wrapper| wrapper.borrow().next_proposal_id);
+        if scalar {
+            Self::new_scalar_proposal(*who, 1, 0, 1000)?;
+        } else {
+            Self::new_couple_proposal(*who, 1)?;
+        }
+        let now = Timestamp::now();
+        Self::set_create_time(id, now)?;
+        Self::set_close_time(id, now + 100)?;
+        Ok(id)
+    }
 }
 
 parameter_types! {
     pub const StakeCurrencyId: CurrencyId = 1;
     pub const AutonomyId: ModuleId = ModuleId(*b"xpgovern");
+    pub const DeadlineBucket: MomentOf<Test> = 1;
+    pub const MaxDisputeRounds: u32 = 2;
 }
 
 impl autonomy::Config for Test {
@@ -466,6 +620,10 @@ impl autonomy::Config for Test {
     type Pool = Proposals;
     type CouplePool = Proposals;
     type AutonomyId = AutonomyId;
+    type DeadlineBucket = DeadlineBucket;
+    type DisputeOrigin = EnsureRoot<AccountId>;
+    type MaxDisputeRounds = MaxDisputeRounds;
+    type WeightInfo = ();
 }
 
 pub fn run_to_block<Module: Hooks<BlockNumber>>(n: BlockNumber) {
@@ -482,6 +640,25 @@ pub fn new_test_ext<F>(f: F) -> ()
 where
     F: FnOnce(Vec<Public>) -> (),
 {
+    let (mut ext, public_key_array) = build_test_ext();
+    ext.execute_with(|| f(public_key_array))
+}
+
+/// Like `new_test_ext`, the test also gets the state of the offchain worker to mock the http
+/// responses and the pool the transactions of the offchain worker are submitted to
+pub fn new_offchain_test_ext<F>(f: F) -> ()
+where
+    F: FnOnce(Vec<Public>, Arc<RwLock<OffchainState>>, Arc<RwLock<PoolState>>) -> (),
+{
+    let (mut ext, public_key_array) = build_test_ext();
+    let (offchain, offchain_state) = TestOffchainExt::new();
+    let (pool, pool_state) = TestTransactionPoolExt::new();
+    ext.register_extension(OffchainExt::new(offchain));
+    ext.register_extension(TransactionPoolExt::new(pool));
+    ext.execute_with(|| f(public_key_array, offchain_state, pool_state))
+}
+
+fn build_test_ext() -> (sp_io::TestExternalities, Vec<Public>) {
     // Initialize key store
     let keystore = KeyStore::new();
     keystore.sr25519_generate_new(KEY_TYPE, None).unwrap();
@@ -523,7 +700,8 @@ where
     autonomy_genesis.assimilate_storage(&mut t).unwrap();
 
     let mut ext = sp_io::TestExternalities::new(t);
+    ext.register_extension(KeystoreExt(Arc::new(keystore)));
 
     ext.execute_with(|| System::set_block_number(1));
-    ext.execute_with(|| f(public_key_array))
+    (ext, public_key_array)
 }
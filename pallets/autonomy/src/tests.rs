@@ -1,8 +1,14 @@
-use crate::{mock::*, Error, Payload};
+use crate::{
+    crypto, mock::*, AccountSlashNumber, CommitPayload, Dispute, Error, Payload, ResultSource,
+    RevealPayload, ScalarPayload, SlashQueue, MAX_RESULT_SOURCE_LENGTH,
+};
 
-use frame_support::{assert_noop, assert_ok};
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use frame_system::offchain::SignedPayload;
+use sp_core::offchain::testing::{OffchainState, PendingRequest};
 use sp_std::collections::btree_map::BTreeMap;
-use xpmrl_traits::{couple::LiquidityCouple, pool::LiquidityPool, ProposalStatus, tokens::Tokens,};
+use xpmrl_traits::{couple::{LiquidityCouple, INVALID_RESULT_ID}, pool::{LiquidityPool, ProposalArchive}, ProposalStatus, Resolution, tokens::Tokens,};
 
 #[test]
 fn test_set_minimal_number() {
@@ -63,6 +69,14 @@ fn test_set_time_period() {
         let event = Event::autonomy(crate::Event::SetPublicityPeriod(publicity_period));
         assert!(System::events().iter().any(|record| record.event == event));
         assert_eq!(AutonomyModule::publicity_period(), Some(publicity_period));
+        let reveal_period: MomentOf<Test> = 100;
+        assert_ok!(AutonomyModule::set_reveal_period(
+            Origin::root(),
+            reveal_period
+        ));
+        let event = Event::autonomy(crate::Event::SetRevealPeriod(reveal_period));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(AutonomyModule::reveal_period(), Some(reveal_period));
     })
 }
 
@@ -180,6 +194,7 @@ fn test_review() {
         run_to_block::<AutonomyModule>(now + review_time + 1);
         assert_eq!(<Proposals as LiquidityPool<Test>>::get_proposal_state(1),
             Ok(ProposalStatus::End));
+        assert_eq!(AutonomyModule::proposal_deadline(1), None);
 
         assert_ok!(Proposals::new_couple_proposal(*account, 2));
         let now = System::block_number();
@@ -195,6 +210,8 @@ fn test_review() {
         assert_eq!(<Proposals as LiquidityPool<Test>>::get_proposal_state(2),
             Ok(ProposalStatus::OriginalPrediction));    
         assert_eq!(AutonomyModule::review_delay(2), Some(1));
+        assert_eq!(AutonomyModule::proposal_deadline(2), Some(now + review_time + review_time));
+        assert_eq!(AutonomyModule::next_scheduled_proposal_id(), Some(3));
         assert_ok!( AutonomyModule::review(Origin::signed(*account), 200, 2, true) );
         run_to_block::<AutonomyModule>(now + review_time + review_time);
         assert_eq!(<Proposals as LiquidityPool<Test>>::get_proposal_state(2),
@@ -202,6 +219,79 @@ fn test_review() {
     })
 }
 
+#[test]
+fn test_failed_state_change_backs_off() {
+    new_test_ext(|public_key_array| {
+        let account = public_key_array.get(0).unwrap();
+        let upload_cycle: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_upload_cycle(
+            Origin::root(),
+            upload_cycle
+        ));
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        let close_time: MomentOf<Test> = 10;
+        assert_ok!(Proposals::set_create_time(0, System::block_number()));
+        assert_ok!(Proposals::set_close_time(0, close_time));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        // the options of the proposal can not be read, so its result can not be announced
+        Proposals::remove_pair(0);
+        let deadline = close_time + upload_cycle;
+        run_to_block::<AutonomyModule>(deadline);
+        assert_eq!(AutonomyModule::proposal_retries(0), Some(1));
+        assert_eq!(AutonomyModule::proposal_deadline(0), Some(deadline + 1));
+        run_to_block::<AutonomyModule>(deadline + 1);
+        assert_eq!(AutonomyModule::proposal_retries(0), Some(2));
+        assert_eq!(AutonomyModule::proposal_deadline(0), Some(deadline + 3));
+        run_to_block::<AutonomyModule>(deadline + 2);
+        assert_eq!(AutonomyModule::proposal_retries(0), Some(2));
+
+        // a proposal that no longer exists leaves the queue
+        Proposals::remove_state(0);
+        run_to_block::<AutonomyModule>(deadline + 3);
+        assert_eq!(AutonomyModule::proposal_retries(0), None);
+        assert_eq!(AutonomyModule::proposal_deadline(0), None);
+    })
+}
+
+#[test]
+fn test_cycle_change_reschedules() {
+    new_test_ext(|public_key_array| {
+        let account = public_key_array.get(0).unwrap();
+        let upload_cycle: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_upload_cycle(
+            Origin::root(),
+            upload_cycle
+        ));
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        let close_time: MomentOf<Test> = 10;
+        assert_ok!(Proposals::set_create_time(0, System::block_number()));
+        assert_ok!(Proposals::set_close_time(0, close_time));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        run_to_block::<AutonomyModule>(System::block_number() + 1);
+        assert_eq!(AutonomyModule::proposal_deadline(0), Some(close_time + upload_cycle));
+
+        assert_ok!(AutonomyModule::set_upload_cycle(
+            Origin::root(),
+            upload_cycle * 2
+        ));
+        assert_eq!(AutonomyModule::rescheduled_proposals(), Some((0, 1)));
+        run_to_block::<AutonomyModule>(System::block_number() + 1);
+        assert_eq!(AutonomyModule::rescheduled_proposals(), None);
+        assert_eq!(AutonomyModule::proposal_deadline(0), Some(close_time + upload_cycle * 2));
+
+        run_to_block::<AutonomyModule>(close_time + upload_cycle);
+        assert_eq!(<Proposals as LiquidityPool<Test>>::get_proposal_state(0),
+            Ok(ProposalStatus::WaitingForResults));
+        assert_eq!(AutonomyModule::upload_delay(0), None);
+    })
+}
+
 #[test]
 fn test_upload_result() {
     new_test_ext(|public_key_array| {
@@ -220,7 +310,7 @@ fn test_upload_result() {
         ));
         let mut payload = Payload {
             proposal_id: 0,
-            result: 3,
+            result: 6,
             public: *account,
             vote_num: 100
         };
@@ -328,6 +418,301 @@ fn test_upload_result() {
     })
 }
 
+#[test]
+fn test_upload_invalid_result() {
+    new_test_ext(|public_key_array| {
+        let account = public_key_array.get(0).unwrap();
+        let other = public_key_array.get(1).unwrap();
+        let upload_cycle: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_upload_cycle(
+            Origin::root(),
+            upload_cycle
+        ));
+        assert_ok!(AutonomyModule::set_lock_ratio(Origin::root(), 10));
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        let close_time: MomentOf<Test> = 10;
+        assert_ok!(Proposals::set_create_time(0, System::block_number()));
+        assert_ok!(Proposals::set_close_time(0, close_time));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        let stake_number: BalanceOf<Test> = 2000;
+        assert_ok!(AutonomyModule::stake(
+            Origin::signed(*account),
+            stake_number
+        ));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*other), stake_number));
+
+        // the reserved id stands for the invalid result of the proposal
+        let invalid = <Proposals as LiquidityCouple<Test>>::proposal_invalid_result(0).unwrap();
+        assert_eq!(invalid, INVALID_RESULT_ID);
+        let payload = Payload {
+            proposal_id: 0,
+            result: invalid,
+            public: *account,
+            vote_num: 100,
+        };
+        let payload1 = Payload {
+            proposal_id: 0,
+            result: 4,
+            public: *other,
+            vote_num: 50,
+        };
+        assert_ok!(AutonomyModule::upload_result(
+            Origin::none(),
+            payload,
+            Default::default()
+        ));
+        assert_ok!(AutonomyModule::upload_result(
+            Origin::none(),
+            payload1,
+            Default::default()
+        ));
+        assert_eq!(AutonomyModule::result_voting_status(0, invalid), Some(100));
+        run_to_block::<AutonomyModule>(close_time + upload_cycle);
+        assert_eq!(
+            <Proposals as LiquidityPool<Test>>::get_proposal_state(0),
+            Ok(ProposalStatus::ResultAnnouncement)
+        );
+        assert_eq!(
+            <Proposals as LiquidityCouple<Test>>::get_proposal_result(0),
+            Ok(invalid)
+        );
+    })
+}
+
+#[test]
+fn test_commit_reveal_result() {
+    new_test_ext(|public_key_array| {
+        let account = public_key_array.get(0).unwrap();
+        let other = public_key_array.get(1).unwrap();
+        let other2 = public_key_array.get(2).unwrap();
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        let upload_cycle: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_upload_cycle(
+            Origin::root(),
+            upload_cycle
+        ));
+        let reveal_period: MomentOf<Test> = 5;
+        assert_ok!(AutonomyModule::set_reveal_period(
+            Origin::root(),
+            reveal_period
+        ));
+        let close_time: MomentOf<Test> = 10;
+        assert_ok!(Proposals::set_create_time(0, System::block_number()));
+        assert_ok!(Proposals::set_close_time(0, close_time));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        assert_ok!(AutonomyModule::set_lock_ratio(Origin::root(), 10));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*account), 1000));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*other), 1000));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*other2), 1000));
+        let payload = Payload {
+            proposal_id: 0,
+            result: 4,
+            public: *account,
+            vote_num: 500,
+        };
+        assert_noop!(
+            AutonomyModule::upload_result(Origin::none(), payload, Default::default()),
+            Error::<Test>::ResultMustBeCommitted
+        );
+
+        // the committed results are not counted until they are revealed
+        for (who, result, vote_num, salt) in &[
+            (account, 4, 500, [1; 32]),
+            (other, 5, 300, [2; 32]),
+            (other2, 5, 200, [3; 32]),
+        ] {
+            let payload = CommitPayload {
+                proposal_id: 0,
+                commitment: AutonomyModule::result_commitment(0, who, *result, *vote_num, *salt),
+                public: **who,
+                vote_num: *vote_num,
+            };
+            assert_ok!(AutonomyModule::commit_result(
+                Origin::none(),
+                payload,
+                Default::default()
+            ));
+        }
+        let event = Event::autonomy(crate::Event::CommitResult(*account, 0, 500));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(AutonomyModule::staked_node_lock_num(0, *account), Some(50));
+        assert_eq!(
+            AutonomyModule::proposal_reveal_period(0),
+            Some(reveal_period)
+        );
+
+        // the committed proposal keeps its reveal period when it is changed
+        assert_ok!(AutonomyModule::set_reveal_period(Origin::root(), 0));
+        assert_eq!(AutonomyModule::node_result_voting_status(0, *account), None);
+        assert_eq!(AutonomyModule::result_voting_status(0, 4), None);
+        let reveal = |who: &AccountId, result, salt| RevealPayload {
+            proposal_id: 0,
+            result,
+            salt,
+            public: *who,
+        };
+        assert_noop!(
+            AutonomyModule::reveal_result(
+                Origin::none(),
+                reveal(account, 4, [1; 32]),
+                Default::default()
+            ),
+            Error::<Test>::RevealPeriodNotStarted
+        );
+
+        // the upload cycle is over, the reveal window starts
+        run_to_block::<AutonomyModule>(close_time + upload_cycle);
+        assert_eq!(
+            AutonomyModule::reveal_end(0),
+            Some(close_time + upload_cycle + reveal_period)
+        );
+        assert_eq!(
+            <Proposals as LiquidityPool<Test>>::get_proposal_state(0),
+            Ok(ProposalStatus::WaitingForResults)
+        );
+        let payload = CommitPayload {
+            proposal_id: 0,
+            commitment: AutonomyModule::result_commitment(0, account, 4, 500, [1; 32]),
+            public: *account,
+            vote_num: 500,
+        };
+        assert_noop!(
+            AutonomyModule::commit_result(Origin::none(), payload, Default::default()),
+            Error::<Test>::CommitPeriodIsOver
+        );
+        assert_noop!(
+            AutonomyModule::reveal_result(
+                Origin::none(),
+                reveal(account, 5, [1; 32]),
+                Default::default()
+            ),
+            Error::<Test>::CommitmentMismatch
+        );
+        assert_ok!(AutonomyModule::reveal_result(
+            Origin::none(),
+            reveal(account, 4, [1; 32]),
+            Default::default()
+        ));
+        let event = Event::autonomy(crate::Event::RevealResult(*account, 0, 4, 500));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(AutonomyModule::result_voting_status(0, 4), Some(500));
+        assert_noop!(
+            AutonomyModule::reveal_result(
+                Origin::none(),
+                reveal(account, 4, [1; 32]),
+                Default::default()
+            ),
+            Error::<Test>::CommitmentNotExist
+        );
+        assert_ok!(AutonomyModule::reveal_result(
+            Origin::none(),
+            reveal(other, 5, [2; 32]),
+            Default::default()
+        ));
+
+        // only the revealed votes are counted, the unrevealed ones are slashed
+        run_to_block::<AutonomyModule>(close_time + upload_cycle + reveal_period);
+        assert_eq!(
+            <Proposals as LiquidityPool<Test>>::get_proposal_state(0),
+            Ok(ProposalStatus::ResultAnnouncement)
+        );
+        assert_eq!(
+            <Proposals as LiquidityCouple<Test>>::get_proposal_result(0),
+            Ok(4)
+        );
+        assert_eq!(AutonomyModule::result_voting_status(0, 5), Some(300));
+        assert_eq!(AutonomyModule::reveal_end(0), None);
+        assert_eq!(AutonomyModule::result_commitments(0, *other2), None);
+        assert_eq!(AutonomyModule::proposal_reveal_period(0), None);
+        assert_eq!(AutonomyModule::staked_node_lock_num(0, *other2), None);
+        assert_eq!(AutonomyModule::staked_node_lock_total_num(*other2), Some(0));
+        assert_eq!(
+            AutonomyModule::staked_node(*other2).map(|(number, _)| number),
+            Some(980)
+        );
+        let event = Event::autonomy(crate::Event::CommitNotRevealed(*other2, 0, 20));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, other2),
+            99000
+        );
+    })
+}
+
+#[test]
+fn test_copied_commitment_is_not_revealed() {
+    new_test_ext(|public_key_array| {
+        let account = public_key_array.get(0).unwrap();
+        let other = public_key_array.get(1).unwrap();
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        let upload_cycle: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_upload_cycle(
+            Origin::root(),
+            upload_cycle
+        ));
+        assert_ok!(AutonomyModule::set_reveal_period(Origin::root(), 5));
+        let close_time: MomentOf<Test> = 10;
+        assert_ok!(Proposals::set_create_time(0, System::block_number()));
+        assert_ok!(Proposals::set_close_time(0, close_time));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        assert_ok!(AutonomyModule::set_lock_ratio(Origin::root(), 10));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*account), 1000));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*other), 1000));
+
+        // the other node copies the commitment of the account from the chain
+        let commitment = AutonomyModule::result_commitment(0, account, 4, 500, [1; 32]);
+        for who in &[account, other] {
+            let payload = CommitPayload {
+                proposal_id: 0,
+                commitment,
+                public: **who,
+                vote_num: 500,
+            };
+            assert_ok!(AutonomyModule::commit_result(
+                Origin::none(),
+                payload,
+                Default::default()
+            ));
+        }
+        run_to_block::<AutonomyModule>(close_time + upload_cycle);
+        assert_ok!(AutonomyModule::reveal_result(
+            Origin::none(),
+            RevealPayload {
+                proposal_id: 0,
+                result: 4,
+                salt: [1; 32],
+                public: *account,
+            },
+            Default::default()
+        ));
+
+        // the revealed result and salt do not open the copied commitment
+        assert_noop!(
+            AutonomyModule::reveal_result(
+                Origin::none(),
+                RevealPayload {
+                    proposal_id: 0,
+                    result: 4,
+                    salt: [1; 32],
+                    public: *other,
+                },
+                Default::default()
+            ),
+            Error::<Test>::CommitmentMismatch
+        );
+        assert_eq!(AutonomyModule::result_voting_status(0, 4), Some(500));
+    })
+}
+
 #[test]
 fn test_report() {
     new_test_ext(|public_key_array| {
@@ -426,9 +811,26 @@ fn test_report() {
             AutonomyModule::report_voting_status(0),
             Some(11000)
         );
+        let dispute = Dispute {
+            announced: 4,
+            outcome: 5,
+            round: 1,
+            bond: 20000,
+            end: close_time + upload_cycle + publicity_period,
+        };
+        assert_eq!(AutonomyModule::disputes(0), Some(dispute));
+        let event = Event::autonomy(crate::Event::DisputeOpened(0, 5, 20000));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_noop!(
+            AutonomyModule::report(Origin::signed(*other3), 0, 1000),
+            Error::<Test>::ProposalNotNeedSecond
+        );
+        // nobody bonded against the outcome, the dispute settles on it when the round ends
         run_to_block::<AutonomyModule>(close_time + upload_cycle + publicity_period);
         assert_eq!(<Proposals as LiquidityPool<Test>>::get_proposal_state(0),
             Ok(ProposalStatus::End));
+        assert_eq!(<Proposals as LiquidityCouple<Test>>::get_proposal_result(0), Ok(5));
+        assert_eq!(AutonomyModule::report_success_flag(0), Some(()));
     })
 }
 
@@ -518,24 +920,37 @@ fn test_slash_and_take_out_unlock() {
         assert_eq!(AutonomyModule::staked_node(*account), Some((1000, true)));
         assert_eq!(AutonomyModule::staked_node_lock_total_num(*account), Some(50));
         assert_eq!(AutonomyModule::staked_node_lock_num(0, *account), Some(50));
-        assert_ok!(AutonomyModule::slash(Origin::root(), *account, 0));
+        assert_eq!(<Proposals as LiquidityCouple<Test>>::get_proposal_result(0), Ok(5));
+        assert_eq!(AutonomyModule::slash_queue(), vec![0]);
+
+        // the nodes that uploaded 4 are slashed one at a time
+        assert_ok!(AutonomyModule::process_slashes(
+            Origin::signed(*other3),
+            0,
+            1
+        ));
+        assert_eq!(AccountSlashNumber::<Test>::iter_prefix(0).count(), 1);
+        assert_eq!(AutonomyModule::slash_finish_flag(0), None);
+        assert!(!<AutonomyModule as ProposalArchive<Test>>::is_drained(0));
+        assert_ok!(AutonomyModule::process_slashes(
+            Origin::signed(*other3),
+            0,
+            1
+        ));
         assert_eq!(AutonomyModule::staked_node_lock_num(0, *account), None);
         assert_eq!(AutonomyModule::staked_node_lock_total_num(*account), Some(0));
         assert_eq!(AutonomyModule::staked_node(*account), Some((950, false)));
-        assert_eq!(AutonomyModule::report_asset_pool(0), Some(50));
         assert_eq!(AutonomyModule::account_slash_number(0, *account), Some(50));
-        assert_eq!(AutonomyModule::slash_finish_flag(0), None);
-        assert_ok!(AutonomyModule::slash(Origin::root(), *other2, 0));
-        assert_eq!(AutonomyModule::report_asset_pool(0), Some(150));
         assert_eq!(AutonomyModule::account_slash_number(0, *other2), Some(100));
-        assert_eq!(<Proposals as LiquidityCouple<Test>>::get_proposal_result(0),
-        Ok(4)
-         );
-        assert_ok!(AutonomyModule::slash_finish(Origin::root(), 0));
-        assert_eq!(<Proposals as LiquidityCouple<Test>>::get_proposal_result(0),
-        Ok(5)
-        );
+        assert_eq!(AutonomyModule::account_slash_number(0, *other), None);
+        assert_eq!(AutonomyModule::report_asset_pool(0), Some(150));
+        assert_eq!(AutonomyModule::final_report_asset_pool(0), Some(150));
         assert_eq!(AutonomyModule::slash_finish_flag(0), Some(()));
+        assert_eq!(AutonomyModule::slash_queue(), vec![]);
+        assert_noop!(
+            AutonomyModule::process_slashes(Origin::signed(*other3), 0, 1),
+            Error::<Test>::SlashHasBeenCompleted
+        );
         assert_eq!(<TokensOf<Test> as Tokens<AccountId>>::balance(1, &module_account), 150);
         assert_eq!(<TokensOf<Test> as Tokens<AccountId>>::balance(1, other), 94000);
         assert_eq!(<TokensOf<Test> as Tokens<AccountId>>::balance(1, other3), 89000);
@@ -545,15 +960,653 @@ fn test_slash_and_take_out_unlock() {
         assert_ok!(AutonomyModule::take_out(Origin::signed(*other), 0));
         println!(" {:?} {:?} {:?}", <TokensOf<Test> as Tokens<AccountId>>::balance(1, &module_account), <TokensOf<Test> as Tokens<AccountId>>::balance(1, other),
         <TokensOf<Test> as Tokens<AccountId>>::balance(1, other3));
-        assert_ok!(AutonomyModule::take_out(Origin::signed(*other3), 0));
-        println!(" {:?} {:?} {:?}", <TokensOf<Test> as Tokens<AccountId>>::balance(1, &module_account), <TokensOf<Test> as Tokens<AccountId>>::balance(1, other),
-        <TokensOf<Test> as Tokens<AccountId>>::balance(1, other3));
-        assert_eq!(<TokensOf<Test> as Tokens<AccountId>>::balance(1, &module_account), 2);
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, &module_account),
+            101
+        );
         assert_eq!(<TokensOf<Test> as Tokens<AccountId>>::balance(1, other), 99049);
-        assert_eq!(<TokensOf<Test> as Tokens<AccountId>>::balance(1, other3), 99099);
         assert_noop!(AutonomyModule::unlock(Origin::signed(*account), 0),
             Error::<Test>::UploadResultWasReported
         );
+
+        // the votes still locked for the proposal are released when it is archived, and the
+        // report that was not taken out is paid its reward
+        assert!(<AutonomyModule as ProposalArchive<Test>>::is_drained(0));
+        assert_eq!(AutonomyModule::staked_node_lock_num(0, *other), Some(30));
+        assert!(<AutonomyModule as ProposalArchive<Test>>::archive(0, 100) < 100);
+        assert_eq!(<TokensOf<Test> as Tokens<AccountId>>::balance(1, &module_account), 2);
+        assert_eq!(<TokensOf<Test> as Tokens<AccountId>>::balance(1, other3), 99099);
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::reserved_balance(1, other3),
+            0
+        );
+        assert_eq!(AutonomyModule::account_report_number(0, *other3), None);
+        assert_eq!(AutonomyModule::staked_node_lock_num(0, *other), None);
+        assert_eq!(AutonomyModule::staked_node_lock_total_num(*other), Some(0));
+        assert_eq!(AutonomyModule::node_result_voting_status(0, *other), None);
+        assert_eq!(AutonomyModule::report_success_flag(0), None);
+        assert_eq!(AutonomyModule::disputes(0), None);
     })
 }
 
+#[test]
+fn test_dispute() {
+    new_test_ext(|public_key_array| {
+        let module_account = AutonomyModule::module_account();
+        let account = public_key_array.get(0).unwrap();
+        let other = public_key_array.get(1).unwrap();
+        let other2 = public_key_array.get(2).unwrap();
+        let other3 = public_key_array.get(3).unwrap();
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        let publicity_period: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_publicity_period(
+            Origin::root(),
+            publicity_period
+        ));
+        let upload_cycle: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_upload_cycle(
+            Origin::root(),
+            upload_cycle
+        ));
+        let close_time: MomentOf<Test> = 10;
+        assert_ok!(Proposals::set_create_time(0, System::block_number()));
+        assert_ok!(Proposals::set_close_time(0, close_time));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        assert_ok!(AutonomyModule::set_lock_ratio(Origin::root(), 10));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*account), 1000));
+        let payload = Payload {
+            proposal_id: 0,
+            result: 4,
+            public: *account,
+            vote_num: 500,
+        };
+        assert_ok!(AutonomyModule::upload_result(
+            Origin::none(),
+            payload,
+            Default::default()
+        ));
+        run_to_block::<AutonomyModule>(close_time + upload_cycle);
+        assert_noop!(
+            AutonomyModule::dispute(Origin::signed(*other), 0, 4, 20000),
+            Error::<Test>::DisputeNotExist
+        );
+        assert_ok!(AutonomyModule::report(Origin::signed(*other), 0, 5000));
+        assert_ok!(AutonomyModule::report(Origin::signed(*other3), 0, 6000));
+        assert_eq!(
+            AutonomyModule::disputes(0).map(|dispute| dispute.outcome),
+            Some(5)
+        );
+
+        // the first round stands on 5, bonds have to be against it and at least twice the
+        // minimal report number
+        assert_noop!(
+            AutonomyModule::dispute(Origin::signed(*account), 0, 5, 20000),
+            Error::<Test>::DisputeOutcomeNotChallenged
+        );
+        assert_noop!(
+            AutonomyModule::dispute(Origin::signed(*account), 0, 4, 10000),
+            Error::<Test>::DisputeBondTooLow
+        );
+        assert_ok!(AutonomyModule::dispute(
+            Origin::signed(*account),
+            0,
+            4,
+            20000
+        ));
+        let event = Event::autonomy(crate::Event::DisputeBond(*account, 0, 4, 20000));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(AutonomyModule::dispute_bonds(0, *account), Some((4, 20000)));
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, &module_account),
+            20000
+        );
+
+        // the round was contested, the second one stands on 4 with a doubled bond
+        run_to_block::<AutonomyModule>(close_time + upload_cycle + publicity_period);
+        assert_eq!(
+            <Proposals as LiquidityPool<Test>>::get_proposal_state(0),
+            Ok(ProposalStatus::ResultAnnouncement)
+        );
+        let dispute = AutonomyModule::disputes(0).unwrap();
+        assert_eq!(
+            (dispute.round, dispute.outcome, dispute.bond),
+            (2, 4, 40000)
+        );
+        let event = Event::autonomy(crate::Event::DisputeEscalated(0, 2, 4, 40000));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(AutonomyModule::dispute_round_voting_status(0, 4), None);
+        assert_noop!(
+            AutonomyModule::dispute(Origin::signed(*account), 0, 5, 40000),
+            Error::<Test>::AttitudeNeedSame
+        );
+        assert_ok!(AutonomyModule::dispute(
+            Origin::signed(*other2),
+            0,
+            5,
+            40000
+        ));
+
+        // the last round was contested too, the outcome is left to the dispute origin
+        run_to_block::<AutonomyModule>(close_time + upload_cycle + 2 * publicity_period);
+        let dispute = AutonomyModule::disputes(0).unwrap();
+        assert_eq!((dispute.round, dispute.outcome), (3, 5));
+        assert_eq!(AutonomyModule::proposal_deadline(0), None);
+        run_to_block::<AutonomyModule>(close_time + upload_cycle + 5 * publicity_period);
+        assert_eq!(
+            <Proposals as LiquidityPool<Test>>::get_proposal_state(0),
+            Ok(ProposalStatus::ResultAnnouncement)
+        );
+        assert_noop!(
+            AutonomyModule::dispute(Origin::signed(*other), 0, 4, 80000),
+            Error::<Test>::DisputeIsEscalated
+        );
+        assert_noop!(
+            AutonomyModule::resolve_dispute(Origin::signed(*other), 0, 5),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(AutonomyModule::resolve_dispute(Origin::root(), 0, 5));
+        let event = Event::autonomy(crate::Event::DisputeSettled(0, 5));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(
+            <Proposals as LiquidityPool<Test>>::get_proposal_state(0),
+            Ok(ProposalStatus::End)
+        );
+        assert_eq!(
+            <Proposals as LiquidityCouple<Test>>::get_proposal_result(0),
+            Ok(5)
+        );
+        assert_eq!(AutonomyModule::report_success_flag(0), Some(()));
+
+        // the bond on 5 takes the bond on 4, the bond on 4 is lost
+        assert_ok!(AutonomyModule::claim_dispute_bond(
+            Origin::signed(*other2),
+            0
+        ));
+        let event = Event::autonomy(crate::Event::DisputeBondClaimed(*other2, 0, 60000));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, other2),
+            120000
+        );
+        assert_ok!(AutonomyModule::claim_dispute_bond(
+            Origin::signed(*account),
+            0
+        ));
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, account),
+            79000
+        );
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, &module_account),
+            0
+        );
+        assert_noop!(
+            AutonomyModule::claim_dispute_bond(Origin::signed(*other2), 0),
+            Error::<Test>::DisputeBondNotExist
+        );
+
+        // the node that uploaded 4 is slashed at the beginning of the next block
+        assert_eq!(AutonomyModule::slash_queue(), vec![0]);
+        assert_eq!(AutonomyModule::pending_slashes(0, *account), Some(()));
+        run_to_block::<AutonomyModule>(close_time + upload_cycle + 5 * publicity_period + 1);
+        assert_eq!(AutonomyModule::pending_slashes(0, *account), None);
+        assert_eq!(AutonomyModule::account_slash_number(0, *account), Some(50));
+        let event = Event::autonomy(crate::Event::Slash(*account, 0, 50));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(AutonomyModule::slash_finish_flag(0), Some(()));
+        assert_eq!(AutonomyModule::slash_queue(), vec![]);
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, &module_account),
+            50
+        );
+    })
+}
+
+#[test]
+fn test_dispute_refunds_bonds_without_winner() {
+    new_test_ext(|public_key_array| {
+        let module_account = AutonomyModule::module_account();
+        let account = public_key_array.get(0).unwrap();
+        let other = public_key_array.get(1).unwrap();
+        let other2 = public_key_array.get(2).unwrap();
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        let publicity_period: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_publicity_period(
+            Origin::root(),
+            publicity_period
+        ));
+        let upload_cycle: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_upload_cycle(
+            Origin::root(),
+            upload_cycle
+        ));
+        let close_time: MomentOf<Test> = 10;
+        assert_ok!(Proposals::set_create_time(0, System::block_number()));
+        assert_ok!(Proposals::set_close_time(0, close_time));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        assert_ok!(AutonomyModule::set_lock_ratio(Origin::root(), 10));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*account), 1000));
+        let payload = Payload {
+            proposal_id: 0,
+            result: 4,
+            public: *account,
+            vote_num: 500,
+        };
+        assert_ok!(AutonomyModule::upload_result(
+            Origin::none(),
+            payload,
+            Default::default()
+        ));
+        run_to_block::<AutonomyModule>(close_time + upload_cycle);
+        assert_ok!(AutonomyModule::report(Origin::signed(*other), 0, 11000));
+        assert_ok!(AutonomyModule::dispute(
+            Origin::signed(*account),
+            0,
+            4,
+            20000
+        ));
+        run_to_block::<AutonomyModule>(close_time + upload_cycle + publicity_period);
+        assert_ok!(AutonomyModule::dispute(
+            Origin::signed(*other2),
+            0,
+            5,
+            40000
+        ));
+        run_to_block::<AutonomyModule>(close_time + upload_cycle + 2 * publicity_period);
+
+        // the dispute origin settles on the invalid result, which nobody bonded on
+        assert_ok!(AutonomyModule::resolve_dispute(
+            Origin::root(),
+            0,
+            INVALID_RESULT_ID
+        ));
+        assert_eq!(
+            <Proposals as LiquidityCouple<Test>>::get_proposal_result(0),
+            Ok(INVALID_RESULT_ID)
+        );
+
+        // every bond is paid back instead of being left in the module account
+        assert_ok!(AutonomyModule::claim_dispute_bond(
+            Origin::signed(*other2),
+            0
+        ));
+        let event = Event::autonomy(crate::Event::DisputeBondClaimed(*other2, 0, 40000));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, other2),
+            100000
+        );
+        assert_ok!(AutonomyModule::claim_dispute_bond(
+            Origin::signed(*account),
+            0
+        ));
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, account),
+            99000
+        );
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, &module_account),
+            0
+        );
+    })
+}
+
+#[test]
+fn test_slash_queue_drops_failed_proposal() {
+    new_test_ext(|_| {
+        SlashQueue::<Test>::put(vec![7]);
+        run_to_block::<AutonomyModule>(System::block_number() + 1);
+        assert_eq!(AutonomyModule::slash_queue(), vec![]);
+        let event = Event::autonomy(crate::Event::SlashFailed(7));
+        assert!(System::events().iter().any(|record| record.event == event));
+    })
+}
+
+#[test]
+fn test_dispute_keeps_announced_result() {
+    new_test_ext(|public_key_array| {
+        let account = public_key_array.get(0).unwrap();
+        let other = public_key_array.get(1).unwrap();
+        let other2 = public_key_array.get(2).unwrap();
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        let publicity_period: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_publicity_period(
+            Origin::root(),
+            publicity_period
+        ));
+        let upload_cycle: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_upload_cycle(
+            Origin::root(),
+            upload_cycle
+        ));
+        let close_time: MomentOf<Test> = 10;
+        assert_ok!(Proposals::set_create_time(0, System::block_number()));
+        assert_ok!(Proposals::set_close_time(0, close_time));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        assert_ok!(AutonomyModule::set_lock_ratio(Origin::root(), 10));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*account), 1000));
+        let payload = Payload {
+            proposal_id: 0,
+            result: 4,
+            public: *account,
+            vote_num: 500,
+        };
+        assert_ok!(AutonomyModule::upload_result(
+            Origin::none(),
+            payload,
+            Default::default()
+        ));
+        run_to_block::<AutonomyModule>(close_time + upload_cycle);
+        assert_ok!(AutonomyModule::report(Origin::signed(*other), 0, 10000));
+        assert_ok!(AutonomyModule::dispute(
+            Origin::signed(*other2),
+            0,
+            4,
+            20000
+        ));
+
+        // the second round is not contested, the announced result stands and the report failed
+        run_to_block::<AutonomyModule>(close_time + upload_cycle + 2 * publicity_period);
+        assert_eq!(
+            <Proposals as LiquidityPool<Test>>::get_proposal_state(0),
+            Ok(ProposalStatus::End)
+        );
+        assert_eq!(
+            <Proposals as LiquidityCouple<Test>>::get_proposal_result(0),
+            Ok(4)
+        );
+        assert_eq!(AutonomyModule::report_success_flag(0), None);
+        assert!(<AutonomyModule as ProposalArchive<Test>>::is_drained(0));
+        assert_noop!(
+            AutonomyModule::process_slashes(Origin::signed(*other), 0, 1),
+            Error::<Test>::ReportNotSuccess
+        );
+        assert_eq!(AutonomyModule::slash_queue(), vec![]);
+        assert_ok!(AutonomyModule::unlock(Origin::signed(*account), 0));
+        assert_ok!(AutonomyModule::take_out(Origin::signed(*other), 0));
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, other),
+            100000
+        );
+
+        // an unclaimed bond is paid out when the proposal is archived
+        assert!(<AutonomyModule as ProposalArchive<Test>>::archive(0, 100) < 100);
+        assert_eq!(AutonomyModule::dispute_bonds(0, *other2), None);
+        assert_eq!(
+            <TokensOf<Test> as Tokens<AccountId>>::balance(1, other2),
+            100000
+        );
+        assert_eq!(AutonomyModule::disputes(0), None);
+    })
+}
+
+
+#[test]
+fn test_upload_scalar_result() {
+    new_test_ext(|public_key_array| {
+        let account = public_key_array.get(0).unwrap();
+        let other = public_key_array.get(1).unwrap();
+        let other1 = public_key_array.get(2).unwrap();
+        let upload_cycle: MomentOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_upload_cycle(Origin::root(), upload_cycle));
+        let lock_ratio: BalanceOf<Test> = 10;
+        assert_ok!(AutonomyModule::set_lock_ratio(Origin::root(), lock_ratio));
+        assert_ok!(Proposals::new_scalar_proposal(*account, 1, 0, 100));
+        let now = System::block_number();
+        let close_time: MomentOf<Test> = 10;
+        assert_ok!(Proposals::set_create_time(0, now));
+        assert_ok!(Proposals::set_close_time(0, close_time));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        let stake_number: BalanceOf<Test> = 2000;
+        assert_ok!(AutonomyModule::stake(Origin::signed(*account), stake_number));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*other), stake_number));
+        assert_ok!(AutonomyModule::stake(Origin::signed(*other1), stake_number));
+
+        let payload = Payload {
+            proposal_id: 0,
+            result: 3,
+            public: *account,
+            vote_num: 100
+        };
+        assert_noop!(
+            AutonomyModule::upload_result(Origin::none(), payload, Default::default()),
+            Error::<Test>::ProposalIsScalar
+        );
+        let mut scalar_payload = ScalarPayload {
+            proposal_id: 0,
+            value: 101,
+            public: *account,
+            vote_num: 100
+        };
+        assert_noop!(
+            AutonomyModule::upload_scalar_result(Origin::none(), scalar_payload.clone(), Default::default()),
+            Error::<Test>::ProposalOptionNotCorrect
+        );
+        scalar_payload.value = 40;
+        assert_ok!(AutonomyModule::upload_scalar_result(
+            Origin::none(),
+            scalar_payload.clone(),
+            Default::default()
+        ));
+        let event = Event::autonomy(crate::Event::UploadScalarResult(*account, 0, 40, 100));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_noop!(
+            AutonomyModule::upload_scalar_result(Origin::none(), scalar_payload, Default::default()),
+            Error::<Test>::AccountHasAlreadyUploaded
+        );
+        assert_ok!(AutonomyModule::upload_scalar_result(
+            Origin::none(),
+            ScalarPayload { proposal_id: 0, value: 60, public: *other, vote_num: 300 },
+            Default::default()
+        ));
+        assert_ok!(AutonomyModule::upload_scalar_result(
+            Origin::none(),
+            ScalarPayload { proposal_id: 0, value: 80, public: *other1, vote_num: 100 },
+            Default::default()
+        ));
+        assert_eq!(AutonomyModule::node_scalar_result_voting_status(0, *other), Some((60, 300)));
+        assert_eq!(AutonomyModule::staked_node_lock_num(0, *other), Some(30));
+        assert_eq!(AutonomyModule::staked_node_lock_total_num(*other), Some(30));
+
+        run_to_block::<AutonomyModule>(close_time + upload_cycle);
+        assert_eq!(Proposals::scalar_result(0), Some(60));
+        assert_eq!(<Proposals as LiquidityPool<Test>>::get_proposal_state(0),
+            Ok(ProposalStatus::ResultAnnouncement));
+        assert_eq!(AutonomyModule::result_announcement_time(0), Some(close_time + upload_cycle));
+        assert_ok!(AutonomyModule::set_minimal_report_number(Origin::root(), 1000));
+        assert_noop!(
+            AutonomyModule::report(Origin::signed(*other), 0, 1000),
+            Error::<Test>::ProposalIsScalar
+        );
+    })
+}
+
+fn result_source() -> ResultSource {
+    ResultSource {
+        url: b"https://example.com/match".to_vec(),
+        path: b"data.winner".to_vec(),
+        yes: b"home".to_vec(),
+        no: b"away".to_vec(),
+    }
+}
+
+fn expect_result_request(offchain_state: &parking_lot::RwLock<OffchainState>, body: &[u8]) {
+    offchain_state.write().expect_request(PendingRequest {
+        method: "GET".into(),
+        uri: "https://example.com/match".into(),
+        response: Some(body.to_vec()),
+        sent: true,
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_set_result_source() {
+    new_test_ext(|public_key_array| {
+        let account = public_key_array.get(0).unwrap();
+        let other = public_key_array.get(1).unwrap();
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        assert_noop!(
+            AutonomyModule::set_result_source(Origin::signed(*other), 0, result_source()),
+            Error::<Test>::NotProposalOwner
+        );
+        let mut source = result_source();
+        source.path = vec![b'a'; MAX_RESULT_SOURCE_LENGTH + 1];
+        assert_noop!(
+            AutonomyModule::set_result_source(Origin::signed(*account), 0, source),
+            Error::<Test>::ResultSourceTooLong
+        );
+        assert_ok!(AutonomyModule::set_result_source(
+            Origin::signed(*account),
+            0,
+            result_source()
+        ));
+        let event = Event::autonomy(crate::Event::SetResultSource(0));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(AutonomyModule::result_sources(0), Some(result_source()));
+
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        assert_noop!(
+            AutonomyModule::set_result_source(Origin::signed(*account), 0, result_source()),
+            Error::<Test>::ProposalAbnormalState
+        );
+
+        assert_ok!(Proposals::new_scalar_proposal(*account, 1, 0, 100));
+        assert_noop!(
+            AutonomyModule::set_result_source(Origin::signed(*account), 1, result_source()),
+            Error::<Test>::ResultSourceNotSupported
+        );
+    })
+}
+
+#[test]
+fn test_offchain_upload_result() {
+    new_offchain_test_ext(|public_key_array, offchain_state, pool_state| {
+        let account = public_key_array.get(0).unwrap();
+        assert_ok!(AutonomyModule::stake(Origin::signed(*account), 1000));
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        assert_ok!(AutonomyModule::set_result_source(
+            Origin::signed(*account),
+            0,
+            result_source()
+        ));
+        let now = System::block_number();
+        assert_ok!(Proposals::set_create_time(0, now));
+        assert_ok!(Proposals::set_close_time(0, now));
+
+        // nothing is fetched before the proposal waits for its result
+        AutonomyModule::offchain_worker(now);
+        assert!(pool_state.read().transactions.is_empty());
+
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        expect_result_request(&offchain_state, br#"{"data":{"winner":"home"}}"#);
+        AutonomyModule::offchain_worker(now);
+
+        // only the staked node uploads the result
+        let tx = pool_state.write().transactions.pop().unwrap();
+        assert!(pool_state.read().transactions.is_empty());
+        let tx = Extrinsic::decode(&mut &*tx).unwrap();
+        assert_eq!(tx.signature, None);
+        match tx.call {
+            Call::AutonomyModule(crate::Call::upload_result(payload, signature)) => {
+                assert_eq!(
+                    payload,
+                    Payload {
+                        proposal_id: 0,
+                        result: 4,
+                        public: *account,
+                        vote_num: 1000
+                    }
+                );
+                assert!(SignedPayload::<Test>::verify::<crypto::OcwAuthId>(
+                    &payload, signature
+                ));
+            }
+            _ => panic!("unexpected call"),
+        }
+    })
+}
+
+#[test]
+fn test_offchain_upload_result_waits_for_resolution() {
+    new_offchain_test_ext(|public_key_array, offchain_state, pool_state| {
+        let account = public_key_array.get(0).unwrap();
+        assert_ok!(AutonomyModule::stake(Origin::signed(*account), 1000));
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        assert_ok!(AutonomyModule::set_result_source(
+            Origin::signed(*account),
+            0,
+            result_source()
+        ));
+        let now = System::block_number();
+        assert_ok!(Proposals::set_create_time(0, now));
+        assert_ok!(Proposals::set_close_time(0, now));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_resolution(
+            0,
+            Resolution {
+                time: now + 2,
+                ..Default::default()
+            }
+        ));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        AutonomyModule::offchain_worker(now);
+        assert!(pool_state.read().transactions.is_empty());
+
+        System::set_block_number(now + 2);
+        expect_result_request(&offchain_state, br#"{"data":{"winner":"away"}}"#);
+        AutonomyModule::offchain_worker(now + 2);
+        let tx = pool_state.write().transactions.pop().unwrap();
+        let tx = Extrinsic::decode(&mut &*tx).unwrap();
+        match tx.call {
+            Call::AutonomyModule(crate::Call::upload_result(payload, _)) => {
+                assert_eq!(payload.result, 5);
+            }
+            _ => panic!("unexpected call"),
+        }
+    })
+}
+
+#[test]
+fn test_offchain_upload_result_ignores_unknown_value() {
+    new_offchain_test_ext(|public_key_array, offchain_state, pool_state| {
+        let account = public_key_array.get(0).unwrap();
+        assert_ok!(AutonomyModule::stake(Origin::signed(*account), 1000));
+        assert_ok!(Proposals::new_couple_proposal(*account, 1));
+        assert_ok!(AutonomyModule::set_result_source(
+            Origin::signed(*account),
+            0,
+            result_source()
+        ));
+        let now = System::block_number();
+        assert_ok!(Proposals::set_create_time(0, now));
+        assert_ok!(Proposals::set_close_time(0, now));
+        assert_ok!(<Proposals as LiquidityPool<Test>>::set_proposal_state(
+            0,
+            ProposalStatus::WaitingForResults,
+        ));
+        expect_result_request(&offchain_state, br#"{"data":{"winner":"draw"}}"#);
+        AutonomyModule::offchain_worker(now);
+        assert!(pool_state.read().transactions.is_empty());
+    })
+}
@@ -0,0 +1,277 @@
+//! Weights for xpmrl_autonomy
+//!
+//! PROVISIONAL: the figures below are estimated by hand from the storage accesses of each call
+//! on top of a fixed base cost, they have not been measured by the benchmarks of this pallet
+//! yet and must be replaced by the output of the benchmark CLI before a release.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for xpmrl_autonomy.
+pub trait WeightInfo {
+    fn stake() -> Weight;
+    fn unstake() -> Weight;
+    fn review() -> Weight;
+    fn process_slashes(n: u32) -> Weight;
+    fn change_state() -> Weight;
+    fn upload_result() -> Weight;
+    fn upload_scalar_result() -> Weight;
+    fn commit_result() -> Weight;
+    fn reveal_result() -> Weight;
+    fn set_result_source() -> Weight;
+    fn report() -> Weight;
+    fn take_out() -> Weight;
+    fn unlock() -> Weight;
+    fn dispute() -> Weight;
+    fn resolve_dispute() -> Weight;
+    fn claim_dispute_bond() -> Weight;
+    fn set_minimal_review_number() -> Weight;
+    fn set_minimal_stake_number() -> Weight;
+    fn set_minimal_report_number() -> Weight;
+    fn set_lock_ratio() -> Weight;
+    fn set_review_cycle() -> Weight;
+    fn set_upload_cycle() -> Weight;
+    fn set_publicity_period() -> Weight;
+    fn set_reveal_period() -> Weight;
+}
+
+/// Provisional weights for xpmrl_autonomy, estimated by hand until they are benchmarked.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn stake() -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn unstake() -> Weight {
+        (56_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn review() -> Weight {
+        (48_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn process_slashes(n: u32) -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add((90_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().reads((10 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes((8 as Weight).saturating_mul(n as Weight)))
+    }
+    fn change_state() -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(17 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    fn upload_result() -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn upload_scalar_result() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn commit_result() -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn reveal_result() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn set_result_source() -> Weight {
+        (32_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn report() -> Weight {
+        (46_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn take_out() -> Weight {
+        (64_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn unlock() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn dispute() -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn resolve_dispute() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn claim_dispute_bond() -> Weight {
+        (54_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn set_minimal_review_number() -> Weight {
+        (12_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_minimal_stake_number() -> Weight {
+        (12_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_minimal_report_number() -> Weight {
+        (12_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_lock_ratio() -> Weight {
+        (12_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_review_cycle() -> Weight {
+        (14_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_upload_cycle() -> Weight {
+        (14_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_publicity_period() -> Weight {
+        (14_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_reveal_period() -> Weight {
+        (12_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn stake() -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn unstake() -> Weight {
+        (56_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn review() -> Weight {
+        (48_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn process_slashes(n: u32) -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add((90_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((10 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((8 as Weight).saturating_mul(n as Weight)))
+    }
+    fn change_state() -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(17 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn upload_result() -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn upload_scalar_result() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn commit_result() -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn reveal_result() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn set_result_source() -> Weight {
+        (32_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn report() -> Weight {
+        (46_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn take_out() -> Weight {
+        (64_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn unlock() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn dispute() -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn resolve_dispute() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn claim_dispute_bond() -> Weight {
+        (54_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn set_minimal_review_number() -> Weight {
+        (12_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_minimal_stake_number() -> Weight {
+        (12_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_minimal_report_number() -> Weight {
+        (12_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_lock_ratio() -> Weight {
+        (12_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_review_cycle() -> Weight {
+        (14_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_upload_cycle() -> Weight {
+        (14_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_publicity_period() -> Weight {
+        (14_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_reveal_period() -> Weight {
+        (12_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+}
@@ -0,0 +1,729 @@
+//! <!-- markdown-link-check-disable -->
+//! # Categorical
+//!
+//! Run `cargo doc --package xpmrl-categorical --open` to view this pallet's documentation.
+//!
+//! A module dedicated to processing proposals with more than two options
+//!
+//! - [`xpmrl_categorical::Config`](./pallet/trait.Config.html)
+//! - [`Call`](./pallet/enum.Call.html)
+//! - [`Pallet`](./pallet/struct.Pallet.html)
+//!
+//! ## Overview
+//!
+//! Works the same way as the couple module, except that a proposal can contain any number of
+//! options between 2 and `MaxOptions`, such as "which team will win the league".
+//!
+//! Each option gets its own option token, and the pool prices them with a multi-asset constant
+//! product invariant: the product of the pool balances of all options never decreases.
+//!
+//! Buying deposits settlement currency, mints the same amount of every option into the pool and
+//! takes the selected option out of it. Selling does the opposite, the pool merges complete sets
+//! of options back into settlement currency.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unused_unit)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+
+pub(crate) mod tools;
+
+use frame_support::{traits::Get, weights::Weight};
+use frame_system::RawOrigin;
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+use xpmrl_traits::{
+    couple::{LiquidityCouple, INVALID_RESULT_ID},
+    pool::{LiquiditySubPool, ProposalArchive},
+    registry::LiquidityEngine,
+};
+
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, traits::Time};
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::Zero;
+    use xpmrl_traits::{
+        pool::LiquidityPool, ruler::RulerAccounts, system::ProposalSystem, tokens::Tokens,
+        ProposalStatus, Resolution,
+    };
+    use xpmrl_utils::with_transaction_result;
+
+    pub(crate) type TokensOf<T> =
+        <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::Tokens;
+    pub(crate) type CurrencyIdOf<T> =
+        <TokensOf<T> as Tokens<<T as frame_system::Config>::AccountId>>::CurrencyId;
+    pub(crate) type BalanceOf<T> =
+        <TokensOf<T> as Tokens<<T as frame_system::Config>::AccountId>>::Balance;
+    pub(crate) type CategoryIdOf<T> =
+        <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::CategoryId;
+    pub(crate) type ProposalIdOf<T> =
+        <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::ProposalId;
+    pub(crate) type VersionIdOf<T> =
+        <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::VersionId;
+    pub(crate) type TimeOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::Time;
+    pub(crate) type MomentOf<T> = <TimeOf<T> as Time>::Moment;
+
+    /// Basic attributes of the proposal
+    #[derive(PartialEq, Eq, Clone, RuntimeDebug, Encode, Decode, Default)]
+    pub struct Proposal<CategoryId> {
+        pub title: Vec<u8>,
+        /// The category of the proposal, such as sports, competition
+        pub category_id: CategoryId,
+        /// The specific description of the proposal
+        pub detail: Vec<u8>,
+    }
+
+    /// This is the pallet's configuration trait
+    ///
+    /// Inherited from the proposal pallet, it can use the related functions of the proposal
+    /// pallet, which is equivalent to deriving the function of the proposal pallet.
+    #[pallet::config]
+    pub trait Config:
+        frame_system::Config + ProposalSystem<<Self as frame_system::Config>::AccountId>
+    {
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+        type Pool: LiquidityPool<Self>;
+        type Ruler: RulerAccounts<Self>;
+
+        /// Decimals of fee
+        #[pallet::constant]
+        type EarnTradingFeeDecimals: Get<u8>;
+
+        /// The version id recorded for proposals created by this module, it must be different
+        /// from the version id of the couple module
+        #[pallet::constant]
+        type CurrentLiquidateVersionId: Get<VersionIdOf<Self>>;
+
+        /// The maximum number of options that a proposal can contain
+        #[pallet::constant]
+        type MaxOptions: Get<u32>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::pallet]
+    #[pallet::generate_store(pub(super) trait Store)]
+    pub struct Pallet<T>(_);
+
+    /// store the basic attributes of all proposals.
+    #[pallet::storage]
+    #[pallet::getter(fn proposals)]
+    pub type Proposals<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, Proposal<T::CategoryId>, OptionQuery>;
+
+    /// It stores the option tokens of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn pool_options)]
+    pub type PoolOptions<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, Vec<CurrencyIdOf<T>>, OptionQuery>;
+
+    /// It stores the settlement token of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_currency_id)]
+    pub type ProposalCurrencyId<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, CurrencyIdOf<T>, OptionQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_volume)]
+    pub type ProposalTotalVolume<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores the liquidity token of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_liquidate_currency_id)]
+    pub type ProposalLiquidateCurrencyId<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, CurrencyIdOf<T>, OptionQuery>;
+
+    /// It stores the fee rate of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_earn_trading_fee)]
+    pub type ProposalTotalEarnTradingFee<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, u32, OptionQuery>;
+
+    /// It stores the results of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_result)]
+    pub type ProposalResult<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, CurrencyIdOf<T>, OptionQuery>;
+
+    /// It stores the proposals settled as invalid, they have no `ProposalResult` and each of
+    /// their option tokens redeems an equal share of a settlement token
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_invalid)]
+    pub type ProposalInvalid<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, (), OptionQuery>;
+
+    /// It stores the option tokens of an invalid proposal an account has retrieved without being
+    /// paid for yet, as one settlement token takes one option token per option
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_account_invalid_remainder)]
+    pub type ProposalAccountInvalidRemainder<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// It stores the participating accounts of the proposal and how many settlement tokens it has
+    /// deposited into the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_account_info)]
+    pub type ProposalAccountInfo<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// It stores the amount of all settlement currencies deposited in the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_market)]
+    pub type ProposalTotalMarket<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores the number of option currencies for the proposal, in the same order as
+    /// `PoolOptions`
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_optional_market)]
+    pub type ProposalTotalOptionalMarket<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, Vec<BalanceOf<T>>, OptionQuery>;
+
+    /// It stores the final option currency information of the proposal
+    ///
+    /// Same as the couple module, it is fixed when the proposal ends
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_finally_optional_market)]
+    pub type ProposalFinallyTotalOptionalMarket<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, Vec<BalanceOf<T>>, OptionQuery>;
+
+    /// It stores the total fee of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_market_fee)]
+    pub type ProposalTotalMarketFee<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores all the final fees of the proposal
+    ///
+    /// Same as `ProposalFinallyTotalOptionalMarket`
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_finally_market_fee)]
+    pub type ProposalFinallyMarketFee<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores all the liquidity of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_market_liquid)]
+    pub type ProposalTotalMarketLiquid<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores all the final liquidity of the proposal
+    ///
+    /// Same as `ProposalFinallyTotalOptionalMarket`
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_finally_market_liquid)]
+    pub type ProposalFinallyMarketLiquid<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores how much commission the provider of the proposal has withdrawn
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_owner_already_withdrawn_fee)]
+    pub type ProposalOwnerAlreadyWithdrawnFee<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// The percentage of the commission that the creator of the proposal can get.
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_liquidity_provider_fee_rate)]
+    pub type ProposalLiquidityProviderFeeRate<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+    /// After the prediction is successful, the withdrawal fee rate charged at the time of
+    /// liquidation
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_withdrawal_fee_rate)]
+    pub type ProposalWithdrawalFeeRate<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+    /// After the proposal is over, when the user is clearing, the total settlement currency reward
+    /// that the node that participates in providing the result can obtain
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_autonomy_reward)]
+    pub type ProposalTotalAutonomyReward<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// Same as `ProposalCurrentAutonomyReward` of the couple module
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_current_autonomy_reward)]
+    pub type ProposalCurrentAutonomyReward<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig {
+        pub liquidity_provider_fee_rate: u32,
+        pub withdrawal_fee_rate: u32,
+    }
+
+    #[cfg(feature = "std")]
+    impl Default for GenesisConfig {
+        fn default() -> Self {
+            Self {
+                liquidity_provider_fee_rate: 9000,
+                withdrawal_fee_rate: 50,
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig {
+        fn build(&self) {
+            ProposalLiquidityProviderFeeRate::<T>::set(Some(self.liquidity_provider_fee_rate));
+            ProposalWithdrawalFeeRate::<T>::set(Some(self.withdrawal_fee_rate));
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::metadata(T::AccountId = "AccountId")]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        AddLiquidity(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        RemoveLiquidity(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        Buy(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        Sell(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        /// A liquidation event occurs after the liquidation, and the amount of liquidation will be
+        /// included in the event
+        Retrieval(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        SetResult(ProposalIdOf<T>, CurrencyIdOf<T>),
+        NewProposal(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>),
+        /// Same as `ProposalArchived` of the couple module
+        ProposalArchived(
+            ProposalIdOf<T>,
+            CurrencyIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// When buying, selling or clearing, if the currency id you enter is not the id of the
+        /// option currency, this error will be thrown
+        CurrencyIdNotFound,
+        /// The status of the current proposal is incorrect, and the current operation is not
+        /// supported.
+        ProposalAbnormalState,
+        /// A non-existent proposal was executed
+        ProposalIdNotExist,
+        /// During liquidation, this error will be thrown if the proposal does not have a result
+        /// set
+        ProposalNotResult,
+        /// The quantity overflowed during calculation
+        BalanceOverflow,
+        InsufficientBalance,
+        CategoryIdNotZero,
+        TokenIdNotZero,
+        NumberMustMoreThanZero,
+        CloseTimeMustLargeThanNow,
+        CurrencyIdNotAllowed,
+        /// A proposal needs at least two options
+        TooFewOptions,
+        /// The number of options exceeds `MaxOptions`
+        TooManyOptions,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Create a new proposal with several options
+        ///
+        /// `resolution` states how the question of the proposal resolves, each of its texts is at
+        /// most `MAX_RESOLUTION_LENGTH` long and its time can not be before `close_time`.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::new_proposal(optional.len() as u32))]
+        pub fn new_proposal(
+            origin: OriginFor<T>,
+            title: Vec<u8>,
+            optional: Vec<Vec<u8>>,
+            close_time: MomentOf<T>,
+            category_id: CategoryIdOf<T>,
+            currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+            earn_fee: u32,
+            detail: Vec<u8>,
+            resolution: Resolution<MomentOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(optional.len() >= 2, Error::<T>::TooFewOptions);
+            ensure!(
+                optional.len() <= T::MaxOptions::get() as usize,
+                Error::<T>::TooManyOptions
+            );
+            ensure!(category_id > Zero::zero(), Error::<T>::CategoryIdNotZero);
+            ensure!(currency_id > Zero::zero(), Error::<T>::TokenIdNotZero);
+            ensure!(number > Zero::zero(), Error::<T>::NumberMustMoreThanZero);
+            let now = <TimeOf<T> as Time>::now();
+            let minimum_interval_time = T::Pool::get_proposal_minimum_interval_time();
+            ensure!(
+                close_time > now + minimum_interval_time,
+                Error::<T>::CloseTimeMustLargeThanNow
+            );
+            ensure!(
+                !T::Pool::is_currency_id_used(currency_id),
+                Error::<T>::CurrencyIdNotAllowed
+            );
+            let proposal_id = with_transaction_result(|| {
+                let proposal_id = T::Pool::get_next_proposal_id()?;
+                Self::init_pool(
+                    &who,
+                    proposal_id,
+                    title,
+                    close_time,
+                    category_id,
+                    earn_fee,
+                    detail,
+                    resolution,
+                )?;
+                Self::new_currency(&who, proposal_id, currency_id, number, optional)
+            })?;
+            Self::deposit_event(Event::NewProposal(who, proposal_id, currency_id));
+            Ok(().into())
+        }
+
+        /// Provide liquidity to proposals
+        ///
+        /// The liquidity is added in proportion to the current pool, so the prices of the options
+        /// do not change, the options that are not needed by the pool are returned to the caller.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::add_liquidity(T::MaxOptions::get()))]
+        pub fn add_liquidity(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            ensure!(number > Zero::zero(), Error::<T>::NumberMustMoreThanZero);
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            with_transaction_result(|| Self::inner_add_liquidity(&who, proposal_id, number))?;
+            Self::deposit_event(Event::AddLiquidity(who, proposal_id, currency_id, number));
+            Ok(().into())
+        }
+
+        /// Get back your own assets through liquidity
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::remove_liquidity(T::MaxOptions::get()))]
+        pub fn remove_liquidity(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::End,
+                Error::<T>::ProposalAbnormalState
+            );
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            with_transaction_result(|| Self::inner_remove_liquidity(&who, proposal_id, number))?;
+            Self::deposit_event(Event::RemoveLiquidity(
+                who,
+                proposal_id,
+                currency_id,
+                number,
+            ));
+            Ok(().into())
+        }
+
+        /// Buy option currency
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::buy(T::MaxOptions::get()))]
+        pub fn buy(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            let (index, options) = Self::option_index(proposal_id, optional_currency_id)?;
+            let actual_number = with_transaction_result(|| {
+                Self::inner_buy(&who, proposal_id, currency_id, index, &options, number)
+            })?;
+            Self::deposit_event(Event::Buy(
+                who,
+                proposal_id,
+                optional_currency_id,
+                actual_number,
+            ));
+            Ok(().into())
+        }
+
+        /// Sell option currency
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::sell(T::MaxOptions::get()))]
+        pub fn sell(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            let (index, options) = Self::option_index(proposal_id, optional_currency_id)?;
+            let actual_number = with_transaction_result(|| {
+                Self::inner_sell(&who, proposal_id, currency_id, index, &options, number)
+            })?;
+            Self::deposit_event(Event::Sell(
+                who,
+                proposal_id,
+                optional_currency_id,
+                actual_number,
+            ));
+            Ok(().into())
+        }
+
+        /// Settlement option currency, get settlement currency
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::retrieval())]
+        pub fn retrieval(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::End,
+                Error::<T>::ProposalAbnormalState
+            );
+            Self::option_index(proposal_id, optional_currency_id)?;
+            let result_id = <Self as LiquidityCouple<T>>::get_proposal_result(proposal_id)?;
+            let balance =
+                <TokensOf<T> as Tokens<T::AccountId>>::balance(optional_currency_id, &who);
+            let number = if number >= balance { balance } else { number };
+            let number = with_transaction_result(|| -> Result<BalanceOf<T>, DispatchError> {
+                if ProposalInvalid::<T>::contains_key(proposal_id) {
+                    Self::inner_invalid_retrieval(&who, proposal_id, optional_currency_id, number)
+                } else {
+                    Self::inner_retrieval(
+                        &who,
+                        proposal_id,
+                        result_id,
+                        optional_currency_id,
+                        number,
+                    )
+                }
+            })?;
+            Self::deposit_event(Event::Retrieval(who, proposal_id, result_id, number));
+            Ok(().into())
+        }
+
+        /// Set result for proposal
+        ///
+        /// The result is one of the options of the proposal, or `INVALID_RESULT_ID` to void the
+        /// proposal, in which case every option token redeems an equal share of the collateral.
+        ///
+        /// The dispatch origin for this call is `root`.
+        #[pallet::weight(T::WeightInfo::set_result())]
+        pub fn set_result(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            currency_id: CurrencyIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_root(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::WaitingForResults,
+                Error::<T>::ProposalAbnormalState
+            );
+            let invalid = Self::is_invalid_result(proposal_id, currency_id);
+            if !invalid {
+                Self::option_index(proposal_id, currency_id)?;
+            }
+            with_transaction_result(|| {
+                T::Pool::set_proposal_state(proposal_id, ProposalStatus::End)?;
+                Self::insert_result(proposal_id, currency_id, invalid);
+                Self::finally_locked(proposal_id)
+            })?;
+            Self::deposit_event(Event::SetResult(proposal_id, currency_id));
+            Ok(().into())
+        }
+
+        /// Set result for proposal when the state is over
+        ///
+        /// As in `set_result`, `INVALID_RESULT_ID` voids the proposal.
+        ///
+        /// The dispatch origin for this call is `root`.
+        #[pallet::weight(T::WeightInfo::set_result_when_end())]
+        pub fn set_result_when_end(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            currency_id: CurrencyIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_root(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::End,
+                Error::<T>::ProposalAbnormalState
+            );
+            let invalid = Self::is_invalid_result(proposal_id, currency_id);
+            if !invalid {
+                Self::option_index(proposal_id, currency_id)?;
+            }
+            with_transaction_result(|| {
+                Self::insert_result(proposal_id, currency_id, invalid);
+                Self::finally_locked(proposal_id)
+            })?;
+            Self::deposit_event(Event::SetResult(proposal_id, currency_id));
+            Ok(().into())
+        }
+    }
+}
+
+impl<T: Config> LiquiditySubPool<T> for Pallet<T> {
+    fn finally_locked(proposal_id: ProposalIdOf<T>) -> Result<(), DispatchError> {
+        Self::finally_locked(proposal_id)
+    }
+
+    fn state_changed_weight() -> Weight {
+        T::DbWeight::get().reads_writes(3, 3)
+    }
+}
+
+impl<T: Config> LiquidityCouple<T> for Pallet<T> {
+    fn proposal_pair(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<(CurrencyIdOf<T>, CurrencyIdOf<T>), DispatchError> {
+        let options = <Self as LiquidityCouple<T>>::proposal_options(proposal_id)?;
+        Ok((options[0], options[1]))
+    }
+
+    fn proposal_options(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<Vec<CurrencyIdOf<T>>, DispatchError> {
+        match PoolOptions::<T>::get(proposal_id) {
+            Some(options) => Ok(options),
+            None => Err(Error::<T>::ProposalIdNotExist.into()),
+        }
+    }
+
+    fn proposal_invalid_result(proposal_id: ProposalIdOf<T>) -> Option<CurrencyIdOf<T>> {
+        if !PoolOptions::<T>::contains_key(proposal_id) {
+            return None;
+        }
+        Some(INVALID_RESULT_ID.into())
+    }
+
+    fn set_proposal_result(
+        proposal_id: ProposalIdOf<T>,
+        result: CurrencyIdOf<T>,
+    ) -> Result<(), DispatchError> {
+        match Self::set_result(RawOrigin::Root.into(), proposal_id, result) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.error),
+        }
+    }
+
+    fn set_proposal_result_when_end(
+        proposal_id: ProposalIdOf<T>,
+        result: CurrencyIdOf<T>,
+    ) -> Result<(), DispatchError> {
+        match Self::set_result_when_end(RawOrigin::Root.into(), proposal_id, result) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.error),
+        }
+    }
+
+    fn get_proposal_result(proposal_id: ProposalIdOf<T>) -> Result<CurrencyIdOf<T>, DispatchError> {
+        if ProposalInvalid::<T>::contains_key(proposal_id) {
+            return match Self::proposal_invalid_result(proposal_id) {
+                Some(result) => Ok(result),
+                None => Err(Error::<T>::ProposalIdNotExist.into()),
+            };
+        }
+        match ProposalResult::<T>::get(proposal_id) {
+            Some(result) => Ok(result),
+            None => Err(Error::<T>::ProposalNotResult.into()),
+        }
+    }
+
+    fn proposal_liquidate_currency_id(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<CurrencyIdOf<T>, DispatchError> {
+        match ProposalLiquidateCurrencyId::<T>::get(proposal_id) {
+            Some(id) => Ok(id),
+            None => Err(Error::<T>::ProposalIdNotExist.into()),
+        }
+    }
+}
+
+impl<T: Config> LiquidityEngine<T> for Pallet<T> {
+    fn version_id() -> VersionIdOf<T> {
+        T::CurrentLiquidateVersionId::get()
+    }
+}
+
+impl<T: Config> ProposalArchive<T> for Pallet<T> {
+    fn is_drained(proposal_id: ProposalIdOf<T>) -> bool {
+        Self::is_drained(proposal_id)
+    }
+
+    fn archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32 {
+        Self::inner_archive(proposal_id, limit)
+    }
+}
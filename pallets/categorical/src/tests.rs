@@ -0,0 +1,354 @@
+use crate::{mock::*, Error};
+
+use frame_support::{assert_noop, assert_ok, traits::Time};
+use xpmrl_traits::{
+    couple::{LiquidityCouple, INVALID_RESULT_ID},
+    pool::LiquidityPool,
+    ProposalStatus as ProposalState,
+};
+
+fn create_proposal(
+    account: AccountId,
+    currency_id: CurrencyIdOf<Test>,
+    number: BalanceOf<Test>,
+    rate: u32,
+    step: MomentOf<Test>,
+) -> ProposalIdOf<Test> {
+    create_proposal_with(step, |close_time| {
+        CategoricalModule::new_proposal(
+            Origin::signed(account),
+            "who will win the league".as_bytes().to_vec(),
+            vec![
+                "the first one".as_bytes().to_vec(),
+                "the second one".as_bytes().to_vec(),
+                "the third one".as_bytes().to_vec(),
+            ],
+            close_time,
+            1,
+            currency_id,
+            number,
+            rate,
+            "proposal detail".as_bytes().to_vec(),
+            resolution(close_time),
+        )
+    })
+}
+
+#[test]
+fn test_new_proposal() {
+    new_test_ext().execute_with(|| {
+        let number: BalanceOf<Test> = 60000;
+        let account: AccountId = 1;
+        assert_noop!(
+            CategoricalModule::new_proposal(
+                Origin::signed(account),
+                "how to test this module".as_bytes().to_vec(),
+                vec!["only one".as_bytes().to_vec()],
+                10,
+                1,
+                1,
+                number,
+                2000,
+                "proposal detail".as_bytes().to_vec(),
+                resolution(10),
+            ),
+            Error::<Test>::TooFewOptions
+        );
+        assert_noop!(
+            CategoricalModule::new_proposal(
+                Origin::signed(account),
+                "how to test this module".as_bytes().to_vec(),
+                vec!["option".as_bytes().to_vec(); 9],
+                10,
+                1,
+                1,
+                number,
+                2000,
+                "proposal detail".as_bytes().to_vec(),
+                resolution(10),
+            ),
+            Error::<Test>::TooManyOptions
+        );
+
+        let now = <Timestamp as Time>::now();
+        let id = create_proposal(account, 1, number, 2000, 10);
+        assert_eq!(CategoricalModule::pool_options(id), Some(vec![3, 4, 5]));
+        assert_eq!(
+            <ProposalsWrapper as LiquidityPool<Test>>::proposal_resolution(id),
+            Ok(resolution(now + 10))
+        );
+        assert_eq!(CategoricalModule::proposal_liquidate_currency_id(id), Some(6));
+        assert_eq!(
+            CategoricalModule::proposal_total_optional_market(id),
+            Some(vec![number, number, number])
+        );
+        assert_eq!(CategoricalModule::proposal_total_market(id), Some(number));
+        assert_eq!(
+            CategoricalModule::proposal_total_market_liquid(id),
+            Some(number)
+        );
+        assert_eq!(XPMRLTokens::free_balance_of(account, 6), Some(number));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(40000));
+    });
+}
+
+#[test]
+fn test_add_liquidity() {
+    new_test_ext().execute_with(|| {
+        let id = create_proposal(1, 1, 60000, 2000, 10);
+        assert_noop!(
+            CategoricalModule::add_liquidity(Origin::signed(2), id, 6800),
+            Error::<Test>::ProposalAbnormalState
+        );
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CategoricalModule::buy(Origin::signed(2), id, 3, 10000));
+        assert_ok!(CategoricalModule::add_liquidity(
+            Origin::signed(2),
+            id,
+            6800
+        ));
+
+        // The pool is [46714, 68000, 68000], so only 4671 of the first option is added
+        assert_eq!(
+            CategoricalModule::proposal_total_optional_market(id),
+            Some(vec![51385, 74800, 74800])
+        );
+        assert_eq!(XPMRLTokens::free_balance_of(2, 3), Some(21286 + 2129));
+        assert_eq!(XPMRLTokens::free_balance_of(2, 6), Some(6000));
+        assert_eq!(
+            CategoricalModule::proposal_total_market_liquid(id),
+            Some(66000)
+        );
+        assert_eq!(CategoricalModule::proposal_total_market(id), Some(74800));
+    });
+}
+
+#[test]
+fn test_buy() {
+    new_test_ext().execute_with(|| {
+        let other_account: AccountId = 2;
+        let id = create_proposal(1, 1, 60000, 2000, 10);
+
+        assert_noop!(
+            CategoricalModule::buy(Origin::signed(other_account), id, 3, 10000),
+            Error::<Test>::ProposalAbnormalState
+        );
+        set_state(id, ProposalState::FormalPrediction);
+        assert_noop!(
+            CategoricalModule::buy(Origin::signed(other_account), id, 6, 10000),
+            Error::<Test>::CurrencyIdNotFound
+        );
+        assert_ok!(CategoricalModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            10000
+        ));
+
+        let buy_event = Event::categorical(crate::Event::Buy(other_account, id, 3, 8000));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == buy_event));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 3), Some(21286));
+        assert_eq!(
+            CategoricalModule::proposal_total_optional_market(id),
+            Some(vec![46714, 68000, 68000])
+        );
+        assert_eq!(CategoricalModule::proposal_total_market_fee(id), Some(2000));
+        assert_eq!(
+            CategoricalModule::proposal_account_info(id, other_account),
+            Some(8000)
+        );
+    });
+}
+
+#[test]
+fn test_sell() {
+    new_test_ext().execute_with(|| {
+        let other_account: AccountId = 2;
+        let id = create_proposal(1, 1, 60000, 2000, 10);
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CategoricalModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            10000
+        ));
+        assert_ok!(CategoricalModule::sell(
+            Origin::signed(other_account),
+            id,
+            3,
+            21286
+        ));
+
+        let sell_event = Event::categorical(crate::Event::Sell(other_account, id, 3, 6400));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == sell_event));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 3), Some(0));
+        assert_eq!(
+            CategoricalModule::proposal_total_optional_market(id),
+            Some(vec![60001, 60001, 60001])
+        );
+        assert_eq!(CategoricalModule::proposal_total_market_fee(id), Some(3599));
+        assert_eq!(
+            XPMRLTokens::free_balance_of(other_account, 1),
+            Some(31250 - 10000 + 6400)
+        );
+    });
+}
+
+#[test]
+fn test_remove_liquidity_and_retrieval() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let id = create_proposal(account, 1, 60000, 2000, 10);
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CategoricalModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            10000
+        ));
+        assert_noop!(
+            CategoricalModule::remove_liquidity(Origin::signed(account), id, 60000),
+            Error::<Test>::ProposalAbnormalState
+        );
+        set_state(id, ProposalState::WaitingForResults);
+        assert_noop!(
+            CategoricalModule::set_result(Origin::root(), id, 6),
+            Error::<Test>::CurrencyIdNotFound
+        );
+        assert_ok!(CategoricalModule::set_result(Origin::root(), id, 3));
+        assert_eq!(
+            <ProposalsWrapper as LiquidityPool<Test>>::get_proposal_state(id),
+            Ok(ProposalState::End)
+        );
+
+        assert_ok!(CategoricalModule::remove_liquidity(
+            Origin::signed(account),
+            id,
+            60000
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(88714));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 3), Some(0));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 4), Some(21286));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 5), Some(21286));
+
+        assert_ok!(CategoricalModule::retrieval(
+            Origin::signed(other_account),
+            id,
+            3,
+            21286
+        ));
+        let retrieval_event =
+            Event::categorical(crate::Event::Retrieval(other_account, id, 3, 21180));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == retrieval_event));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 1), Some(42430));
+        assert_eq!(XPMRLTokens::free_balance_of(3, 1), Some(53));
+        assert_eq!(CategoricalModule::proposal_total_autonomy_reward(id), Some(53));
+
+        assert_ok!(CategoricalModule::retrieval(
+            Origin::signed(account),
+            id,
+            4,
+            21286
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 4), Some(0));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(88714));
+    });
+}
+
+#[test]
+fn test_invalid_result() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let id = create_proposal(account, 1, 60000, 2000, 10);
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CategoricalModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            10000
+        ));
+        set_state(id, ProposalState::WaitingForResults);
+        assert_eq!(
+            <CategoricalModule as LiquidityCouple<Test>>::proposal_invalid_result(id),
+            Some(INVALID_RESULT_ID)
+        );
+        assert_noop!(
+            CategoricalModule::set_result(Origin::root(), id, 6),
+            Error::<Test>::CurrencyIdNotFound
+        );
+        assert_ok!(CategoricalModule::set_result(
+            Origin::root(),
+            id,
+            INVALID_RESULT_ID
+        ));
+        assert_eq!(CategoricalModule::proposal_invalid(id), Some(()));
+        assert_eq!(CategoricalModule::proposal_result(id), None);
+        assert_eq!(
+            <CategoricalModule as LiquidityCouple<Test>>::get_proposal_result(id),
+            Ok(INVALID_RESULT_ID)
+        );
+        assert_eq!(CategoricalModule::retrievable(&other_account, id), Ok(7060));
+
+        // Every option token redeems a third of a settlement token
+        assert_ok!(CategoricalModule::retrieval(
+            Origin::signed(other_account),
+            id,
+            3,
+            21286
+        ));
+        let retrieval_event = Event::categorical(crate::Event::Retrieval(
+            other_account,
+            id,
+            INVALID_RESULT_ID,
+            7060,
+        ));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == retrieval_event));
+        assert_eq!(
+            XPMRLTokens::free_balance_of(other_account, 1),
+            Some(31250 - 10000 + 7060)
+        );
+        assert_eq!(
+            CategoricalModule::proposal_account_invalid_remainder(id, other_account),
+            Some(1)
+        );
+
+        assert_ok!(CategoricalModule::remove_liquidity(
+            Origin::signed(account),
+            id,
+            60000
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(88714));
+        assert_eq!(CategoricalModule::retrievable(&account, id), Ok(14120));
+        assert_ok!(CategoricalModule::retrieval(
+            Origin::signed(account),
+            id,
+            4,
+            21286
+        ));
+        assert_ok!(CategoricalModule::retrieval(
+            Origin::signed(account),
+            id,
+            5,
+            21286
+        ));
+        assert_eq!(
+            XPMRLTokens::free_balance_of(account, 1),
+            Some(88714 + 2 * 7060)
+        );
+        assert_eq!(
+            CategoricalModule::proposal_account_invalid_remainder(id, account),
+            Some(2)
+        );
+        assert_eq!(CategoricalModule::proposal_total_market(id), Some(1));
+    });
+}
@@ -0,0 +1,782 @@
+use crate::*;
+
+use frame_support::{
+    ensure,
+    traits::{Get, Time},
+};
+use num_traits::pow::pow;
+use sp_runtime::{
+    traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Saturating, Zero},
+    DispatchError,
+};
+use sp_std::{cmp, vec::Vec};
+use xpmrl_traits::{
+    pool::LiquidityPool, ruler::RulerAccounts, tokens::Tokens, ProposalStatus, Resolution,
+    RulerModule,
+};
+use xpmrl_utils::runtime_format;
+
+impl<T: Config> Pallet<T> {
+    pub(crate) fn option_index(
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+    ) -> Result<(usize, Vec<CurrencyIdOf<T>>), DispatchError> {
+        let options = PoolOptions::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let index = options
+            .iter()
+            .position(|id| *id == optional_currency_id)
+            .ok_or(Error::<T>::CurrencyIdNotFound)?;
+        Ok((index, options))
+    }
+
+    /// `a * b / c`, rounded up
+    pub(crate) fn mul_div_ceil(
+        a: BalanceOf<T>,
+        b: BalanceOf<T>,
+        c: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let product = a.checked_mul(&b).ok_or(Error::<T>::BalanceOverflow)?;
+        let quotient = product.checked_div(&c).ok_or(Error::<T>::BalanceOverflow)?;
+        if (product % c).is_zero() {
+            Ok(quotient)
+        } else {
+            Ok(quotient
+                .checked_add(&One::one())
+                .ok_or(Error::<T>::BalanceOverflow)?)
+        }
+    }
+
+    /// Put `number` of every option into the pool and take the option at `index` out of it
+    ///
+    /// Returns the new pool and the number of options at `index` taken out
+    pub(crate) fn calc_buy(
+        index: usize,
+        number: BalanceOf<T>,
+        pool: &[BalanceOf<T>],
+    ) -> Result<(Vec<BalanceOf<T>>, BalanceOf<T>), DispatchError> {
+        let mut ending = pool[index];
+        let mut new_pool = Vec::with_capacity(pool.len());
+        for (i, balance) in pool.iter().enumerate() {
+            let added = balance
+                .checked_add(&number)
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            if i != index {
+                ending = Self::mul_div_ceil(ending, *balance, added)?;
+            }
+            new_pool.push(added);
+        }
+        let bought = new_pool[index]
+            .checked_sub(&ending)
+            .unwrap_or_else(Zero::zero);
+        new_pool[index] = ending;
+        Ok((new_pool, bought))
+    }
+
+    fn keep_invariant_after_sell(
+        index: usize,
+        number: BalanceOf<T>,
+        amount: BalanceOf<T>,
+        pool: &[BalanceOf<T>],
+    ) -> Result<bool, DispatchError> {
+        let value = pool[index]
+            .checked_add(&number)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let mut value = match value.checked_sub(&amount) {
+            Some(value) => value,
+            None => return Ok(false),
+        };
+        for (i, balance) in pool.iter().enumerate() {
+            if i == index {
+                continue;
+            }
+            let left = match balance.checked_sub(&amount) {
+                Some(left) => left,
+                None => return Ok(false),
+            };
+            value = value
+                .checked_mul(&left)
+                .ok_or(Error::<T>::BalanceOverflow)?
+                .checked_div(balance)
+                .ok_or(Error::<T>::BalanceOverflow)?;
+        }
+        Ok(value >= pool[index])
+    }
+
+    /// Put `number` of the option at `index` into the pool and merge as many complete sets as
+    /// possible out of it without decreasing the product of the pool
+    ///
+    /// Returns the new pool and the number of complete sets merged
+    pub(crate) fn calc_sell(
+        index: usize,
+        number: BalanceOf<T>,
+        pool: &[BalanceOf<T>],
+    ) -> Result<(Vec<BalanceOf<T>>, BalanceOf<T>), DispatchError> {
+        let mut low: BalanceOf<T> = Zero::zero();
+        let mut high = pool[index]
+            .checked_add(&number)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        for (i, balance) in pool.iter().enumerate() {
+            if i != index {
+                high = cmp::min(high, *balance);
+            }
+        }
+        while low < high {
+            let step = high
+                .checked_sub(&low)
+                .unwrap_or_else(Zero::zero)
+                .checked_add(&One::one())
+                .ok_or(Error::<T>::BalanceOverflow)?
+                .checked_div(&2u32.into())
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            let middle = low.checked_add(&step).ok_or(Error::<T>::BalanceOverflow)?;
+            if Self::keep_invariant_after_sell(index, number, middle, pool)? {
+                low = middle;
+            } else {
+                high = middle.checked_sub(&One::one()).unwrap_or_else(Zero::zero);
+            }
+        }
+        let mut new_pool = Vec::with_capacity(pool.len());
+        for (i, balance) in pool.iter().enumerate() {
+            let balance = if i == index {
+                balance
+                    .checked_add(&number)
+                    .ok_or(Error::<T>::BalanceOverflow)?
+            } else {
+                *balance
+            };
+            new_pool.push(balance.checked_sub(&low).unwrap_or_else(Zero::zero));
+        }
+        Ok((new_pool, low))
+    }
+
+    pub(crate) fn get_fee_from_total(
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        let fee_decimals: u8 = T::EarnTradingFeeDecimals::get();
+        let one = pow(10u32, fee_decimals.into());
+        let fee_rate = ProposalTotalEarnTradingFee::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        let mut rate = number
+            .checked_mul(&(fee_rate.into()))
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        rate = rate
+            .checked_div(&(one.into()))
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let actual_number = number.checked_sub(&rate).unwrap_or_else(Zero::zero);
+        Ok((actual_number, rate))
+    }
+
+    pub(crate) fn get_fee_of_liquid(
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+        total_liquid: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let market_fee = ProposalFinallyMarketFee::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+
+        let decimals = T::EarnTradingFeeDecimals::get();
+        let one = pow(10u32, decimals.into());
+        let liquidity_provider_fee_rate: u32 =
+            ProposalLiquidityProviderFeeRate::<T>::get().unwrap_or_else(Zero::zero);
+
+        let mul_market_fee = market_fee
+            .checked_mul(&number)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let mul_market_fee = mul_market_fee
+            .checked_mul(&liquidity_provider_fee_rate.into())
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let fee = mul_market_fee
+            .checked_div(&total_liquid)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let fee = fee
+            .checked_div(&one.into())
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        Ok(fee)
+    }
+
+    pub(crate) fn get_fee_of_creator(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let owner = T::Pool::proposal_owner(proposal_id)?;
+        if owner == *who && !ProposalOwnerAlreadyWithdrawnFee::<T>::contains_key(proposal_id, &who)
+        {
+            let market_fee =
+                ProposalFinallyMarketFee::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+
+            let decimals = T::EarnTradingFeeDecimals::get();
+            let one = pow(10u32, decimals.into());
+            let liquidity_provider_fee_rate: u32 =
+                ProposalLiquidityProviderFeeRate::<T>::get().unwrap_or_else(Zero::zero);
+
+            let mul_market_fee = market_fee
+                .checked_mul(&liquidity_provider_fee_rate.into())
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            let fee = mul_market_fee
+                .checked_div(&one.into())
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            let fee = market_fee.checked_sub(&fee).unwrap_or_else(Zero::zero);
+            ProposalOwnerAlreadyWithdrawnFee::<T>::insert(proposal_id, &who, fee);
+            Ok(fee)
+        } else {
+            Ok(Zero::zero())
+        }
+    }
+
+    pub(crate) fn get_withdrawal_fee(
+        number: BalanceOf<T>,
+    ) -> (BalanceOf<T>, BalanceOf<T>, BalanceOf<T>) {
+        let rate = ProposalWithdrawalFeeRate::<T>::get().unwrap_or_else(Zero::zero);
+        let decimals: u8 = T::EarnTradingFeeDecimals::get();
+        let scale = pow(10u32, decimals.into());
+        let fee = number.checked_mul(&rate.into()).unwrap_or_else(Zero::zero);
+        let fee = fee.checked_div(&scale.into()).unwrap_or_else(Zero::zero);
+        let number = number.checked_sub(&fee).unwrap_or_else(Zero::zero);
+        let reward = fee.checked_div(&2u32.into()).unwrap_or_else(Zero::zero);
+        let dividends = fee.checked_sub(&reward).unwrap_or_else(Zero::zero);
+        (number, reward, dividends)
+    }
+
+    pub(crate) fn finally_locked(proposal_id: ProposalIdOf<T>) -> Result<(), DispatchError> {
+        let finally_liquid =
+            ProposalTotalMarketLiquid::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        let finally_fee = ProposalTotalMarketFee::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        let finally_optional = ProposalTotalOptionalMarket::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        ProposalFinallyMarketFee::<T>::insert(proposal_id, finally_fee);
+        ProposalFinallyMarketLiquid::<T>::insert(proposal_id, finally_liquid);
+        ProposalFinallyTotalOptionalMarket::<T>::insert(proposal_id, finally_optional);
+        Ok(())
+    }
+
+    pub(crate) fn appropriation(
+        currency_id: CurrencyIdOf<T>,
+        who: &T::AccountId,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        <TokensOf<T> as Tokens<T::AccountId>>::appropriation(currency_id, who, number)
+    }
+
+    pub(crate) fn new_asset(
+        name: Vec<u8>,
+        symbol: Vec<u8>,
+        decimals: u8,
+    ) -> Result<CurrencyIdOf<T>, DispatchError> {
+        <TokensOf<T> as Tokens<T::AccountId>>::new_asset(name, symbol, decimals)
+    }
+
+    pub(crate) fn init_pool(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        title: Vec<u8>,
+        close_time: MomentOf<T>,
+        category_id: T::CategoryId,
+        earn_fee: u32,
+        detail: Vec<u8>,
+        resolution: Resolution<MomentOf<T>>,
+    ) -> Result<ProposalIdOf<T>, DispatchError> {
+        let version: VersionIdOf<T> = T::CurrentLiquidateVersionId::get();
+        Proposals::<T>::insert(
+            proposal_id,
+            Proposal {
+                title,
+                category_id,
+                detail,
+            },
+        );
+        T::Pool::init_proposal(
+            proposal_id,
+            who,
+            ProposalStatus::OriginalPrediction,
+            T::Time::now(),
+            close_time,
+            version,
+        );
+        T::Pool::set_proposal_resolution(proposal_id, resolution)?;
+        ProposalTotalEarnTradingFee::<T>::insert(proposal_id, earn_fee);
+        Ok(proposal_id)
+    }
+
+    pub(crate) fn new_currency(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+        optional: Vec<Vec<u8>>,
+    ) -> Result<ProposalIdOf<T>, DispatchError> {
+        ProposalCurrencyId::<T>::insert(proposal_id, currency_id);
+        <TokensOf<T> as Tokens<T::AccountId>>::donate(currency_id, who, number)?;
+        let decimals = <TokensOf<T> as Tokens<T::AccountId>>::decimals(currency_id)?;
+        let mut options = Vec::with_capacity(optional.len());
+        for (index, name) in optional.into_iter().enumerate() {
+            let symbol = runtime_format!("{:?}-OPT{}", proposal_id, index);
+            let asset_id = Self::new_asset(name, symbol, decimals)?;
+            T::Pool::append_used_currency(asset_id);
+            <TokensOf<T> as Tokens<T::AccountId>>::mint_donate(asset_id, number)?;
+            options.push(asset_id);
+        }
+        let lp_name = runtime_format!("LP-{:?}", proposal_id);
+        let asset_id_lp = Self::new_asset(lp_name.clone(), lp_name, decimals)?;
+        T::Pool::append_used_currency(asset_id_lp);
+
+        ProposalTotalOptionalMarket::<T>::insert(proposal_id, sp_std::vec![number; options.len()]);
+
+        ProposalLiquidateCurrencyId::<T>::insert(proposal_id, asset_id_lp);
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(asset_id_lp, who, number)?;
+
+        PoolOptions::<T>::insert(proposal_id, options);
+        ProposalAccountInfo::<T>::insert(proposal_id, who.clone(), number);
+        ProposalTotalMarket::<T>::insert(proposal_id, number);
+        ProposalTotalMarketLiquid::<T>::insert(proposal_id, number);
+        Ok(proposal_id)
+    }
+
+    pub(crate) fn total_and_account_add(
+        proposal_id: ProposalIdOf<T>,
+        who: &T::AccountId,
+        diff: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        ProposalTotalMarket::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.ok_or(Error::<T>::ProposalIdNotExist)?;
+                *optional = Some(old.checked_add(&diff).ok_or(Error::<T>::BalanceOverflow)?);
+                Ok(())
+            },
+        )?;
+        ProposalAccountInfo::<T>::try_mutate(
+            proposal_id,
+            who,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_add(&diff).ok_or(Error::<T>::BalanceOverflow)?);
+                Ok(())
+            },
+        )
+    }
+
+    pub(crate) fn total_and_account_sub(
+        proposal_id: ProposalIdOf<T>,
+        who: &T::AccountId,
+        diff: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        ProposalTotalMarket::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.ok_or(Error::<T>::ProposalIdNotExist)?;
+                *optional = Some(old.checked_sub(&diff).unwrap_or_else(Zero::zero));
+                Ok(())
+            },
+        )?;
+        ProposalAccountInfo::<T>::try_mutate(
+            proposal_id,
+            who,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_sub(&diff).unwrap_or_else(Zero::zero));
+                Ok(())
+            },
+        )
+    }
+
+    pub(crate) fn market_fee_add(
+        proposal_id: ProposalIdOf<T>,
+        fee: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        ProposalTotalMarketFee::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_add(&fee).ok_or(Error::<T>::BalanceOverflow)?);
+                Ok(())
+            },
+        )
+    }
+
+    pub(crate) fn volume_add(
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        ProposalTotalVolume::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(
+                    old.checked_add(&number)
+                        .ok_or(Error::<T>::BalanceOverflow)?,
+                );
+                Ok(())
+            },
+        )
+    }
+
+    pub(crate) fn inner_add_liquidity(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let liquidate_currency_id = ProposalLiquidateCurrencyId::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        let options = PoolOptions::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let total_liquid =
+            ProposalTotalMarketLiquid::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        <TokensOf<T> as Tokens<T::AccountId>>::donate(currency_id, who, number)?;
+        let liquid = ProposalTotalOptionalMarket::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<BalanceOf<T>, DispatchError> {
+                let pool = optional.as_mut().ok_or(Error::<T>::ProposalIdNotExist)?;
+                let max = pool.iter().copied().max().unwrap_or_else(Zero::zero);
+                for (asset_id, balance) in options.iter().zip(pool.iter_mut()) {
+                    let added = number
+                        .checked_mul(balance)
+                        .ok_or(Error::<T>::BalanceOverflow)?
+                        .checked_div(&max)
+                        .ok_or(Error::<T>::BalanceOverflow)?;
+                    <TokensOf<T> as Tokens<T::AccountId>>::mint_donate(*asset_id, number)?;
+                    Self::appropriation(
+                        *asset_id,
+                        who,
+                        number.checked_sub(&added).unwrap_or_else(Zero::zero),
+                    )?;
+                    *balance = balance
+                        .checked_add(&added)
+                        .ok_or(Error::<T>::BalanceOverflow)?;
+                }
+                Ok(number
+                    .checked_mul(&total_liquid)
+                    .ok_or(Error::<T>::BalanceOverflow)?
+                    .checked_div(&max)
+                    .ok_or(Error::<T>::BalanceOverflow)?)
+            },
+        )?;
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(liquidate_currency_id, who, liquid)?;
+        ProposalTotalMarketLiquid::<T>::insert(
+            proposal_id,
+            total_liquid
+                .checked_add(&liquid)
+                .ok_or(Error::<T>::BalanceOverflow)?,
+        );
+        Self::total_and_account_add(proposal_id, who, number)
+    }
+
+    pub(crate) fn inner_remove_liquidity(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let liquidate_currency_id = ProposalLiquidateCurrencyId::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        let options = PoolOptions::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let finally_pool = ProposalFinallyTotalOptionalMarket::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::burn(liquidate_currency_id, who, number)?;
+        ProposalTotalMarketLiquid::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_sub(&number).unwrap_or_else(Zero::zero));
+                Ok(())
+            },
+        )?;
+        let total_liquid =
+            ProposalFinallyMarketLiquid::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        let fee = Self::get_fee_of_liquid(proposal_id, number, total_liquid)?;
+        let creater_fee = Self::get_fee_of_creator(who, proposal_id)?;
+        let fee = fee
+            .checked_add(&creater_fee)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        ProposalTotalMarketFee::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_sub(&fee).unwrap_or_else(Zero::zero));
+                Ok(())
+            },
+        )?;
+        let shares = ProposalTotalOptionalMarket::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<Vec<BalanceOf<T>>, DispatchError> {
+                let pool = optional.as_mut().ok_or(Error::<T>::ProposalIdNotExist)?;
+                let mut shares = Vec::with_capacity(pool.len());
+                for (balance, finally) in pool.iter_mut().zip(finally_pool.iter()) {
+                    let share = finally
+                        .checked_mul(&number)
+                        .ok_or(Error::<T>::BalanceOverflow)?
+                        .checked_div(&total_liquid)
+                        .ok_or(Error::<T>::BalanceOverflow)?;
+                    let share = cmp::min(share, *balance);
+                    *balance = balance.checked_sub(&share).unwrap_or_else(Zero::zero);
+                    shares.push(share);
+                }
+                Ok(shares)
+            },
+        )?;
+        let min = shares.iter().copied().min().unwrap_or_else(Zero::zero);
+        for asset_id in options.iter() {
+            <TokensOf<T> as Tokens<T::AccountId>>::burn_donate(*asset_id, min)?;
+        }
+        Self::total_and_account_sub(proposal_id, who, min)?;
+        let actual_amount = min.checked_add(&fee).ok_or(Error::<T>::BalanceOverflow)?;
+        Self::appropriation(currency_id, who, actual_amount)?;
+        for (asset_id, share) in options.iter().zip(shares.iter()) {
+            let amount = share.checked_sub(&min).unwrap_or_else(Zero::zero);
+            Self::appropriation(*asset_id, who, amount)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn inner_buy(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        index: usize,
+        options: &[CurrencyIdOf<T>],
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let (actual_number, fee) = Self::get_fee_from_total(proposal_id, number)?;
+        ensure!(
+            actual_number > Zero::zero(),
+            Error::<T>::NumberMustMoreThanZero
+        );
+        <TokensOf<T> as Tokens<T::AccountId>>::donate(currency_id, who, number)?;
+        for asset_id in options.iter() {
+            <TokensOf<T> as Tokens<T::AccountId>>::mint_donate(*asset_id, actual_number)?;
+        }
+        let bought = ProposalTotalOptionalMarket::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<BalanceOf<T>, DispatchError> {
+                let pool = optional.as_mut().ok_or(Error::<T>::ProposalIdNotExist)?;
+                let (new_pool, bought) = Self::calc_buy(index, actual_number, pool)?;
+                *pool = new_pool;
+                Ok(bought)
+            },
+        )?;
+        Self::total_and_account_add(proposal_id, who, actual_number)?;
+        Self::market_fee_add(proposal_id, fee)?;
+        Self::appropriation(options[index], who, bought)?;
+        Self::volume_add(proposal_id, number)?;
+        Ok(actual_number)
+    }
+
+    pub(crate) fn inner_sell(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        index: usize,
+        options: &[CurrencyIdOf<T>],
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        <TokensOf<T> as Tokens<T::AccountId>>::donate(options[index], who, number)?;
+        let merged = ProposalTotalOptionalMarket::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<BalanceOf<T>, DispatchError> {
+                let pool = optional.as_mut().ok_or(Error::<T>::ProposalIdNotExist)?;
+                let (new_pool, merged) = Self::calc_sell(index, number, pool)?;
+                *pool = new_pool;
+                Ok(merged)
+            },
+        )?;
+        for asset_id in options.iter() {
+            <TokensOf<T> as Tokens<T::AccountId>>::burn_donate(*asset_id, merged)?;
+        }
+        Self::volume_add(proposal_id, merged)?;
+        let (actual_number, fee) = Self::get_fee_from_total(proposal_id, merged)?;
+        Self::market_fee_add(proposal_id, fee)?;
+        Self::total_and_account_sub(proposal_id, who, merged)?;
+        Self::appropriation(currency_id, who, actual_number)?;
+        Ok(actual_number)
+    }
+
+    pub(crate) fn inner_retrieval(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        result_id: CurrencyIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        <TokensOf<T> as Tokens<T::AccountId>>::burn(optional_currency_id, who, number)?;
+        if optional_currency_id != result_id {
+            return Ok(Zero::zero());
+        }
+        Self::pay_out(who, proposal_id, number)
+    }
+
+    /// Burn `number` option tokens of an invalid proposal, one settlement token is paid for as
+    /// many option tokens as the proposal has options, whichever options and calls they are
+    /// retrieved in, the rest is kept in `ProposalAccountInvalidRemainder` until the next
+    /// retrieval
+    pub(crate) fn inner_invalid_retrieval(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let options = PoolOptions::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::burn(optional_currency_id, who, number)?;
+        let count: BalanceOf<T> = (options.len() as u32).into();
+        let pooled = ProposalAccountInvalidRemainder::<T>::take(proposal_id, who)
+            .unwrap_or_else(Zero::zero)
+            .checked_add(&number)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let remainder = pooled % count;
+        if !remainder.is_zero() {
+            ProposalAccountInvalidRemainder::<T>::insert(proposal_id, who, remainder);
+        }
+        let share = pooled.checked_div(&count).unwrap_or_else(Zero::zero);
+        Self::pay_out(who, proposal_id, share)
+    }
+
+    /// Pay `number` settlement tokens of the proposal to `who`, net of the withdrawal fee
+    pub(crate) fn pay_out(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        ProposalTotalMarket::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_sub(&number).unwrap_or_else(Zero::zero));
+                Ok(())
+            },
+        )?;
+        let (number, reward, dividends) = Self::get_withdrawal_fee(number);
+        ProposalTotalAutonomyReward::<T>::mutate(proposal_id, |optional| {
+            let old = optional.unwrap_or_else(Zero::zero);
+            *optional = Some(old.checked_add(&reward).unwrap_or_else(Zero::zero));
+        });
+        ProposalCurrentAutonomyReward::<T>::mutate(proposal_id, |optional| {
+            let old = optional.unwrap_or_else(Zero::zero);
+            *optional = Some(old.checked_add(&reward).unwrap_or_else(Zero::zero));
+        });
+        let dividends_account = T::Ruler::get_account(RulerModule::PlatformDividend)?;
+        Self::appropriation(currency_id, &dividends_account, dividends)?;
+        Self::appropriation(currency_id, who, number)
+    }
+
+    /// Whether `currency_id` is the id voted for to settle the proposal as invalid
+    pub(crate) fn is_invalid_result(
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+    ) -> bool {
+        <Self as LiquidityCouple<T>>::proposal_invalid_result(proposal_id) == Some(currency_id)
+    }
+
+    /// Store the result of the proposal, an invalid result is flagged instead of being stored as
+    /// an option of the proposal
+    pub(crate) fn insert_result(
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        invalid: bool,
+    ) {
+        if invalid {
+            ProposalResult::<T>::remove(proposal_id);
+            ProposalInvalid::<T>::insert(proposal_id, ());
+        } else {
+            ProposalInvalid::<T>::remove(proposal_id);
+            ProposalResult::<T>::insert(proposal_id, currency_id);
+        }
+    }
+
+    /// The settlement currency, net of the withdrawal fee, that `who` gets back for
+    /// retrieving the winning option currency of an ended proposal, or all of its option
+    /// currencies if the proposal is invalid
+    pub fn retrievable(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        if T::Pool::get_proposal_state(proposal_id)? != ProposalStatus::End {
+            return Ok(Zero::zero());
+        }
+        if ProposalInvalid::<T>::contains_key(proposal_id) {
+            let options =
+                PoolOptions::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            let pooled = options.iter().fold(
+                ProposalAccountInvalidRemainder::<T>::get(proposal_id, who)
+                    .unwrap_or_else(Zero::zero),
+                |pooled, asset_id| {
+                    pooled.saturating_add(<TokensOf<T> as Tokens<T::AccountId>>::balance(
+                        *asset_id, who,
+                    ))
+                },
+            );
+            let count: BalanceOf<T> = (options.len() as u32).into();
+            let share = pooled.checked_div(&count).unwrap_or_else(Zero::zero);
+            let (number, _, _) = Self::get_withdrawal_fee(share);
+            return Ok(number);
+        }
+        let result_id =
+            ProposalResult::<T>::get(proposal_id).ok_or(Error::<T>::ProposalNotResult)?;
+        let number = <TokensOf<T> as Tokens<T::AccountId>>::balance(result_id, who);
+        let (number, _, _) = Self::get_withdrawal_fee(number);
+        Ok(number)
+    }
+
+    /// Whether every option and LP token of the proposal has been burned, which stays true once
+    /// the storage of the proposal has been removed
+    pub(crate) fn is_drained(proposal_id: ProposalIdOf<T>) -> bool {
+        let mut assets = match PoolOptions::<T>::get(proposal_id) {
+            Some(options) => options,
+            None => return true,
+        };
+        match ProposalLiquidateCurrencyId::<T>::get(proposal_id) {
+            Some(asset_id) => assets.push(asset_id),
+            None => return false,
+        }
+        assets.iter().all(|asset_id| {
+            <TokensOf<T> as Tokens<T::AccountId>>::total_supply(*asset_id).is_zero()
+        })
+    }
+
+    /// Remove at most `limit` storage entries of a drained proposal, the entries of the accounts
+    /// go first and the proposal itself last, its final figures are kept in the event
+    pub(crate) fn inner_archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32 {
+        let mut removed = ProposalAccountInfo::<T>::drain_prefix(proposal_id)
+            .take(limit as usize)
+            .count() as u32;
+        removed += ProposalOwnerAlreadyWithdrawnFee::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += ProposalAccountInvalidRemainder::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        if removed < limit {
+            if let Some(currency_id) = ProposalCurrencyId::<T>::take(proposal_id) {
+                let volume = ProposalTotalVolume::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+                let fee =
+                    ProposalFinallyMarketFee::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+                let liquid =
+                    ProposalFinallyMarketLiquid::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+                let reward =
+                    ProposalTotalAutonomyReward::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+                Proposals::<T>::remove(proposal_id);
+                PoolOptions::<T>::remove(proposal_id);
+                ProposalLiquidateCurrencyId::<T>::remove(proposal_id);
+                ProposalTotalEarnTradingFee::<T>::remove(proposal_id);
+                ProposalResult::<T>::remove(proposal_id);
+                ProposalInvalid::<T>::remove(proposal_id);
+                ProposalTotalMarket::<T>::remove(proposal_id);
+                ProposalTotalOptionalMarket::<T>::remove(proposal_id);
+                ProposalFinallyTotalOptionalMarket::<T>::remove(proposal_id);
+                ProposalTotalMarketFee::<T>::remove(proposal_id);
+                ProposalTotalMarketLiquid::<T>::remove(proposal_id);
+                ProposalCurrentAutonomyReward::<T>::remove(proposal_id);
+                Self::deposit_event(Event::ProposalArchived(
+                    proposal_id,
+                    currency_id,
+                    volume,
+                    fee,
+                    liquid,
+                    reward,
+                ));
+            }
+        }
+        removed
+    }
+}
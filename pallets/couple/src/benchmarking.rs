@@ -0,0 +1,545 @@
+//! Benchmarks for the couple pallet
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::Time;
+use sp_runtime::traits::{One, Saturating, Zero};
+use sp_std::{vec, vec::Vec};
+use xpmrl_traits::{
+    pool::LiquidityPool, tokens::Tokens, ProposalStatus, Resolution, MAX_RESOLUTION_LENGTH,
+};
+
+const INITIAL_BALANCE: u32 = 1_000_000_000;
+const LIQUIDITY: u32 = 100_000_000;
+const ORDER_NUMBER: u32 = 100_000;
+const SELL_PRICE: u32 = 900_000;
+
+fn settlement_currency<T: Config>(who: &T::AccountId) -> CurrencyIdOf<T> {
+    let currency_id = <TokensOf<T> as Tokens<T::AccountId>>::new_asset(
+        b"Bench USD".to_vec(),
+        b"BUSD".to_vec(),
+        6,
+    )
+    .expect("create asset failed");
+    <TokensOf<T> as Tokens<T::AccountId>>::mint(currency_id, who, INITIAL_BALANCE.into())
+        .expect("mint failed");
+    currency_id
+}
+
+fn close_time<T: Config>() -> MomentOf<T> {
+    <TimeOf<T> as Time>::now()
+        .saturating_add(T::Pool::get_proposal_minimum_interval_time())
+        .saturating_add(1_000_000u32.into())
+}
+
+/// The longest resolution accepted for a proposal closing at `time`
+fn resolution<T: Config>(time: MomentOf<T>) -> Resolution<MomentOf<T>> {
+    Resolution {
+        source: vec![b's'; MAX_RESOLUTION_LENGTH],
+        rules: vec![b'r'; MAX_RESOLUTION_LENGTH],
+        time,
+        invalid_condition: vec![b'i'; MAX_RESOLUTION_LENGTH],
+    }
+}
+
+pub(crate) fn create_proposal<T: Config>(who: &T::AccountId) -> ProposalIdOf<T> {
+    let currency_id = settlement_currency::<T>(who);
+    let close_time = close_time::<T>();
+    Pallet::<T>::new_proposal(
+        RawOrigin::Signed(who.clone()).into(),
+        b"benchmark proposal".to_vec(),
+        [b"the one".to_vec(), b"other one".to_vec()],
+        close_time,
+        One::one(),
+        currency_id,
+        LIQUIDITY.into(),
+        2000,
+        b"benchmark proposal detail".to_vec(),
+        resolution::<T>(close_time),
+    )
+    .expect("create proposal failed");
+    T::Pool::max_proposal_id().saturating_sub(One::one())
+}
+
+pub(crate) fn create_scalar_proposal<T: Config>(who: &T::AccountId) -> ProposalIdOf<T> {
+    let currency_id = settlement_currency::<T>(who);
+    let close_time = close_time::<T>();
+    Pallet::<T>::new_scalar_proposal(
+        RawOrigin::Signed(who.clone()).into(),
+        b"benchmark scalar proposal".to_vec(),
+        0u32.into(),
+        1_000u32.into(),
+        close_time,
+        One::one(),
+        currency_id,
+        LIQUIDITY.into(),
+        2000,
+        b"benchmark proposal detail".to_vec(),
+        resolution::<T>(close_time),
+    )
+    .expect("create proposal failed");
+    T::Pool::max_proposal_id().saturating_sub(One::one())
+}
+
+fn trading_proposal<T: Config>(
+    who: &T::AccountId,
+) -> Result<(ProposalIdOf<T>, CurrencyIdOf<T>), &'static str> {
+    let proposal_id = create_proposal::<T>(who);
+    T::Pool::set_proposal_state(proposal_id, ProposalStatus::FormalPrediction)?;
+    let (asset_id_1, _) = PoolPairs::<T>::get(proposal_id).ok_or("proposal not exist")?;
+    Ok((proposal_id, asset_id_1))
+}
+
+fn settle<T: Config>(
+    proposal_id: ProposalIdOf<T>,
+    result_id: CurrencyIdOf<T>,
+) -> Result<(), &'static str> {
+    T::Pool::set_proposal_state(proposal_id, ProposalStatus::WaitingForResults)?;
+    Pallet::<T>::set_result(RawOrigin::Root.into(), proposal_id, result_id).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// An account holding settlement currency of the proposal, all of it approved to `spender`
+fn approved_owner<T: Config>(
+    spender: &T::AccountId,
+    proposal_id: ProposalIdOf<T>,
+) -> Result<T::AccountId, &'static str> {
+    let owner: T::AccountId = account("owner", 0, 0);
+    let currency_id =
+        ProposalCurrencyId::<T>::get(proposal_id).ok_or("proposal currency not exist")?;
+    <TokensOf<T> as Tokens<T::AccountId>>::transfer(
+        currency_id,
+        spender,
+        &owner,
+        LIQUIDITY.into(),
+    )?;
+    <TokensOf<T> as Tokens<T::AccountId>>::approve(currency_id, &owner, spender, LIQUIDITY.into())?;
+    Ok(owner)
+}
+
+/// The `index`th account placing limit orders, holding `ORDER_NUMBER` of `currency_id` from
+/// `who`, as an account can only have `MaxAccountOpenOrders` open orders on a proposal
+fn order_maker<T: Config>(
+    who: &T::AccountId,
+    currency_id: CurrencyIdOf<T>,
+    index: u32,
+) -> Result<T::AccountId, &'static str> {
+    let maker: T::AccountId = account("maker", index, 0);
+    <TokensOf<T> as Tokens<T::AccountId>>::transfer(currency_id, who, &maker, ORDER_NUMBER.into())?;
+    Ok(maker)
+}
+
+/// `n` sell orders of the first option of the proposal, priced out of the reach of the pool
+fn open_sell_orders<T: Config>(
+    who: &T::AccountId,
+    proposal_id: ProposalIdOf<T>,
+    optional_currency_id: CurrencyIdOf<T>,
+    n: u32,
+) -> Result<(), &'static str> {
+    Pallet::<T>::buy(
+        RawOrigin::Signed(who.clone()).into(),
+        proposal_id,
+        optional_currency_id,
+        10_000_000u32.into(),
+        Zero::zero(),
+        None,
+    )
+    .map_err(|e| e.error)?;
+    for index in 0..n {
+        let maker = order_maker::<T>(who, optional_currency_id, index)?;
+        Pallet::<T>::place_order(
+            RawOrigin::Signed(maker).into(),
+            proposal_id,
+            optional_currency_id,
+            OrderSide::Sell,
+            SELL_PRICE.into(),
+            ORDER_NUMBER.into(),
+        )
+        .map_err(|e| e.error)?;
+    }
+    Ok(())
+}
+
+benchmarks! {
+    new_proposal {
+        let caller: T::AccountId = whitelisted_caller();
+        let currency_id = settlement_currency::<T>(&caller);
+        let close_time = close_time::<T>();
+    }: _(
+        RawOrigin::Signed(caller),
+        b"benchmark proposal".to_vec(),
+        [b"the one".to_vec(), b"other one".to_vec()],
+        close_time,
+        One::one(),
+        currency_id,
+        LIQUIDITY.into(),
+        2000,
+        b"benchmark proposal detail".to_vec(),
+        resolution::<T>(close_time)
+    )
+    verify {
+        assert!(PoolPairs::<T>::contains_key(T::Pool::max_proposal_id() - One::one()));
+    }
+
+    new_scalar_proposal {
+        let caller: T::AccountId = whitelisted_caller();
+        let currency_id = settlement_currency::<T>(&caller);
+        let close_time = close_time::<T>();
+    }: _(
+        RawOrigin::Signed(caller),
+        b"benchmark scalar proposal".to_vec(),
+        0u32.into(),
+        1_000u32.into(),
+        close_time,
+        One::one(),
+        currency_id,
+        LIQUIDITY.into(),
+        2000,
+        b"benchmark proposal detail".to_vec(),
+        resolution::<T>(close_time)
+    )
+    verify {
+        let proposal_id = T::Pool::max_proposal_id() - One::one();
+        assert!(ProposalScalarRange::<T>::contains_key(proposal_id));
+    }
+
+    add_liquidity {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, _) = trading_proposal::<T>(&caller)?;
+    }: _(RawOrigin::Signed(caller), proposal_id, LIQUIDITY.into(), None)
+
+    remove_liquidity {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        settle::<T>(proposal_id, asset_id_1)?;
+    }: _(RawOrigin::Signed(caller), proposal_id, LIQUIDITY.into(), Zero::zero(), None)
+
+    buy {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+    }: _(
+        RawOrigin::Signed(caller.clone()),
+        proposal_id,
+        asset_id_1,
+        1_000_000u32.into(),
+        Zero::zero(),
+        None
+    )
+    verify {
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_1, &caller);
+        assert!(balance > Zero::zero());
+    }
+
+    sell {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        Pallet::<T>::buy(
+            RawOrigin::Signed(caller.clone()).into(),
+            proposal_id,
+            asset_id_1,
+            1_000_000u32.into(),
+            Zero::zero(),
+            None,
+        )
+        .map_err(|e| e.error)?;
+        let number = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_1, &caller);
+    }: _(RawOrigin::Signed(caller), proposal_id, asset_id_1, number, Zero::zero(), None)
+
+    buy_exact_out {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+    }: _(
+        RawOrigin::Signed(caller),
+        proposal_id,
+        asset_id_1,
+        1_000_000u32.into(),
+        INITIAL_BALANCE.into(),
+        None
+    )
+
+    sell_exact_out {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        Pallet::<T>::buy(
+            RawOrigin::Signed(caller.clone()).into(),
+            proposal_id,
+            asset_id_1,
+            1_000_000u32.into(),
+            Zero::zero(),
+            None,
+        )
+        .map_err(|e| e.error)?;
+        let max_sold = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_1, &caller);
+    }: _(
+        RawOrigin::Signed(caller),
+        proposal_id,
+        asset_id_1,
+        100_000u32.into(),
+        max_sold,
+        None
+    )
+
+    buy_from {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        let owner = approved_owner::<T>(&caller, proposal_id)?;
+    }: _(
+        RawOrigin::Signed(caller),
+        owner.clone(),
+        proposal_id,
+        asset_id_1,
+        1_000_000u32.into(),
+        Zero::zero(),
+        None
+    )
+    verify {
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_1, &owner);
+        assert!(balance > Zero::zero());
+    }
+
+    sell_from {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        let owner = approved_owner::<T>(&caller, proposal_id)?;
+        Pallet::<T>::buy(
+            RawOrigin::Signed(owner.clone()).into(),
+            proposal_id,
+            asset_id_1,
+            1_000_000u32.into(),
+            Zero::zero(),
+            None,
+        )
+        .map_err(|e| e.error)?;
+        let number = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_1, &owner);
+        <TokensOf<T> as Tokens<T::AccountId>>::approve(asset_id_1, &owner, &caller, number)?;
+    }: _(
+        RawOrigin::Signed(caller),
+        owner.clone(),
+        proposal_id,
+        asset_id_1,
+        number,
+        Zero::zero(),
+        None
+    )
+    verify {
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_1, &owner);
+        assert_eq!(balance, Zero::zero());
+    }
+
+    split {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id, 1_000_000u32.into())
+    verify {
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_1, &caller);
+        assert_eq!(balance, 1_000_000u32.into());
+    }
+
+    merge {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        Pallet::<T>::split(
+            RawOrigin::Signed(caller.clone()).into(),
+            proposal_id,
+            1_000_000u32.into(),
+        )
+        .map_err(|e| e.error)?;
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id, 1_000_000u32.into())
+    verify {
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_1, &caller);
+        assert_eq!(balance, Zero::zero());
+    }
+
+    claim_lp_fees {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        Pallet::<T>::buy(
+            RawOrigin::Signed(caller.clone()).into(),
+            proposal_id,
+            asset_id_1,
+            1_000_000u32.into(),
+            Zero::zero(),
+            None,
+        )
+        .map_err(|e| e.error)?;
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id)
+    verify {
+        let fee = ProposalAccountUnclaimedFee::<T>::get(proposal_id, &caller);
+        assert_eq!(fee, None);
+    }
+
+    retrieval {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        Pallet::<T>::buy(
+            RawOrigin::Signed(caller.clone()).into(),
+            proposal_id,
+            asset_id_1,
+            1_000_000u32.into(),
+            Zero::zero(),
+            None,
+        )
+        .map_err(|e| e.error)?;
+        settle::<T>(proposal_id, asset_id_1)?;
+        let number = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_1, &caller);
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id, asset_id_1, number)
+    verify {
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_1, &caller);
+        assert_eq!(balance, Zero::zero());
+    }
+
+    set_result {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        T::Pool::set_proposal_state(proposal_id, ProposalStatus::WaitingForResults)?;
+    }: _(RawOrigin::Root, proposal_id, asset_id_1)
+    verify {
+        assert_eq!(ProposalResult::<T>::get(proposal_id), Some(asset_id_1));
+    }
+
+    set_result_when_end {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        settle::<T>(proposal_id, asset_id_1)?;
+        let (_, asset_id_2) = PoolPairs::<T>::get(proposal_id).ok_or("proposal not exist")?;
+    }: _(RawOrigin::Root, proposal_id, asset_id_2)
+    verify {
+        assert_eq!(ProposalResult::<T>::get(proposal_id), Some(asset_id_2));
+    }
+
+    set_scalar_result {
+        let caller: T::AccountId = whitelisted_caller();
+        let proposal_id = create_scalar_proposal::<T>(&caller);
+        T::Pool::set_proposal_state(proposal_id, ProposalStatus::FormalPrediction)?;
+        T::Pool::set_proposal_state(proposal_id, ProposalStatus::WaitingForResults)?;
+        let value: BalanceOf<T> = 750u32.into();
+    }: _(RawOrigin::Root, proposal_id, value)
+    verify {
+        assert_eq!(ProposalScalarResult::<T>::get(proposal_id), Some(value));
+    }
+
+    place_order {
+        let n in 1 .. T::MaxOpenOrders::get() - 1;
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        open_sell_orders::<T>(&caller, proposal_id, asset_id_1, n)?;
+        let number: BalanceOf<T> = (ORDER_NUMBER * n).into();
+    }: _(
+        RawOrigin::Signed(caller),
+        proposal_id,
+        asset_id_1,
+        OrderSide::Buy,
+        950_000u32.into(),
+        number
+    )
+    verify {
+        assert!(ProposalOpenOrders::<T>::get(proposal_id).is_empty());
+    }
+
+    cancel_order {
+        let n in 1 .. T::MaxOpenOrders::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        open_sell_orders::<T>(&caller, proposal_id, asset_id_1, n)?;
+        let order_id = NextOrderId::<T>::get() - 1;
+        let maker = Orders::<T>::get(proposal_id, order_id).ok_or("order not exist")?.owner;
+    }: _(RawOrigin::Signed(maker), proposal_id, order_id)
+    verify {
+        assert!(!Orders::<T>::contains_key(proposal_id, order_id));
+    }
+
+    match_orders {
+        let n in 1 .. T::MaxOpenOrders::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or("proposal currency not exist")?;
+        for index in 0..n {
+            let maker = order_maker::<T>(&caller, currency_id, index)?;
+            Pallet::<T>::place_order(
+                RawOrigin::Signed(maker).into(),
+                proposal_id,
+                asset_id_1,
+                OrderSide::Buy,
+                400_000u32.into(),
+                ORDER_NUMBER.into(),
+            )
+            .map_err(|e| e.error)?;
+        }
+        let (_, asset_id_2) = PoolPairs::<T>::get(proposal_id).ok_or("proposal not exist")?;
+        Pallet::<T>::buy(
+            RawOrigin::Signed(caller.clone()).into(),
+            proposal_id,
+            asset_id_2,
+            (3 * LIQUIDITY).into(),
+            Zero::zero(),
+            None,
+        )
+        .map_err(|e| e.error)?;
+    }: _(RawOrigin::Signed(caller), proposal_id, n)
+    verify {
+        assert!(ProposalOpenOrders::<T>::get(proposal_id).is_empty());
+    }
+
+    refund_orders {
+        let n in 1 .. T::MaxOpenOrders::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, asset_id_1) = trading_proposal::<T>(&caller)?;
+        open_sell_orders::<T>(&caller, proposal_id, asset_id_1, n)?;
+        // the orders are refunded when the proposal leaves formal prediction, they are put
+        // back to be refunded by the call
+        let mut orders: Vec<(OrderId, OrderOf<T>)> =
+            Orders::<T>::iter_prefix(proposal_id).collect();
+        orders.sort_by_key(|(order_id, _)| *order_id);
+        T::Pool::set_proposal_state(proposal_id, ProposalStatus::WaitingForResults)?;
+        for (order_id, order) in orders {
+            <TokensOf<T> as Tokens<T::AccountId>>::reserve(
+                asset_id_1,
+                &order.owner,
+                order.reserved,
+            )?;
+            AccountOpenOrders::<T>::mutate(proposal_id, &order.owner, |count| *count += 1);
+            ProposalOpenOrders::<T>::mutate(proposal_id, |ids| ids.push(order_id));
+            Orders::<T>::insert(proposal_id, order_id, order);
+        }
+    }: _(RawOrigin::Signed(caller), proposal_id, n)
+    verify {
+        assert!(ProposalOpenOrders::<T>::get(proposal_id).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_new_proposal::<Test>());
+            assert_ok!(test_benchmark_new_scalar_proposal::<Test>());
+            assert_ok!(test_benchmark_add_liquidity::<Test>());
+            assert_ok!(test_benchmark_remove_liquidity::<Test>());
+            assert_ok!(test_benchmark_buy::<Test>());
+            assert_ok!(test_benchmark_sell::<Test>());
+            assert_ok!(test_benchmark_buy_exact_out::<Test>());
+            assert_ok!(test_benchmark_sell_exact_out::<Test>());
+            assert_ok!(test_benchmark_buy_from::<Test>());
+            assert_ok!(test_benchmark_sell_from::<Test>());
+            assert_ok!(test_benchmark_split::<Test>());
+            assert_ok!(test_benchmark_merge::<Test>());
+            assert_ok!(test_benchmark_claim_lp_fees::<Test>());
+            assert_ok!(test_benchmark_retrieval::<Test>());
+            assert_ok!(test_benchmark_set_result::<Test>());
+            assert_ok!(test_benchmark_set_result_when_end::<Test>());
+            assert_ok!(test_benchmark_set_scalar_result::<Test>());
+            assert_ok!(test_benchmark_place_order::<Test>());
+            assert_ok!(test_benchmark_cancel_order::<Test>());
+            assert_ok!(test_benchmark_match_orders::<Test>());
+            assert_ok!(test_benchmark_refund_orders::<Test>());
+        });
+    }
+}
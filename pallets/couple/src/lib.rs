@@ -18,14 +18,36 @@
 //! who can provide liquidity and participate in the total market pool
 //!
 //! 10% of the transaction fee is proposed by the provider, and 90% is given to the liquidity
-//! provider, and the final transaction fee is allocated according to the proportion of liquidity.
+//! provider. The fee of each trade is shared out among the liquidity tokens held at the time of
+//! the trade, so liquidity added late does not earn the fee of the trades before it, and what was
+//! earned can be claimed at any time with `claim_lp_fees`.
+//!
+//! Liquidity can also be removed while the proposal is in formal prediction. The provider gets
+//! its share of the pool, with the complete sets merged back into settlement currency, together
+//! with the fee it earned so far.
 //!
 //! For the specific rules of buying and selling, please refer to our white paper
 //!
+//! Scalar proposals are settled on a number instead of an option. Their two options are the
+//! LONG and SHORT tokens over a `[min, max]` range, and each side is paid pro rata to where the
+//! result landed in the range, e.g. a result at 75% of the range pays 0.75 per LONG token and
+//! 0.25 per SHORT token.
+//!
+//! One token of each option can be split from, or merged back into, one settlement token at
+//! any time before the proposal ends, which keeps the price of the pool in line with the
+//! collateral.
+//!
+//! Besides the pool, the option tokens can be traded with limit orders. A limit order reserves
+//! what it trades and is matched against the open orders on the other side, then against the
+//! pool while the price of the pool with the trading fee is within the limit. The open orders
+//! are refunded when the proposal leaves formal prediction, and the ones that expire at the
+//! close time of the proposal before that can be refunded with `refund_orders`.
+//!
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::unused_unit)]
+#![allow(clippy::type_complexity)]
 
 pub use pallet::*;
 
@@ -35,14 +57,26 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+
 /// Import macros about storage-related operations
 pub(crate) mod macros;
+pub(crate) mod orders;
 pub(crate) mod tools;
 
-use frame_support::traits::Get;
+use frame_support::{traits::Get, weights::Weight};
 use frame_system::RawOrigin;
 use sp_runtime::DispatchError;
-use xpmrl_traits::{couple::LiquidityCouple, pool::LiquiditySubPool};
+use xpmrl_traits::{
+    couple::{LiquidityCouple, INVALID_RESULT_ID},
+    pool::{LiquiditySubPool, ProposalArchive},
+    registry::LiquidityEngine,
+    tokens::BeforeTransfer,
+};
+
+pub use weights::WeightInfo;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -53,7 +87,7 @@ pub mod pallet {
     use sp_std::vec::Vec;
     use xpmrl_traits::{
         autonomy::Autonomy, pool::LiquidityPool, ruler::RulerAccounts, system::ProposalSystem,
-        tokens::Tokens, ProposalStatus,
+        tokens::Tokens, ProposalStatus, Resolution,
     };
     use xpmrl_utils::with_transaction_result;
 
@@ -71,6 +105,8 @@ pub mod pallet {
         <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::VersionId;
     pub(crate) type TimeOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::Time;
     pub(crate) type MomentOf<T> = <TimeOf<T> as Time>::Moment;
+    pub(crate) type OrderOf<T> =
+        Order<<T as frame_system::Config>::AccountId, CurrencyIdOf<T>, BalanceOf<T>>;
 
     macro_rules! ensure_optional_id_belong_proposal {
         ($id: ident, $proposal_id: ident) => {
@@ -93,6 +129,32 @@ pub mod pallet {
         pub detail: Vec<u8>,
     }
 
+    /// The id of a limit order, unique across the proposals
+    pub type OrderId = u64;
+
+    /// Whether a limit order buys or sells the option currency
+    #[derive(PartialEq, Eq, Clone, Copy, RuntimeDebug, Encode, Decode)]
+    pub enum OrderSide {
+        Buy,
+        Sell,
+    }
+
+    /// A limit order on one of the option currencies of a proposal
+    #[derive(PartialEq, Eq, Clone, RuntimeDebug, Encode, Decode)]
+    pub struct Order<AccountId, CurrencyId, Balance> {
+        pub owner: AccountId,
+        pub optional_currency_id: CurrencyId,
+        pub side: OrderSide,
+        /// The highest price a buy order pays or the lowest price a sell order takes, in
+        /// settlement currency for one whole option token as in `spot_prices`
+        pub price: Balance,
+        /// The option tokens left to fill
+        pub number: Balance,
+        /// The escrow left in reserve, settlement currency for a buy order and option tokens
+        /// for a sell order
+        pub reserved: Balance,
+    }
+
     /// This is the pallet's configuration trait
     ///
     /// Inherited from the proposal pallet, it can use the related functions of the proposal
@@ -112,6 +174,26 @@ pub mod pallet {
 
         #[pallet::constant]
         type CurrentLiquidateVersionId: Get<VersionIdOf<Self>>;
+
+        /// The most limit orders that can be open on a proposal at the same time
+        #[pallet::constant]
+        type MaxOpenOrders: Get<u32>;
+
+        /// The most limit orders that an account can have open on a proposal at the same time
+        #[pallet::constant]
+        type MaxAccountOpenOrders: Get<u32>;
+
+        /// The most open limit orders a pool trade fills after it moved the price of the pool
+        /// across their limit, the rest are left to `match_orders`
+        #[pallet::constant]
+        type MaxTradeMatches: Get<u32>;
+
+        /// The fewest option tokens a limit order can be placed for
+        #[pallet::constant]
+        type MinOrderNumber: Get<BalanceOf<Self>>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
@@ -164,6 +246,40 @@ pub mod pallet {
     pub type ProposalResult<T: Config> =
         StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, CurrencyIdOf<T>, OptionQuery>;
 
+    /// It stores the `[min, max]` range of the scalar proposals, the first option token of their
+    /// pair is LONG and the second one is SHORT
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_scalar_range)]
+    pub type ProposalScalarRange<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, (BalanceOf<T>, BalanceOf<T>), OptionQuery>;
+
+    /// It stores the numeric results of the scalar proposals
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_scalar_result)]
+    pub type ProposalScalarResult<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores the proposals settled as invalid, they have no `ProposalResult` and each of
+    /// their option tokens redeems half of a settlement token
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_invalid)]
+    pub type ProposalInvalid<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, (), OptionQuery>;
+
+    /// It stores the option token of an invalid proposal an account has retrieved without being
+    /// paid for yet, as two of them are needed for one settlement token
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_account_invalid_remainder)]
+    pub type ProposalAccountInvalidRemainder<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
     /// It stores the participating accounts of the proposal and how many settlement tokens it has
     /// deposited into the proposal
     #[pallet::storage]
@@ -227,19 +343,6 @@ pub mod pallet {
     pub type ProposalFinallyMarketLiquid<T: Config> =
         StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
 
-    /// It stores how much commission the provider of the proposal has withdrawn
-    #[pallet::storage]
-    #[pallet::getter(fn proposal_owner_already_withdrawn_fee)]
-    pub type ProposalOwnerAlreadyWithdrawnFee<T: Config> = StorageDoubleMap<
-        _,
-        Blake2_128Concat,
-        ProposalIdOf<T>,
-        Twox64Concat,
-        T::AccountId,
-        BalanceOf<T>,
-        OptionQuery,
-    >;
-
     /// The percentage of the commission that the creator of the proposal can get.
     #[pallet::storage]
     #[pallet::getter(fn proposal_liquidity_provider_fee_rate)]
@@ -266,10 +369,21 @@ pub mod pallet {
     pub type ProposalCurrentAutonomyReward<T: Config> =
         StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
 
-    /// With the withdrawal of users, this value will be updated accordingly. This value is the
-    /// value of the current total pool, so the amount withdrawn by the user is:
-    /// `current rewards` = `total pool` - `current value` * `percentage`
-    /// `percentage` = 1 / `total number of correct votes`
+    /// The trading fee earned by each liquidity token of the proposal since it was created,
+    /// scaled up by `FEE_PER_SHARE_SCALE`
+    ///
+    /// It grows with every trade by the part of the fee that goes to the liquidity providers
+    /// divided by the liquidity at that time.
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_fee_per_share)]
+    pub type ProposalFeePerShare<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// `ProposalFeePerShare` when the fee earned by the account was last brought up to date
+    ///
+    /// The account earns the growth of `ProposalFeePerShare` since then for each liquidity token
+    /// it holds, it is brought up to date whenever its liquidity tokens are minted, burned or
+    /// transferred.
     #[pallet::storage]
     #[pallet::getter(fn proposal_account_reward_start)]
     pub type ProposalAccountRewardStart<T: Config> = StorageDoubleMap<
@@ -282,6 +396,64 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// The trading fee earned by the account in the proposal and not claimed yet, as liquidity
+    /// provider or as the owner of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_account_unclaimed_fee)]
+    pub type ProposalAccountUnclaimedFee<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// The proposal a liquidity token belongs to
+    #[pallet::storage]
+    #[pallet::getter(fn liquidate_currency_proposal_id)]
+    pub type LiquidateCurrencyProposalId<T: Config> =
+        StorageMap<_, Blake2_128Concat, CurrencyIdOf<T>, ProposalIdOf<T>, OptionQuery>;
+
+    /// The id of the next limit order
+    #[pallet::storage]
+    #[pallet::getter(fn next_order_id)]
+    pub type NextOrderId<T: Config> = StorageValue<_, OrderId, ValueQuery>;
+
+    /// The open limit orders of each proposal
+    #[pallet::storage]
+    #[pallet::getter(fn orders)]
+    pub type Orders<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        OrderId,
+        OrderOf<T>,
+        OptionQuery,
+    >;
+
+    /// The ids of the open limit orders of each proposal, oldest first, at most `MaxOpenOrders`
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_open_orders)]
+    pub type ProposalOpenOrders<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, Vec<OrderId>, ValueQuery>;
+
+    /// The number of open limit orders of an account on a proposal, at most
+    /// `MaxAccountOpenOrders`
+    #[pallet::storage]
+    #[pallet::getter(fn account_open_orders)]
+    pub type AccountOpenOrders<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        u32,
+        ValueQuery,
+    >;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig {
         pub liquidity_provider_fee_rate: u32,
@@ -314,12 +486,63 @@ pub mod pallet {
         RemoveLiquidity(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
         Buy(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
         Sell(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        /// A spender bought for an owner out of the allowance of the owner, with the spender
+        /// first
+        BuyFrom(
+            T::AccountId,
+            T::AccountId,
+            ProposalIdOf<T>,
+            CurrencyIdOf<T>,
+            BalanceOf<T>,
+        ),
+        /// A spender sold for an owner out of the allowance of the owner, with the spender first
+        SellFrom(
+            T::AccountId,
+            T::AccountId,
+            ProposalIdOf<T>,
+            CurrencyIdOf<T>,
+            BalanceOf<T>,
+        ),
         /// A liquidation event occurs after the liquidation, and the amount of liquidation will be
         /// included in the event
         Retrieval(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
         SetResult(ProposalIdOf<T>, CurrencyIdOf<T>),
+        SetScalarResult(ProposalIdOf<T>, BalanceOf<T>),
         NewProposal(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>),
         WithdrawalReward(T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
+        /// The storage of a settled proposal was removed, with its settlement currency, total
+        /// volume, final fee, final liquidity and total autonomy reward
+        ProposalArchived(
+            ProposalIdOf<T>,
+            CurrencyIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Settlement currency was split into the same number of tokens of each option
+        Split(T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
+        /// The same number of tokens of each option was merged back into settlement currency
+        Merge(T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
+        /// The trading fee earned in a proposal was claimed, in its settlement currency
+        ClaimLpFees(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        /// A limit order was placed, with its option currency, side, price and number
+        OrderPlaced(
+            T::AccountId,
+            ProposalIdOf<T>,
+            OrderId,
+            CurrencyIdOf<T>,
+            OrderSide,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Part of a limit order was filled, with the option tokens traded and the settlement
+        /// currency paid or received for them
+        OrderFilled(ProposalIdOf<T>, OrderId, BalanceOf<T>, BalanceOf<T>),
+        /// A limit order was cancelled by its owner
+        OrderCancelled(T::AccountId, ProposalIdOf<T>, OrderId),
+        /// A limit order was closed and its escrow returned, because the proposal stopped trading
+        OrderRefunded(T::AccountId, ProposalIdOf<T>, OrderId),
     }
 
     #[pallet::error]
@@ -349,6 +572,46 @@ pub mod pallet {
         ProposalIdOverflow,
         /// What the user uploaded is not the correct result
         UploadedNotResult,
+        /// The minimum of the range of a scalar proposal must be less than the maximum
+        ScalarRangeInvalid,
+        /// The proposal is a scalar proposal, its result is a number
+        ProposalIsScalar,
+        /// The proposal is not a scalar proposal, its result is an option
+        ProposalNotScalar,
+        /// The transaction was included after its deadline
+        DeadlineExpired,
+        /// The amount received is less than the minimum the transactor accepts
+        ReceivedLessThanMinimum,
+        /// The amount paid is more than the maximum the transactor accepts
+        PaidMoreThanMaximum,
+        /// The amount sold is more than the maximum the transactor accepts
+        SoldMoreThanMaximum,
+        /// The exact amount asked for can not be reached with the current pool
+        ExactOutUnreachable,
+        /// The price of a limit order must be more than zero and less than one settlement token
+        OrderPriceInvalid,
+        /// The limit order does not exist or is already closed
+        OrderNotExist,
+        /// Only the owner of the limit order can cancel it
+        NotOrderOwner,
+        /// The proposal already has `MaxOpenOrders` open limit orders
+        TooManyOpenOrders,
+        /// The account already has `MaxAccountOpenOrders` open limit orders on the proposal
+        TooManyAccountOpenOrders,
+        /// A limit order must be for at least `MinOrderNumber` option tokens
+        OrderTooSmall,
+        /// The order id has reached the upper limit
+        OrderIdOverflow,
+        /// Limit orders are only taken before the close time of a proposal in formal prediction
+        OrdersClosed,
+        /// The proposal is still trading, its limit orders can not be refunded
+        OrdersStillOpen,
+        /// The account has not earned any trading fee in the proposal since it last claimed
+        NoFeeToClaim,
+        /// The liquidity left in the pool of a proposal in formal prediction can not all be removed
+        PoolCannotBeEmptied,
+        /// The proposal is traded by another market engine than the couple pool
+        UnsupportedEngine,
     }
 
     #[pallet::hooks]
@@ -358,8 +621,11 @@ pub mod pallet {
     impl<T: Config> Pallet<T> {
         /// Create a new proposal
         ///
+        /// `resolution` states how the question of the proposal resolves, each of its texts is at
+        /// most `MAX_RESOLUTION_LENGTH` long and its time can not be before `close_time`.
+        ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::new_proposal())]
         pub fn new_proposal(
             origin: OriginFor<T>,
             title: Vec<u8>,
@@ -370,21 +636,55 @@ pub mod pallet {
             number: BalanceOf<T>,
             earn_fee: u32,
             detail: Vec<u8>,
+            resolution: Resolution<MomentOf<T>>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
-            ensure!(category_id > Zero::zero(), Error::<T>::CategoryIdNotZero);
-            ensure!(currency_id > Zero::zero(), Error::<T>::TokenIdNotZero);
-            ensure!(number > Zero::zero(), Error::<T>::NumberMustMoreThanZero);
-            let now = <TimeOf<T> as Time>::now();
-            let minimum_interval_time = T::Pool::get_proposal_minimum_interval_time();
-            ensure!(
-                close_time > now + minimum_interval_time,
-                Error::<T>::CloseTimeMustLargeThanNow
-            );
-            ensure!(
-                !T::Pool::is_currency_id_used(currency_id),
-                Error::<T>::CurrencyIdNotAllowed
-            );
+            Self::ensure_new_proposal(close_time, category_id, currency_id, number)?;
+            let proposal_id = with_transaction_result(|| {
+                let proposal_id = T::Pool::get_next_proposal_id()?;
+                Self::init_pool(
+                    &who,
+                    proposal_id,
+                    title,
+                    close_time,
+                    category_id,
+                    earn_fee,
+                    detail,
+                    resolution,
+                )?;
+                Self::new_currency(
+                    &who,
+                    proposal_id,
+                    currency_id,
+                    number,
+                    optional,
+                    ["YES", "NO"],
+                )
+            })?;
+            Self::deposit_event(Event::NewProposal(who, proposal_id, currency_id));
+            Ok(().into())
+        }
+
+        /// Create a new scalar proposal, which is settled on a number in `[min, max]`
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::new_scalar_proposal())]
+        pub fn new_scalar_proposal(
+            origin: OriginFor<T>,
+            title: Vec<u8>,
+            min: BalanceOf<T>,
+            max: BalanceOf<T>,
+            close_time: MomentOf<T>,
+            category_id: CategoryIdOf<T>,
+            currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+            earn_fee: u32,
+            detail: Vec<u8>,
+            resolution: Resolution<MomentOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(min < max, Error::<T>::ScalarRangeInvalid);
+            Self::ensure_new_proposal(close_time, category_id, currency_id, number)?;
             let proposal_id = with_transaction_result(|| {
                 let proposal_id = T::Pool::get_next_proposal_id()?;
                 Self::init_pool(
@@ -395,8 +695,17 @@ pub mod pallet {
                     category_id,
                     earn_fee,
                     detail,
+                    resolution,
                 )?;
-                Self::new_currency(&who, proposal_id, currency_id, number, optional)
+                ProposalScalarRange::<T>::insert(proposal_id, (min, max));
+                Self::new_currency(
+                    &who,
+                    proposal_id,
+                    currency_id,
+                    number,
+                    [b"LONG".to_vec(), b"SHORT".to_vec()],
+                    ["LONG", "SHORT"],
+                )
             })?;
             Self::deposit_event(Event::NewProposal(who, proposal_id, currency_id));
             Ok(().into())
@@ -404,14 +713,19 @@ pub mod pallet {
 
         /// Provide liquidity to proposals
         ///
+        /// The liquidity tokens are minted one to one with `number`, so only a `deadline` after
+        /// which the transaction fails can be given.
+        ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        #[pallet::weight(T::WeightInfo::add_liquidity())]
         pub fn add_liquidity(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
             number: BalanceOf<T>,
+            deadline: Option<MomentOf<T>>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
             let status = T::Pool::get_proposal_state(proposal_id)?;
             ensure!(
                 status == ProposalStatus::FormalPrediction,
@@ -440,17 +754,27 @@ pub mod pallet {
 
         /// Get back your own assets through liquidity
         ///
+        /// The trading fee earned so far and not claimed yet is paid out with it. While the
+        /// proposal is in formal prediction, the share of the pool is paid out without moving the
+        /// price of the pool, the last liquidity can not be removed until the proposal ends.
+        ///
+        /// Fails if less than `min_received` settlement currency would be returned, or if the
+        /// transaction is included after `deadline`.
+        ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        #[pallet::weight(T::WeightInfo::remove_liquidity())]
         pub fn remove_liquidity(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
             number: BalanceOf<T>,
+            min_received: BalanceOf<T>,
+            deadline: Option<MomentOf<T>>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
             let status = T::Pool::get_proposal_state(proposal_id)?;
             ensure!(
-                status == ProposalStatus::End,
+                status == ProposalStatus::FormalPrediction || status == ProposalStatus::End,
                 Error::<T>::ProposalAbnormalState
             );
             let currency_id =
@@ -459,10 +783,22 @@ pub mod pallet {
                 .ok_or(Error::<T>::ProposalIdNotExist)?;
             let (asset_id_1, asset_id_2) =
                 PoolPairs::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
-            let (finally_o1, finally_o2) =
-                ProposalFinallyTotalOptionalMarket::<T>::get(proposal_id)
-                    .ok_or(Error::<T>::ProposalIdNotExist)?;
             with_transaction_result(|| {
+                if status == ProposalStatus::FormalPrediction {
+                    return Self::inner_remove_live_liquidity(
+                        &who,
+                        proposal_id,
+                        currency_id,
+                        liquidate_currency_id,
+                        asset_id_1,
+                        asset_id_2,
+                        number,
+                        min_received,
+                    );
+                }
+                let (finally_o1, finally_o2) =
+                    ProposalFinallyTotalOptionalMarket::<T>::get(proposal_id)
+                        .ok_or(Error::<T>::ProposalIdNotExist)?;
                 Self::inner_remove_liquidity(
                     &who,
                     proposal_id,
@@ -473,6 +809,7 @@ pub mod pallet {
                     number,
                     finally_o1,
                     finally_o2,
+                    min_received,
                 )
             })?;
             Self::deposit_event(Event::RemoveLiquidity(
@@ -486,15 +823,21 @@ pub mod pallet {
 
         /// Buy option currency
         ///
+        /// Fails if less than `min_received` option currency would be received, or if the
+        /// transaction is included after `deadline`.
+        ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+        #[pallet::weight(T::WeightInfo::buy().saturating_add(Pallet::<T>::trade_match_weight()))]
         pub fn buy(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
             optional_currency_id: CurrencyIdOf<T>,
             number: BalanceOf<T>,
+            min_received: BalanceOf<T>,
+            deadline: Option<MomentOf<T>>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
             let status = T::Pool::get_proposal_state(proposal_id)?;
             ensure!(
                 status == ProposalStatus::FormalPrediction,
@@ -505,14 +848,18 @@ pub mod pallet {
             ensure_optional_id_belong_proposal!(optional_currency_id, proposal_id);
             let other_currency = Self::get_other_optional_id(proposal_id, optional_currency_id)?;
             let actual_number = with_transaction_result(|| {
-                Self::inner_buy(
+                let actual_number = Self::inner_buy(
+                    &who,
                     &who,
                     proposal_id,
                     currency_id,
                     optional_currency_id,
                     number,
                     other_currency,
-                )
+                    min_received,
+                )?;
+                Self::match_crossed_orders(proposal_id)?;
+                Ok(actual_number)
             })?;
             Self::deposit_event(Event::Buy(
                 who,
@@ -525,15 +872,21 @@ pub mod pallet {
 
         /// Sell option currency
         ///
+        /// Fails if less than `min_received` settlement currency would be received, or if the
+        /// transaction is included after `deadline`.
+        ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+        #[pallet::weight(T::WeightInfo::sell().saturating_add(Pallet::<T>::trade_match_weight()))]
         pub fn sell(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
             optional_currency_id: CurrencyIdOf<T>,
             number: BalanceOf<T>,
+            min_received: BalanceOf<T>,
+            deadline: Option<MomentOf<T>>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
             let status = T::Pool::get_proposal_state(proposal_id)?;
             ensure!(
                 status == ProposalStatus::FormalPrediction,
@@ -544,14 +897,241 @@ pub mod pallet {
             ensure_optional_id_belong_proposal!(optional_currency_id, proposal_id);
             let other_currency = Self::get_other_optional_id(proposal_id, optional_currency_id)?;
             let actual_number = with_transaction_result(|| {
-                Self::inner_sell(
+                let actual_number = Self::inner_sell(
+                    &who,
                     &who,
                     proposal_id,
                     currency_id,
                     optional_currency_id,
                     number,
                     other_currency,
-                )
+                    min_received,
+                )?;
+                Self::match_crossed_orders(proposal_id)?;
+                Ok(actual_number)
+            })?;
+            Self::deposit_event(Event::Sell(
+                who,
+                proposal_id,
+                optional_currency_id,
+                actual_number,
+            ));
+            Ok(().into())
+        }
+
+        /// Buy option currency for `owner`, paid out of the settlement currency `owner` approved
+        /// to the transactor
+        ///
+        /// The option currency bought goes to `owner`. Fails if less than `min_received` option
+        /// currency would be received, or if the transaction is included after `deadline`.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::buy_from().saturating_add(Pallet::<T>::trade_match_weight()))]
+        pub fn buy_from(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+            min_received: BalanceOf<T>,
+            deadline: Option<MomentOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            ensure_optional_id_belong_proposal!(optional_currency_id, proposal_id);
+            let other_currency = Self::get_other_optional_id(proposal_id, optional_currency_id)?;
+            let actual_number = with_transaction_result(|| {
+                <TokensOf<T> as Tokens<T::AccountId>>::transfer_from_allowance(
+                    currency_id,
+                    &who,
+                    &owner,
+                    &who,
+                    number,
+                )?;
+                let actual_number = Self::inner_buy(
+                    &who,
+                    &owner,
+                    proposal_id,
+                    currency_id,
+                    optional_currency_id,
+                    number,
+                    other_currency,
+                    min_received,
+                )?;
+                Self::match_crossed_orders(proposal_id)?;
+                Ok(actual_number)
+            })?;
+            Self::deposit_event(Event::BuyFrom(
+                who,
+                owner,
+                proposal_id,
+                optional_currency_id,
+                actual_number,
+            ));
+            Ok(().into())
+        }
+
+        /// Sell option currency of `owner`, out of the option currency `owner` approved to the
+        /// transactor
+        ///
+        /// The settlement currency received goes to `owner`. Fails if less than `min_received`
+        /// settlement currency would be received, or if the transaction is included after
+        /// `deadline`.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::sell_from().saturating_add(Pallet::<T>::trade_match_weight()))]
+        pub fn sell_from(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+            min_received: BalanceOf<T>,
+            deadline: Option<MomentOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            ensure_optional_id_belong_proposal!(optional_currency_id, proposal_id);
+            let other_currency = Self::get_other_optional_id(proposal_id, optional_currency_id)?;
+            let actual_number = with_transaction_result(|| {
+                <TokensOf<T> as Tokens<T::AccountId>>::transfer_from_allowance(
+                    optional_currency_id,
+                    &who,
+                    &owner,
+                    &who,
+                    number,
+                )?;
+                let actual_number = Self::inner_sell(
+                    &who,
+                    &owner,
+                    proposal_id,
+                    currency_id,
+                    optional_currency_id,
+                    number,
+                    other_currency,
+                    min_received,
+                )?;
+                Self::match_crossed_orders(proposal_id)?;
+                Ok(actual_number)
+            })?;
+            Self::deposit_event(Event::SellFrom(
+                who,
+                owner,
+                proposal_id,
+                optional_currency_id,
+                actual_number,
+            ));
+            Ok(().into())
+        }
+
+        /// Buy `number` tokens of the option currency
+        ///
+        /// Fails if more than `max_paid` settlement currency would be spent, or if the
+        /// transaction is included after `deadline`.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::buy_exact_out().saturating_add(Pallet::<T>::trade_match_weight()))]
+        pub fn buy_exact_out(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+            max_paid: BalanceOf<T>,
+            deadline: Option<MomentOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            ensure_optional_id_belong_proposal!(optional_currency_id, proposal_id);
+            let other_currency = Self::get_other_optional_id(proposal_id, optional_currency_id)?;
+            let actual_number = with_transaction_result(|| {
+                let (paid, _) =
+                    Self::buy_exact_out_quote(proposal_id, optional_currency_id, number)?;
+                ensure!(paid <= max_paid, Error::<T>::PaidMoreThanMaximum);
+                let actual_number = Self::inner_buy(
+                    &who,
+                    &who,
+                    proposal_id,
+                    currency_id,
+                    optional_currency_id,
+                    paid,
+                    other_currency,
+                    number,
+                )?;
+                Self::match_crossed_orders(proposal_id)?;
+                Ok(actual_number)
+            })?;
+            Self::deposit_event(Event::Buy(
+                who,
+                proposal_id,
+                optional_currency_id,
+                actual_number,
+            ));
+            Ok(().into())
+        }
+
+        /// Sell the option currency for `number` settlement currency
+        ///
+        /// Fails if more than `max_sold` option currency would be sold, or if the transaction
+        /// is included after `deadline`.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::sell_exact_out().saturating_add(Pallet::<T>::trade_match_weight()))]
+        pub fn sell_exact_out(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+            max_sold: BalanceOf<T>,
+            deadline: Option<MomentOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            ensure_optional_id_belong_proposal!(optional_currency_id, proposal_id);
+            let other_currency = Self::get_other_optional_id(proposal_id, optional_currency_id)?;
+            let actual_number = with_transaction_result(|| {
+                let (sold, _) =
+                    Self::sell_exact_out_quote(proposal_id, optional_currency_id, number)?;
+                ensure!(sold <= max_sold, Error::<T>::SoldMoreThanMaximum);
+                let actual_number = Self::inner_sell(
+                    &who,
+                    &who,
+                    proposal_id,
+                    currency_id,
+                    optional_currency_id,
+                    sold,
+                    other_currency,
+                    number,
+                )?;
+                Self::match_crossed_orders(proposal_id)?;
+                Ok(actual_number)
             })?;
             Self::deposit_event(Event::Sell(
                 who,
@@ -562,10 +1142,113 @@ pub mod pallet {
             Ok(().into())
         }
 
+        /// Split `number` settlement currency into `number` tokens of each option
+        ///
+        /// Holding one token of each option is worth one settlement token whatever the result,
+        /// so no fee is taken and the pool is not touched.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::split())]
+        pub fn split(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            ensure!(number > Zero::zero(), Error::<T>::NumberMustMoreThanZero);
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            let (asset_id_1, asset_id_2) =
+                PoolPairs::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            with_transaction_result(|| {
+                Self::inner_split(
+                    &who,
+                    proposal_id,
+                    currency_id,
+                    asset_id_1,
+                    asset_id_2,
+                    number,
+                )
+            })?;
+            Self::deposit_event(Event::Split(who, proposal_id, number));
+            Ok(().into())
+        }
+
+        /// Merge `number` tokens of each option back into `number` settlement currency
+        ///
+        /// Possible from formal prediction until the result is final, the option tokens are then
+        /// retrieved instead. Merging while the result is awaited is safe as one token of each
+        /// option retrieves one settlement token whatever the result, invalid included, so the
+        /// pair is worth the same merged or retrieved. No pair exists before formal prediction.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::merge())]
+        pub fn merge(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                matches!(
+                    status,
+                    ProposalStatus::FormalPrediction
+                        | ProposalStatus::WaitingForResults
+                        | ProposalStatus::ResultAnnouncement
+                ),
+                Error::<T>::ProposalAbnormalState
+            );
+            ensure!(number > Zero::zero(), Error::<T>::NumberMustMoreThanZero);
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            let (asset_id_1, asset_id_2) =
+                PoolPairs::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            with_transaction_result(|| {
+                Self::inner_merge(
+                    &who,
+                    proposal_id,
+                    currency_id,
+                    asset_id_1,
+                    asset_id_2,
+                    number,
+                )
+            })?;
+            Self::deposit_event(Event::Merge(who, proposal_id, number));
+            Ok(().into())
+        }
+
+        /// Claim the trading fee earned in the proposal so far
+        ///
+        /// Liquidity providers earn their part of the fee of every trade pro rata to the
+        /// liquidity tokens they held at the time of the trade, and the owner of the proposal
+        /// earns the rest. Possible in any state of the proposal.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::claim_lp_fees())]
+        pub fn claim_lp_fees(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            let number = with_transaction_result(|| {
+                Self::inner_claim_lp_fees(&who, proposal_id, currency_id)
+            })?;
+            Self::deposit_event(Event::ClaimLpFees(who, proposal_id, currency_id, number));
+            Ok(().into())
+        }
+
         /// Settlement option currency, get settlement currency
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::retrieval())]
         pub fn retrieval(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
@@ -579,23 +1262,64 @@ pub mod pallet {
                 Error::<T>::ProposalAbnormalState
             );
             ensure_optional_id_belong_proposal!(optional_currency_id, proposal_id);
-            let result_id =
-                ProposalResult::<T>::get(proposal_id).ok_or(Error::<T>::ProposalNotResult)?;
             let balance =
                 <TokensOf<T> as Tokens<T::AccountId>>::balance(optional_currency_id, &who);
             ensure!(balance >= Zero::zero(), Error::<T>::InsufficientBalance);
             let number = if number >= balance { balance } else { number };
-            let number = with_transaction_result(|| -> Result<BalanceOf<T>, DispatchError> {
-                Self::inner_retrieval(&who, proposal_id, result_id, optional_currency_id, number)
-            })?;
+            let (result_id, number) = match ProposalScalarRange::<T>::get(proposal_id) {
+                Some(range) => {
+                    let value = ProposalScalarResult::<T>::get(proposal_id)
+                        .ok_or(Error::<T>::ProposalNotResult)?;
+                    let number = with_transaction_result(|| {
+                        Self::inner_scalar_retrieval(
+                            &who,
+                            proposal_id,
+                            optional_currency_id,
+                            number,
+                            range,
+                            value,
+                        )
+                    })?;
+                    (optional_currency_id, number)
+                }
+                None if ProposalInvalid::<T>::contains_key(proposal_id) => {
+                    let result_id = <Self as LiquidityCouple<T>>::get_proposal_result(proposal_id)?;
+                    let number = with_transaction_result(|| {
+                        Self::inner_invalid_retrieval(
+                            &who,
+                            proposal_id,
+                            optional_currency_id,
+                            number,
+                        )
+                    })?;
+                    (result_id, number)
+                }
+                None => {
+                    let result_id = ProposalResult::<T>::get(proposal_id)
+                        .ok_or(Error::<T>::ProposalNotResult)?;
+                    let number = with_transaction_result(|| {
+                        Self::inner_retrieval(
+                            &who,
+                            proposal_id,
+                            result_id,
+                            optional_currency_id,
+                            number,
+                        )
+                    })?;
+                    (result_id, number)
+                }
+            };
             Self::deposit_event(Event::Retrieval(who, proposal_id, result_id, number));
             Ok(().into())
         }
 
         /// Set result for proposal
         ///
+        /// The result is one of the options of the proposal, or `INVALID_RESULT_ID` to void the
+        /// proposal, in which case every option token redeems half of the collateral.
+        ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+        #[pallet::weight(T::WeightInfo::set_result())]
         pub fn set_result(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
@@ -607,10 +1331,17 @@ pub mod pallet {
                 status == ProposalStatus::WaitingForResults,
                 Error::<T>::ProposalAbnormalState
             );
-            ensure_optional_id_belong_proposal!(currency_id, proposal_id);
+            ensure!(
+                !ProposalScalarRange::<T>::contains_key(proposal_id),
+                Error::<T>::ProposalIsScalar
+            );
+            let invalid = Self::is_invalid_result(proposal_id, currency_id);
+            if !invalid {
+                ensure_optional_id_belong_proposal!(currency_id, proposal_id);
+            }
             with_transaction_result(|| {
                 T::Pool::set_proposal_state(proposal_id, ProposalStatus::End)?;
-                ProposalResult::<T>::insert(proposal_id, currency_id);
+                Self::insert_result(proposal_id, currency_id, invalid);
                 Self::finally_locked(proposal_id)
             })?;
             Self::deposit_event(Event::SetResult(proposal_id, currency_id));
@@ -619,8 +1350,10 @@ pub mod pallet {
 
         /// Set result for proposal when the state is over 
         ///
+        /// As in `set_result`, `INVALID_RESULT_ID` voids the proposal.
+        ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+        #[pallet::weight(T::WeightInfo::set_result_when_end())]
         pub fn set_result_when_end(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
@@ -632,14 +1365,157 @@ pub mod pallet {
                 status == ProposalStatus::End,
                 Error::<T>::ProposalAbnormalState
             );
-            ensure_optional_id_belong_proposal!(currency_id, proposal_id);
+            ensure!(
+                !ProposalScalarRange::<T>::contains_key(proposal_id),
+                Error::<T>::ProposalIsScalar
+            );
+            let invalid = Self::is_invalid_result(proposal_id, currency_id);
+            if !invalid {
+                ensure_optional_id_belong_proposal!(currency_id, proposal_id);
+            }
             with_transaction_result(|| {
-                ProposalResult::<T>::insert(proposal_id, currency_id);
+                Self::insert_result(proposal_id, currency_id, invalid);
                 Self::finally_locked(proposal_id)
             })?;
             Self::deposit_event(Event::SetResult(proposal_id, currency_id));
             Ok(().into())
         }
+
+        /// Set the numeric result for a scalar proposal
+        ///
+        /// The value does not have to be in the range of the proposal, it is clamped to it when
+        /// the option tokens are settled.
+        ///
+        /// The dispatch origin for this call is `root`.
+        #[pallet::weight(T::WeightInfo::set_scalar_result())]
+        pub fn set_scalar_result(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            value: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_root(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::WaitingForResults,
+                Error::<T>::ProposalAbnormalState
+            );
+            ensure!(
+                ProposalScalarRange::<T>::contains_key(proposal_id),
+                Error::<T>::ProposalNotScalar
+            );
+            with_transaction_result(|| {
+                T::Pool::set_proposal_state(proposal_id, ProposalStatus::End)?;
+                ProposalScalarResult::<T>::insert(proposal_id, value);
+                Self::finally_locked(proposal_id)
+            })?;
+            Self::deposit_event(Event::SetScalarResult(proposal_id, value));
+            Ok(().into())
+        }
+
+        /// Place a limit order to buy or sell `number` tokens of an option at `price`
+        ///
+        /// A buy order reserves the settlement currency it pays at most, a sell order reserves
+        /// the option tokens. The order is first matched against the open orders on the other
+        /// side, best price first, then against the pool while the price of the pool with the
+        /// trading fee stays within `price`. What is left stays open until it is filled or
+        /// cancelled, or until the proposal closes.
+        ///
+        /// `price` is in settlement currency for one whole option token, as in `spot_prices`.
+        /// An order is for at least `MinOrderNumber` tokens, and an account can have at most
+        /// `MaxAccountOpenOrders` open orders on a proposal.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::place_order(T::MaxOpenOrders::get()))]
+        pub fn place_order(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            side: OrderSide,
+            price: BalanceOf<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(Self::orders_open(proposal_id)?, Error::<T>::OrdersClosed);
+            ensure_optional_id_belong_proposal!(optional_currency_id, proposal_id);
+            ensure!(number > Zero::zero(), Error::<T>::NumberMustMoreThanZero);
+            let unit = Self::settlement_unit(proposal_id)?;
+            ensure!(
+                price > Zero::zero() && price < unit,
+                Error::<T>::OrderPriceInvalid
+            );
+            let order_id = with_transaction_result(|| {
+                Self::inner_place_order(
+                    &who,
+                    proposal_id,
+                    optional_currency_id,
+                    side,
+                    price,
+                    number,
+                )
+            })?;
+            Self::deposit_event(Event::OrderPlaced(
+                who,
+                proposal_id,
+                order_id,
+                optional_currency_id,
+                side,
+                price,
+                number,
+            ));
+            Ok(().into())
+        }
+
+        /// Cancel an open limit order and get back what is left of its escrow
+        ///
+        /// The dispatch origin for this call must be `Signed` by the owner of the order.
+        #[pallet::weight(T::WeightInfo::cancel_order(T::MaxOpenOrders::get()))]
+        pub fn cancel_order(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            order_id: OrderId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let order = Orders::<T>::get(proposal_id, order_id).ok_or(Error::<T>::OrderNotExist)?;
+            ensure!(order.owner == who, Error::<T>::NotOrderOwner);
+            with_transaction_result(|| Self::close_order(proposal_id, order_id, &order))?;
+            Self::deposit_event(Event::OrderCancelled(who, proposal_id, order_id));
+            Ok(().into())
+        }
+
+        /// Fill at most `max` of the open limit orders of a proposal, oldest first, against the
+        /// pool whose price crossed their limit since they were placed
+        ///
+        /// The dispatch origin for this call must be `Signed`.
+        #[pallet::weight(T::WeightInfo::match_orders(*max))]
+        pub fn match_orders(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            max: u32,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_signed(origin)?;
+            ensure!(Self::orders_open(proposal_id)?, Error::<T>::OrdersClosed);
+            let checked = with_transaction_result(|| Self::inner_match_orders(proposal_id, max))?;
+            Ok(Some(T::WeightInfo::match_orders(checked)).into())
+        }
+
+        /// Refund at most `max` of the open limit orders of a proposal that stopped trading,
+        /// because it left formal prediction or reached its close time
+        ///
+        /// The dispatch origin for this call must be `Signed`.
+        #[pallet::weight(T::WeightInfo::refund_orders(*max))]
+        pub fn refund_orders(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            max: u32,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_signed(origin)?;
+            ensure!(
+                !Self::orders_open(proposal_id)?,
+                Error::<T>::OrdersStillOpen
+            );
+            let refunded = with_transaction_result(|| Self::inner_refund_orders(proposal_id, max))?;
+            Ok(Some(T::WeightInfo::refund_orders(refunded)).into())
+        }
     }
 }
 
@@ -647,6 +1523,22 @@ impl<T: Config> LiquiditySubPool<T> for Pallet<T> {
     fn finally_locked(proposal_id: ProposalIdOf<T>) -> Result<(), DispatchError> {
         Self::finally_locked(proposal_id)
     }
+
+    /// The open limit orders are refunded as soon as the proposal leaves formal prediction
+    fn state_changed(
+        proposal_id: ProposalIdOf<T>,
+        new_state: ProposalStatus,
+    ) -> Result<(), DispatchError> {
+        if new_state != ProposalStatus::FormalPrediction {
+            Self::inner_refund_orders(proposal_id, T::MaxOpenOrders::get())?;
+        }
+        Ok(())
+    }
+
+    fn state_changed_weight() -> Weight {
+        T::WeightInfo::refund_orders(T::MaxOpenOrders::get())
+            .saturating_add(T::DbWeight::get().reads_writes(3, 3))
+    }
 }
 
 impl<T: Config> LiquidityCouple<T> for Pallet<T> {
@@ -659,6 +1551,15 @@ impl<T: Config> LiquidityCouple<T> for Pallet<T> {
         }
     }
 
+    fn proposal_invalid_result(proposal_id: ProposalIdOf<T>) -> Option<CurrencyIdOf<T>> {
+        if !PoolPairs::<T>::contains_key(proposal_id)
+            || ProposalScalarRange::<T>::contains_key(proposal_id)
+        {
+            return None;
+        }
+        Some(INVALID_RESULT_ID.into())
+    }
+
     fn set_proposal_result(
         proposal_id: ProposalIdOf<T>,
         result: CurrencyIdOf<T>,
@@ -680,6 +1581,12 @@ impl<T: Config> LiquidityCouple<T> for Pallet<T> {
     }
 
     fn get_proposal_result(proposal_id: ProposalIdOf<T>) -> Result<CurrencyIdOf<T>, DispatchError> {
+        if ProposalInvalid::<T>::contains_key(proposal_id) {
+            return match Self::proposal_invalid_result(proposal_id) {
+                Some(result) => Ok(result),
+                None => Err(Error::<T>::ProposalIdNotExist.into()),
+            };
+        }
         match ProposalResult::<T>::get(proposal_id) {
             Some(result) => Ok(result),
             None => Err(Error::<T>::ProposalNotResult.into()),
@@ -694,4 +1601,81 @@ impl<T: Config> LiquidityCouple<T> for Pallet<T> {
             None => Err(Error::<T>::ProposalIdNotExist.into()),
         }
     }
+
+    fn proposal_scalar_range(proposal_id: ProposalIdOf<T>) -> Option<(BalanceOf<T>, BalanceOf<T>)> {
+        ProposalScalarRange::<T>::get(proposal_id)
+    }
+
+    fn set_proposal_scalar_result(
+        proposal_id: ProposalIdOf<T>,
+        value: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        match Self::set_scalar_result(RawOrigin::Root.into(), proposal_id, value) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.error),
+        }
+    }
+
+    fn get_proposal_scalar_result(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        match ProposalScalarResult::<T>::get(proposal_id) {
+            Some(value) => Ok(value),
+            None => Err(Error::<T>::ProposalNotResult.into()),
+        }
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn new_benchmark_proposal(
+        who: &T::AccountId,
+        scalar: bool,
+    ) -> Result<ProposalIdOf<T>, DispatchError> {
+        if scalar {
+            Ok(benchmarking::create_scalar_proposal::<T>(who))
+        } else {
+            Ok(benchmarking::create_proposal::<T>(who))
+        }
+    }
+}
+
+impl<T: Config> LiquidityEngine<T> for Pallet<T> {
+    fn version_id() -> VersionIdOf<T> {
+        T::CurrentLiquidateVersionId::get()
+    }
+}
+
+impl<T: Config> BeforeTransfer<T::AccountId, CurrencyIdOf<T>, BalanceOf<T>> for Pallet<T> {
+    fn before_transfer(
+        currency_id: CurrencyIdOf<T>,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        _number: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        if let Some(proposal_id) = LiquidateCurrencyProposalId::<T>::get(currency_id) {
+            Self::update_account_fee(proposal_id, from)?;
+            Self::update_account_fee(proposal_id, to)?;
+        }
+        Ok(())
+    }
+
+    fn before_mint_or_burn(
+        currency_id: CurrencyIdOf<T>,
+        who: &T::AccountId,
+        _number: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        if let Some(proposal_id) = LiquidateCurrencyProposalId::<T>::get(currency_id) {
+            Self::update_account_fee(proposal_id, who)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Config> ProposalArchive<T> for Pallet<T> {
+    fn is_drained(proposal_id: ProposalIdOf<T>) -> bool {
+        Self::is_drained(proposal_id)
+    }
+
+    fn archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32 {
+        Self::inner_archive(proposal_id, limit)
+    }
 }
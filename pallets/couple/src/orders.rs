@@ -0,0 +1,471 @@
+use crate::*;
+
+use frame_support::{
+    ensure,
+    traits::{Get, Time},
+};
+use num_traits::pow::pow;
+use sp_runtime::{
+    traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, IntegerSquareRoot, One, Zero},
+    DispatchError,
+};
+use sp_std::{cmp, vec::Vec};
+use xpmrl_traits::{pool::LiquidityPool, tokens::Tokens, ProposalStatus};
+
+impl<T: Config> Pallet<T> {
+    /// Whether the proposal takes limit orders, which is until its close time in formal
+    /// prediction
+    pub(crate) fn orders_open(proposal_id: ProposalIdOf<T>) -> Result<bool, DispatchError> {
+        let status = T::Pool::get_proposal_state(proposal_id)?;
+        let close_time = T::Pool::proposal_close_time(proposal_id)?;
+        Ok(status == ProposalStatus::FormalPrediction && <TimeOf<T> as Time>::now() < close_time)
+    }
+
+    /// The settlement currency that `number` option tokens are worth at `price`
+    pub(crate) fn order_value(
+        number: BalanceOf<T>,
+        price: BalanceOf<T>,
+        unit: BalanceOf<T>,
+        round_up: bool,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let mut value = number
+            .checked_mul(&price)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        if round_up {
+            value = value
+                .checked_add(&unit)
+                .ok_or(Error::<T>::BalanceOverflow)?
+                .checked_sub(&One::one())
+                .unwrap_or_else(Zero::zero);
+        }
+        Ok(value
+            .checked_div(&unit)
+            .ok_or(Error::<T>::BalanceOverflow)?)
+    }
+
+    pub(crate) fn inner_place_order(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        side: OrderSide,
+        price: BalanceOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<OrderId, DispatchError> {
+        ensure!(
+            number >= T::MinOrderNumber::get(),
+            Error::<T>::OrderTooSmall
+        );
+        let open = ProposalOpenOrders::<T>::get(proposal_id);
+        ensure!(
+            (open.len() as u32) < T::MaxOpenOrders::get(),
+            Error::<T>::TooManyOpenOrders
+        );
+        ensure!(
+            AccountOpenOrders::<T>::get(proposal_id, who) < T::MaxAccountOpenOrders::get(),
+            Error::<T>::TooManyAccountOpenOrders
+        );
+        let unit = Self::settlement_unit(proposal_id)?;
+        let reserved = match side {
+            OrderSide::Buy => Self::order_value(number, price, unit, true)?,
+            OrderSide::Sell => number,
+        };
+        let mut order = Order {
+            owner: who.clone(),
+            optional_currency_id,
+            side,
+            price,
+            number,
+            reserved,
+        };
+        let escrow_currency_id = Self::escrow_currency_id(proposal_id, &order)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::reserve(escrow_currency_id, who, reserved)?;
+        let order_id = NextOrderId::<T>::try_mutate(|next| -> Result<OrderId, DispatchError> {
+            let order_id = *next;
+            *next = order_id.checked_add(1).ok_or(Error::<T>::OrderIdOverflow)?;
+            Ok(order_id)
+        })?;
+        Self::match_with_orders(proposal_id, order_id, &mut order, unit)?;
+        Self::match_with_pool(proposal_id, order_id, &mut order, unit)?;
+        if order.number.is_zero() {
+            Self::release_escrow(proposal_id, &order)?;
+        } else {
+            AccountOpenOrders::<T>::mutate(proposal_id, who, |count| *count += 1);
+            Orders::<T>::insert(proposal_id, order_id, order);
+            ProposalOpenOrders::<T>::mutate(proposal_id, |ids| ids.push(order_id));
+        }
+        Ok(order_id)
+    }
+
+    /// Fill `order` with the open orders on the other side of its option, best price first and
+    /// the oldest first at the same price, each at the price of the open order
+    fn match_with_orders(
+        proposal_id: ProposalIdOf<T>,
+        order_id: OrderId,
+        order: &mut OrderOf<T>,
+        unit: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        let mut makers: Vec<(OrderId, OrderOf<T>)> = ProposalOpenOrders::<T>::get(proposal_id)
+            .into_iter()
+            .filter_map(|id| Orders::<T>::get(proposal_id, id).map(|maker| (id, maker)))
+            .filter(|(_, maker)| {
+                maker.optional_currency_id == order.optional_currency_id
+                    && match order.side {
+                        OrderSide::Buy => {
+                            maker.side == OrderSide::Sell && maker.price <= order.price
+                        }
+                        OrderSide::Sell => {
+                            maker.side == OrderSide::Buy && maker.price >= order.price
+                        }
+                    }
+            })
+            .collect();
+        match order.side {
+            OrderSide::Buy => makers.sort_by(|a, b| a.1.price.cmp(&b.1.price).then(a.0.cmp(&b.0))),
+            OrderSide::Sell => makers.sort_by(|a, b| b.1.price.cmp(&a.1.price).then(a.0.cmp(&b.0))),
+        }
+        for (maker_id, mut maker) in makers {
+            if order.number.is_zero() {
+                break;
+            }
+            let number = cmp::min(order.number, maker.number);
+            let price = maker.price;
+            let paid = match order.side {
+                OrderSide::Buy => {
+                    Self::settle_match(proposal_id, order, &mut maker, number, price, unit)?
+                }
+                OrderSide::Sell => {
+                    Self::settle_match(proposal_id, &mut maker, order, number, price, unit)?
+                }
+            };
+            Self::deposit_event(Event::OrderFilled(proposal_id, maker_id, number, paid));
+            Self::deposit_event(Event::OrderFilled(proposal_id, order_id, number, paid));
+            if maker.number.is_zero() {
+                Self::close_order(proposal_id, maker_id, &maker)?;
+            } else {
+                Orders::<T>::insert(proposal_id, maker_id, maker);
+            }
+        }
+        Ok(())
+    }
+
+    /// Trade `number` option tokens from the escrow of `sell` for settlement currency from the
+    /// escrow of `buy` at `price`, returns the settlement currency paid
+    fn settle_match(
+        proposal_id: ProposalIdOf<T>,
+        buy: &mut OrderOf<T>,
+        sell: &mut OrderOf<T>,
+        number: BalanceOf<T>,
+        price: BalanceOf<T>,
+        unit: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let paid = cmp::min(Self::order_value(number, price, unit, false)?, buy.reserved);
+        <TokensOf<T> as Tokens<T::AccountId>>::unreserve(currency_id, &buy.owner, paid)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::transfer(
+            currency_id,
+            &buy.owner,
+            &sell.owner,
+            paid,
+        )?;
+        <TokensOf<T> as Tokens<T::AccountId>>::unreserve(
+            sell.optional_currency_id,
+            &sell.owner,
+            number,
+        )?;
+        <TokensOf<T> as Tokens<T::AccountId>>::transfer(
+            sell.optional_currency_id,
+            &sell.owner,
+            &buy.owner,
+            number,
+        )?;
+        buy.number = buy.number.checked_sub(&number).unwrap_or_else(Zero::zero);
+        buy.reserved = buy.reserved.checked_sub(&paid).unwrap_or_else(Zero::zero);
+        sell.number = sell.number.checked_sub(&number).unwrap_or_else(Zero::zero);
+        sell.reserved = sell
+            .reserved
+            .checked_sub(&number)
+            .unwrap_or_else(Zero::zero);
+        ProposalTotalVolume::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_add(&paid).ok_or(Error::<T>::BalanceOverflow)?);
+                Ok(())
+            },
+        )?;
+        Ok(paid)
+    }
+
+    /// Fill `order` with the pool, up to where the price of the pool with the trading fee
+    /// reaches the limit of the order
+    ///
+    /// Along the curve the average price of a trade is between the prices of the pool before
+    /// and after it, so a trade that moves the pool to `price * (1 - fee)` for a buy order, or
+    /// to `price / (1 - fee)` for a sell order, stays within the limit with the fee.
+    pub(crate) fn match_with_pool(
+        proposal_id: ProposalIdOf<T>,
+        order_id: OrderId,
+        order: &mut OrderOf<T>,
+        unit: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        if order.number.is_zero() {
+            return Ok(());
+        }
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let other_currency = Self::get_other_optional_id(proposal_id, order.optional_currency_id)?;
+        let (current_index, old_pair) =
+            Self::trading_pool(proposal_id, order.optional_currency_id)?;
+        let one: BalanceOf<T> = pow(10u32, T::EarnTradingFeeDecimals::get().into()).into();
+        let fee_rate: BalanceOf<T> = ProposalTotalEarnTradingFee::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?
+            .into();
+        let rest = one.checked_sub(&fee_rate).unwrap_or_else(Zero::zero);
+        if rest.is_zero() {
+            return Ok(());
+        }
+        let (numerator, denominator) = match order.side {
+            OrderSide::Buy => (rest, one),
+            OrderSide::Sell => (one, rest),
+        };
+        let target = order
+            .price
+            .checked_mul(&numerator)
+            .ok_or(Error::<T>::BalanceOverflow)?
+            .checked_div(&denominator)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        if target.is_zero() || target >= unit {
+            return Ok(());
+        }
+        let other = old_pair[1 - current_index];
+        let other_target = Self::pool_other_at_price(target, unit, &old_pair)?;
+        let owner = order.owner.clone();
+        match order.side {
+            OrderSide::Buy => {
+                if other_target <= other {
+                    return Ok(());
+                }
+                let actual_number = other_target - other;
+                let received = Self::buy_received(current_index, actual_number, &old_pair)?;
+                let (number, paid) = if received > order.number {
+                    let (paid, _) = Self::buy_exact_out_quote(
+                        proposal_id,
+                        order.optional_currency_id,
+                        order.number,
+                    )?;
+                    (order.number, paid)
+                } else {
+                    let (paid, _) = Self::get_total_from_actual(proposal_id, actual_number)?;
+                    (received, paid)
+                };
+                let limit = Self::order_value(number, order.price, unit, false)?;
+                if number.is_zero() || paid > limit || paid > order.reserved {
+                    return Ok(());
+                }
+                <TokensOf<T> as Tokens<T::AccountId>>::unreserve(currency_id, &owner, paid)?;
+                Self::inner_buy(
+                    &owner,
+                    &owner,
+                    proposal_id,
+                    currency_id,
+                    order.optional_currency_id,
+                    paid,
+                    other_currency,
+                    number,
+                )?;
+                order.number = order.number.checked_sub(&number).unwrap_or_else(Zero::zero);
+                order.reserved = order.reserved.checked_sub(&paid).unwrap_or_else(Zero::zero);
+                Self::deposit_event(Event::OrderFilled(proposal_id, order_id, number, paid));
+            }
+            OrderSide::Sell => {
+                if other_target.is_zero() || other_target >= other {
+                    return Ok(());
+                }
+                let number = cmp::min(
+                    Self::sell_number_for_received(current_index, other - other_target, &old_pair)?,
+                    order.number,
+                );
+                let total = Self::sell_received(current_index, number, &old_pair)?;
+                let (received, _) = Self::get_fee_from_total(proposal_id, total)?;
+                let limit = Self::order_value(number, order.price, unit, true)?;
+                if received.is_zero() || received < limit {
+                    return Ok(());
+                }
+                <TokensOf<T> as Tokens<T::AccountId>>::unreserve(
+                    order.optional_currency_id,
+                    &owner,
+                    number,
+                )?;
+                let received = Self::inner_sell(
+                    &owner,
+                    &owner,
+                    proposal_id,
+                    currency_id,
+                    order.optional_currency_id,
+                    number,
+                    other_currency,
+                    received,
+                )?;
+                order.number = order.number.checked_sub(&number).unwrap_or_else(Zero::zero);
+                order.reserved = order
+                    .reserved
+                    .checked_sub(&number)
+                    .unwrap_or_else(Zero::zero);
+                Self::deposit_event(Event::OrderFilled(proposal_id, order_id, number, received));
+            }
+        }
+        Ok(())
+    }
+
+    /// The other side of the pool at which the option at `current_index` is priced at
+    /// `price`, the pool keeps its product so it is `sqrt(o1 * o2 * price / (unit - price))`
+    fn pool_other_at_price(
+        price: BalanceOf<T>,
+        unit: BalanceOf<T>,
+        old_pair: &[BalanceOf<T>; 2],
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let base = old_pair[0]
+            .checked_mul(&old_pair[1])
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let rest = unit
+            .checked_sub(&price)
+            .ok_or(Error::<T>::OrderPriceInvalid)?;
+        Ok(base
+            .checked_mul(&price)
+            .ok_or(Error::<T>::BalanceOverflow)?
+            .checked_div(&rest)
+            .ok_or(Error::<T>::BalanceOverflow)?
+            .integer_sqrt())
+    }
+
+    /// The currency an order keeps in reserve
+    fn escrow_currency_id(
+        proposal_id: ProposalIdOf<T>,
+        order: &OrderOf<T>,
+    ) -> Result<CurrencyIdOf<T>, DispatchError> {
+        match order.side {
+            OrderSide::Buy => {
+                Ok(ProposalCurrencyId::<T>::get(proposal_id)
+                    .ok_or(Error::<T>::ProposalIdNotExist)?)
+            }
+            OrderSide::Sell => Ok(order.optional_currency_id),
+        }
+    }
+
+    fn release_escrow(
+        proposal_id: ProposalIdOf<T>,
+        order: &OrderOf<T>,
+    ) -> Result<(), DispatchError> {
+        if !order.reserved.is_zero() {
+            let currency_id = Self::escrow_currency_id(proposal_id, order)?;
+            <TokensOf<T> as Tokens<T::AccountId>>::unreserve(
+                currency_id,
+                &order.owner,
+                order.reserved,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Return what is left of the escrow of an open order to its owner and remove the order
+    pub(crate) fn close_order(
+        proposal_id: ProposalIdOf<T>,
+        order_id: OrderId,
+        order: &OrderOf<T>,
+    ) -> Result<(), DispatchError> {
+        Self::release_escrow(proposal_id, order)?;
+        AccountOpenOrders::<T>::mutate_exists(proposal_id, &order.owner, |count| {
+            *count = count
+                .map(|count| count.saturating_sub(1))
+                .filter(|count| *count > 0);
+        });
+        Orders::<T>::remove(proposal_id, order_id);
+        ProposalOpenOrders::<T>::mutate(proposal_id, |ids| ids.retain(|id| *id != order_id));
+        Ok(())
+    }
+
+    /// Fill at most `max` open orders, oldest first, with the pool, returns the orders checked
+    pub(crate) fn inner_match_orders(
+        proposal_id: ProposalIdOf<T>,
+        max: u32,
+    ) -> Result<u32, DispatchError> {
+        let unit = Self::settlement_unit(proposal_id)?;
+        let mut checked = 0u32;
+        for order_id in ProposalOpenOrders::<T>::get(proposal_id)
+            .into_iter()
+            .take(max as usize)
+        {
+            if let Some(mut order) = Orders::<T>::get(proposal_id, order_id) {
+                Self::match_with_pool(proposal_id, order_id, &mut order, unit)?;
+                if order.number.is_zero() {
+                    Self::close_order(proposal_id, order_id, &order)?;
+                } else {
+                    Orders::<T>::insert(proposal_id, order_id, order);
+                }
+            }
+            checked += 1;
+        }
+        Ok(checked)
+    }
+
+    /// Fill at most `MaxTradeMatches` open orders, oldest first, whose limit the pool crossed
+    /// after a pool trade, returns the orders filled
+    ///
+    /// A pool trade only moves the price, the orders it crossed are not filled by the trade
+    /// itself. Those beyond `MaxTradeMatches` are left to `match_orders`.
+    pub(crate) fn match_crossed_orders(proposal_id: ProposalIdOf<T>) -> Result<u32, DispatchError> {
+        let open = ProposalOpenOrders::<T>::get(proposal_id);
+        if open.is_empty() || !Self::orders_open(proposal_id)? {
+            return Ok(0);
+        }
+        let unit = Self::settlement_unit(proposal_id)?;
+        let mut filled = 0u32;
+        for order_id in open {
+            if filled >= T::MaxTradeMatches::get() {
+                break;
+            }
+            if let Some(mut order) = Orders::<T>::get(proposal_id, order_id) {
+                let number = order.number;
+                Self::match_with_pool(proposal_id, order_id, &mut order, unit)?;
+                if order.number == number {
+                    continue;
+                }
+                filled += 1;
+                if order.number.is_zero() {
+                    Self::close_order(proposal_id, order_id, &order)?;
+                } else {
+                    Orders::<T>::insert(proposal_id, order_id, order);
+                }
+            }
+        }
+        Ok(filled)
+    }
+
+    /// The weight a pool trade adds for `match_crossed_orders`, which reads every open order
+    /// and fills at most `MaxTradeMatches` of them
+    pub(crate) fn trade_match_weight() -> Weight {
+        T::WeightInfo::match_orders(T::MaxTradeMatches::get()).saturating_add(
+            T::DbWeight::get().reads((T::MaxOpenOrders::get() as Weight).saturating_add(3)),
+        )
+    }
+
+    /// Close at most `max` open orders, oldest first, returns the orders refunded
+    pub(crate) fn inner_refund_orders(
+        proposal_id: ProposalIdOf<T>,
+        max: u32,
+    ) -> Result<u32, DispatchError> {
+        let mut refunded = 0u32;
+        for order_id in ProposalOpenOrders::<T>::get(proposal_id)
+            .into_iter()
+            .take(max as usize)
+        {
+            if let Some(order) = Orders::<T>::get(proposal_id, order_id) {
+                Self::close_order(proposal_id, order_id, &order)?;
+                Self::deposit_event(Event::OrderRefunded(order.owner, proposal_id, order_id));
+            }
+            refunded += 1;
+        }
+        Ok(refunded)
+    }
+}
@@ -1,7 +1,12 @@
-use crate::{mock::*, Error};
+use crate::{mock::*, Error, Order, OrderSide};
 
 use frame_support::{assert_noop, assert_ok, traits::Time};
-use xpmrl_traits::{pool::LiquidityPool, ProposalStatus as ProposalState};
+use xpmrl_traits::{
+    couple::{LiquidityCouple, INVALID_RESULT_ID},
+    pool::{LiquidityPool, ProposalArchive},
+    tokens::Tokens,
+    ProposalStatus as ProposalState,
+};
 
 fn create_proposal(
     account: AccountId,
@@ -10,23 +15,23 @@ fn create_proposal(
     rate: u32,
     step: MomentOf<Test>,
 ) -> ProposalIdOf<Test> {
-    let now = <Timestamp as Time>::now();
-    let close_time = now + step;
-    assert_ok!(CoupleModule::new_proposal(
-        Origin::signed(account),
-        "how to test this module".as_bytes().to_vec(),
-        [
-            "the one".as_bytes().to_vec(),
-            "other one".as_bytes().to_vec()
-        ],
-        close_time,
-        1,
-        currency_id,
-        number,
-        rate,
-        "proposal detail".as_bytes().to_vec(),
-    ));
-    <ProposalsWrapper as LiquidityPool<Test>>::max_proposal_id() - 1
+    create_proposal_with(step, |close_time| {
+        CoupleModule::new_proposal(
+            Origin::signed(account),
+            "how to test this module".as_bytes().to_vec(),
+            [
+                "the one".as_bytes().to_vec(),
+                "other one".as_bytes().to_vec(),
+            ],
+            close_time,
+            1,
+            currency_id,
+            number,
+            rate,
+            "proposal detail".as_bytes().to_vec(),
+            resolution(close_time),
+        )
+    })
 }
 
 #[test]
@@ -37,7 +42,12 @@ fn test_new_proposal() {
         let fee_rate: u32 = 2000;
         let currency_id: CurrencyIdOf<Test> = 1;
         let account: AccountId = 1;
+        let now = <Timestamp as Time>::now();
         let id = create_proposal(account, currency_id, number, fee_rate, step);
+        assert_eq!(
+            <ProposalsWrapper as LiquidityPool<Test>>::proposal_resolution(id),
+            Ok(resolution(now + step))
+        );
 
         assert_eq!(CoupleModule::pool_pairs(id), Some((3, 4)));
         assert_eq!(CoupleModule::proposal_currency_id(id), Some(currency_id));
@@ -70,23 +80,19 @@ fn test_add_liquidity() {
 
         let next_number: BalanceOf<Test> = 100;
         assert_noop!(
-            CoupleModule::add_liquidity(Origin::signed(other_account), id + 1, next_number),
+            CoupleModule::add_liquidity(Origin::signed(other_account), id + 1, next_number, None),
             Error::<Test>::ProposalIdNotExist
         );
         assert_noop!(
-            CoupleModule::add_liquidity(Origin::signed(other_account), id, next_number),
+            CoupleModule::add_liquidity(Origin::signed(other_account), id, next_number, None),
             Error::<Test>::ProposalAbnormalState
         );
-        assert_ok!(
-            <ProposalsWrapper as LiquidityPool<Test>>::set_proposal_state(
-                id,
-                ProposalState::FormalPrediction
-            )
-        );
+        set_state(id, ProposalState::FormalPrediction);
         assert_ok!(CoupleModule::add_liquidity(
             Origin::signed(other_account),
             id,
-            next_number
+            next_number,
+            None
         ));
         let add_liquidity_event = Event::couple(crate::Event::AddLiquidity(
             other_account,
@@ -127,37 +133,35 @@ fn test_remove_liquidity() {
         let other_account: AccountId = 2;
         let number: BalanceOf<Test> = 100000;
         let id = create_proposal(account, 1, number, 2000, 10);
-        assert_ok!(
-            <ProposalsWrapper as LiquidityPool<Test>>::set_proposal_state(
-                id,
-                ProposalState::FormalPrediction
-            )
-        );
+        set_state(id, ProposalState::FormalPrediction);
         assert_ok!(CoupleModule::buy(
             Origin::signed(other_account),
             id,
             3,
-            31250
+            31250,
+            0,
+            None
         ));
         assert_noop!(
-            CoupleModule::remove_liquidity(Origin::signed(account), id, number),
-            Error::<Test>::ProposalAbnormalState
+            CoupleModule::remove_liquidity(Origin::signed(account), id, number, 0, None),
+            Error::<Test>::PoolCannotBeEmptied
         );
-        assert_ok!(
-            <ProposalsWrapper as LiquidityPool<Test>>::set_proposal_state(
-                id,
-                ProposalState::WaitingForResults
-            )
+        set_state(id, ProposalState::WaitingForResults);
+        assert_noop!(
+            CoupleModule::remove_liquidity(Origin::signed(account), id, number, 0, None),
+            Error::<Test>::ProposalAbnormalState
         );
         assert_ok!(CoupleModule::set_result(Origin::root(), id, 3));
         assert_noop!(
-            CoupleModule::remove_liquidity(Origin::signed(account), 1, number),
+            CoupleModule::remove_liquidity(Origin::signed(account), 1, number, 0, None),
             Error::<Test>::ProposalIdNotExist
         );
         assert_ok!(CoupleModule::remove_liquidity(
             Origin::signed(account),
             id,
-            number
+            number,
+            0,
+            None
         ));
 
         let remove_liquidity_event =
@@ -172,6 +176,178 @@ fn test_remove_liquidity() {
     });
 }
 
+#[test]
+fn test_remove_live_liquidity() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let number: BalanceOf<Test> = 100000;
+        let id = create_proposal(account, 1, number, 2000, 10);
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CoupleModule::add_liquidity(
+            Origin::signed(other_account),
+            id,
+            20000,
+            None
+        ));
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            11250,
+            0,
+            None
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 3), Some(17373));
+        assert_eq!(
+            CoupleModule::proposal_total_optional_market(id),
+            Some((111627, 129000))
+        );
+        assert_eq!(CoupleModule::spot_prices(id), Ok((536099, 463900)));
+
+        assert_noop!(
+            CoupleModule::remove_liquidity(Origin::signed(other_account), id, 20001, 0, None),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_noop!(
+            CoupleModule::remove_liquidity(Origin::signed(other_account), id, 20000, 18942, None),
+            Error::<Test>::ReceivedLessThanMinimum
+        );
+        assert_eq!(
+            CoupleModule::remove_liquidity_quote(&other_account, id, 20000),
+            Ok((18941, 0, 2896, 337))
+        );
+        assert_ok!(CoupleModule::remove_liquidity(
+            Origin::signed(other_account),
+            id,
+            20000,
+            18941,
+            None
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 1), Some(18941));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 4), Some(2896));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 5), Some(0));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(0));
+        assert_eq!(
+            CoupleModule::proposal_account_unclaimed_fee(id, account),
+            Some(225)
+        );
+        assert_eq!(
+            CoupleModule::proposal_total_optional_market(id),
+            Some((93023, 107500))
+        );
+        assert_eq!(CoupleModule::spot_prices(id), Ok((536098, 463901)));
+        assert_eq!(CoupleModule::proposal_total_market_liquid(id), Some(number));
+        assert_eq!(CoupleModule::proposal_total_market_fee(id), Some(1913));
+        assert_eq!(CoupleModule::proposal_total_market(id), Some(110396));
+        assert_noop!(
+            CoupleModule::remove_liquidity(Origin::signed(account), id, number, 0, None),
+            Error::<Test>::PoolCannotBeEmptied
+        );
+
+        set_state(id, ProposalState::WaitingForResults);
+        assert_ok!(CoupleModule::set_result(Origin::root(), id, 3));
+        assert_ok!(CoupleModule::remove_liquidity(
+            Origin::signed(account),
+            id,
+            number,
+            0,
+            None
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(94935));
+        assert_eq!(CoupleModule::proposal_total_market_fee(id), Some(1));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 4), Some(14477));
+        assert_eq!(CoupleModule::proposal_total_market(id), Some(17373));
+        assert_ok!(CoupleModule::retrieval(
+            Origin::signed(other_account),
+            id,
+            3,
+            17373
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 1), Some(36228));
+        assert_eq!(CoupleModule::proposal_total_market(id), Some(0));
+    });
+}
+
+#[test]
+fn test_claim_lp_fees() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let third_account: AccountId = 3;
+        let number: BalanceOf<Test> = 100000;
+        let id = create_proposal(account, 1, number, 2000, 10);
+        assert_noop!(
+            CoupleModule::claim_lp_fees(Origin::signed(account), id),
+            Error::<Test>::NoFeeToClaim
+        );
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            10000,
+            0,
+            None
+        ));
+        assert_eq!(CoupleModule::proposal_fee_per_share(id), Some(18000000000));
+        assert_eq!(
+            CoupleModule::proposal_account_unclaimed_fee(id, account),
+            Some(200)
+        );
+
+        // the liquidity tokens only earn the fee of the trades made while they are held
+        assert_ok!(XPMRLTokens::transfer(
+            Origin::signed(account),
+            5,
+            third_account,
+            50000
+        ));
+        assert_eq!(
+            CoupleModule::proposal_account_unclaimed_fee(id, account),
+            Some(2000)
+        );
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            10000,
+            0,
+            None
+        ));
+        assert_eq!(CoupleModule::proposal_fee_per_share(id), Some(36000000000));
+        assert_eq!(CoupleModule::proposal_total_market_fee(id), Some(4000));
+
+        assert_eq!(CoupleModule::claimable_fee(&third_account, id), Ok(900));
+        // burning the liquidity tokens keeps the fee they earned
+        assert_ok!(XPMRLTokens::burn(Origin::signed(third_account), 5, 50000));
+        assert_eq!(
+            CoupleModule::proposal_account_unclaimed_fee(id, third_account),
+            Some(900)
+        );
+        assert_ok!(CoupleModule::claim_lp_fees(
+            Origin::signed(third_account),
+            id
+        ));
+        let claim_event = Event::couple(crate::Event::ClaimLpFees(third_account, id, 1, 900));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == claim_event));
+        assert_eq!(XPMRLTokens::free_balance_of(third_account, 1), Some(900));
+        assert_noop!(
+            CoupleModule::claim_lp_fees(Origin::signed(third_account), id),
+            Error::<Test>::NoFeeToClaim
+        );
+        assert_ok!(CoupleModule::claim_lp_fees(Origin::signed(account), id));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(3100));
+        assert_eq!(
+            CoupleModule::proposal_account_unclaimed_fee(id, account),
+            None
+        );
+        assert_eq!(CoupleModule::proposal_total_market_fee(id), Some(0));
+    });
+}
+
 #[test]
 fn test_buy() {
     new_test_ext().execute_with(|| {
@@ -179,28 +355,25 @@ fn test_buy() {
         let id = create_proposal(1, 1, 100000, 2000, 10);
 
         assert_noop!(
-            CoupleModule::buy(Origin::signed(other_account), id, 3, 31250),
+            CoupleModule::buy(Origin::signed(other_account), id, 3, 31250, 0, None),
             Error::<Test>::ProposalAbnormalState
         );
         assert_noop!(
-            CoupleModule::buy(Origin::signed(other_account), id + 1, 3, 31250),
+            CoupleModule::buy(Origin::signed(other_account), id + 1, 3, 31250, 0, None),
             Error::<Test>::ProposalIdNotExist
         );
-        assert_ok!(
-            <ProposalsWrapper as LiquidityPool<Test>>::set_proposal_state(
-                id,
-                ProposalState::FormalPrediction
-            )
-        );
+        set_state(id, ProposalState::FormalPrediction);
         assert_noop!(
-            CoupleModule::buy(Origin::signed(other_account), id, 5, 31250),
+            CoupleModule::buy(Origin::signed(other_account), id, 5, 31250, 0, None),
             Error::<Test>::CurrencyIdNotFound
         );
         assert_ok!(CoupleModule::buy(
             Origin::signed(other_account),
             id,
             3,
-            31250
+            31250,
+            0,
+            None
         ));
 
         let buy_event = Event::couple(crate::Event::Buy(other_account, id, 3, 25000));
@@ -229,34 +402,33 @@ fn test_sell() {
         let id = create_proposal(1, 1, number, 2000, 10);
 
         assert_noop!(
-            CoupleModule::sell(Origin::signed(other_account), id, 3, 255),
+            CoupleModule::sell(Origin::signed(other_account), id, 3, 255, 0, None),
             Error::<Test>::ProposalAbnormalState
         );
         assert_noop!(
-            CoupleModule::sell(Origin::signed(other_account), id + 1, 3, 255),
+            CoupleModule::sell(Origin::signed(other_account), id + 1, 3, 255, 0, None),
             Error::<Test>::ProposalIdNotExist
         );
-        assert_ok!(
-            <ProposalsWrapper as LiquidityPool<Test>>::set_proposal_state(
-                id,
-                ProposalState::FormalPrediction
-            )
-        );
+        set_state(id, ProposalState::FormalPrediction);
         assert_noop!(
-            CoupleModule::sell(Origin::signed(other_account), id, 5, 255),
+            CoupleModule::sell(Origin::signed(other_account), id, 5, 255, 0, None),
             Error::<Test>::CurrencyIdNotFound
         );
         assert_ok!(CoupleModule::buy(
             Origin::signed(other_account),
             id,
             3,
-            31250
+            31250,
+            0,
+            None
         ));
         assert_ok!(CoupleModule::sell(
             Origin::signed(other_account),
             id,
             3,
-            45000
+            45000,
+            0,
+            None
         ));
 
         let sell_event = Event::couple(crate::Event::Sell(other_account, id, 3, 20000));
@@ -278,104 +450,1027 @@ fn test_sell() {
 }
 
 #[test]
-fn test_retrieval() {
+fn test_trade_from_allowance() {
     new_test_ext().execute_with(|| {
-        let number: BalanceOf<Test> = 100000;
-        let account: AccountId = 1;
-        let other_account: AccountId = 2;
-        let currency_id: CurrencyIdOf<Test> = 1;
-        let id = create_proposal(account, currency_id, number, 2000, 10);
+        let owner: AccountId = 2;
+        let spender: AccountId = 3;
+        let id = create_proposal(1, 1, 100000, 2000, 10);
+        set_state(id, ProposalState::FormalPrediction);
         assert_noop!(
-            CoupleModule::retrieval(Origin::signed(account), id + 1, 3, number),
-            Error::<Test>::ProposalIdNotExist
+            CoupleModule::buy_from(Origin::signed(spender), owner, id, 3, 31250, 0, None),
+            xpmrl_tokens::Error::<Test>::OriginNotAllowed
         );
+        assert_ok!(XPMRLTokens::approve(
+            Origin::signed(owner),
+            1,
+            spender,
+            31250
+        ));
+        assert_ok!(CoupleModule::buy_from(
+            Origin::signed(spender),
+            owner,
+            id,
+            3,
+            31250,
+            0,
+            None
+        ));
+        let buy_event = Event::couple(crate::Event::BuyFrom(spender, owner, id, 3, 25000));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == buy_event));
+        assert_eq!(XPMRLTokens::free_balance_of(owner, 1), Some(0));
+        assert_eq!(XPMRLTokens::free_balance_of(owner, 3), Some(45000));
+        assert_eq!(XPMRLTokens::free_balance_of(spender, 1), Some(0));
+        assert_eq!(XPMRLTokens::free_balance_of(spender, 3), None);
+        assert_eq!(CoupleModule::proposal_account_info(id, owner), Some(25000));
+        assert_eq!(CoupleModule::proposal_account_info(id, spender), None);
+
         assert_noop!(
-            CoupleModule::retrieval(Origin::signed(account), id, 3, number),
-            Error::<Test>::ProposalAbnormalState
+            CoupleModule::sell_from(Origin::signed(spender), owner, id, 3, 45000, 0, None),
+            xpmrl_tokens::Error::<Test>::OriginNotAllowed
         );
-        assert_ok!(
-            <ProposalsWrapper as LiquidityPool<Test>>::set_proposal_state(id, ProposalState::End)
+        assert_ok!(XPMRLTokens::approve(
+            Origin::signed(owner),
+            3,
+            spender,
+            45000
+        ));
+        assert_ok!(CoupleModule::sell_from(
+            Origin::signed(spender),
+            owner,
+            id,
+            3,
+            45000,
+            0,
+            None
+        ));
+        let sell_event = Event::couple(crate::Event::SellFrom(spender, owner, id, 3, 20000));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == sell_event));
+        assert_eq!(XPMRLTokens::free_balance_of(owner, 1), Some(20000));
+        assert_eq!(XPMRLTokens::free_balance_of(owner, 3), Some(0));
+        assert_eq!(XPMRLTokens::free_balance_of(spender, 3), Some(0));
+        assert_eq!(CoupleModule::proposal_account_info(id, owner), Some(0));
+    });
+}
+
+#[test]
+fn test_slippage_and_deadline() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let number: BalanceOf<Test> = 100000;
+        let id = create_proposal(account, 1, number, 2000, 10);
+        set_state(id, ProposalState::FormalPrediction);
+        System::set_block_number(5);
+        assert_noop!(
+            CoupleModule::add_liquidity(Origin::signed(other_account), id, 100, Some(4)),
+            Error::<Test>::DeadlineExpired
         );
         assert_noop!(
-            CoupleModule::retrieval(Origin::signed(account), id, 3, number),
-            Error::<Test>::ProposalNotResult
+            CoupleModule::buy(Origin::signed(other_account), id, 3, 31250, 0, Some(4)),
+            Error::<Test>::DeadlineExpired
         );
-        assert_ok!(
-            <ProposalsWrapper as LiquidityPool<Test>>::set_proposal_state(
-                id,
-                ProposalState::FormalPrediction
-            )
+        assert_noop!(
+            CoupleModule::buy(Origin::signed(other_account), id, 3, 31250, 45001, None),
+            Error::<Test>::ReceivedLessThanMinimum
         );
         assert_ok!(CoupleModule::buy(
             Origin::signed(other_account),
             id,
             3,
-            31250
+            31250,
+            45000,
+            Some(5)
         ));
-        assert_ok!(
-            <ProposalsWrapper as LiquidityPool<Test>>::set_proposal_state(
-                id,
-                ProposalState::WaitingForResults
-            )
+        assert_noop!(
+            CoupleModule::sell(Origin::signed(other_account), id, 3, 45000, 0, Some(4)),
+            Error::<Test>::DeadlineExpired
+        );
+        assert_noop!(
+            CoupleModule::sell(Origin::signed(other_account), id, 3, 45000, 20001, None),
+            Error::<Test>::ReceivedLessThanMinimum
         );
+
+        set_state(id, ProposalState::WaitingForResults);
         assert_ok!(CoupleModule::set_result(Origin::root(), id, 3));
+        assert_noop!(
+            CoupleModule::remove_liquidity(Origin::signed(account), id, number, 0, Some(4)),
+            Error::<Test>::DeadlineExpired
+        );
+        assert_noop!(
+            CoupleModule::remove_liquidity(Origin::signed(account), id, number, 86251, None),
+            Error::<Test>::ReceivedLessThanMinimum
+        );
         assert_ok!(CoupleModule::remove_liquidity(
             Origin::signed(account),
             id,
-            number
+            number,
+            86250,
+            None
         ));
-        assert_ok!(CoupleModule::retrieval(
-            Origin::signed(account),
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(86250));
+    });
+}
+
+#[test]
+fn test_exact_out() {
+    new_test_ext().execute_with(|| {
+        let other_account: AccountId = 2;
+        let id = create_proposal(1, 1, 100000, 2000, 10);
+        assert_noop!(
+            CoupleModule::buy_exact_out(Origin::signed(other_account), id, 3, 45000, 31250, None),
+            Error::<Test>::ProposalAbnormalState
+        );
+        set_state(id, ProposalState::FormalPrediction);
+        assert_eq!(
+            CoupleModule::buy_exact_out_quote(id, 3, 45000),
+            Ok((31250, 6250))
+        );
+        assert_noop!(
+            CoupleModule::buy_exact_out_quote(id, 5, 45000),
+            Error::<Test>::CurrencyIdNotFound
+        );
+        assert_noop!(
+            CoupleModule::buy_exact_out(Origin::signed(other_account), id, 3, 45000, 31249, None),
+            Error::<Test>::PaidMoreThanMaximum
+        );
+        assert_ok!(CoupleModule::buy_exact_out(
+            Origin::signed(other_account),
             id,
             3,
-            number
+            45000,
+            31250,
+            None
         ));
-        let retrieval_event = Event::couple(crate::Event::Retrieval(account, id, 3, 0));
+        let buy_event = Event::couple(crate::Event::Buy(other_account, id, 3, 25000));
         assert!(System::events()
             .iter()
-            .any(|record| record.event == retrieval_event));
+            .any(|record| record.event == buy_event));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 1), Some(0));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 3), Some(45000));
 
-        assert_ok!(CoupleModule::retrieval(
+        assert_eq!(
+            CoupleModule::sell_exact_out_quote(id, 3, 20000),
+            Ok((45000, 5000))
+        );
+        assert_noop!(
+            CoupleModule::sell_exact_out_quote(id, 3, 100000),
+            Error::<Test>::ExactOutUnreachable
+        );
+        assert_noop!(
+            CoupleModule::sell_exact_out(Origin::signed(other_account), id, 3, 20000, 44999, None),
+            Error::<Test>::SoldMoreThanMaximum
+        );
+        assert_ok!(CoupleModule::sell_exact_out(
             Origin::signed(other_account),
             id,
             3,
-            number
+            20000,
+            45000,
+            None
         ));
-        let retrieval_event = Event::couple(crate::Event::Retrieval(other_account, id, 3, 44775));
+        let sell_event = Event::couple(crate::Event::Sell(other_account, id, 3, 20000));
         assert!(System::events()
             .iter()
-            .any(|record| record.event == retrieval_event));
-
-        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(86250));
-        assert_eq!(XPMRLTokens::free_balance_of(other_account, 1), Some(44775));
-        assert_eq!(CoupleModule::proposal_total_market(0), Some(0));
+            .any(|record| record.event == sell_event));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 1), Some(20000));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 3), Some(0));
+        assert_eq!(
+            CoupleModule::proposal_total_optional_market(id),
+            Some((100000, 100000))
+        );
     });
 }
 
 #[test]
-fn test_set_result() {
+fn test_quote() {
     new_test_ext().execute_with(|| {
-        let id = create_proposal(1, 1, 100000, 200, 10);
-
-        assert_ok!(
-            <ProposalsWrapper as LiquidityPool<Test>>::set_proposal_state(
-                id,
-                ProposalState::WaitingForResults
-            )
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let number: BalanceOf<Test> = 100000;
+        let id = create_proposal(account, 1, number, 2000, 10);
+        set_state(id, ProposalState::FormalPrediction);
+        assert_eq!(CoupleModule::spot_prices(id), Ok((500000, 500000)));
+        assert_eq!(
+            CoupleModule::buy_quote(id, 3, 31250),
+            Ok((45000, 6250, 1111))
         );
         assert_noop!(
-            CoupleModule::set_result(Origin::root(), id, 5),
+            CoupleModule::buy_quote(id, 5, 31250),
             Error::<Test>::CurrencyIdNotFound
         );
-        assert_ok!(CoupleModule::set_result(Origin::root(), id, 3));
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            31250,
+            0,
+            None
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 3), Some(45000));
+        assert_eq!(CoupleModule::spot_prices(id), Ok((609756, 390243)));
+        assert_eq!(
+            CoupleModule::sell_quote(id, 3, 45000),
+            Ok((20000, 5000, 889))
+        );
 
-        let set_result_event = Event::couple(crate::Event::SetResult(id, 3));
-        assert!(System::events()
-            .iter()
-            .any(|record| record.event == set_result_event));
+        set_state(id, ProposalState::WaitingForResults);
+        assert_ok!(CoupleModule::set_result(Origin::root(), id, 3));
         assert_eq!(
-            <ProposalsWrapper as LiquidityPool<Test>>::get_proposal_state(id),
-            Ok(ProposalState::End)
+            CoupleModule::remove_liquidity_quote(&account, id, number),
+            Ok((86250, 0, 45000, 6250))
+        );
+        assert_eq!(
+            CoupleModule::remove_liquidity_quote(&account, id, number),
+            Ok((86250, 0, 45000, 6250))
+        );
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(0));
+        assert_ok!(CoupleModule::remove_liquidity(
+            Origin::signed(account),
+            id,
+            number,
+            86250,
+            None
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(86250));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 4), Some(45000));
+    });
+}
+
+#[test]
+fn test_quote_of_other_engine() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 1;
+        let id = create_proposal(account, 1, 100000, 2000, 10);
+        let other_id = id + 1;
+        <ProposalsWrapper as LiquidityPool<Test>>::init_proposal(
+            other_id,
+            &account,
+            ProposalState::FormalPrediction,
+            0,
+            10,
+            2,
+        );
+        assert_noop!(
+            CoupleModule::spot_prices(other_id),
+            Error::<Test>::UnsupportedEngine
         );
+        assert_noop!(
+            CoupleModule::buy_quote(other_id, 3, 31250),
+            Error::<Test>::UnsupportedEngine
+        );
+        assert_noop!(
+            CoupleModule::sell_quote(other_id, 3, 31250),
+            Error::<Test>::UnsupportedEngine
+        );
+        assert_noop!(
+            CoupleModule::buy_exact_out_quote(other_id, 3, 31250),
+            Error::<Test>::UnsupportedEngine
+        );
+        assert_noop!(
+            CoupleModule::sell_exact_out_quote(other_id, 3, 31250),
+            Error::<Test>::UnsupportedEngine
+        );
+        assert_noop!(
+            CoupleModule::remove_liquidity_quote(&account, other_id, 100000),
+            Error::<Test>::UnsupportedEngine
+        );
+    });
+}
+
+#[test]
+fn test_split_and_merge() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 2;
+        let id = create_proposal(1, 1, 100000, 2000, 10);
+
+        assert_noop!(
+            CoupleModule::split(Origin::signed(account), id, 10000),
+            Error::<Test>::ProposalAbnormalState
+        );
+        assert_noop!(
+            CoupleModule::merge(Origin::signed(account), id, 10000),
+            Error::<Test>::ProposalAbnormalState
+        );
+        set_state(id, ProposalState::FormalPrediction);
+        assert_noop!(
+            CoupleModule::split(Origin::signed(account), id, 0),
+            Error::<Test>::NumberMustMoreThanZero
+        );
+        assert_ok!(CoupleModule::split(Origin::signed(account), id, 10000));
+        let split_event = Event::couple(crate::Event::Split(account, id, 10000));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == split_event));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(21250));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 3), Some(10000));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 4), Some(10000));
+        assert_eq!(CoupleModule::proposal_total_market(id), Some(110000));
+        assert_eq!(
+            CoupleModule::proposal_account_info(id, account),
+            Some(10000)
+        );
+        assert_eq!(
+            CoupleModule::proposal_total_optional_market(id),
+            Some((100000, 100000))
+        );
+
+        assert_noop!(
+            CoupleModule::merge(Origin::signed(account), id, 10001),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_ok!(CoupleModule::merge(Origin::signed(account), id, 4000));
+        let merge_event = Event::couple(crate::Event::Merge(account, id, 4000));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == merge_event));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(25250));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 3), Some(6000));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 4), Some(6000));
+        assert_eq!(CoupleModule::proposal_total_market(id), Some(106000));
+        assert_eq!(CoupleModule::proposal_account_info(id, account), Some(6000));
+
+        // Pairs can still be merged while the result is awaited or announced, but not once it
+        // is final
+        set_state(id, ProposalState::WaitingForResults);
+        assert_noop!(
+            CoupleModule::split(Origin::signed(account), id, 1000),
+            Error::<Test>::ProposalAbnormalState
+        );
+        assert_ok!(CoupleModule::merge(Origin::signed(account), id, 500));
+        set_state(id, ProposalState::ResultAnnouncement);
+        assert_ok!(CoupleModule::merge(Origin::signed(account), id, 500));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(26250));
+        set_state(id, ProposalState::WaitingForResults);
+        assert_ok!(CoupleModule::set_result(Origin::root(), id, 3));
+        assert_noop!(
+            CoupleModule::merge(Origin::signed(account), id, 1000),
+            Error::<Test>::ProposalAbnormalState
+        );
+
+        // The winning half of the pairs is paid from the collateral of the split
+        assert_ok!(CoupleModule::retrieval(
+            Origin::signed(account),
+            id,
+            3,
+            5000
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(31225));
+        assert_eq!(CoupleModule::proposal_total_market(id), Some(100000));
+    });
+}
+
+#[test]
+fn test_retrieval() {
+    new_test_ext().execute_with(|| {
+        let number: BalanceOf<Test> = 100000;
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let currency_id: CurrencyIdOf<Test> = 1;
+        let id = create_proposal(account, currency_id, number, 2000, 10);
+        assert!(!<CoupleModule as ProposalArchive<Test>>::is_drained(id));
+        assert_noop!(
+            CoupleModule::retrieval(Origin::signed(account), id + 1, 3, number),
+            Error::<Test>::ProposalIdNotExist
+        );
+        assert_noop!(
+            CoupleModule::retrieval(Origin::signed(account), id, 3, number),
+            Error::<Test>::ProposalAbnormalState
+        );
+        set_state(id, ProposalState::End);
+        assert_noop!(
+            CoupleModule::retrieval(Origin::signed(account), id, 3, number),
+            Error::<Test>::ProposalNotResult
+        );
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            31250,
+            0,
+            None
+        ));
+        assert_eq!(CoupleModule::retrievable(&other_account, id), Ok(0));
+        set_state(id, ProposalState::WaitingForResults);
+        assert_ok!(CoupleModule::set_result(Origin::root(), id, 3));
+        assert_ok!(CoupleModule::remove_liquidity(
+            Origin::signed(account),
+            id,
+            number,
+            0,
+            None
+        ));
+        assert_eq!(CoupleModule::retrievable(&account, id), Ok(0));
+        assert_eq!(CoupleModule::retrievable(&other_account, id), Ok(44775));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 3), Some(45000));
+        assert_ok!(CoupleModule::retrieval(
+            Origin::signed(account),
+            id,
+            3,
+            number
+        ));
+        let retrieval_event = Event::couple(crate::Event::Retrieval(account, id, 3, 0));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == retrieval_event));
+
+        assert_ok!(CoupleModule::retrieval(
+            Origin::signed(other_account),
+            id,
+            3,
+            number
+        ));
+        let retrieval_event = Event::couple(crate::Event::Retrieval(other_account, id, 3, 44775));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == retrieval_event));
+
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(86250));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 1), Some(44775));
+        assert_eq!(CoupleModule::proposal_total_market(0), Some(0));
+        assert_eq!(CoupleModule::retrievable(&other_account, id), Ok(0));
+
+        // once the losing option is retrieved as well, the assets of the proposal are destroyed
+        assert!(XPMRLTokens::currencies(4).is_some());
+        assert_ok!(CoupleModule::retrieval(
+            Origin::signed(account),
+            id,
+            4,
+            number
+        ));
+        assert_eq!(XPMRLTokens::currencies(3), None);
+        assert_eq!(XPMRLTokens::currencies(4), None);
+        assert_eq!(XPMRLTokens::currencies(5), None);
+
+        // and the storage of the proposal can be archived
+        assert!(<CoupleModule as ProposalArchive<Test>>::is_drained(id));
+        assert_eq!(<CoupleModule as ProposalArchive<Test>>::archive(id, 0), 0);
+        assert!(CoupleModule::pool_pairs(id).is_some());
+        assert!(<CoupleModule as ProposalArchive<Test>>::archive(id, 10) < 10);
+        assert_eq!(CoupleModule::pool_pairs(id), None);
+        assert_eq!(CoupleModule::proposal_currency_id(id), None);
+        assert_eq!(CoupleModule::proposal_total_market(id), None);
+        assert_eq!(CoupleModule::proposal_account_info(id, account), None);
+        assert!(System::events().iter().any(|record| matches!(
+            record.event,
+            Event::couple(crate::Event::ProposalArchived(proposal_id, 1, ..))
+                if proposal_id == id
+        )));
+    });
+}
+
+#[test]
+fn test_retrieval_invalid() {
+    new_test_ext().execute_with(|| {
+        let number: BalanceOf<Test> = 100000;
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let currency_id: CurrencyIdOf<Test> = 1;
+        let id = create_proposal(account, currency_id, number, 2000, 10);
+        assert_eq!(
+            <CoupleModule as LiquidityCouple<Test>>::proposal_invalid_result(id),
+            Some(INVALID_RESULT_ID)
+        );
+        assert_eq!(
+            <CoupleModule as LiquidityCouple<Test>>::proposal_results(id),
+            Ok(vec![3, 4, INVALID_RESULT_ID])
+        );
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            31250,
+            0,
+            None
+        ));
+        set_state(id, ProposalState::WaitingForResults);
+        assert_noop!(
+            CoupleModule::set_result(Origin::root(), id, 5),
+            Error::<Test>::CurrencyIdNotFound
+        );
+        assert_ok!(CoupleModule::set_result(
+            Origin::root(),
+            id,
+            INVALID_RESULT_ID
+        ));
+        assert_eq!(CoupleModule::proposal_result(id), None);
+        assert_eq!(CoupleModule::proposal_invalid(id), Some(()));
+        assert_eq!(
+            <CoupleModule as LiquidityCouple<Test>>::get_proposal_result(id),
+            Ok(INVALID_RESULT_ID)
+        );
+        assert_ok!(CoupleModule::remove_liquidity(
+            Origin::signed(account),
+            id,
+            number,
+            0,
+            None
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 3), Some(86250));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 4), Some(131250));
+
+        // every option token redeems half of the collateral, net of the withdrawal fee
+        assert_eq!(CoupleModule::retrievable(&other_account, id), Ok(22388));
+        assert_eq!(CoupleModule::retrievable(&account, id), Ok(108207));
+        assert_ok!(CoupleModule::retrieval(
+            Origin::signed(other_account),
+            id,
+            3,
+            45000
+        ));
+        let retrieval_event = Event::couple(crate::Event::Retrieval(
+            other_account,
+            id,
+            INVALID_RESULT_ID,
+            22388,
+        ));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == retrieval_event));
+
+        // an odd option token is paid together with the next one, whichever call retrieves it
+        let balance = XPMRLTokens::free_balance_of(account, 1).unwrap_or(0);
+        assert_ok!(CoupleModule::retrieval(
+            Origin::signed(account),
+            id,
+            3,
+            86249
+        ));
+        assert_eq!(
+            CoupleModule::proposal_account_invalid_remainder(id, account),
+            Some(1)
+        );
+        assert_ok!(CoupleModule::retrieval(Origin::signed(account), id, 3, 1));
+        assert_eq!(
+            CoupleModule::proposal_account_invalid_remainder(id, account),
+            None
+        );
+        assert_ok!(CoupleModule::retrieval(
+            Origin::signed(account),
+            id,
+            4,
+            131250
+        ));
+        assert_eq!(
+            XPMRLTokens::free_balance_of(account, 1),
+            Some(balance + 108207)
+        );
+        assert_eq!(CoupleModule::proposal_total_market(id), Some(0));
+        assert!(<CoupleModule as ProposalArchive<Test>>::is_drained(id));
+    });
+}
+
+#[test]
+fn test_set_result() {
+    new_test_ext().execute_with(|| {
+        let id = create_proposal(1, 1, 100000, 200, 10);
+
+        set_state(id, ProposalState::WaitingForResults);
+        assert_noop!(
+            CoupleModule::set_result(Origin::root(), id, 6),
+            Error::<Test>::CurrencyIdNotFound
+        );
+        assert_ok!(CoupleModule::set_result(Origin::root(), id, 3));
+
+        let set_result_event = Event::couple(crate::Event::SetResult(id, 3));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == set_result_event));
+        assert_eq!(
+            <ProposalsWrapper as LiquidityPool<Test>>::get_proposal_state(id),
+            Ok(ProposalState::End)
+        );
+    });
+}
+
+#[test]
+fn test_scalar_proposal() {
+    new_test_ext().execute_with(|| {
+        let number: BalanceOf<Test> = 100000;
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let now = <Timestamp as Time>::now();
+        assert_noop!(
+            CoupleModule::new_scalar_proposal(
+                Origin::signed(account),
+                "btc price on dec 31".as_bytes().to_vec(),
+                100,
+                100,
+                now + 10,
+                1,
+                1,
+                number,
+                2000,
+                "proposal detail".as_bytes().to_vec(),
+                resolution(now + 10),
+            ),
+            Error::<Test>::ScalarRangeInvalid
+        );
+        assert_ok!(CoupleModule::new_scalar_proposal(
+            Origin::signed(account),
+            "btc price on dec 31".as_bytes().to_vec(),
+            0,
+            100,
+            now + 10,
+            1,
+            1,
+            number,
+            2000,
+            "proposal detail".as_bytes().to_vec(),
+            resolution(now + 10),
+        ));
+        let id = <ProposalsWrapper as LiquidityPool<Test>>::max_proposal_id() - 1;
+        assert_eq!(CoupleModule::pool_pairs(id), Some((3, 4)));
+        assert_eq!(CoupleModule::proposal_scalar_range(id), Some((0, 100)));
+
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            31250,
+            0,
+            None
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 3), Some(45000));
+        set_state(id, ProposalState::WaitingForResults);
+        assert_noop!(
+            CoupleModule::set_result(Origin::root(), id, 3),
+            Error::<Test>::ProposalIsScalar
+        );
+        assert_ok!(CoupleModule::set_scalar_result(Origin::root(), id, 75));
+        assert_eq!(CoupleModule::proposal_scalar_result(id), Some(75));
+
+        assert_ok!(CoupleModule::remove_liquidity(
+            Origin::signed(account),
+            id,
+            number,
+            0,
+            None
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(86250));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 4), Some(45000));
+
+        // 45000 LONG are worth 75% of the range, minus the withdrawal fee
+        assert_ok!(CoupleModule::retrieval(
+            Origin::signed(other_account),
+            id,
+            3,
+            45000
+        ));
+        let retrieval_event = Event::couple(crate::Event::Retrieval(other_account, id, 3, 33582));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == retrieval_event));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 3), Some(0));
+        assert_eq!(XPMRLTokens::free_balance_of(other_account, 1), Some(33582));
+
+        assert_ok!(CoupleModule::retrieval(
+            Origin::signed(account),
+            id,
+            4,
+            45000
+        ));
+        let retrieval_event = Event::couple(crate::Event::Retrieval(account, id, 4, 11194));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == retrieval_event));
+        assert_eq!(XPMRLTokens::free_balance_of(account, 1), Some(97444));
+        assert_eq!(XPMRLTokens::free_balance_of(3, 1), Some(112));
+        assert_eq!(CoupleModule::proposal_total_autonomy_reward(id), Some(112));
+        assert_eq!(CoupleModule::proposal_total_market(id), Some(0));
+    });
+}
+
+#[test]
+fn test_set_scalar_result() {
+    new_test_ext().execute_with(|| {
+        let id = create_proposal(1, 1, 100000, 200, 10);
+        set_state(id, ProposalState::WaitingForResults);
+        assert_noop!(
+            CoupleModule::set_scalar_result(Origin::root(), id, 75),
+            Error::<Test>::ProposalNotScalar
+        );
+    });
+}
+
+#[test]
+fn test_limit_orders() {
+    new_test_ext().execute_with(|| {
+        let seller: AccountId = 2;
+        let buyer: AccountId = 3;
+        let id = create_proposal(1, 1, 100000, 2000, 10);
+        assert_ok!(<XPMRLTokens as Tokens<AccountId>>::mint(1, &buyer, 100000));
+
+        assert_noop!(
+            CoupleModule::place_order(Origin::signed(seller), id, 3, OrderSide::Sell, 900000, 100),
+            Error::<Test>::OrdersClosed
+        );
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(seller),
+            id,
+            3,
+            31250,
+            0,
+            None
+        ));
+        assert_noop!(
+            CoupleModule::place_order(Origin::signed(seller), id, 5, OrderSide::Sell, 900000, 100),
+            Error::<Test>::CurrencyIdNotFound
+        );
+        assert_noop!(
+            CoupleModule::place_order(Origin::signed(seller), id, 3, OrderSide::Sell, 0, 100),
+            Error::<Test>::OrderPriceInvalid
+        );
+        assert_noop!(
+            CoupleModule::place_order(Origin::signed(seller), id, 3, OrderSide::Sell, 1000000, 100),
+            Error::<Test>::OrderPriceInvalid
+        );
+        assert_noop!(
+            CoupleModule::place_order(Origin::signed(seller), id, 3, OrderSide::Sell, 900000, 0),
+            Error::<Test>::NumberMustMoreThanZero
+        );
+        assert_noop!(
+            CoupleModule::place_order(Origin::signed(seller), id, 3, OrderSide::Sell, 900000, 999),
+            Error::<Test>::OrderTooSmall
+        );
+
+        // Out of the reach of the pool, the sell order stays open
+        assert_ok!(CoupleModule::place_order(
+            Origin::signed(seller),
+            id,
+            3,
+            OrderSide::Sell,
+            900000,
+            20000
+        ));
+        assert_eq!(XPMRLTokens::free_balance_of(seller, 3), Some(25000));
+        assert_eq!(XPMRLTokens::reserve_of(seller, 3), Some(20000));
+        assert_eq!(CoupleModule::proposal_open_orders(id), vec![0]);
+
+        // Filled by the sell order at its price, the rest of the escrow is released
+        assert_ok!(CoupleModule::place_order(
+            Origin::signed(buyer),
+            id,
+            3,
+            OrderSide::Buy,
+            950000,
+            5000
+        ));
+        let maker_event = Event::couple(crate::Event::OrderFilled(id, 0, 5000, 4500));
+        let taker_event = Event::couple(crate::Event::OrderFilled(id, 1, 5000, 4500));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == maker_event));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == taker_event));
+        assert_eq!(XPMRLTokens::free_balance_of(buyer, 1), Some(95500));
+        assert_eq!(XPMRLTokens::reserve_of(buyer, 1), Some(0));
+        assert_eq!(XPMRLTokens::free_balance_of(buyer, 3), Some(5000));
+        assert_eq!(XPMRLTokens::free_balance_of(seller, 1), Some(4500));
+        assert_eq!(XPMRLTokens::reserve_of(seller, 3), Some(15000));
+        assert_eq!(
+            CoupleModule::orders(id, 0),
+            Some(Order {
+                owner: seller,
+                optional_currency_id: 3,
+                side: OrderSide::Sell,
+                price: 900000,
+                number: 15000,
+                reserved: 15000,
+            })
+        );
+        assert_eq!(CoupleModule::orders(id, 1), None);
+
+        // Crosses neither the sell order nor the pool
+        assert_ok!(CoupleModule::place_order(
+            Origin::signed(buyer),
+            id,
+            3,
+            OrderSide::Buy,
+            300000,
+            10000
+        ));
+        assert_eq!(XPMRLTokens::reserve_of(buyer, 1), Some(3000));
+        assert_eq!(CoupleModule::proposal_open_orders(id), vec![0, 2]);
+        assert_noop!(
+            CoupleModule::cancel_order(Origin::signed(seller), id, 2),
+            Error::<Test>::NotOrderOwner
+        );
+        assert_ok!(CoupleModule::cancel_order(Origin::signed(buyer), id, 2));
+        assert_eq!(XPMRLTokens::free_balance_of(buyer, 1), Some(95500));
+        assert_eq!(XPMRLTokens::reserve_of(buyer, 1), Some(0));
+        assert_noop!(
+            CoupleModule::cancel_order(Origin::signed(buyer), id, 2),
+            Error::<Test>::OrderNotExist
+        );
+
+        assert_noop!(
+            CoupleModule::refund_orders(Origin::signed(buyer), id, 8),
+            Error::<Test>::OrdersStillOpen
+        );
+        // The orders expire at the close time
+        System::set_block_number(11);
+        assert_noop!(
+            CoupleModule::place_order(Origin::signed(seller), id, 3, OrderSide::Sell, 900000, 100),
+            Error::<Test>::OrdersClosed
+        );
+        assert_ok!(CoupleModule::refund_orders(Origin::signed(buyer), id, 8));
+        let refund_event = Event::couple(crate::Event::OrderRefunded(seller, id, 0));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == refund_event));
+        assert_eq!(XPMRLTokens::free_balance_of(seller, 3), Some(40000));
+        assert_eq!(XPMRLTokens::reserve_of(seller, 3), Some(0));
+        assert!(CoupleModule::proposal_open_orders(id).is_empty());
+    });
+}
+
+#[test]
+fn test_limit_orders_per_account() {
+    new_test_ext().execute_with(|| {
+        let seller: AccountId = 2;
+        let other: AccountId = 3;
+        let id = create_proposal(1, 1, 100000, 2000, 10);
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(seller),
+            id,
+            3,
+            31250,
+            0,
+            None
+        ));
+        assert_ok!(<XPMRLTokens as Tokens<AccountId>>::transfer(
+            3, &seller, &other, 5000
+        ));
+
+        // An account can not take up all the open orders of the proposal
+        for _ in 0..2 {
+            assert_ok!(CoupleModule::place_order(
+                Origin::signed(seller),
+                id,
+                3,
+                OrderSide::Sell,
+                900000,
+                5000
+            ));
+        }
+        assert_eq!(CoupleModule::account_open_orders(id, seller), 2);
+        assert_noop!(
+            CoupleModule::place_order(Origin::signed(seller), id, 3, OrderSide::Sell, 900000, 5000),
+            Error::<Test>::TooManyAccountOpenOrders
+        );
+        assert_ok!(CoupleModule::place_order(
+            Origin::signed(other),
+            id,
+            3,
+            OrderSide::Sell,
+            900000,
+            5000
+        ));
+        assert_ok!(CoupleModule::cancel_order(Origin::signed(seller), id, 0));
+        assert_eq!(CoupleModule::account_open_orders(id, seller), 1);
+        assert_ok!(CoupleModule::place_order(
+            Origin::signed(seller),
+            id,
+            3,
+            OrderSide::Sell,
+            900000,
+            5000
+        ));
+        assert_eq!(CoupleModule::proposal_open_orders(id), vec![1, 2, 3]);
+
+        System::set_block_number(11);
+        assert_ok!(CoupleModule::refund_orders(Origin::signed(other), id, 8));
+        assert_eq!(CoupleModule::account_open_orders(id, seller), 0);
+        assert_eq!(CoupleModule::account_open_orders(id, other), 0);
+    });
+}
+
+#[test]
+fn test_limit_orders_against_pool() {
+    new_test_ext().execute_with(|| {
+        let trader: AccountId = 2;
+        let buyer: AccountId = 3;
+        let id = create_proposal(1, 1, 100000, 2000, 10);
+        assert_ok!(<XPMRLTokens as Tokens<AccountId>>::mint(1, &buyer, 100000));
+        set_state(id, ProposalState::FormalPrediction);
+
+        // Filled by the pool until its price with the fee reaches the limit
+        assert_ok!(CoupleModule::place_order(
+            Origin::signed(buyer),
+            id,
+            3,
+            OrderSide::Buy,
+            700000,
+            50000
+        ));
+        let fill_event = Event::couple(crate::Event::OrderFilled(id, 0, 24175, 16018));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == fill_event));
+        assert_eq!(CoupleModule::spot_prices(id), Ok((560000, 439999)));
+        assert_eq!(XPMRLTokens::free_balance_of(buyer, 3), Some(24175));
+        assert_eq!(XPMRLTokens::free_balance_of(buyer, 1), Some(65000));
+        assert_eq!(XPMRLTokens::reserve_of(buyer, 1), Some(18982));
+
+        // Once a pool trade moves the price back the rest of the order is filled with it
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(trader),
+            id,
+            4,
+            31250,
+            0,
+            None
+        ));
+        let fill_event = Event::couple(crate::Event::OrderFilled(id, 0, 25825, 15117));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == fill_event));
+        assert_eq!(XPMRLTokens::free_balance_of(buyer, 3), Some(50001));
+        assert_eq!(XPMRLTokens::free_balance_of(buyer, 1), Some(68865));
+        assert_eq!(XPMRLTokens::reserve_of(buyer, 1), Some(0));
+        assert_eq!(CoupleModule::orders(id, 0), None);
+
+        // A sell order is filled down to its limit and the rest stays open
+        assert_eq!(XPMRLTokens::free_balance_of(trader, 4), Some(49819));
+        assert_ok!(CoupleModule::place_order(
+            Origin::signed(trader),
+            id,
+            4,
+            OrderSide::Sell,
+            380000,
+            40000
+        ));
+        let fill_event = Event::couple(crate::Event::OrderFilled(id, 1, 9833, 3833));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == fill_event));
+        assert_eq!(CoupleModule::spot_prices(id), Ok((525003, 474996)));
+        assert_eq!(XPMRLTokens::free_balance_of(trader, 1), Some(3833));
+        assert_eq!(XPMRLTokens::free_balance_of(trader, 4), Some(9820));
+        assert_eq!(XPMRLTokens::reserve_of(trader, 4), Some(30167));
+
+        // Refunded as soon as the proposal leaves formal prediction
+        set_state(id, ProposalState::WaitingForResults);
+        let refund_event = Event::couple(crate::Event::OrderRefunded(trader, id, 1));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == refund_event));
+        assert_eq!(XPMRLTokens::free_balance_of(trader, 4), Some(39987));
+        assert_eq!(XPMRLTokens::reserve_of(trader, 4), Some(0));
+        assert!(CoupleModule::proposal_open_orders(id).is_empty());
+    });
+}
+
+#[test]
+fn test_limit_orders_crossed_by_trade() {
+    new_test_ext().execute_with(|| {
+        let trader: AccountId = 2;
+        let id = create_proposal(1, 1, 100000, 2000, 10);
+        assert_ok!(<XPMRLTokens as Tokens<AccountId>>::mint(1, &trader, 100000));
+        set_state(id, ProposalState::FormalPrediction);
+        for buyer in 3..6 {
+            assert_ok!(<XPMRLTokens as Tokens<AccountId>>::mint(1, &buyer, 10000));
+            assert_ok!(CoupleModule::place_order(
+                Origin::signed(buyer),
+                id,
+                3,
+                OrderSide::Buy,
+                500000,
+                5000
+            ));
+        }
+        assert_eq!(CoupleModule::proposal_open_orders(id), vec![0, 1, 2]);
+
+        // The trade crosses all three orders but only fills `MaxTradeMatches` of them
+        assert_ok!(CoupleModule::buy(
+            Origin::signed(trader),
+            id,
+            4,
+            60000,
+            0,
+            None
+        ));
+        assert_eq!(CoupleModule::orders(id, 0), None);
+        assert_eq!(CoupleModule::orders(id, 1), None);
+        assert_eq!(
+            CoupleModule::orders(id, 2).map(|order| order.number),
+            Some(5000)
+        );
+        assert_eq!(CoupleModule::proposal_open_orders(id), vec![2]);
+
+        // The rest are left to `match_orders`
+        assert_ok!(CoupleModule::match_orders(Origin::signed(trader), id, 8));
+        assert_eq!(CoupleModule::orders(id, 2), None);
+        assert!(CoupleModule::proposal_open_orders(id).is_empty());
     });
 }
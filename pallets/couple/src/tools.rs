@@ -7,13 +7,21 @@ use frame_support::{
 use num_traits::pow::pow;
 use sp_runtime::{
     traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, IntegerSquareRoot, One, Zero},
-    DispatchError,
+    DispatchError, DispatchResult,
 };
 use sp_std::{cmp, vec::Vec};
 use xpmrl_traits::{
-    pool::LiquidityPool, ruler::RulerAccounts, tokens::Tokens, ProposalStatus, RulerModule,
+    couple::LiquidityCouple, pool::LiquidityPool, ruler::RulerAccounts, tokens::Tokens,
+    ProposalStatus, Resolution, RulerModule,
 };
-use xpmrl_utils::{runtime_format, storage_try_mutate, sub_abs};
+use xpmrl_utils::{runtime_format, storage_try_mutate, sub_abs, with_transaction_rollback};
+
+/// Upper bound of the steps taken to round the result of the inverse trading maths up
+const ROUNDING_STEPS: u32 = 16;
+
+/// `ProposalFeePerShare` is scaled up by `10^FEE_PER_SHARE_DECIMALS`, so that small fees shared
+/// over a lot of liquidity are not rounded away
+pub(crate) const FEE_PER_SHARE_DECIMALS: usize = 12;
 
 impl<T: Config> Pallet<T> {
     pub(crate) fn quadratic_equation(
@@ -57,6 +65,482 @@ impl<T: Config> Pallet<T> {
         Ok(new_pair)
     }
 
+    /// The pool after selling `number` option tokens at `current_index`
+    ///
+    /// The sold tokens are split so that the pool gives back as many tokens of the other
+    /// option as it keeps of the sold one.
+    pub(crate) fn sell_adjust_pool(
+        current_index: usize,
+        number: BalanceOf<T>,
+        old_pair: &[BalanceOf<T>; 2],
+    ) -> Result<[BalanceOf<T>; 2], DispatchError> {
+        let b: BalanceOf<T> = old_pair[0]
+            .checked_add(&old_pair[1])
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let b: BalanceOf<T> = b.checked_sub(&number).unwrap_or_else(Zero::zero);
+        let c: BalanceOf<T> = number
+            .checked_mul(&old_pair[current_index])
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let actual_number = Self::quadratic_equation(One::one(), b, c)?;
+        Self::adjust_pool(current_index, actual_number, old_pair)
+    }
+
+    /// Option tokens received for `actual_number` settlement currency, after the trading fee,
+    /// spent on the option at `current_index`
+    pub(crate) fn buy_received(
+        current_index: usize,
+        actual_number: BalanceOf<T>,
+        old_pair: &[BalanceOf<T>; 2],
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let new_pair = Self::adjust_pool(1 - current_index, actual_number, old_pair)?;
+        let diff = old_pair[current_index]
+            .checked_sub(&new_pair[current_index])
+            .unwrap_or_else(Zero::zero);
+        Ok(actual_number
+            .checked_add(&diff)
+            .ok_or(Error::<T>::BalanceOverflow)?)
+    }
+
+    /// Settlement currency, before the trading fee, received for selling `number` option
+    /// tokens at `current_index`
+    pub(crate) fn sell_received(
+        current_index: usize,
+        number: BalanceOf<T>,
+        old_pair: &[BalanceOf<T>; 2],
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let new_pair = Self::sell_adjust_pool(current_index, number, old_pair)?;
+        let kept = new_pair[current_index]
+            .checked_sub(&old_pair[current_index])
+            .unwrap_or_else(Zero::zero);
+        let last_select_currency = number.checked_sub(&kept).unwrap_or_else(Zero::zero);
+        let acquired_currency = old_pair[1 - current_index]
+            .checked_sub(&new_pair[1 - current_index])
+            .unwrap_or_else(Zero::zero);
+        Ok(cmp::min(last_select_currency, acquired_currency))
+    }
+
+    /// Inverse of `buy_received`, the settlement currency after the trading fee that has to be
+    /// spent to receive at least `received` option tokens
+    ///
+    /// Solves `x^2 + (current + other - received) * x - received * other = 0`.
+    pub(crate) fn buy_actual_for_received(
+        current_index: usize,
+        received: BalanceOf<T>,
+        old_pair: &[BalanceOf<T>; 2],
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let other = old_pair[1 - current_index];
+        let sum = old_pair[0]
+            .checked_add(&old_pair[1])
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let c = received
+            .checked_mul(&other)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let actual_number = if sum >= received {
+            Self::quadratic_equation(One::one(), sum - received, c)?
+        } else {
+            let b = received - sum;
+            let _4c = c
+                .checked_mul(&4u32.into())
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            let delta = pow(b, 2)
+                .checked_add(&_4c)
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            let tmp = b
+                .checked_add(&delta.integer_sqrt())
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            tmp.checked_div(&2u32.into())
+                .ok_or(Error::<T>::BalanceOverflow)?
+        };
+        Self::round_up_to(actual_number, received, |number| {
+            Self::buy_received(current_index, number, old_pair)
+        })
+    }
+
+    /// Inverse of `sell_received`, the option tokens that have to be sold to receive at least
+    /// `received` settlement currency before the trading fee
+    pub(crate) fn sell_number_for_received(
+        current_index: usize,
+        received: BalanceOf<T>,
+        old_pair: &[BalanceOf<T>; 2],
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let current = old_pair[current_index];
+        let other = old_pair[1 - current_index];
+        ensure!(received < other, Error::<T>::ExactOutUnreachable);
+        let base = current
+            .checked_mul(&other)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let rest = other - received;
+        let new_current = base
+            .checked_add(&rest)
+            .ok_or(Error::<T>::BalanceOverflow)?
+            .checked_sub(&One::one())
+            .unwrap_or_else(Zero::zero)
+            .checked_div(&rest)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let kept = new_current.checked_sub(&current).unwrap_or_else(Zero::zero);
+        let number = kept
+            .checked_add(&received)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        Self::round_up_to(number, received, |number| {
+            Self::sell_received(current_index, number, old_pair)
+        })
+    }
+
+    /// Inverse of `get_fee_from_total`, the total that leaves at least `actual_number` after
+    /// the trading fee, together with the fee
+    pub(crate) fn get_total_from_actual(
+        proposal_id: ProposalIdOf<T>,
+        actual_number: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        let fee_decimals: u8 = T::EarnTradingFeeDecimals::get();
+        let one = pow(10u32, fee_decimals.into());
+        let fee_rate = ProposalTotalEarnTradingFee::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        ensure!(fee_rate < one, Error::<T>::ExactOutUnreachable);
+        let total = actual_number
+            .checked_mul(&(one.into()))
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let total = total
+            .checked_div(&((one - fee_rate).into()))
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let total = Self::round_up_to(total, actual_number, |number| {
+            Ok(Self::get_fee_from_total(proposal_id, number)?.0)
+        })?;
+        let (_, fee) = Self::get_fee_from_total(proposal_id, total)?;
+        Ok((total, fee))
+    }
+
+    /// Integer square roots and divisions round the inverse maths down, step `number` up until
+    /// `output(number)` reaches `target`
+    fn round_up_to<F>(
+        mut number: BalanceOf<T>,
+        target: BalanceOf<T>,
+        output: F,
+    ) -> Result<BalanceOf<T>, DispatchError>
+    where
+        F: Fn(BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError>,
+    {
+        for _ in 0..ROUNDING_STEPS {
+            if output(number)? >= target {
+                return Ok(number);
+            }
+            number = number
+                .checked_add(&One::one())
+                .ok_or(Error::<T>::BalanceOverflow)?;
+        }
+        Err(Error::<T>::ExactOutUnreachable.into())
+    }
+
+    /// The settlement currency to pay, and the trading fee in it, to buy `number` tokens of
+    /// `optional_currency_id`
+    pub fn buy_exact_out_quote(
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        Self::ensure_couple_engine(proposal_id)?;
+        let (current_index, old_pair) = Self::trading_pool(proposal_id, optional_currency_id)?;
+        let actual_number = Self::buy_actual_for_received(current_index, number, &old_pair)?;
+        Self::get_total_from_actual(proposal_id, actual_number)
+    }
+
+    /// The tokens of `optional_currency_id` to sell, and the trading fee in settlement
+    /// currency, to receive `number` settlement currency
+    pub fn sell_exact_out_quote(
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        Self::ensure_couple_engine(proposal_id)?;
+        let (current_index, old_pair) = Self::trading_pool(proposal_id, optional_currency_id)?;
+        let (total, fee) = Self::get_total_from_actual(proposal_id, number)?;
+        let sold = Self::sell_number_for_received(current_index, total, &old_pair)?;
+        Ok((sold, fee))
+    }
+
+    /// Quotes only make sense for proposals traded by the couple pool
+    pub(crate) fn ensure_couple_engine(proposal_id: ProposalIdOf<T>) -> DispatchResult {
+        ensure!(
+            T::Pool::proposal_liquidate_version_id(proposal_id)?
+                == T::CurrentLiquidateVersionId::get(),
+            Error::<T>::UnsupportedEngine
+        );
+        Ok(())
+    }
+
+    /// The index of `optional_currency_id` in the pool of the proposal, and the pool
+    pub(crate) fn trading_pool(
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+    ) -> Result<(usize, [BalanceOf<T>; 2]), DispatchError> {
+        let (asset_id_1, asset_id_2) =
+            PoolPairs::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        ensure!(
+            optional_currency_id == asset_id_1 || optional_currency_id == asset_id_2,
+            Error::<T>::CurrencyIdNotFound
+        );
+        let (o1, o2) = ProposalTotalOptionalMarket::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        let other_currency = Self::get_other_optional_id(proposal_id, optional_currency_id)?;
+        Ok((1 - other_currency.0, [o1, o2]))
+    }
+
+    /// One whole token of the settlement currency of the proposal
+    pub(crate) fn settlement_unit(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let decimals = <TokensOf<T> as Tokens<T::AccountId>>::decimals(currency_id)?;
+        Ok(pow(BalanceOf::<T>::from(10u32), decimals.into()))
+    }
+
+    /// The price of one whole token of each option of the proposal, in settlement currency
+    pub fn spot_prices(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        Self::ensure_couple_engine(proposal_id)?;
+        let unit = Self::settlement_unit(proposal_id)?;
+        let (o1, o2) = ProposalTotalOptionalMarket::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        let sum = o1.checked_add(&o2).ok_or(Error::<T>::BalanceOverflow)?;
+        let price = |other: BalanceOf<T>| -> Result<BalanceOf<T>, DispatchError> {
+            Ok(other
+                .checked_mul(&unit)
+                .ok_or(Error::<T>::BalanceOverflow)?
+                .checked_div(&sum)
+                .ok_or(Error::<T>::BalanceOverflow)?)
+        };
+        Ok((price(o2)?, price(o1)?))
+    }
+
+    /// The option tokens received, the trading fee and the price impact of buying with `number`
+    /// settlement currency
+    ///
+    /// The price impact compares the price paid, without the fee, to the spot price, it has the
+    /// decimals of the trading fee rate.
+    pub fn buy_quote(
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        Self::ensure_couple_engine(proposal_id)?;
+        let (current_index, old_pair) = Self::trading_pool(proposal_id, optional_currency_id)?;
+        let (actual_number, fee) = Self::get_fee_from_total(proposal_id, number)?;
+        let received = Self::buy_received(current_index, actual_number, &old_pair)?;
+        let impact = Self::price_impact(actual_number, received, current_index, &old_pair)?;
+        let one: BalanceOf<T> = pow(10u32, T::EarnTradingFeeDecimals::get().into()).into();
+        Ok((
+            received,
+            fee,
+            impact.checked_sub(&one).unwrap_or_else(Zero::zero),
+        ))
+    }
+
+    /// The settlement currency received, the trading fee and the price impact of selling
+    /// `number` option tokens
+    ///
+    /// The price impact compares the price received, without the fee, to the spot price, it
+    /// has the decimals of the trading fee rate.
+    pub fn sell_quote(
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        Self::ensure_couple_engine(proposal_id)?;
+        let (current_index, old_pair) = Self::trading_pool(proposal_id, optional_currency_id)?;
+        let total = Self::sell_received(current_index, number, &old_pair)?;
+        let (actual_number, fee) = Self::get_fee_from_total(proposal_id, total)?;
+        let impact = Self::price_impact(total, number, current_index, &old_pair)?;
+        let one: BalanceOf<T> = pow(10u32, T::EarnTradingFeeDecimals::get().into()).into();
+        Ok((
+            actual_number,
+            fee,
+            one.checked_sub(&impact).unwrap_or_else(Zero::zero),
+        ))
+    }
+
+    /// The settlement currency, the two option currencies and the fee included in the
+    /// settlement currency that `who` gets back for removing `number` liquidity
+    ///
+    /// Runs the same code as `remove_liquidity` and rolls the changes back.
+    pub fn remove_liquidity_quote(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        Self::ensure_couple_engine(proposal_id)?;
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let liquidate_currency_id = ProposalLiquidateCurrencyId::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        let (asset_id_1, asset_id_2) =
+            PoolPairs::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        if T::Pool::get_proposal_state(proposal_id)? == ProposalStatus::FormalPrediction {
+            return with_transaction_rollback(|| {
+                Self::inner_remove_live_liquidity(
+                    who,
+                    proposal_id,
+                    currency_id,
+                    liquidate_currency_id,
+                    asset_id_1,
+                    asset_id_2,
+                    number,
+                    Zero::zero(),
+                )
+            });
+        }
+        let (finally_o1, finally_o2) = ProposalFinallyTotalOptionalMarket::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        with_transaction_rollback(|| {
+            Self::inner_remove_liquidity(
+                who,
+                proposal_id,
+                currency_id,
+                liquidate_currency_id,
+                asset_id_1,
+                asset_id_2,
+                number,
+                finally_o1,
+                finally_o2,
+                Zero::zero(),
+            )
+        })
+    }
+
+    /// The settlement currency, net of the withdrawal fee, that `who` gets back for
+    /// retrieving all of the option currencies held in an ended proposal
+    ///
+    /// Runs the same code as `retrieval` and rolls the changes back.
+    pub fn retrievable(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        if T::Pool::get_proposal_state(proposal_id)? != ProposalStatus::End {
+            return Ok(Zero::zero());
+        }
+        let (asset_id_1, asset_id_2) =
+            PoolPairs::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        with_transaction_rollback(|| -> Result<BalanceOf<T>, DispatchError> {
+            let mut total: BalanceOf<T> = Zero::zero();
+            for optional_currency_id in [asset_id_1, asset_id_2].iter().copied() {
+                let number =
+                    <TokensOf<T> as Tokens<T::AccountId>>::balance(optional_currency_id, who);
+                if number.is_zero() {
+                    continue;
+                }
+                let received = match ProposalScalarRange::<T>::get(proposal_id) {
+                    Some(range) => {
+                        let value = ProposalScalarResult::<T>::get(proposal_id)
+                            .ok_or(Error::<T>::ProposalNotResult)?;
+                        Self::inner_scalar_retrieval(
+                            who,
+                            proposal_id,
+                            optional_currency_id,
+                            number,
+                            range,
+                            value,
+                        )?
+                    }
+                    None if ProposalInvalid::<T>::contains_key(proposal_id) => {
+                        Self::inner_invalid_retrieval(
+                            who,
+                            proposal_id,
+                            optional_currency_id,
+                            number,
+                        )?
+                    }
+                    None => {
+                        let result_id = ProposalResult::<T>::get(proposal_id)
+                            .ok_or(Error::<T>::ProposalNotResult)?;
+                        if optional_currency_id != result_id {
+                            continue;
+                        }
+                        Self::inner_retrieval(
+                            who,
+                            proposal_id,
+                            result_id,
+                            optional_currency_id,
+                            number,
+                        )?
+                    }
+                };
+                total = total
+                    .checked_add(&received)
+                    .ok_or(Error::<T>::BalanceOverflow)?;
+            }
+            Ok(total)
+        })
+    }
+
+    /// The trading fee `who` can claim from the proposal, including the fee its liquidity has
+    /// earned since its share was last brought up to date
+    ///
+    /// Runs the same code as `claim_lp_fees` and rolls the changes back.
+    pub fn claimable_fee(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        with_transaction_rollback(|| Self::take_unclaimed_fee(proposal_id, who))
+    }
+
+    /// The ratio of the price `paid / received` to the spot price of the option at
+    /// `current_index`, with the decimals of the trading fee rate
+    fn price_impact(
+        paid: BalanceOf<T>,
+        received: BalanceOf<T>,
+        current_index: usize,
+        old_pair: &[BalanceOf<T>; 2],
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let one: BalanceOf<T> = pow(10u32, T::EarnTradingFeeDecimals::get().into()).into();
+        let sum = old_pair[0]
+            .checked_add(&old_pair[1])
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let numerator = paid
+            .checked_mul(&sum)
+            .ok_or(Error::<T>::BalanceOverflow)?
+            .checked_mul(&one)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let denominator = received
+            .checked_mul(&old_pair[1 - current_index])
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        Ok(numerator
+            .checked_div(&denominator)
+            .ok_or(Error::<T>::BalanceOverflow)?)
+    }
+
+    pub(crate) fn ensure_deadline(deadline: Option<MomentOf<T>>) -> Result<(), DispatchError> {
+        if let Some(deadline) = deadline {
+            ensure!(
+                <TimeOf<T> as Time>::now() <= deadline,
+                Error::<T>::DeadlineExpired
+            );
+        }
+        Ok(())
+    }
+
+    pub(crate) fn ensure_new_proposal(
+        close_time: MomentOf<T>,
+        category_id: CategoryIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        ensure!(category_id > Zero::zero(), Error::<T>::CategoryIdNotZero);
+        ensure!(currency_id > Zero::zero(), Error::<T>::TokenIdNotZero);
+        ensure!(number > Zero::zero(), Error::<T>::NumberMustMoreThanZero);
+        let now = <TimeOf<T> as Time>::now();
+        let minimum_interval_time = T::Pool::get_proposal_minimum_interval_time();
+        ensure!(
+            close_time > now + minimum_interval_time,
+            Error::<T>::CloseTimeMustLargeThanNow
+        );
+        ensure!(
+            !T::Pool::is_currency_id_used(currency_id),
+            Error::<T>::CurrencyIdNotAllowed
+        );
+        Ok(())
+    }
+
     pub(crate) fn get_other_optional_id(
         proposal_id: ProposalIdOf<T>,
         optional_currency_id: CurrencyIdOf<T>,
@@ -89,60 +573,138 @@ impl<T: Config> Pallet<T> {
         Ok((actual_number, rate))
     }
 
-    pub(crate) fn get_fee_of_liquid(
+    /// The liquidity tokens of the proposal held by `who`, the reserved ones included
+    pub(crate) fn liquidity_of(
         proposal_id: ProposalIdOf<T>,
-        number: BalanceOf<T>,
-        total_liquid: BalanceOf<T>,
+        who: &T::AccountId,
     ) -> Result<BalanceOf<T>, DispatchError> {
-        let market_fee = ProposalFinallyMarketFee::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        let asset_id_lp = ProposalLiquidateCurrencyId::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        let free = <TokensOf<T> as Tokens<T::AccountId>>::balance(asset_id_lp, who);
+        let reserved = <TokensOf<T> as Tokens<T::AccountId>>::reserved_balance(asset_id_lp, who);
+        Ok(free
+            .checked_add(&reserved)
+            .ok_or(Error::<T>::BalanceOverflow)?)
+    }
 
+    /// Add the trading fee of a trade to the proposal
+    ///
+    /// The part of the liquidity providers is shared out over the liquidity at this time through
+    /// `ProposalFeePerShare`, the rest is earned by the owner of the proposal.
+    pub(crate) fn accrue_fee(
+        proposal_id: ProposalIdOf<T>,
+        fee: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        proposal_total_market_fee_try_mutate!(
+            proposal_id,
+            old_value,
+            old_value
+                .checked_add(&fee)
+                .ok_or(Error::<T>::BalanceOverflow)?
+        )?;
         let decimals = T::EarnTradingFeeDecimals::get();
         let one = pow(10u32, decimals.into());
         let liquidity_provider_fee_rate: u32 =
             ProposalLiquidityProviderFeeRate::<T>::get().unwrap_or_else(Zero::zero);
-
-        let mul_market_fee = market_fee
-            .checked_mul(&number)
-            .ok_or(Error::<T>::BalanceOverflow)?;
-        let mul_market_fee = mul_market_fee
+        let liquidity_fee = fee
             .checked_mul(&liquidity_provider_fee_rate.into())
             .ok_or(Error::<T>::BalanceOverflow)?;
-        let fee = mul_market_fee
+        let liquidity_fee = liquidity_fee
+            .checked_div(&one.into())
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let total_liquid =
+            ProposalTotalMarketLiquid::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        let fee_per_share = liquidity_fee
+            .checked_mul(&pow(BalanceOf::<T>::from(10u32), FEE_PER_SHARE_DECIMALS))
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let fee_per_share = fee_per_share
             .checked_div(&total_liquid)
             .ok_or(Error::<T>::BalanceOverflow)?;
+        ProposalFeePerShare::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(
+                    old.checked_add(&fee_per_share)
+                        .ok_or(Error::<T>::BalanceOverflow)?,
+                );
+                Ok(())
+            },
+        )?;
+        let owner = T::Pool::proposal_owner(proposal_id)?;
+        let owner_fee = fee.checked_sub(&liquidity_fee).unwrap_or_else(Zero::zero);
+        Self::add_unclaimed_fee(proposal_id, &owner, owner_fee)
+    }
+
+    pub(crate) fn add_unclaimed_fee(
+        proposal_id: ProposalIdOf<T>,
+        who: &T::AccountId,
+        fee: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        if fee.is_zero() {
+            return Ok(());
+        }
+        ProposalAccountUnclaimedFee::<T>::try_mutate(
+            proposal_id,
+            who,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_add(&fee).ok_or(Error::<T>::BalanceOverflow)?);
+                Ok(())
+            },
+        )
+    }
+
+    /// Add the fee earned by the liquidity tokens of `who` since it was last brought up to date
+    /// to its unclaimed fee
+    ///
+    /// Has to be called before the liquidity tokens of `who` are minted, burned or transferred.
+    pub(crate) fn update_account_fee(
+        proposal_id: ProposalIdOf<T>,
+        who: &T::AccountId,
+    ) -> Result<(), DispatchError> {
+        let fee_per_share = ProposalFeePerShare::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        let start =
+            ProposalAccountRewardStart::<T>::get(proposal_id, who).unwrap_or_else(Zero::zero);
+        if fee_per_share == start {
+            return Ok(());
+        }
+        let liquidity = Self::liquidity_of(proposal_id, who)?;
+        let fee = liquidity
+            .checked_mul(&fee_per_share.checked_sub(&start).unwrap_or_else(Zero::zero))
+            .ok_or(Error::<T>::BalanceOverflow)?;
         let fee = fee
-            .checked_div(&one.into())
+            .checked_div(&pow(BalanceOf::<T>::from(10u32), FEE_PER_SHARE_DECIMALS))
             .ok_or(Error::<T>::BalanceOverflow)?;
+        Self::add_unclaimed_fee(proposal_id, who, fee)?;
+        ProposalAccountRewardStart::<T>::insert(proposal_id, who, fee_per_share);
+        Ok(())
+    }
+
+    /// Bring the fee earned by `who` up to date and take all of it out of the proposal
+    pub(crate) fn take_unclaimed_fee(
+        proposal_id: ProposalIdOf<T>,
+        who: &T::AccountId,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Self::update_account_fee(proposal_id, who)?;
+        let fee =
+            ProposalAccountUnclaimedFee::<T>::take(proposal_id, who).unwrap_or_else(Zero::zero);
+        proposal_total_market_fee_try_mutate!(
+            proposal_id,
+            old_value,
+            old_value.checked_sub(&fee).unwrap_or_else(Zero::zero)
+        )?;
         Ok(fee)
     }
 
-    pub(crate) fn get_fee_of_creator(
+    pub(crate) fn inner_claim_lp_fees(
         who: &T::AccountId,
         proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
     ) -> Result<BalanceOf<T>, DispatchError> {
-        let owner = T::Pool::proposal_owner(proposal_id)?;
-        if owner == *who && !ProposalOwnerAlreadyWithdrawnFee::<T>::contains_key(proposal_id, &who)
-        {
-            let market_fee =
-                ProposalFinallyMarketFee::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
-
-            let decimals = T::EarnTradingFeeDecimals::get();
-            let one = pow(10u32, decimals.into());
-            let liquidity_provider_fee_rate: u32 =
-                ProposalLiquidityProviderFeeRate::<T>::get().unwrap_or_else(Zero::zero);
-
-            let mul_market_fee = market_fee
-                .checked_mul(&liquidity_provider_fee_rate.into())
-                .ok_or(Error::<T>::BalanceOverflow)?;
-            let fee = mul_market_fee
-                .checked_div(&one.into())
-                .ok_or(Error::<T>::BalanceOverflow)?;
-            let fee = market_fee.checked_sub(&fee).unwrap_or_else(Zero::zero);
-            ProposalOwnerAlreadyWithdrawnFee::<T>::insert(proposal_id, &who, fee);
-            Ok(fee)
-        } else {
-            Ok(Zero::zero())
-        }
+        let fee = Self::take_unclaimed_fee(proposal_id, who)?;
+        ensure!(!fee.is_zero(), Error::<T>::NoFeeToClaim);
+        Self::appropriation(currency_id, who, fee)
     }
 
     pub(crate) fn get_withdrawal_fee(
@@ -195,6 +757,7 @@ impl<T: Config> Pallet<T> {
         category_id: T::CategoryId,
         earn_fee: u32,
         detail: Vec<u8>,
+        resolution: Resolution<MomentOf<T>>,
     ) -> Result<ProposalIdOf<T>, DispatchError> {
         let version: VersionIdOf<T> = T::CurrentLiquidateVersionId::get();
         Proposals::<T>::insert(
@@ -213,6 +776,7 @@ impl<T: Config> Pallet<T> {
             close_time,
             version,
         );
+        T::Pool::set_proposal_resolution(proposal_id, resolution)?;
         ProposalTotalEarnTradingFee::<T>::insert(proposal_id, earn_fee);
         Ok(proposal_id)
     }
@@ -223,14 +787,15 @@ impl<T: Config> Pallet<T> {
         currency_id: CurrencyIdOf<T>,
         number: BalanceOf<T>,
         optional: [Vec<u8>; 2],
+        suffix: [&str; 2],
     ) -> Result<ProposalIdOf<T>, DispatchError> {
         ProposalCurrencyId::<T>::insert(proposal_id, currency_id);
         <TokensOf<T> as Tokens<T::AccountId>>::donate(currency_id, who, number)?;
         let decimals = <TokensOf<T> as Tokens<T::AccountId>>::decimals(currency_id)?;
-        let yes_symbol = runtime_format!("{:?}-YES", proposal_id);
-        let asset_id_1 = Self::new_asset(optional[0].clone(), yes_symbol, decimals)?;
-        let no_symbol = runtime_format!("{:?}-NO", proposal_id);
-        let asset_id_2 = Self::new_asset(optional[1].clone(), no_symbol, decimals)?;
+        let symbol_1 = runtime_format!("{:?}-{}", proposal_id, suffix[0]);
+        let asset_id_1 = Self::new_asset(optional[0].clone(), symbol_1, decimals)?;
+        let symbol_2 = runtime_format!("{:?}-{}", proposal_id, suffix[1]);
+        let asset_id_2 = Self::new_asset(optional[1].clone(), symbol_2, decimals)?;
         let lp_name = runtime_format!("LP-{:?}", proposal_id);
         let asset_id_lp = Self::new_asset(lp_name.clone(), lp_name, decimals)?;
 
@@ -243,6 +808,7 @@ impl<T: Config> Pallet<T> {
         ProposalTotalOptionalMarket::<T>::insert(proposal_id, (number, number));
 
         ProposalLiquidateCurrencyId::<T>::insert(proposal_id, asset_id_lp);
+        LiquidateCurrencyProposalId::<T>::insert(asset_id_lp, proposal_id);
         <TokensOf<T> as Tokens<T::AccountId>>::mint(asset_id_lp, who, number)?;
 
         PoolPairs::<T>::insert(proposal_id, (asset_id_1, asset_id_2));
@@ -306,6 +872,7 @@ impl<T: Config> Pallet<T> {
         <TokensOf<T> as Tokens<T::AccountId>>::donate(currency_id, who, number)?;
         <TokensOf<T> as Tokens<T::AccountId>>::mint_donate(asset_id_1, number)?;
         <TokensOf<T> as Tokens<T::AccountId>>::mint_donate(asset_id_2, number)?;
+        Self::update_account_fee(proposal_id, who)?;
         <TokensOf<T> as Tokens<T::AccountId>>::mint(liquidate_currency_id, who, number)?;
         proposal_total_optional_market_try_mutate!(proposal_id, o1, o2, {
             let new_o1 = o1.checked_add(&number).ok_or(Error::<T>::BalanceOverflow)?;
@@ -332,7 +899,9 @@ impl<T: Config> Pallet<T> {
         number: BalanceOf<T>,
         finally_o1: BalanceOf<T>,
         finally_o2: BalanceOf<T>,
-    ) -> Result<BalanceOf<T>, DispatchError> {
+        min_received: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        let fee = Self::take_unclaimed_fee(proposal_id, who)?;
         <TokensOf<T> as Tokens<T::AccountId>>::burn(liquidate_currency_id, who, number)?;
         proposal_total_market_liquid_try_mutate!(
             proposal_id,
@@ -341,16 +910,6 @@ impl<T: Config> Pallet<T> {
         )?;
         let total_liquid =
             ProposalFinallyMarketLiquid::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
-        let fee = Self::get_fee_of_liquid(proposal_id, number, total_liquid)?;
-        let creater_fee = Self::get_fee_of_creator(who, proposal_id)?;
-        let fee = fee
-            .checked_add(&creater_fee)
-            .ok_or(Error::<T>::BalanceOverflow)?;
-        proposal_total_market_fee_try_mutate!(
-            proposal_id,
-            old_value,
-            old_value.checked_sub(&fee).unwrap_or_else(Zero::zero)
-        )?;
         let (o1, o2) = proposal_total_optional_market_try_mutate!(proposal_id, o1, o2, {
             let new_o1 = finally_o1
                 .checked_mul(&number)
@@ -374,23 +933,127 @@ impl<T: Config> Pallet<T> {
         <TokensOf<T> as Tokens<T::AccountId>>::burn_donate(asset_id_2, min)?;
         Self::total_and_account_sub(proposal_id, who, min)?;
         let actual_amount = min.checked_add(&fee).ok_or(Error::<T>::BalanceOverflow)?;
+        ensure!(
+            actual_amount >= min_received,
+            Error::<T>::ReceivedLessThanMinimum
+        );
+        Self::appropriation(currency_id, who, actual_amount)?;
+        let yes_amount = o1.checked_sub(&min).unwrap_or_else(Zero::zero);
+        Self::appropriation(asset_id_1, who, yes_amount)?;
+        let no_amount = o2.checked_sub(&min).unwrap_or_else(Zero::zero);
+        Self::appropriation(asset_id_2, who, no_amount)?;
+        Self::destroy_retrieved_assets(proposal_id)?;
+        Ok((actual_amount, yes_amount, no_amount, fee))
+    }
+
+    /// Remove liquidity of a proposal that is still in formal prediction
+    ///
+    /// The share of the pool is taken out without moving its price, the complete sets in it are
+    /// merged back into settlement currency and the option currency left over is paid as it is,
+    /// together with the trading fee earned so far. The liquidity left in the pool keeps
+    /// earning the fee of the later trades on its own.
+    pub(crate) fn inner_remove_live_liquidity(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        liquidate_currency_id: CurrencyIdOf<T>,
+        asset_id_1: CurrencyIdOf<T>,
+        asset_id_2: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+        min_received: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        let total_liquid =
+            ProposalTotalMarketLiquid::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        ensure!(number < total_liquid, Error::<T>::PoolCannotBeEmptied);
+        ensure!(
+            <TokensOf<T> as Tokens<T::AccountId>>::balance(liquidate_currency_id, who) >= number,
+            Error::<T>::InsufficientBalance
+        );
+        let fee = Self::take_unclaimed_fee(proposal_id, who)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::burn(liquidate_currency_id, who, number)?;
+        proposal_total_market_liquid_try_mutate!(
+            proposal_id,
+            old_value,
+            old_value.checked_sub(&number).unwrap_or_else(Zero::zero)
+        )?;
+        let (o1, o2) = proposal_total_optional_market_try_mutate!(proposal_id, o1, o2, {
+            let new_o1 = o1.checked_mul(&number).ok_or(Error::<T>::BalanceOverflow)?;
+            let new_o1 = new_o1
+                .checked_div(&total_liquid)
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            let new_o1 = o1.checked_sub(&new_o1).unwrap_or_else(Zero::zero);
+
+            let new_o2 = o2.checked_mul(&number).ok_or(Error::<T>::BalanceOverflow)?;
+            let new_o2 = new_o2
+                .checked_div(&total_liquid)
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            let new_o2 = o2.checked_sub(&new_o2).unwrap_or_else(Zero::zero);
+            (new_o1, new_o2)
+        })?;
+        let min = cmp::min(o1, o2);
+        <TokensOf<T> as Tokens<T::AccountId>>::burn_donate(asset_id_1, min)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::burn_donate(asset_id_2, min)?;
+        Self::total_and_account_sub(proposal_id, who, min)?;
+        let actual_amount = min.checked_add(&fee).ok_or(Error::<T>::BalanceOverflow)?;
+        ensure!(
+            actual_amount >= min_received,
+            Error::<T>::ReceivedLessThanMinimum
+        );
         Self::appropriation(currency_id, who, actual_amount)?;
         let yes_amount = o1.checked_sub(&min).unwrap_or_else(Zero::zero);
         Self::appropriation(asset_id_1, who, yes_amount)?;
         let no_amount = o2.checked_sub(&min).unwrap_or_else(Zero::zero);
-        Self::appropriation(asset_id_2, who, no_amount)
+        Self::appropriation(asset_id_2, who, no_amount)?;
+        Ok((actual_amount, yes_amount, no_amount, fee))
+    }
+
+    pub(crate) fn inner_split(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        asset_id_1: CurrencyIdOf<T>,
+        asset_id_2: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        <TokensOf<T> as Tokens<T::AccountId>>::donate(currency_id, who, number)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(asset_id_1, who, number)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(asset_id_2, who, number)?;
+        Self::total_and_account_add(proposal_id, who, number)
+    }
+
+    pub(crate) fn inner_merge(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        asset_id_1: CurrencyIdOf<T>,
+        asset_id_2: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        for asset_id in [asset_id_1, asset_id_2].iter() {
+            ensure!(
+                <TokensOf<T> as Tokens<T::AccountId>>::balance(*asset_id, who) >= number,
+                Error::<T>::InsufficientBalance
+            );
+        }
+        <TokensOf<T> as Tokens<T::AccountId>>::burn(asset_id_1, who, number)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::burn(asset_id_2, who, number)?;
+        Self::total_and_account_sub(proposal_id, who, number)?;
+        Self::appropriation(currency_id, who, number)?;
+        Ok(())
     }
 
     pub(crate) fn inner_buy(
+        payer: &T::AccountId,
         who: &T::AccountId,
         proposal_id: ProposalIdOf<T>,
         currency_id: CurrencyIdOf<T>,
         optional_currency_id: CurrencyIdOf<T>,
         number: BalanceOf<T>,
         other_currency: (usize, CurrencyIdOf<T>),
+        min_received: BalanceOf<T>,
     ) -> Result<BalanceOf<T>, DispatchError> {
         let (actual_number, fee) = Self::get_fee_from_total(proposal_id, number)?;
-        <TokensOf<T> as Tokens<T::AccountId>>::donate(currency_id, who, number)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::donate(currency_id, payer, number)?;
         <TokensOf<T> as Tokens<T::AccountId>>::mint(optional_currency_id, who, actual_number)?;
         <TokensOf<T> as Tokens<T::AccountId>>::mint_donate(other_currency.1, actual_number)?;
         let (d1, d2) = proposal_total_optional_market_try_mutate!(proposal_id, o1, o2, {
@@ -399,14 +1062,15 @@ impl<T: Config> Pallet<T> {
             (new_pair[0], new_pair[1])
         })?;
         let diff = [d1, d2][1 - other_currency.0];
+        let received = actual_number
+            .checked_add(&diff)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        ensure!(
+            received >= min_received,
+            Error::<T>::ReceivedLessThanMinimum
+        );
         Self::total_and_account_add(proposal_id, who, actual_number)?;
-        proposal_total_market_fee_try_mutate!(
-            proposal_id,
-            old_value,
-            old_value
-                .checked_add(&fee)
-                .ok_or(Error::<T>::BalanceOverflow)?
-        )?;
+        Self::accrue_fee(proposal_id, fee)?;
         Self::appropriation(optional_currency_id, who, diff)?;
         ProposalTotalVolume::<T>::try_mutate(
             proposal_id,
@@ -423,27 +1087,19 @@ impl<T: Config> Pallet<T> {
     }
 
     pub(crate) fn inner_sell(
+        payer: &T::AccountId,
         who: &T::AccountId,
         proposal_id: ProposalIdOf<T>,
         currency_id: CurrencyIdOf<T>,
         optional_currency_id: CurrencyIdOf<T>,
         number: BalanceOf<T>,
         other_currency: (usize, CurrencyIdOf<T>),
+        min_received: BalanceOf<T>,
     ) -> Result<BalanceOf<T>, DispatchError> {
-        <TokensOf<T> as Tokens<T::AccountId>>::donate(optional_currency_id, who, number)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::donate(optional_currency_id, payer, number)?;
         let (d1, d2) = proposal_total_optional_market_try_mutate!(proposal_id, o1, o2, {
             let old_pair = [o1, o2];
-            let other_currency = Self::get_other_optional_id(proposal_id, optional_currency_id)?;
-            let current_index = 1 - other_currency.0;
-
-            let b: BalanceOf<T> = o1.checked_add(&o2).ok_or(Error::<T>::BalanceOverflow)?;
-            let b: BalanceOf<T> = b.checked_sub(&number).unwrap_or_else(Zero::zero);
-            let c: BalanceOf<T> = number
-                .checked_mul(&old_pair[current_index])
-                .ok_or(Error::<T>::BalanceOverflow)?;
-            let actual_number = Self::quadratic_equation(One::one(), b, c)?;
-
-            let new_pair = Self::adjust_pool(current_index, actual_number, &old_pair)?;
+            let new_pair = Self::sell_adjust_pool(1 - other_currency.0, number, &old_pair)?;
             (new_pair[0], new_pair[1])
         })?;
         let diff = [d1, d2];
@@ -462,13 +1118,11 @@ impl<T: Config> Pallet<T> {
         )?;
         <TokensOf<T> as Tokens<T::AccountId>>::burn_donate(other_currency.1, min)?;
         let (actual_number, fee) = Self::get_fee_from_total(proposal_id, min)?;
-        proposal_total_market_fee_try_mutate!(
-            proposal_id,
-            old_value,
-            old_value
-                .checked_add(&fee)
-                .ok_or(Error::<T>::BalanceOverflow)?
-        )?;
+        ensure!(
+            actual_number >= min_received,
+            Error::<T>::ReceivedLessThanMinimum
+        );
+        Self::accrue_fee(proposal_id, fee)?;
         Self::total_and_account_sub(proposal_id, who, min)?;
         Self::appropriation(currency_id, who, actual_number)?;
         let last = last_select_currency
@@ -482,6 +1136,68 @@ impl<T: Config> Pallet<T> {
         Ok(actual_number)
     }
 
+    /// Pay out the settlement token to `who` for `number` settled option tokens, the withdrawal
+    /// fee is split between the autonomy reward and the platform dividends
+    pub(crate) fn pay_out(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        proposal_total_market_try_mutate!(
+            proposal_id,
+            old_amount,
+            old_amount.checked_sub(&number).unwrap_or_else(Zero::zero)
+        )?;
+        let (number, reward, dividends) = Self::get_withdrawal_fee(number);
+        ProposalTotalAutonomyReward::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_add(&reward).unwrap_or_else(Zero::zero));
+                Ok(())
+            },
+        )?;
+        ProposalCurrentAutonomyReward::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_add(&reward).unwrap_or_else(Zero::zero));
+                Ok(())
+            },
+        )?;
+        let dividends_account = T::Ruler::get_account(RulerModule::PlatformDividend)?;
+        Self::appropriation(currency_id, &dividends_account, dividends)?;
+        Self::appropriation(currency_id, who, number)
+    }
+
+    /// Whether `currency_id` is the id voted for to settle the proposal as invalid
+    pub(crate) fn is_invalid_result(
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+    ) -> bool {
+        <Self as LiquidityCouple<T>>::proposal_invalid_result(proposal_id) == Some(currency_id)
+    }
+
+    /// Store the result of the proposal, an invalid result is flagged instead of being stored as
+    /// an option of the proposal
+    pub(crate) fn insert_result(
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        invalid: bool,
+    ) {
+        if invalid {
+            ProposalResult::<T>::remove(proposal_id);
+            ProposalInvalid::<T>::insert(proposal_id, ());
+        } else {
+            ProposalInvalid::<T>::remove(proposal_id);
+            ProposalResult::<T>::insert(proposal_id, currency_id);
+        }
+    }
+
+    /// Burn `number` option tokens of an ended proposal, the tokens of the result are paid one
+    /// settlement token each, the others nothing
     pub(crate) fn inner_retrieval(
         who: &T::AccountId,
         proposal_id: ProposalIdOf<T>,
@@ -489,37 +1205,179 @@ impl<T: Config> Pallet<T> {
         optional_currency_id: CurrencyIdOf<T>,
         number: BalanceOf<T>,
     ) -> Result<BalanceOf<T>, DispatchError> {
-        if optional_currency_id == result_id {
-            let currency_id =
-                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
-            proposal_total_market_try_mutate!(
-                proposal_id,
-                old_amount,
-                old_amount.checked_sub(&number).unwrap_or_else(Zero::zero)
-            )?;
+        let number = if optional_currency_id == result_id {
             <TokensOf<T> as Tokens<T::AccountId>>::burn(result_id, who, number)?;
-            let (number, reward, dividends) = Self::get_withdrawal_fee(number);
-            ProposalTotalAutonomyReward::<T>::try_mutate(
-                proposal_id,
-                |optional| -> Result<(), DispatchError> {
-                    let old = optional.unwrap_or_else(Zero::zero);
-                    *optional = Some(old.checked_add(&reward).unwrap_or_else(Zero::zero));
-                    Ok(())
-                },
-            )?;
-            ProposalCurrentAutonomyReward::<T>::try_mutate(
-                proposal_id,
-                |optional| -> Result<(), DispatchError> {
-                    let old = optional.unwrap_or_else(Zero::zero);
-                    *optional = Some(old.checked_add(&reward).unwrap_or_else(Zero::zero));
-                    Ok(())
-                },
-            )?;
-            let dividends_account = T::Ruler::get_account(RulerModule::PlatformDividend)?;
-            Self::appropriation(currency_id, &dividends_account, dividends)?;
-            Self::appropriation(currency_id, who, number)
+            Self::pay_out(who, proposal_id, number)?
         } else {
-            <TokensOf<T> as Tokens<T::AccountId>>::burn(optional_currency_id, who, number)
+            <TokensOf<T> as Tokens<T::AccountId>>::burn(optional_currency_id, who, number)?
+        };
+        Self::destroy_retrieved_assets(proposal_id)?;
+        Ok(number)
+    }
+
+    /// Burn `number` option tokens of an invalid proposal, every two option tokens of the account
+    /// are paid one settlement token whichever calls they are retrieved in, an odd one is kept
+    /// in `ProposalAccountInvalidRemainder` until the next retrieval
+    pub(crate) fn inner_invalid_retrieval(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        <TokensOf<T> as Tokens<T::AccountId>>::burn(optional_currency_id, who, number)?;
+        let two: BalanceOf<T> = 2u32.into();
+        let pooled = ProposalAccountInvalidRemainder::<T>::take(proposal_id, who)
+            .unwrap_or_else(Zero::zero)
+            .checked_add(&number)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let remainder = pooled % two;
+        if !remainder.is_zero() {
+            ProposalAccountInvalidRemainder::<T>::insert(proposal_id, who, remainder);
+        }
+        let half = pooled.checked_div(&two).unwrap_or_else(Zero::zero);
+        let number = Self::pay_out(who, proposal_id, half)?;
+        Self::destroy_retrieved_assets(proposal_id)?;
+        Ok(number)
+    }
+
+    /// Once every option and LP token of the proposal has been burned, the assets are of no
+    /// more use and are removed from the tokens module
+    pub(crate) fn destroy_retrieved_assets(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<(), DispatchError> {
+        let (asset_id_1, asset_id_2) =
+            PoolPairs::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let asset_id_lp = ProposalLiquidateCurrencyId::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        let assets = [asset_id_1, asset_id_2, asset_id_lp];
+        if assets.iter().all(|asset_id| {
+            <TokensOf<T> as Tokens<T::AccountId>>::total_supply(*asset_id).is_zero()
+        }) {
+            for asset_id in assets.iter() {
+                <TokensOf<T> as Tokens<T::AccountId>>::destroy_asset(*asset_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether every option and LP token of the proposal has been burned, every limit order
+    /// closed and every trading fee claimed, which stays true once the storage of the proposal
+    /// has been removed
+    pub(crate) fn is_drained(proposal_id: ProposalIdOf<T>) -> bool {
+        if !ProposalOpenOrders::<T>::get(proposal_id).is_empty() {
+            return false;
+        }
+        if ProposalAccountUnclaimedFee::<T>::iter_prefix(proposal_id)
+            .next()
+            .is_some()
+        {
+            return false;
+        }
+        let (asset_id_1, asset_id_2) = match PoolPairs::<T>::get(proposal_id) {
+            Some(pair) => pair,
+            None => return true,
+        };
+        let asset_id_lp = match ProposalLiquidateCurrencyId::<T>::get(proposal_id) {
+            Some(asset_id) => asset_id,
+            None => return false,
+        };
+        [asset_id_1, asset_id_2, asset_id_lp]
+            .iter()
+            .all(|asset_id| {
+                <TokensOf<T> as Tokens<T::AccountId>>::total_supply(*asset_id).is_zero()
+            })
+    }
+
+    /// Remove at most `limit` storage entries of a drained proposal, the entries of the accounts
+    /// go first and the proposal itself last, its final figures are kept in the event
+    pub(crate) fn inner_archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32 {
+        let mut removed = ProposalAccountInfo::<T>::drain_prefix(proposal_id)
+            .take(limit as usize)
+            .count() as u32;
+        removed += ProposalAccountRewardStart::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        removed += ProposalAccountInvalidRemainder::<T>::drain_prefix(proposal_id)
+            .take((limit - removed) as usize)
+            .count() as u32;
+        if removed < limit {
+            if let Some(currency_id) = ProposalCurrencyId::<T>::take(proposal_id) {
+                let volume = ProposalTotalVolume::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+                let fee =
+                    ProposalFinallyMarketFee::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+                let liquid =
+                    ProposalFinallyMarketLiquid::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+                let reward =
+                    ProposalTotalAutonomyReward::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+                Proposals::<T>::remove(proposal_id);
+                PoolPairs::<T>::remove(proposal_id);
+                if let Some(asset_id_lp) = ProposalLiquidateCurrencyId::<T>::take(proposal_id) {
+                    LiquidateCurrencyProposalId::<T>::remove(asset_id_lp);
+                }
+                ProposalTotalEarnTradingFee::<T>::remove(proposal_id);
+                ProposalResult::<T>::remove(proposal_id);
+                ProposalInvalid::<T>::remove(proposal_id);
+                ProposalScalarRange::<T>::remove(proposal_id);
+                ProposalScalarResult::<T>::remove(proposal_id);
+                ProposalTotalMarket::<T>::remove(proposal_id);
+                ProposalTotalOptionalMarket::<T>::remove(proposal_id);
+                ProposalFinallyTotalOptionalMarket::<T>::remove(proposal_id);
+                ProposalTotalMarketFee::<T>::remove(proposal_id);
+                ProposalFeePerShare::<T>::remove(proposal_id);
+                ProposalTotalMarketLiquid::<T>::remove(proposal_id);
+                ProposalCurrentAutonomyReward::<T>::remove(proposal_id);
+                Self::deposit_event(Event::ProposalArchived(
+                    proposal_id,
+                    currency_id,
+                    volume,
+                    fee,
+                    liquid,
+                    reward,
+                ));
+            }
         }
+        removed
+    }
+
+    /// The settlement tokens that `number` LONG or SHORT tokens are worth, according to where
+    /// `value` landed in `[min, max]`
+    pub(crate) fn scalar_payout(
+        is_long: bool,
+        number: BalanceOf<T>,
+        (min, max): (BalanceOf<T>, BalanceOf<T>),
+        value: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let value = cmp::min(cmp::max(value, min), max);
+        let range = max
+            .checked_sub(&min)
+            .ok_or(Error::<T>::ScalarRangeInvalid)?;
+        let share = if is_long {
+            value.checked_sub(&min).unwrap_or_else(Zero::zero)
+        } else {
+            max.checked_sub(&value).unwrap_or_else(Zero::zero)
+        };
+        let payout = number
+            .checked_mul(&share)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        Ok(payout
+            .checked_div(&range)
+            .ok_or(Error::<T>::ScalarRangeInvalid)?)
+    }
+
+    pub(crate) fn inner_scalar_retrieval(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+        range: (BalanceOf<T>, BalanceOf<T>),
+        value: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let (long_id, _) =
+            PoolPairs::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let payout = Self::scalar_payout(optional_currency_id == long_id, number, range, value)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::burn(optional_currency_id, who, number)?;
+        let payout = Self::pay_out(who, proposal_id, payout)?;
+        Self::destroy_retrieved_assets(proposal_id)?;
+        Ok(payout)
     }
 }
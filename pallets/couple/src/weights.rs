@@ -0,0 +1,274 @@
+//! Weights for xpmrl_couple
+//!
+//! PROVISIONAL: the figures below are estimated by hand from the storage accesses of each call
+//! on top of a fixed base cost, they have not been measured by the benchmarks of this pallet
+//! yet and must be replaced by the output of the benchmark CLI before a release.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for xpmrl_couple.
+pub trait WeightInfo {
+    fn new_proposal() -> Weight;
+    fn new_scalar_proposal() -> Weight;
+    fn add_liquidity() -> Weight;
+    fn remove_liquidity() -> Weight;
+    fn buy() -> Weight;
+    fn sell() -> Weight;
+    fn buy_exact_out() -> Weight;
+    fn sell_exact_out() -> Weight;
+    fn buy_from() -> Weight;
+    fn sell_from() -> Weight;
+    fn split() -> Weight;
+    fn merge() -> Weight;
+    fn claim_lp_fees() -> Weight;
+    fn retrieval() -> Weight;
+    fn set_result() -> Weight;
+    fn set_result_when_end() -> Weight;
+    fn set_scalar_result() -> Weight;
+    fn place_order(n: u32) -> Weight;
+    fn cancel_order(n: u32) -> Weight;
+    fn match_orders(n: u32) -> Weight;
+    fn refund_orders(n: u32) -> Weight;
+}
+
+/// Provisional weights for xpmrl_couple, estimated by hand until they are benchmarked.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn new_proposal() -> Weight {
+        (180_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(24 as Weight))
+    }
+    fn new_scalar_proposal() -> Weight {
+        (185_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(25 as Weight))
+    }
+    fn add_liquidity() -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(14 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    fn remove_liquidity() -> Weight {
+        (120_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(21 as Weight))
+            .saturating_add(T::DbWeight::get().writes(13 as Weight))
+    }
+    fn buy() -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(18 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    fn sell() -> Weight {
+        (98_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(18 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    fn buy_exact_out() -> Weight {
+        (105_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(19 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    fn sell_exact_out() -> Weight {
+        (108_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(19 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    fn buy_from() -> Weight {
+        (118_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(21 as Weight))
+            .saturating_add(T::DbWeight::get().writes(13 as Weight))
+    }
+    fn sell_from() -> Weight {
+        (121_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(21 as Weight))
+            .saturating_add(T::DbWeight::get().writes(13 as Weight))
+    }
+    fn split() -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    fn merge() -> Weight {
+        (66_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    fn claim_lp_fees() -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn retrieval() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(17 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    fn set_result() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn set_result_when_end() -> Weight {
+        (38_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn set_scalar_result() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn place_order(n: u32) -> Weight {
+        (140_000_000 as Weight)
+            .saturating_add((12_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(16 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    fn cancel_order(n: u32) -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add((300_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn match_orders(n: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((110_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().reads((14 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes((10 as Weight).saturating_mul(n as Weight)))
+    }
+    fn refund_orders(n: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((35_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn new_proposal() -> Weight {
+        (180_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(24 as Weight))
+    }
+    fn new_scalar_proposal() -> Weight {
+        (185_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(25 as Weight))
+    }
+    fn add_liquidity() -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(14 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn remove_liquidity() -> Weight {
+        (120_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(21 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(13 as Weight))
+    }
+    fn buy() -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(18 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn sell() -> Weight {
+        (98_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(18 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn buy_exact_out() -> Weight {
+        (105_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(19 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn sell_exact_out() -> Weight {
+        (108_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(19 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn buy_from() -> Weight {
+        (118_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(21 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(13 as Weight))
+    }
+    fn sell_from() -> Weight {
+        (121_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(21 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(13 as Weight))
+    }
+    fn split() -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    fn merge() -> Weight {
+        (66_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    fn claim_lp_fees() -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn retrieval() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(17 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn set_result() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn set_result_when_end() -> Weight {
+        (38_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn set_scalar_result() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn place_order(n: u32) -> Weight {
+        (140_000_000 as Weight)
+            .saturating_add((12_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(16 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    fn cancel_order(n: u32) -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add((300_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn match_orders(n: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((110_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((14 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((10 as Weight).saturating_mul(n as Weight)))
+    }
+    fn refund_orders(n: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((35_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
+    }
+}
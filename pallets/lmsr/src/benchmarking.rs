@@ -0,0 +1,211 @@
+//! Benchmarks for the lmsr pallet
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::traits::Time;
+use sp_runtime::traits::{Bounded, One, Saturating, Zero};
+use sp_std::vec;
+use xpmrl_traits::{
+    pool::LiquidityPool, tokens::Tokens, ProposalStatus, Resolution, MAX_RESOLUTION_LENGTH,
+};
+
+const INITIAL_BALANCE: u32 = 1_000_000_000;
+const LIQUIDITY: u32 = 100_000_000;
+
+fn settlement_currency<T: Config>(who: &T::AccountId) -> CurrencyIdOf<T> {
+    let currency_id = <TokensOf<T> as Tokens<T::AccountId>>::new_asset(
+        b"Bench USD".to_vec(),
+        b"BUSD".to_vec(),
+        6,
+    )
+    .expect("create asset failed");
+    <TokensOf<T> as Tokens<T::AccountId>>::mint(currency_id, who, INITIAL_BALANCE.into())
+        .expect("mint failed");
+    currency_id
+}
+
+fn close_time<T: Config>() -> MomentOf<T> {
+    <TimeOf<T> as Time>::now()
+        .saturating_add(T::Pool::get_proposal_minimum_interval_time())
+        .saturating_add(1_000_000u32.into())
+}
+
+fn option_names(o: u32) -> Vec<Vec<u8>> {
+    (0..o).map(|i| vec![b'A' + (i % 26) as u8]).collect()
+}
+
+/// The longest resolution accepted for a proposal closing at `time`
+fn resolution<T: Config>(time: MomentOf<T>) -> Resolution<MomentOf<T>> {
+    Resolution {
+        source: vec![b's'; MAX_RESOLUTION_LENGTH],
+        rules: vec![b'r'; MAX_RESOLUTION_LENGTH],
+        time,
+        invalid_condition: vec![b'i'; MAX_RESOLUTION_LENGTH],
+    }
+}
+
+fn create_proposal<T: Config>(who: &T::AccountId, o: u32) -> ProposalIdOf<T> {
+    let currency_id = settlement_currency::<T>(who);
+    let close_time = close_time::<T>();
+    Pallet::<T>::new_proposal(
+        RawOrigin::Signed(who.clone()).into(),
+        b"benchmark proposal".to_vec(),
+        option_names(o),
+        close_time,
+        One::one(),
+        currency_id,
+        LIQUIDITY.into(),
+        2000,
+        b"benchmark proposal detail".to_vec(),
+        resolution::<T>(close_time),
+    )
+    .expect("create proposal failed");
+    T::Pool::max_proposal_id().saturating_sub(One::one())
+}
+
+fn trading_proposal<T: Config>(
+    who: &T::AccountId,
+    o: u32,
+) -> Result<(ProposalIdOf<T>, CurrencyIdOf<T>), &'static str> {
+    let proposal_id = create_proposal::<T>(who, o);
+    T::Pool::set_proposal_state(proposal_id, ProposalStatus::FormalPrediction)?;
+    let options = PoolOptions::<T>::get(proposal_id).ok_or("proposal not exist")?;
+    Ok((proposal_id, options[0]))
+}
+
+fn settle<T: Config>(
+    proposal_id: ProposalIdOf<T>,
+    result_id: CurrencyIdOf<T>,
+) -> Result<(), &'static str> {
+    T::Pool::set_proposal_state(proposal_id, ProposalStatus::WaitingForResults)?;
+    Pallet::<T>::set_result(RawOrigin::Root.into(), proposal_id, result_id).map_err(|e| e.error)?;
+    Ok(())
+}
+
+benchmarks! {
+    new_proposal {
+        let o in 2 .. T::MaxOptions::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let currency_id = settlement_currency::<T>(&caller);
+        let close_time = close_time::<T>();
+    }: _(
+        RawOrigin::Signed(caller),
+        b"benchmark proposal".to_vec(),
+        option_names(o),
+        close_time,
+        One::one(),
+        currency_id,
+        LIQUIDITY.into(),
+        2000,
+        b"benchmark proposal detail".to_vec(),
+        resolution::<T>(close_time)
+    )
+    verify {
+        let proposal_id = T::Pool::max_proposal_id() - One::one();
+        assert_eq!(PoolOptions::<T>::get(proposal_id).map(|x| x.len()), Some(o as usize));
+    }
+
+    remove_liquidity {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, result_id) = trading_proposal::<T>(&caller, T::MaxOptions::get())?;
+        settle::<T>(proposal_id, result_id)?;
+        let lp_id = ProposalLiquidateCurrencyId::<T>::get(proposal_id).ok_or("proposal not exist")?;
+        let number = <TokensOf<T> as Tokens<T::AccountId>>::balance(lp_id, &caller);
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id, number)
+    verify {
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(lp_id, &caller);
+        assert_eq!(balance, Zero::zero());
+    }
+
+    buy {
+        let o in 2 .. T::MaxOptions::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, option_id) = trading_proposal::<T>(&caller, o)?;
+    }: _(
+        RawOrigin::Signed(caller.clone()),
+        proposal_id,
+        option_id,
+        1_000_000u32.into(),
+        Bounded::max_value()
+    )
+    verify {
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(option_id, &caller);
+        assert!(balance > Zero::zero());
+    }
+
+    sell {
+        let o in 2 .. T::MaxOptions::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, option_id) = trading_proposal::<T>(&caller, o)?;
+        Pallet::<T>::buy(
+            RawOrigin::Signed(caller.clone()).into(),
+            proposal_id,
+            option_id,
+            1_000_000u32.into(),
+            Bounded::max_value(),
+        )
+        .map_err(|e| e.error)?;
+        let number = <TokensOf<T> as Tokens<T::AccountId>>::balance(option_id, &caller);
+    }: _(RawOrigin::Signed(caller), proposal_id, option_id, number, Zero::zero())
+
+    retrieval {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, option_id) = trading_proposal::<T>(&caller, T::MaxOptions::get())?;
+        Pallet::<T>::buy(
+            RawOrigin::Signed(caller.clone()).into(),
+            proposal_id,
+            option_id,
+            1_000_000u32.into(),
+            Bounded::max_value(),
+        )
+        .map_err(|e| e.error)?;
+        settle::<T>(proposal_id, option_id)?;
+        let number = <TokensOf<T> as Tokens<T::AccountId>>::balance(option_id, &caller);
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id, option_id, number)
+    verify {
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(option_id, &caller);
+        assert_eq!(balance, Zero::zero());
+    }
+
+    set_result {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, option_id) = trading_proposal::<T>(&caller, T::MaxOptions::get())?;
+        T::Pool::set_proposal_state(proposal_id, ProposalStatus::WaitingForResults)?;
+    }: _(RawOrigin::Root, proposal_id, option_id)
+    verify {
+        assert_eq!(ProposalResult::<T>::get(proposal_id), Some(option_id));
+    }
+
+    set_result_when_end {
+        let caller: T::AccountId = whitelisted_caller();
+        let (proposal_id, option_id) = trading_proposal::<T>(&caller, T::MaxOptions::get())?;
+        settle::<T>(proposal_id, option_id)?;
+        let options = PoolOptions::<T>::get(proposal_id).ok_or("proposal not exist")?;
+        let other_id = options[1];
+    }: _(RawOrigin::Root, proposal_id, other_id)
+    verify {
+        assert_eq!(ProposalResult::<T>::get(proposal_id), Some(other_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_new_proposal::<Test>());
+            assert_ok!(test_benchmark_remove_liquidity::<Test>());
+            assert_ok!(test_benchmark_buy::<Test>());
+            assert_ok!(test_benchmark_sell::<Test>());
+            assert_ok!(test_benchmark_retrieval::<Test>());
+            assert_ok!(test_benchmark_set_result::<Test>());
+            assert_ok!(test_benchmark_set_result_when_end::<Test>());
+        });
+    }
+}
@@ -0,0 +1,711 @@
+//! <!-- markdown-link-check-disable -->
+//! # Lmsr
+//!
+//! Run `cargo doc --package xpmrl-lmsr --open` to view this pallet's documentation.
+//!
+//! A market engine pricing the options of a proposal with the logarithmic market scoring rule
+//!
+//! - [`xpmrl_lmsr::Config`](./pallet/trait.Config.html)
+//! - [`Call`](./pallet/enum.Call.html)
+//! - [`Pallet`](./pallet/struct.Pallet.html)
+//!
+//! ## Overview
+//!
+//! Instead of a pool of option tokens, the market maker keeps the number `q_i` of tokens of
+//! each option sold so far, and the cost function `C(q) = b * ln(sum(e^(q_i / b)))` prices
+//! every trade: buying or selling moves `q` and costs the difference of `C` before and after.
+//!
+//! The liquidity parameter `b` is chosen by the creator of the proposal, who funds the market
+//! with the subsidy `b * ln(n)` for `n` options and gets LP tokens for it. This is the most the
+//! market maker can lose, whatever is traded. After the result is set, the LP tokens take back
+//! what is left once every winning token is paid, together with the trading fees.
+//!
+//! Prices stay defined however thin the market is, so a proposal only needs a small subsidy
+//! instead of a whole pool.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unused_unit)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod math;
+pub mod weights;
+
+pub(crate) mod tools;
+
+use frame_support::{traits::Get, weights::Weight};
+use frame_system::RawOrigin;
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+use xpmrl_traits::{
+    couple::{LiquidityCouple, INVALID_RESULT_ID},
+    pool::{LiquiditySubPool, ProposalArchive},
+    registry::LiquidityEngine,
+};
+
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, traits::Time};
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::Zero;
+    use xpmrl_traits::{
+        pool::LiquidityPool, ruler::RulerAccounts, system::ProposalSystem, tokens::Tokens,
+        ProposalStatus, Resolution,
+    };
+    use xpmrl_utils::with_transaction_result;
+
+    pub(crate) type TokensOf<T> =
+        <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::Tokens;
+    pub(crate) type CurrencyIdOf<T> =
+        <TokensOf<T> as Tokens<<T as frame_system::Config>::AccountId>>::CurrencyId;
+    pub(crate) type BalanceOf<T> =
+        <TokensOf<T> as Tokens<<T as frame_system::Config>::AccountId>>::Balance;
+    pub(crate) type CategoryIdOf<T> =
+        <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::CategoryId;
+    pub(crate) type ProposalIdOf<T> =
+        <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::ProposalId;
+    pub(crate) type VersionIdOf<T> =
+        <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::VersionId;
+    pub(crate) type TimeOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::Time;
+    pub(crate) type MomentOf<T> = <TimeOf<T> as Time>::Moment;
+
+    /// Basic attributes of the proposal
+    #[derive(PartialEq, Eq, Clone, RuntimeDebug, Encode, Decode, Default)]
+    pub struct Proposal<CategoryId> {
+        pub title: Vec<u8>,
+        /// The category of the proposal, such as sports, competition
+        pub category_id: CategoryId,
+        /// The specific description of the proposal
+        pub detail: Vec<u8>,
+    }
+
+    /// This is the pallet's configuration trait
+    ///
+    /// Inherited from the proposal pallet, it can use the related functions of the proposal
+    /// pallet, which is equivalent to deriving the function of the proposal pallet.
+    #[pallet::config]
+    pub trait Config:
+        frame_system::Config + ProposalSystem<<Self as frame_system::Config>::AccountId>
+    {
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+        type Pool: LiquidityPool<Self>;
+        type Ruler: RulerAccounts<Self>;
+
+        /// Decimals of fee
+        #[pallet::constant]
+        type EarnTradingFeeDecimals: Get<u8>;
+
+        /// The version id recorded for proposals created by this module, it must be different
+        /// from the version ids of the other market engines
+        #[pallet::constant]
+        type CurrentLiquidateVersionId: Get<VersionIdOf<Self>>;
+
+        /// The maximum number of options that a proposal can contain
+        #[pallet::constant]
+        type MaxOptions: Get<u32>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::pallet]
+    #[pallet::generate_store(pub(super) trait Store)]
+    pub struct Pallet<T>(_);
+
+    /// store the basic attributes of all proposals.
+    #[pallet::storage]
+    #[pallet::getter(fn proposals)]
+    pub type Proposals<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, Proposal<T::CategoryId>, OptionQuery>;
+
+    /// It stores the option tokens of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn pool_options)]
+    pub type PoolOptions<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, Vec<CurrencyIdOf<T>>, OptionQuery>;
+
+    /// It stores the settlement token of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_currency_id)]
+    pub type ProposalCurrencyId<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, CurrencyIdOf<T>, OptionQuery>;
+
+    /// It stores the liquidity parameter `b` of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_liquidity)]
+    pub type ProposalLiquidity<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores the number of tokens of each option sold by the market maker, in the same
+    /// order as `PoolOptions`, the retrieved tokens are taken off it once the proposal ends
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_outstanding)]
+    pub type ProposalOutstanding<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, Vec<BalanceOf<T>>, OptionQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_volume)]
+    pub type ProposalTotalVolume<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores the liquidity token of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_liquidate_currency_id)]
+    pub type ProposalLiquidateCurrencyId<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, CurrencyIdOf<T>, OptionQuery>;
+
+    /// It stores the fee rate of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_earn_trading_fee)]
+    pub type ProposalTotalEarnTradingFee<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, u32, OptionQuery>;
+
+    /// It stores the results of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_result)]
+    pub type ProposalResult<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, CurrencyIdOf<T>, OptionQuery>;
+
+    /// It stores the proposals settled as invalid, they have no `ProposalResult` and each of
+    /// their option tokens redeems an equal share of a settlement token
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_invalid)]
+    pub type ProposalInvalid<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, (), OptionQuery>;
+
+    /// It stores the option tokens of an invalid proposal an account has retrieved without being
+    /// paid for yet, as one settlement token takes one option token per option
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_account_invalid_remainder)]
+    pub type ProposalAccountInvalidRemainder<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// It stores the sum of `ProposalAccountInvalidRemainder` over the accounts of the proposal,
+    /// so that the LP tokens leave enough for those remainders to be paid
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_invalid_remainder)]
+    pub type ProposalTotalInvalidRemainder<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores the settlement currency held by the market maker of the proposal, the subsidy
+    /// and everything paid for the options, without the fees
+    ///
+    /// Once the result is set, what is left after paying the winning tokens belongs to the LP
+    /// tokens.
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_market)]
+    pub type ProposalTotalMarket<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores the total fee of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_market_fee)]
+    pub type ProposalTotalMarketFee<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores all the final fees of the proposal, fixed when the result is set
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_finally_market_fee)]
+    pub type ProposalFinallyMarketFee<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores all the liquidity of the proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_market_liquid)]
+    pub type ProposalTotalMarketLiquid<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// It stores all the final liquidity of the proposal
+    ///
+    /// Same as `ProposalFinallyMarketFee`
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_finally_market_liquid)]
+    pub type ProposalFinallyMarketLiquid<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// After the prediction is successful, the withdrawal fee rate charged at the time of
+    /// liquidation
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_withdrawal_fee_rate)]
+    pub type ProposalWithdrawalFeeRate<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+    /// After the proposal is over, when the user is clearing, the total settlement currency reward
+    /// that the node that participates in providing the result can obtain
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_total_autonomy_reward)]
+    pub type ProposalTotalAutonomyReward<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    /// Same as `ProposalCurrentAutonomyReward` of the couple module
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_current_autonomy_reward)]
+    pub type ProposalCurrentAutonomyReward<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig {
+        pub withdrawal_fee_rate: u32,
+    }
+
+    #[cfg(feature = "std")]
+    impl Default for GenesisConfig {
+        fn default() -> Self {
+            Self {
+                withdrawal_fee_rate: 50,
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig {
+        fn build(&self) {
+            ProposalWithdrawalFeeRate::<T>::set(Some(self.withdrawal_fee_rate));
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::metadata(T::AccountId = "AccountId")]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        RemoveLiquidity(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        /// Bought a number of option tokens, paying the amount of settlement currency in the
+        /// last field, fee included
+        Buy(
+            T::AccountId,
+            ProposalIdOf<T>,
+            CurrencyIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Sold a number of option tokens, receiving the amount of settlement currency in the
+        /// last field, fee deducted
+        Sell(
+            T::AccountId,
+            ProposalIdOf<T>,
+            CurrencyIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+        /// A liquidation event occurs after the liquidation, and the amount of liquidation will be
+        /// included in the event
+        Retrieval(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        SetResult(ProposalIdOf<T>, CurrencyIdOf<T>),
+        /// A proposal was created, funded with the subsidy in the last field
+        NewProposal(T::AccountId, ProposalIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>),
+        /// Same as `ProposalArchived` of the couple module
+        ProposalArchived(
+            ProposalIdOf<T>,
+            CurrencyIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// When buying, selling or clearing, if the currency id you enter is not the id of the
+        /// option currency, this error will be thrown
+        CurrencyIdNotFound,
+        /// The status of the current proposal is incorrect, and the current operation is not
+        /// supported.
+        ProposalAbnormalState,
+        /// A non-existent proposal was executed
+        ProposalIdNotExist,
+        /// During liquidation, this error will be thrown if the proposal does not have a result
+        /// set
+        ProposalNotResult,
+        /// The quantity overflowed during calculation
+        BalanceOverflow,
+        InsufficientBalance,
+        CategoryIdNotZero,
+        TokenIdNotZero,
+        NumberMustMoreThanZero,
+        CloseTimeMustLargeThanNow,
+        CurrencyIdNotAllowed,
+        /// A proposal needs at least two options
+        TooFewOptions,
+        /// The number of options exceeds `MaxOptions`
+        TooManyOptions,
+        /// The amount received is less than the minimum the transactor accepts
+        ReceivedLessThanMinimum,
+        /// The amount paid is more than the maximum the transactor accepts
+        PaidMoreThanMaximum,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Create a new proposal with several options, priced with the liquidity parameter
+        /// `liquidity`
+        ///
+        /// The creator pays the subsidy `liquidity * ln(n)` for `n` options and gets as many LP
+        /// tokens. A larger `liquidity` makes the prices move less for the same trade.
+        ///
+        /// `resolution` states how the question of the proposal resolves, each of its texts is at
+        /// most `MAX_RESOLUTION_LENGTH` long and its time can not be before `close_time`.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::new_proposal(optional.len() as u32))]
+        pub fn new_proposal(
+            origin: OriginFor<T>,
+            title: Vec<u8>,
+            optional: Vec<Vec<u8>>,
+            close_time: MomentOf<T>,
+            category_id: CategoryIdOf<T>,
+            currency_id: CurrencyIdOf<T>,
+            liquidity: BalanceOf<T>,
+            earn_fee: u32,
+            detail: Vec<u8>,
+            resolution: Resolution<MomentOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(optional.len() >= 2, Error::<T>::TooFewOptions);
+            ensure!(
+                optional.len() <= T::MaxOptions::get() as usize,
+                Error::<T>::TooManyOptions
+            );
+            ensure!(category_id > Zero::zero(), Error::<T>::CategoryIdNotZero);
+            ensure!(currency_id > Zero::zero(), Error::<T>::TokenIdNotZero);
+            ensure!(liquidity > Zero::zero(), Error::<T>::NumberMustMoreThanZero);
+            let now = <TimeOf<T> as Time>::now();
+            let minimum_interval_time = T::Pool::get_proposal_minimum_interval_time();
+            ensure!(
+                close_time > now + minimum_interval_time,
+                Error::<T>::CloseTimeMustLargeThanNow
+            );
+            ensure!(
+                !T::Pool::is_currency_id_used(currency_id),
+                Error::<T>::CurrencyIdNotAllowed
+            );
+            let (proposal_id, subsidy) = with_transaction_result(|| {
+                let proposal_id = T::Pool::get_next_proposal_id()?;
+                Self::init_pool(
+                    &who,
+                    proposal_id,
+                    title,
+                    close_time,
+                    category_id,
+                    earn_fee,
+                    detail,
+                    resolution,
+                )?;
+                let subsidy =
+                    Self::new_currency(&who, proposal_id, currency_id, liquidity, optional)?;
+                Ok((proposal_id, subsidy))
+            })?;
+            Self::deposit_event(Event::NewProposal(who, proposal_id, currency_id, subsidy));
+            Ok(().into())
+        }
+
+        /// Take back the share of the LP tokens in what is left of the subsidy and the payments
+        /// once every winning token is paid, together with their share of the trading fees
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::remove_liquidity())]
+        pub fn remove_liquidity(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::End,
+                Error::<T>::ProposalAbnormalState
+            );
+            let currency_id =
+                ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            let amount = with_transaction_result(|| {
+                Self::inner_remove_liquidity(&who, proposal_id, number)
+            })?;
+            Self::deposit_event(Event::RemoveLiquidity(
+                who,
+                proposal_id,
+                currency_id,
+                amount,
+            ));
+            Ok(().into())
+        }
+
+        /// Buy `number` option tokens, paying at most `max_cost` settlement currency for them,
+        /// fee included
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::buy(T::MaxOptions::get()))]
+        pub fn buy(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+            max_cost: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            ensure!(number > Zero::zero(), Error::<T>::NumberMustMoreThanZero);
+            let index = Self::option_index(proposal_id, optional_currency_id)?;
+            let paid = with_transaction_result(|| {
+                Self::inner_buy(&who, proposal_id, index, number, max_cost)
+            })?;
+            Self::deposit_event(Event::Buy(
+                who,
+                proposal_id,
+                optional_currency_id,
+                number,
+                paid,
+            ));
+            Ok(().into())
+        }
+
+        /// Sell `number` option tokens, receiving at least `min_proceeds` settlement currency for
+        /// them, fee deducted
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::sell(T::MaxOptions::get()))]
+        pub fn sell(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+            min_proceeds: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::FormalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            ensure!(number > Zero::zero(), Error::<T>::NumberMustMoreThanZero);
+            let index = Self::option_index(proposal_id, optional_currency_id)?;
+            let received = with_transaction_result(|| {
+                Self::inner_sell(&who, proposal_id, index, number, min_proceeds)
+            })?;
+            Self::deposit_event(Event::Sell(
+                who,
+                proposal_id,
+                optional_currency_id,
+                number,
+                received,
+            ));
+            Ok(().into())
+        }
+
+        /// Settlement option currency, get settlement currency
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::retrieval())]
+        pub fn retrieval(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            optional_currency_id: CurrencyIdOf<T>,
+            number: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::End,
+                Error::<T>::ProposalAbnormalState
+            );
+            Self::option_index(proposal_id, optional_currency_id)?;
+            let result_id = <Self as LiquidityCouple<T>>::get_proposal_result(proposal_id)?;
+            let balance =
+                <TokensOf<T> as Tokens<T::AccountId>>::balance(optional_currency_id, &who);
+            let number = if number >= balance { balance } else { number };
+            let number = with_transaction_result(|| -> Result<BalanceOf<T>, DispatchError> {
+                if ProposalInvalid::<T>::contains_key(proposal_id) {
+                    Self::inner_invalid_retrieval(&who, proposal_id, optional_currency_id, number)
+                } else {
+                    Self::inner_retrieval(
+                        &who,
+                        proposal_id,
+                        result_id,
+                        optional_currency_id,
+                        number,
+                    )
+                }
+            })?;
+            Self::deposit_event(Event::Retrieval(who, proposal_id, result_id, number));
+            Ok(().into())
+        }
+
+        /// Set result for proposal
+        ///
+        /// The result is one of the options of the proposal, or `INVALID_RESULT_ID` to void the
+        /// proposal, in which case every option token redeems an equal share of the collateral.
+        ///
+        /// The dispatch origin for this call is `root`.
+        #[pallet::weight(T::WeightInfo::set_result())]
+        pub fn set_result(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            currency_id: CurrencyIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_root(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::WaitingForResults,
+                Error::<T>::ProposalAbnormalState
+            );
+            let invalid = Self::is_invalid_result(proposal_id, currency_id);
+            if !invalid {
+                Self::option_index(proposal_id, currency_id)?;
+            }
+            with_transaction_result(|| {
+                T::Pool::set_proposal_state(proposal_id, ProposalStatus::End)?;
+                Self::insert_result(proposal_id, currency_id, invalid);
+                Self::finally_locked(proposal_id)
+            })?;
+            Self::deposit_event(Event::SetResult(proposal_id, currency_id));
+            Ok(().into())
+        }
+
+        /// Set result for proposal when the state is over
+        ///
+        /// As in `set_result`, `INVALID_RESULT_ID` voids the proposal.
+        ///
+        /// The dispatch origin for this call is `root`.
+        #[pallet::weight(T::WeightInfo::set_result_when_end())]
+        pub fn set_result_when_end(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            currency_id: CurrencyIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_root(origin)?;
+            let status = T::Pool::get_proposal_state(proposal_id)?;
+            ensure!(
+                status == ProposalStatus::End,
+                Error::<T>::ProposalAbnormalState
+            );
+            let invalid = Self::is_invalid_result(proposal_id, currency_id);
+            if !invalid {
+                Self::option_index(proposal_id, currency_id)?;
+            }
+            with_transaction_result(|| {
+                Self::insert_result(proposal_id, currency_id, invalid);
+                Self::finally_locked(proposal_id)
+            })?;
+            Self::deposit_event(Event::SetResult(proposal_id, currency_id));
+            Ok(().into())
+        }
+    }
+}
+
+impl<T: Config> LiquiditySubPool<T> for Pallet<T> {
+    fn finally_locked(proposal_id: ProposalIdOf<T>) -> Result<(), DispatchError> {
+        Self::finally_locked(proposal_id)
+    }
+
+    fn state_changed_weight() -> Weight {
+        T::DbWeight::get().reads_writes(2, 2)
+    }
+}
+
+impl<T: Config> LiquidityCouple<T> for Pallet<T> {
+    fn proposal_pair(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<(CurrencyIdOf<T>, CurrencyIdOf<T>), DispatchError> {
+        let options = <Self as LiquidityCouple<T>>::proposal_options(proposal_id)?;
+        Ok((options[0], options[1]))
+    }
+
+    fn proposal_options(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<Vec<CurrencyIdOf<T>>, DispatchError> {
+        match PoolOptions::<T>::get(proposal_id) {
+            Some(options) => Ok(options),
+            None => Err(Error::<T>::ProposalIdNotExist.into()),
+        }
+    }
+
+    fn proposal_invalid_result(proposal_id: ProposalIdOf<T>) -> Option<CurrencyIdOf<T>> {
+        if !PoolOptions::<T>::contains_key(proposal_id) {
+            return None;
+        }
+        Some(INVALID_RESULT_ID.into())
+    }
+
+    fn set_proposal_result(
+        proposal_id: ProposalIdOf<T>,
+        result: CurrencyIdOf<T>,
+    ) -> Result<(), DispatchError> {
+        match Self::set_result(RawOrigin::Root.into(), proposal_id, result) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.error),
+        }
+    }
+
+    fn set_proposal_result_when_end(
+        proposal_id: ProposalIdOf<T>,
+        result: CurrencyIdOf<T>,
+    ) -> Result<(), DispatchError> {
+        match Self::set_result_when_end(RawOrigin::Root.into(), proposal_id, result) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.error),
+        }
+    }
+
+    fn get_proposal_result(proposal_id: ProposalIdOf<T>) -> Result<CurrencyIdOf<T>, DispatchError> {
+        if ProposalInvalid::<T>::contains_key(proposal_id) {
+            return match Self::proposal_invalid_result(proposal_id) {
+                Some(result) => Ok(result),
+                None => Err(Error::<T>::ProposalIdNotExist.into()),
+            };
+        }
+        match ProposalResult::<T>::get(proposal_id) {
+            Some(result) => Ok(result),
+            None => Err(Error::<T>::ProposalNotResult.into()),
+        }
+    }
+
+    fn proposal_liquidate_currency_id(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<CurrencyIdOf<T>, DispatchError> {
+        match ProposalLiquidateCurrencyId::<T>::get(proposal_id) {
+            Some(id) => Ok(id),
+            None => Err(Error::<T>::ProposalIdNotExist.into()),
+        }
+    }
+}
+
+impl<T: Config> LiquidityEngine<T> for Pallet<T> {
+    fn version_id() -> VersionIdOf<T> {
+        T::CurrentLiquidateVersionId::get()
+    }
+}
+
+impl<T: Config> ProposalArchive<T> for Pallet<T> {
+    fn is_drained(proposal_id: ProposalIdOf<T>) -> bool {
+        Self::is_drained(proposal_id)
+    }
+
+    fn archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32 {
+        Self::inner_archive(proposal_id, limit)
+    }
+}
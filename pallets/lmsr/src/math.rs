@@ -0,0 +1,130 @@
+//! Fixed-point arithmetic of the LMSR cost function
+//!
+//! Fixed-point numbers are `u128` scaled by `ONE`. Only `e^-x` for `x >= 0` and `ln(x)` for
+//! `x >= 1` are needed, so every intermediate value stays unsigned and no floating point is
+//! involved.
+
+use sp_runtime::helpers_128bit::multiply_by_rational;
+use sp_std::vec::Vec;
+
+/// `1` in fixed point
+pub const ONE: u128 = 1_000_000_000_000_000_000;
+
+/// `ln(2)` in fixed point
+const LN_2: u128 = 693_147_180_559_945_309;
+
+/// `a * b / c`, rounded down, `None` on overflow or if `c` is zero
+pub fn mul_div(a: u128, b: u128, c: u128) -> Option<u128> {
+    if c == 0 {
+        return None;
+    }
+    multiply_by_rational(a, b, c).ok()
+}
+
+/// `e^-x` for a fixed-point `x`, rounded down
+///
+/// `x` is split into `k * ln(2) + r` with `0 <= r < ln(2)`, `e^r` is summed from its Taylor
+/// series, whose terms are all positive, and `e^-x = 2^-k / e^r`.
+pub fn exp_neg(x: u128) -> u128 {
+    let k = x / LN_2;
+    // 2^-60 is already below the precision of `ONE`
+    if k >= 60 {
+        return 0;
+    }
+    let r = x - k * LN_2;
+    let mut sum = ONE;
+    let mut term = ONE;
+    let mut i = 1;
+    while term > 0 {
+        term = term * r / ONE / i;
+        sum += term;
+        i += 1;
+    }
+    (ONE * ONE / sum) >> k
+}
+
+/// `ln(x)` for a fixed-point `x`, rounded down, zero for `x <= 1`
+///
+/// `x` is split into `2^k * y` with `1 <= y < 2`, and `ln(y) = 2 * atanh(z)` with
+/// `z = (y - 1) / (y + 1) < 1 / 3` is summed from the series of `atanh`.
+pub fn ln(x: u128) -> u128 {
+    if x <= ONE {
+        return 0;
+    }
+    let mut k = 0;
+    let mut y = x;
+    while y >= 2 * ONE {
+        y /= 2;
+        k += 1;
+    }
+    let z = (y - ONE) * ONE / (y + ONE);
+    let z2 = z * z / ONE;
+    let mut sum = 0;
+    let mut power = z;
+    let mut i = 1;
+    while power > 0 {
+        sum += power / i;
+        power = power * z2 / ONE;
+        i += 2;
+    }
+    k * LN_2 + 2 * sum
+}
+
+/// `e^((q - max) / b)` of every quantity in fixed point, the one of the largest quantity is
+/// exactly `ONE`, so their sum is at least `ONE`
+fn weights(b: u128, quantities: &[u128]) -> Option<(u128, u128, Vec<u128>)> {
+    let max = quantities.iter().copied().max()?;
+    let mut sum: u128 = 0;
+    let mut weights = Vec::with_capacity(quantities.len());
+    for quantity in quantities {
+        let x = mul_div(max - quantity, ONE, b).unwrap_or(u128::MAX);
+        let weight = exp_neg(x);
+        sum = sum.checked_add(weight)?;
+        weights.push(weight);
+    }
+    Some((max, sum, weights))
+}
+
+/// The LMSR cost `C(q) = b * ln(sum(e^(q_i / b)))` of the outstanding `quantities`, rounded
+/// down
+///
+/// It is computed as `max(q) + b * ln(sum(e^((q_i - max(q)) / b)))`, so no exponent is positive
+/// and the cost is never below the largest quantity.
+pub fn cost(b: u128, quantities: &[u128]) -> Option<u128> {
+    let (max, sum, _) = weights(b, quantities)?;
+    max.checked_add(mul_div(b, ln(sum), ONE)?)
+}
+
+/// The price `e^(q_i / b) / sum(e^(q_j / b))` of every option in fixed point, the prices add
+/// up to `ONE` up to rounding
+pub fn prices(b: u128, quantities: &[u128]) -> Option<Vec<u128>> {
+    let (_, sum, weights) = weights(b, quantities)?;
+    weights
+        .into_iter()
+        .map(|weight| mul_div(weight, ONE, sum))
+        .collect()
+}
+
+/// The subsidy that funds a market of `options` options with the liquidity parameter `b`
+///
+/// It is `C(0) = b * ln(options)` rounded up, the most the market maker can lose.
+pub fn subsidy(b: u128, options: usize) -> Option<u128> {
+    cost(b, &sp_std::vec![0; options])?.checked_add(1)
+}
+
+/// The settlement currency to pay for adding `number` to the quantity at `index`, rounded up
+pub fn buy_cost(b: u128, quantities: &[u128], index: usize, number: u128) -> Option<u128> {
+    let before = cost(b, quantities)?;
+    let mut after = quantities.to_vec();
+    after[index] = after[index].checked_add(number)?;
+    Some(cost(b, &after)?.checked_add(1)?.saturating_sub(before))
+}
+
+/// The settlement currency received for removing `number` from the quantity at `index`,
+/// rounded down
+pub fn sell_proceeds(b: u128, quantities: &[u128], index: usize, number: u128) -> Option<u128> {
+    let before = cost(b, quantities)?;
+    let mut after = quantities.to_vec();
+    after[index] = after[index].checked_sub(number)?;
+    Some(before.saturating_sub(cost(b, &after)?.saturating_add(1)))
+}
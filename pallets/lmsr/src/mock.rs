@@ -0,0 +1,48 @@
+#![allow(clippy::from_over_into)]
+
+use crate as lmsr;
+use frame_support::{parameter_types, traits::GenesisBuild};
+
+xpmrl_test_support::mock_runtime!(LmsrModule: lmsr, BeforeTransfer = ());
+
+parameter_types! {
+    pub const CurrentLiquidateVersionId: VersionId = 3;
+    pub const EarnTradingFeeDecimals: u8 = 4;
+    pub const MaxOptions: u32 = 8;
+}
+
+impl lmsr::Config for Test {
+    type Event = Event;
+    type Pool = ProposalsWrapper;
+    type Ruler = RulerWrapper;
+    type EarnTradingFeeDecimals = EarnTradingFeeDecimals;
+    type CurrentLiquidateVersionId = CurrentLiquidateVersionId;
+    type MaxOptions = MaxOptions;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    let tokens_genesis = xpmrl_tokens::GenesisConfig::<Test> {
+        tokens: vec![
+            (
+                "Tether USD".as_bytes().to_vec(),
+                "USDT".as_bytes().to_vec(),
+                6,
+            ),
+            ("Bitcoin".as_bytes().to_vec(), "BTC".as_bytes().to_vec(), 8),
+        ],
+        balances: vec![(1, 1_000_000_000), (2, 1_000_000_000)],
+    };
+    let lmsr_genesis = lmsr::GenesisConfig {
+        withdrawal_fee_rate: 50,
+    };
+
+    tokens_genesis.assimilate_storage(&mut t).unwrap();
+    GenesisBuild::<Test>::assimilate_storage(&lmsr_genesis, &mut t).unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
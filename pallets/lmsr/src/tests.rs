@@ -0,0 +1,470 @@
+use crate::{math, mock::*, Error};
+
+use frame_support::{assert_noop, assert_ok};
+use xpmrl_traits::{
+    couple::{LiquidityCouple, INVALID_RESULT_ID},
+    pool::{LiquidityPool, ProposalArchive},
+    ProposalStatus as ProposalState,
+};
+
+fn create_proposal(
+    account: AccountId,
+    options: usize,
+    liquidity: BalanceOf<Test>,
+    rate: u32,
+) -> ProposalIdOf<Test> {
+    create_proposal_with(10, |close_time| {
+        LmsrModule::new_proposal(
+            Origin::signed(account),
+            "who will win the league".as_bytes().to_vec(),
+            (0..options)
+                .map(|i| format!("team {}", i).as_bytes().to_vec())
+                .collect(),
+            close_time,
+            1,
+            1,
+            liquidity,
+            rate,
+            "proposal detail".as_bytes().to_vec(),
+            resolution(close_time),
+        )
+    })
+}
+
+fn balance(account: AccountId, currency_id: CurrencyIdOf<Test>) -> BalanceOf<Test> {
+    XPMRLTokens::free_balance_of(account, currency_id).unwrap_or(0)
+}
+
+#[test]
+fn test_math() {
+    for x in [
+        0u128,
+        1,
+        math::ONE / 3,
+        math::ONE,
+        5 * math::ONE,
+        40 * math::ONE,
+    ]
+    .iter()
+    {
+        let expected = (-(*x as f64) / 1e18).exp();
+        let actual = math::exp_neg(*x) as f64 / 1e18;
+        assert!((expected - actual).abs() < 1e-12);
+    }
+    for x in [
+        math::ONE,
+        math::ONE + 1,
+        2 * math::ONE,
+        3 * math::ONE,
+        1000 * math::ONE,
+    ]
+    .iter()
+    {
+        let expected = (*x as f64 / 1e18).ln();
+        let actual = math::ln(*x) as f64 / 1e18;
+        assert!((expected - actual).abs() < 1e-12);
+    }
+    assert_eq!(math::subsidy(1_000_000, 2), Some(693148));
+    assert_eq!(math::subsidy(1_000_000, 3), Some(1098613));
+    // Rounded against the trader both ways, so a round trip always loses a little
+    assert_eq!(
+        math::buy_cost(1_000_000, &[0, 0, 0], 0, 1_000_000),
+        Some(452833)
+    );
+    assert_eq!(
+        math::sell_proceeds(1_000_000, &[1_000_000, 0, 0], 0, 1_000_000),
+        Some(452831)
+    );
+    assert_eq!(math::sell_proceeds(1_000_000, &[0, 0, 0], 0, 1), None);
+}
+
+#[test]
+fn test_new_proposal() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 1;
+        assert_noop!(
+            LmsrModule::new_proposal(
+                Origin::signed(account),
+                "how to test this module".as_bytes().to_vec(),
+                vec!["only one".as_bytes().to_vec()],
+                10,
+                1,
+                1,
+                1_000_000,
+                100,
+                "proposal detail".as_bytes().to_vec(),
+                resolution(10),
+            ),
+            Error::<Test>::TooFewOptions
+        );
+        assert_noop!(
+            LmsrModule::new_proposal(
+                Origin::signed(account),
+                "how to test this module".as_bytes().to_vec(),
+                vec!["option".as_bytes().to_vec(); 9],
+                10,
+                1,
+                1,
+                1_000_000,
+                100,
+                "proposal detail".as_bytes().to_vec(),
+                resolution(10),
+            ),
+            Error::<Test>::TooManyOptions
+        );
+        assert_noop!(
+            LmsrModule::new_proposal(
+                Origin::signed(account),
+                "how to test this module".as_bytes().to_vec(),
+                vec!["option".as_bytes().to_vec(); 3],
+                10,
+                1,
+                1,
+                0,
+                100,
+                "proposal detail".as_bytes().to_vec(),
+                resolution(10),
+            ),
+            Error::<Test>::NumberMustMoreThanZero
+        );
+
+        let id = create_proposal(account, 3, 1_000_000, 100);
+        let new_event = Event::lmsr(crate::Event::NewProposal(account, id, 1, 1098613));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == new_event));
+        assert_eq!(LmsrModule::pool_options(id), Some(vec![3, 4, 5]));
+        assert_eq!(LmsrModule::proposal_liquidate_currency_id(id), Some(6));
+        assert_eq!(LmsrModule::proposal_liquidity(id), Some(1_000_000));
+        assert_eq!(LmsrModule::proposal_outstanding(id), Some(vec![0, 0, 0]));
+        assert_eq!(LmsrModule::proposal_total_market(id), Some(1098613));
+        assert_eq!(LmsrModule::proposal_total_market_liquid(id), Some(1098613));
+        assert_eq!(balance(account, 6), 1098613);
+        assert_eq!(balance(account, 1), 1_000_000_000 - 1098613);
+        assert_eq!(
+            LmsrModule::spot_prices(id),
+            Ok(vec![333333, 333333, 333333])
+        );
+    });
+}
+
+#[test]
+fn test_buy() {
+    new_test_ext().execute_with(|| {
+        let other_account: AccountId = 2;
+        let id = create_proposal(1, 3, 1_000_000, 100);
+
+        assert_noop!(
+            LmsrModule::buy(Origin::signed(other_account), id, 3, 1_000_000, u128::MAX),
+            Error::<Test>::ProposalAbnormalState
+        );
+        set_state(id, ProposalState::FormalPrediction);
+        assert_noop!(
+            LmsrModule::buy(Origin::signed(other_account), id, 6, 1_000_000, u128::MAX),
+            Error::<Test>::CurrencyIdNotFound
+        );
+        assert_eq!(LmsrModule::buy_quote(id, 3, 1_000_000), Ok((457361, 4528)));
+        assert_noop!(
+            LmsrModule::buy(Origin::signed(other_account), id, 3, 1_000_000, 457360),
+            Error::<Test>::PaidMoreThanMaximum
+        );
+        assert_ok!(LmsrModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            1_000_000,
+            457361
+        ));
+
+        let buy_event = Event::lmsr(crate::Event::Buy(other_account, id, 3, 1_000_000, 457361));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == buy_event));
+        assert_eq!(balance(other_account, 3), 1_000_000);
+        assert_eq!(balance(other_account, 1), 1_000_000_000 - 457361);
+        assert_eq!(
+            LmsrModule::proposal_outstanding(id),
+            Some(vec![1_000_000, 0, 0])
+        );
+        assert_eq!(
+            LmsrModule::proposal_total_market(id),
+            Some(1098613 + 452833)
+        );
+        assert_eq!(LmsrModule::proposal_total_market_fee(id), Some(4528));
+        assert_eq!(LmsrModule::proposal_total_volume(id), Some(457361));
+        assert_eq!(
+            LmsrModule::spot_prices(id),
+            Ok(vec![576116, 211941, 211941])
+        );
+    });
+}
+
+#[test]
+fn test_sell() {
+    new_test_ext().execute_with(|| {
+        let other_account: AccountId = 2;
+        let id = create_proposal(1, 3, 1_000_000, 100);
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(LmsrModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            1_000_000,
+            u128::MAX
+        ));
+        assert_noop!(
+            LmsrModule::sell(Origin::signed(other_account), id, 4, 1, 0),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_eq!(LmsrModule::sell_quote(id, 3, 1_000_000), Ok((448303, 4528)));
+        assert_noop!(
+            LmsrModule::sell(Origin::signed(other_account), id, 3, 1_000_000, 448304),
+            Error::<Test>::ReceivedLessThanMinimum
+        );
+        assert_ok!(LmsrModule::sell(
+            Origin::signed(other_account),
+            id,
+            3,
+            1_000_000,
+            448303
+        ));
+
+        let sell_event = Event::lmsr(crate::Event::Sell(other_account, id, 3, 1_000_000, 448303));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == sell_event));
+        assert_eq!(balance(other_account, 3), 0);
+        assert_eq!(balance(other_account, 1), 1_000_000_000 - 457361 + 448303);
+        assert_eq!(LmsrModule::proposal_outstanding(id), Some(vec![0, 0, 0]));
+        // The round trip leaves the rounding in the market and the fees with the LP tokens
+        assert_eq!(LmsrModule::proposal_total_market(id), Some(1098615));
+        assert_eq!(LmsrModule::proposal_total_market_fee(id), Some(9056));
+    });
+}
+
+#[test]
+fn test_remove_liquidity_and_retrieval() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let id = create_proposal(account, 3, 1_000_000, 100);
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(LmsrModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            1_000_000,
+            u128::MAX
+        ));
+        assert_noop!(
+            LmsrModule::remove_liquidity(Origin::signed(account), id, 1098613),
+            Error::<Test>::ProposalAbnormalState
+        );
+        set_state(id, ProposalState::WaitingForResults);
+        assert_noop!(
+            LmsrModule::set_result(Origin::root(), id, 6),
+            Error::<Test>::CurrencyIdNotFound
+        );
+        assert_ok!(LmsrModule::set_result(Origin::root(), id, 3));
+        assert_eq!(
+            <ProposalsWrapper as LiquidityPool<Test>>::get_proposal_state(id),
+            Ok(ProposalState::End)
+        );
+        assert_eq!(LmsrModule::retrievable(&other_account, id), Ok(995000));
+
+        assert_ok!(LmsrModule::retrieval(
+            Origin::signed(other_account),
+            id,
+            3,
+            1_000_000
+        ));
+        let retrieval_event = Event::lmsr(crate::Event::Retrieval(other_account, id, 3, 995000));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == retrieval_event));
+        assert_eq!(balance(other_account, 1), 1_000_000_000 - 457361 + 995000);
+        assert_eq!(balance(3, 1), 2500);
+        assert_eq!(LmsrModule::proposal_total_autonomy_reward(id), Some(2500));
+        assert!(!<LmsrModule as ProposalArchive<Test>>::is_drained(id));
+
+        // What is left after the winning tokens, with every trading fee
+        assert_ok!(LmsrModule::remove_liquidity(
+            Origin::signed(account),
+            id,
+            1098613
+        ));
+        let remove_event = Event::lmsr(crate::Event::RemoveLiquidity(account, id, 1, 555974));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == remove_event));
+        assert_eq!(balance(account, 1), 1_000_000_000 - 1098613 + 555974);
+        assert_eq!(balance(account, 6), 0);
+        assert_eq!(LmsrModule::proposal_total_market(id), Some(0));
+
+        assert!(<LmsrModule as ProposalArchive<Test>>::is_drained(id));
+        assert_eq!(<LmsrModule as ProposalArchive<Test>>::archive(id, 0), 0);
+        assert_eq!(<LmsrModule as ProposalArchive<Test>>::archive(id, 10), 1);
+        assert_eq!(LmsrModule::pool_options(id), None);
+        assert_eq!(LmsrModule::proposal_outstanding(id), None);
+    });
+}
+
+#[test]
+fn test_invalid_result() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let id = create_proposal(account, 3, 1_000_000, 100);
+        set_state(id, ProposalState::FormalPrediction);
+        assert_ok!(LmsrModule::buy(
+            Origin::signed(other_account),
+            id,
+            3,
+            1_000_000,
+            u128::MAX
+        ));
+        set_state(id, ProposalState::WaitingForResults);
+        assert_eq!(
+            <LmsrModule as LiquidityCouple<Test>>::proposal_invalid_result(id),
+            Some(INVALID_RESULT_ID)
+        );
+        assert_noop!(
+            LmsrModule::set_result(Origin::root(), id, 6),
+            Error::<Test>::CurrencyIdNotFound
+        );
+        assert_ok!(LmsrModule::set_result(
+            Origin::root(),
+            id,
+            INVALID_RESULT_ID
+        ));
+        assert_eq!(LmsrModule::proposal_invalid(id), Some(()));
+        assert_eq!(
+            <LmsrModule as LiquidityCouple<Test>>::get_proposal_result(id),
+            Ok(INVALID_RESULT_ID)
+        );
+        assert_eq!(LmsrModule::retrievable(&other_account, id), Ok(331667));
+
+        // Every option token redeems a third of a settlement token
+        assert_ok!(LmsrModule::retrieval(
+            Origin::signed(other_account),
+            id,
+            3,
+            1_000_000
+        ));
+        let retrieval_event = Event::lmsr(crate::Event::Retrieval(
+            other_account,
+            id,
+            INVALID_RESULT_ID,
+            331667,
+        ));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == retrieval_event));
+        assert_eq!(balance(other_account, 1), 1_000_000_000 - 457361 + 331667);
+        assert_eq!(
+            LmsrModule::proposal_account_invalid_remainder(id, other_account),
+            Some(1)
+        );
+        assert_eq!(LmsrModule::proposal_total_invalid_remainder(id), Some(1));
+
+        // The LP tokens leave what the remainder may still be paid
+        assert_ok!(LmsrModule::remove_liquidity(
+            Origin::signed(account),
+            id,
+            1098613
+        ));
+        let remove_event = Event::lmsr(crate::Event::RemoveLiquidity(account, id, 1, 1222640));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == remove_event));
+        assert_eq!(LmsrModule::proposal_total_market(id), Some(1));
+
+        assert!(<LmsrModule as ProposalArchive<Test>>::is_drained(id));
+        assert_eq!(<LmsrModule as ProposalArchive<Test>>::archive(id, 10), 2);
+        assert_eq!(
+            LmsrModule::proposal_account_invalid_remainder(id, other_account),
+            None
+        );
+        assert_eq!(LmsrModule::proposal_invalid(id), None);
+    });
+}
+
+fn next(seed: &mut u64) -> u128 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*seed >> 33) as u128
+}
+
+/// Random trades never cost the creator more than `b * ln(n)`, and the market always holds
+/// enough to pay the winning tokens
+#[test]
+fn test_creator_loss_is_bounded() {
+    new_test_ext().execute_with(|| {
+        let account: AccountId = 1;
+        let other_account: AccountId = 2;
+        let mut seed = 42u64;
+        for round in 0..20 {
+            let options = 2 + round % 7;
+            let liquidity = 10_000 + next(&mut seed) % 500_000;
+            let before = balance(account, 1);
+            let id = create_proposal(account, options, liquidity, 0);
+            let option_ids = LmsrModule::pool_options(id).unwrap();
+            set_state(id, ProposalState::FormalPrediction);
+            for _ in 0..30 {
+                let option_id = option_ids[(next(&mut seed) % options as u128) as usize];
+                let held = balance(other_account, option_id);
+                if next(&mut seed) % 3 == 0 && held > 0 {
+                    let number = next(&mut seed) % held + 1;
+                    assert_ok!(LmsrModule::sell(
+                        Origin::signed(other_account),
+                        id,
+                        option_id,
+                        number,
+                        0
+                    ));
+                } else {
+                    let number = next(&mut seed) % (2 * liquidity) + 1;
+                    assert_ok!(LmsrModule::buy(
+                        Origin::signed(other_account),
+                        id,
+                        option_id,
+                        number,
+                        u128::MAX
+                    ));
+                }
+            }
+
+            let index = (next(&mut seed) % options as u128) as usize;
+            let outstanding = LmsrModule::proposal_outstanding(id).unwrap();
+            assert!(LmsrModule::proposal_total_market(id).unwrap() > outstanding[index]);
+            set_state(id, ProposalState::WaitingForResults);
+            assert_ok!(LmsrModule::set_result(
+                Origin::root(),
+                id,
+                option_ids[index]
+            ));
+            for option_id in option_ids.iter() {
+                let held = balance(other_account, *option_id);
+                if held > 0 {
+                    assert_ok!(LmsrModule::retrieval(
+                        Origin::signed(other_account),
+                        id,
+                        *option_id,
+                        held
+                    ));
+                }
+            }
+            let lp = LmsrModule::proposal_liquidate_currency_id(id).unwrap();
+            assert_ok!(LmsrModule::remove_liquidity(
+                Origin::signed(account),
+                id,
+                balance(account, lp)
+            ));
+            assert!(<LmsrModule as ProposalArchive<Test>>::is_drained(id));
+
+            let loss = before.saturating_sub(balance(account, 1));
+            assert!(loss <= math::subsidy(liquidity, options).unwrap());
+        }
+    });
+}
@@ -0,0 +1,643 @@
+use crate::*;
+
+use frame_support::{
+    ensure,
+    traits::{Get, Time},
+};
+use num_traits::pow::pow;
+use sp_runtime::{
+    traits::{
+        CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, SaturatedConversion, Saturating, Zero,
+    },
+    DispatchError,
+};
+use sp_std::{convert::TryInto, vec::Vec};
+use xpmrl_traits::{
+    pool::LiquidityPool, ruler::RulerAccounts, tokens::Tokens, ProposalStatus, Resolution,
+    RulerModule,
+};
+use xpmrl_utils::runtime_format;
+
+impl<T: Config> Pallet<T> {
+    pub(crate) fn option_index(
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+    ) -> Result<usize, DispatchError> {
+        let options = PoolOptions::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let index = options
+            .iter()
+            .position(|id| *id == optional_currency_id)
+            .ok_or(Error::<T>::CurrencyIdNotFound)?;
+        Ok(index)
+    }
+
+    pub(crate) fn to_balance(number: u128) -> Result<BalanceOf<T>, DispatchError> {
+        number
+            .try_into()
+            .map_err(|_| Error::<T>::BalanceOverflow.into())
+    }
+
+    /// The liquidity parameter and the outstanding tokens of the proposal, as the `u128` the
+    /// cost function works on
+    pub(crate) fn market(proposal_id: ProposalIdOf<T>) -> Result<(u128, Vec<u128>), DispatchError> {
+        let liquidity =
+            ProposalLiquidity::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let outstanding =
+            ProposalOutstanding::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        Ok((
+            liquidity.saturated_into(),
+            outstanding
+                .into_iter()
+                .map(|number| number.saturated_into())
+                .collect(),
+        ))
+    }
+
+    /// The settlement currency to pay for `number` tokens of the option at `index`, without
+    /// the fee
+    pub(crate) fn buy_cost(
+        proposal_id: ProposalIdOf<T>,
+        index: usize,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let (liquidity, outstanding) = Self::market(proposal_id)?;
+        let cost = math::buy_cost(liquidity, &outstanding, index, number.saturated_into())
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        Self::to_balance(cost)
+    }
+
+    /// The settlement currency received for `number` tokens of the option at `index`, without
+    /// the fee
+    pub(crate) fn sell_proceeds(
+        proposal_id: ProposalIdOf<T>,
+        index: usize,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let (liquidity, outstanding) = Self::market(proposal_id)?;
+        let proceeds = math::sell_proceeds(liquidity, &outstanding, index, number.saturated_into())
+            .ok_or(Error::<T>::InsufficientBalance)?;
+        Self::to_balance(proceeds)
+    }
+
+    /// The settlement currency to pay, and the trading fee in it, to buy `number` tokens of
+    /// `optional_currency_id`
+    pub fn buy_quote(
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        let index = Self::option_index(proposal_id, optional_currency_id)?;
+        let cost = Self::buy_cost(proposal_id, index, number)?;
+        let fee = Self::get_trading_fee(proposal_id, cost)?;
+        let paid = cost.checked_add(&fee).ok_or(Error::<T>::BalanceOverflow)?;
+        Ok((paid, fee))
+    }
+
+    /// The settlement currency received, and the trading fee taken from it, to sell `number`
+    /// tokens of `optional_currency_id`
+    pub fn sell_quote(
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        let index = Self::option_index(proposal_id, optional_currency_id)?;
+        let proceeds = Self::sell_proceeds(proposal_id, index, number)?;
+        let fee = Self::get_trading_fee(proposal_id, proceeds)?;
+        Ok((proceeds.checked_sub(&fee).unwrap_or_else(Zero::zero), fee))
+    }
+
+    /// The price of one whole token of each option of the proposal, in settlement currency
+    pub fn spot_prices(proposal_id: ProposalIdOf<T>) -> Result<Vec<BalanceOf<T>>, DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let decimals = <TokensOf<T> as Tokens<T::AccountId>>::decimals(currency_id)?;
+        let unit = pow(10u128, decimals.into());
+        let (liquidity, outstanding) = Self::market(proposal_id)?;
+        let prices = math::prices(liquidity, &outstanding).ok_or(Error::<T>::BalanceOverflow)?;
+        prices
+            .into_iter()
+            .map(|price| {
+                let price =
+                    math::mul_div(price, unit, math::ONE).ok_or(Error::<T>::BalanceOverflow)?;
+                Self::to_balance(price)
+            })
+            .collect()
+    }
+
+    pub(crate) fn get_trading_fee(
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let fee_decimals: u8 = T::EarnTradingFeeDecimals::get();
+        let one = pow(10u32, fee_decimals.into());
+        let fee_rate = ProposalTotalEarnTradingFee::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        let fee = number
+            .checked_mul(&(fee_rate.into()))
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        Ok(fee
+            .checked_div(&(one.into()))
+            .ok_or(Error::<T>::BalanceOverflow)?)
+    }
+
+    pub(crate) fn get_withdrawal_fee(
+        number: BalanceOf<T>,
+    ) -> (BalanceOf<T>, BalanceOf<T>, BalanceOf<T>) {
+        let rate = ProposalWithdrawalFeeRate::<T>::get().unwrap_or_else(Zero::zero);
+        let decimals: u8 = T::EarnTradingFeeDecimals::get();
+        let scale = pow(10u32, decimals.into());
+        let fee = number.checked_mul(&rate.into()).unwrap_or_else(Zero::zero);
+        let fee = fee.checked_div(&scale.into()).unwrap_or_else(Zero::zero);
+        let number = number.checked_sub(&fee).unwrap_or_else(Zero::zero);
+        let reward = fee.checked_div(&2u32.into()).unwrap_or_else(Zero::zero);
+        let dividends = fee.checked_sub(&reward).unwrap_or_else(Zero::zero);
+        (number, reward, dividends)
+    }
+
+    pub(crate) fn finally_locked(proposal_id: ProposalIdOf<T>) -> Result<(), DispatchError> {
+        let finally_liquid =
+            ProposalTotalMarketLiquid::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        let finally_fee = ProposalTotalMarketFee::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        ProposalFinallyMarketFee::<T>::insert(proposal_id, finally_fee);
+        ProposalFinallyMarketLiquid::<T>::insert(proposal_id, finally_liquid);
+        Ok(())
+    }
+
+    pub(crate) fn appropriation(
+        currency_id: CurrencyIdOf<T>,
+        who: &T::AccountId,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        <TokensOf<T> as Tokens<T::AccountId>>::appropriation(currency_id, who, number)
+    }
+
+    pub(crate) fn new_asset(
+        name: Vec<u8>,
+        symbol: Vec<u8>,
+        decimals: u8,
+    ) -> Result<CurrencyIdOf<T>, DispatchError> {
+        <TokensOf<T> as Tokens<T::AccountId>>::new_asset(name, symbol, decimals)
+    }
+
+    pub(crate) fn init_pool(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        title: Vec<u8>,
+        close_time: MomentOf<T>,
+        category_id: T::CategoryId,
+        earn_fee: u32,
+        detail: Vec<u8>,
+        resolution: Resolution<MomentOf<T>>,
+    ) -> Result<ProposalIdOf<T>, DispatchError> {
+        let version: VersionIdOf<T> = T::CurrentLiquidateVersionId::get();
+        Proposals::<T>::insert(
+            proposal_id,
+            Proposal {
+                title,
+                category_id,
+                detail,
+            },
+        );
+        T::Pool::init_proposal(
+            proposal_id,
+            who,
+            ProposalStatus::OriginalPrediction,
+            T::Time::now(),
+            close_time,
+            version,
+        );
+        T::Pool::set_proposal_resolution(proposal_id, resolution)?;
+        ProposalTotalEarnTradingFee::<T>::insert(proposal_id, earn_fee);
+        Ok(proposal_id)
+    }
+
+    /// Create the option and LP tokens of the proposal and take the subsidy from `who`
+    ///
+    /// Returns the subsidy, which is also the number of LP tokens minted to `who`
+    pub(crate) fn new_currency(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        liquidity: BalanceOf<T>,
+        optional: Vec<Vec<u8>>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let subsidy = math::subsidy(liquidity.saturated_into(), optional.len())
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let subsidy = Self::to_balance(subsidy)?;
+        ProposalCurrencyId::<T>::insert(proposal_id, currency_id);
+        <TokensOf<T> as Tokens<T::AccountId>>::donate(currency_id, who, subsidy)?;
+        let decimals = <TokensOf<T> as Tokens<T::AccountId>>::decimals(currency_id)?;
+        let mut options = Vec::with_capacity(optional.len());
+        for (index, name) in optional.into_iter().enumerate() {
+            let symbol = runtime_format!("{:?}-OPT{}", proposal_id, index);
+            let asset_id = Self::new_asset(name, symbol, decimals)?;
+            T::Pool::append_used_currency(asset_id);
+            options.push(asset_id);
+        }
+        let lp_name = runtime_format!("LP-{:?}", proposal_id);
+        let asset_id_lp = Self::new_asset(lp_name.clone(), lp_name, decimals)?;
+        T::Pool::append_used_currency(asset_id_lp);
+        ProposalLiquidateCurrencyId::<T>::insert(proposal_id, asset_id_lp);
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(asset_id_lp, who, subsidy)?;
+
+        ProposalLiquidity::<T>::insert(proposal_id, liquidity);
+        let outstanding: Vec<BalanceOf<T>> = sp_std::vec![Zero::zero(); options.len()];
+        ProposalOutstanding::<T>::insert(proposal_id, outstanding);
+        PoolOptions::<T>::insert(proposal_id, options);
+        ProposalTotalMarket::<T>::insert(proposal_id, subsidy);
+        ProposalTotalMarketLiquid::<T>::insert(proposal_id, subsidy);
+        Ok(subsidy)
+    }
+
+    pub(crate) fn total_market_add(
+        proposal_id: ProposalIdOf<T>,
+        diff: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        ProposalTotalMarket::<T>::try_mutate(proposal_id, |optional| -> Result<(), DispatchError> {
+            let old = optional.ok_or(Error::<T>::ProposalIdNotExist)?;
+            *optional = Some(old.checked_add(&diff).ok_or(Error::<T>::BalanceOverflow)?);
+            Ok(())
+        })
+    }
+
+    pub(crate) fn total_market_sub(
+        proposal_id: ProposalIdOf<T>,
+        diff: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        ProposalTotalMarket::<T>::try_mutate(proposal_id, |optional| -> Result<(), DispatchError> {
+            let old = optional.ok_or(Error::<T>::ProposalIdNotExist)?;
+            *optional = Some(old.checked_sub(&diff).unwrap_or_else(Zero::zero));
+            Ok(())
+        })
+    }
+
+    /// Add `number` to the outstanding tokens of the option at `index` if `add` is set, take it
+    /// off otherwise
+    pub(crate) fn outstanding_mutate(
+        proposal_id: ProposalIdOf<T>,
+        index: usize,
+        number: BalanceOf<T>,
+        add: bool,
+    ) -> Result<(), DispatchError> {
+        ProposalOutstanding::<T>::try_mutate(proposal_id, |optional| -> Result<(), DispatchError> {
+            let outstanding = optional.as_mut().ok_or(Error::<T>::ProposalIdNotExist)?;
+            outstanding[index] = if add {
+                outstanding[index]
+                    .checked_add(&number)
+                    .ok_or(Error::<T>::BalanceOverflow)?
+            } else {
+                outstanding[index]
+                    .checked_sub(&number)
+                    .ok_or(Error::<T>::InsufficientBalance)?
+            };
+            Ok(())
+        })
+    }
+
+    pub(crate) fn market_fee_add(
+        proposal_id: ProposalIdOf<T>,
+        fee: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        ProposalTotalMarketFee::<T>::try_mutate(
+            proposal_id,
+            |optional| -> Result<(), DispatchError> {
+                let old = optional.unwrap_or_else(Zero::zero);
+                *optional = Some(old.checked_add(&fee).ok_or(Error::<T>::BalanceOverflow)?);
+                Ok(())
+            },
+        )
+    }
+
+    pub(crate) fn volume_add(
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        ProposalTotalVolume::<T>::try_mutate(proposal_id, |optional| -> Result<(), DispatchError> {
+            let old = optional.unwrap_or_else(Zero::zero);
+            *optional = Some(
+                old.checked_add(&number)
+                    .ok_or(Error::<T>::BalanceOverflow)?,
+            );
+            Ok(())
+        })
+    }
+
+    /// The settlement currency the option tokens still have to be paid, one per outstanding
+    /// token of the result, or an equal share of every outstanding token and of the remainders
+    /// of the accounts, rounded up, if the proposal is invalid
+    pub(crate) fn owed_to_options(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let outstanding =
+            ProposalOutstanding::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        if ProposalInvalid::<T>::contains_key(proposal_id) {
+            let count: BalanceOf<T> = (outstanding.len() as u32).into();
+            let pooled = outstanding.iter().try_fold(
+                ProposalTotalInvalidRemainder::<T>::get(proposal_id).unwrap_or_else(Zero::zero),
+                |pooled, number| pooled.checked_add(number),
+            );
+            let pooled = pooled.ok_or(Error::<T>::BalanceOverflow)?;
+            let owed = pooled.checked_div(&count).unwrap_or_else(Zero::zero);
+            return if (pooled % count).is_zero() {
+                Ok(owed)
+            } else {
+                Ok(owed.saturating_add(One::one()))
+            };
+        }
+        let result_id =
+            ProposalResult::<T>::get(proposal_id).ok_or(Error::<T>::ProposalNotResult)?;
+        let index = Self::option_index(proposal_id, result_id)?;
+        Ok(outstanding[index])
+    }
+
+    /// Burn `number` LP tokens of `who` for their share of what the option tokens are not owed
+    /// and of the trading fees
+    ///
+    /// The shares are taken from what is left at the time, so the LP tokens get the same share
+    /// whatever order they and the winning tokens are retrieved in.
+    pub(crate) fn inner_remove_liquidity(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let liquidate_currency_id = ProposalLiquidateCurrencyId::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
+        let owed = Self::owed_to_options(proposal_id)?;
+        let total_liquid =
+            ProposalTotalMarketLiquid::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        let total = ProposalTotalMarket::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        let total_fee = ProposalTotalMarketFee::<T>::get(proposal_id).unwrap_or_else(Zero::zero);
+        let number =
+            <TokensOf<T> as Tokens<T::AccountId>>::burn(liquidate_currency_id, who, number)?;
+
+        let surplus = total.checked_sub(&owed).unwrap_or_else(Zero::zero);
+        let share = |amount: BalanceOf<T>| -> Result<BalanceOf<T>, DispatchError> {
+            let amount = math::mul_div(
+                amount.saturated_into(),
+                number.saturated_into(),
+                total_liquid.saturated_into(),
+            )
+            .ok_or(Error::<T>::BalanceOverflow)?;
+            Self::to_balance(amount)
+        };
+        let amount = share(surplus)?;
+        let fee = share(total_fee)?;
+        Self::total_market_sub(proposal_id, amount)?;
+        ProposalTotalMarketFee::<T>::insert(
+            proposal_id,
+            total_fee.checked_sub(&fee).unwrap_or_else(Zero::zero),
+        );
+        ProposalTotalMarketLiquid::<T>::insert(
+            proposal_id,
+            total_liquid.checked_sub(&number).unwrap_or_else(Zero::zero),
+        );
+        let amount = amount
+            .checked_add(&fee)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        Self::appropriation(currency_id, who, amount)
+    }
+
+    pub(crate) fn inner_buy(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        index: usize,
+        number: BalanceOf<T>,
+        max_cost: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let options = PoolOptions::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let cost = Self::buy_cost(proposal_id, index, number)?;
+        let fee = Self::get_trading_fee(proposal_id, cost)?;
+        let paid = cost.checked_add(&fee).ok_or(Error::<T>::BalanceOverflow)?;
+        ensure!(paid <= max_cost, Error::<T>::PaidMoreThanMaximum);
+        <TokensOf<T> as Tokens<T::AccountId>>::donate(currency_id, who, paid)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(options[index], who, number)?;
+        Self::outstanding_mutate(proposal_id, index, number, true)?;
+        Self::total_market_add(proposal_id, cost)?;
+        Self::market_fee_add(proposal_id, fee)?;
+        Self::volume_add(proposal_id, paid)?;
+        Ok(paid)
+    }
+
+    pub(crate) fn inner_sell(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        index: usize,
+        number: BalanceOf<T>,
+        min_proceeds: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let options = PoolOptions::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(options[index], who);
+        ensure!(balance >= number, Error::<T>::InsufficientBalance);
+        <TokensOf<T> as Tokens<T::AccountId>>::burn(options[index], who, number)?;
+        let proceeds = Self::sell_proceeds(proposal_id, index, number)?;
+        let fee = Self::get_trading_fee(proposal_id, proceeds)?;
+        let received = proceeds.checked_sub(&fee).unwrap_or_else(Zero::zero);
+        ensure!(
+            received >= min_proceeds,
+            Error::<T>::ReceivedLessThanMinimum
+        );
+        Self::outstanding_mutate(proposal_id, index, number, false)?;
+        Self::total_market_sub(proposal_id, proceeds)?;
+        Self::market_fee_add(proposal_id, fee)?;
+        Self::volume_add(proposal_id, proceeds)?;
+        Self::appropriation(currency_id, who, received)
+    }
+
+    pub(crate) fn inner_retrieval(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        result_id: CurrencyIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let index = Self::option_index(proposal_id, optional_currency_id)?;
+        let number =
+            <TokensOf<T> as Tokens<T::AccountId>>::burn(optional_currency_id, who, number)?;
+        Self::outstanding_mutate(proposal_id, index, number, false)?;
+        if optional_currency_id != result_id {
+            return Ok(Zero::zero());
+        }
+        Self::pay_out(who, proposal_id, number)
+    }
+
+    /// Burn `number` option tokens of an invalid proposal, one settlement token is paid for as
+    /// many option tokens as the proposal has options, whichever options and calls they are
+    /// retrieved in, the rest is kept in `ProposalAccountInvalidRemainder` until the next
+    /// retrieval
+    pub(crate) fn inner_invalid_retrieval(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        optional_currency_id: CurrencyIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let options = PoolOptions::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let index = Self::option_index(proposal_id, optional_currency_id)?;
+        let number =
+            <TokensOf<T> as Tokens<T::AccountId>>::burn(optional_currency_id, who, number)?;
+        Self::outstanding_mutate(proposal_id, index, number, false)?;
+        let count: BalanceOf<T> = (options.len() as u32).into();
+        let previous =
+            ProposalAccountInvalidRemainder::<T>::take(proposal_id, who).unwrap_or_else(Zero::zero);
+        let pooled = previous
+            .checked_add(&number)
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let remainder = pooled % count;
+        if !remainder.is_zero() {
+            ProposalAccountInvalidRemainder::<T>::insert(proposal_id, who, remainder);
+        }
+        ProposalTotalInvalidRemainder::<T>::mutate(proposal_id, |optional| {
+            let old = optional.unwrap_or_else(Zero::zero);
+            *optional = Some(
+                old.checked_sub(&previous)
+                    .unwrap_or_else(Zero::zero)
+                    .saturating_add(remainder),
+            );
+        });
+        let share = pooled.checked_div(&count).unwrap_or_else(Zero::zero);
+        Self::pay_out(who, proposal_id, share)
+    }
+
+    /// Pay `number` settlement tokens of the proposal to `who`, net of the withdrawal fee
+    pub(crate) fn pay_out(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let currency_id =
+            ProposalCurrencyId::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        Self::total_market_sub(proposal_id, number)?;
+        let (number, reward, dividends) = Self::get_withdrawal_fee(number);
+        ProposalTotalAutonomyReward::<T>::mutate(proposal_id, |optional| {
+            let old = optional.unwrap_or_else(Zero::zero);
+            *optional = Some(old.checked_add(&reward).unwrap_or_else(Zero::zero));
+        });
+        ProposalCurrentAutonomyReward::<T>::mutate(proposal_id, |optional| {
+            let old = optional.unwrap_or_else(Zero::zero);
+            *optional = Some(old.checked_add(&reward).unwrap_or_else(Zero::zero));
+        });
+        let dividends_account = T::Ruler::get_account(RulerModule::PlatformDividend)?;
+        Self::appropriation(currency_id, &dividends_account, dividends)?;
+        Self::appropriation(currency_id, who, number)
+    }
+
+    /// Whether `currency_id` is the id voted for to settle the proposal as invalid
+    pub(crate) fn is_invalid_result(
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+    ) -> bool {
+        <Self as LiquidityCouple<T>>::proposal_invalid_result(proposal_id) == Some(currency_id)
+    }
+
+    /// Store the result of the proposal, an invalid result is flagged instead of being stored as
+    /// an option of the proposal
+    pub(crate) fn insert_result(
+        proposal_id: ProposalIdOf<T>,
+        currency_id: CurrencyIdOf<T>,
+        invalid: bool,
+    ) {
+        if invalid {
+            ProposalResult::<T>::remove(proposal_id);
+            ProposalInvalid::<T>::insert(proposal_id, ());
+        } else {
+            ProposalInvalid::<T>::remove(proposal_id);
+            ProposalResult::<T>::insert(proposal_id, currency_id);
+        }
+    }
+
+    /// The settlement currency, net of the withdrawal fee, that `who` gets back for
+    /// retrieving the winning option currency of an ended proposal, or all of its option
+    /// currencies if the proposal is invalid
+    pub fn retrievable(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        if T::Pool::get_proposal_state(proposal_id)? != ProposalStatus::End {
+            return Ok(Zero::zero());
+        }
+        if ProposalInvalid::<T>::contains_key(proposal_id) {
+            let options =
+                PoolOptions::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+            let pooled = options.iter().fold(
+                ProposalAccountInvalidRemainder::<T>::get(proposal_id, who)
+                    .unwrap_or_else(Zero::zero),
+                |pooled, asset_id| {
+                    pooled.saturating_add(<TokensOf<T> as Tokens<T::AccountId>>::balance(
+                        *asset_id, who,
+                    ))
+                },
+            );
+            let count: BalanceOf<T> = (options.len() as u32).into();
+            let share = pooled.checked_div(&count).unwrap_or_else(Zero::zero);
+            let (number, _, _) = Self::get_withdrawal_fee(share);
+            return Ok(number);
+        }
+        let result_id =
+            ProposalResult::<T>::get(proposal_id).ok_or(Error::<T>::ProposalNotResult)?;
+        let number = <TokensOf<T> as Tokens<T::AccountId>>::balance(result_id, who);
+        let (number, _, _) = Self::get_withdrawal_fee(number);
+        Ok(number)
+    }
+
+    /// Whether every option and LP token of the proposal has been burned, which stays true once
+    /// the storage of the proposal has been removed
+    pub(crate) fn is_drained(proposal_id: ProposalIdOf<T>) -> bool {
+        let mut assets = match PoolOptions::<T>::get(proposal_id) {
+            Some(options) => options,
+            None => return true,
+        };
+        match ProposalLiquidateCurrencyId::<T>::get(proposal_id) {
+            Some(asset_id) => assets.push(asset_id),
+            None => return false,
+        }
+        assets.iter().all(|asset_id| {
+            <TokensOf<T> as Tokens<T::AccountId>>::total_supply(*asset_id).is_zero()
+        })
+    }
+
+    /// Remove at most `limit` storage entries of a drained proposal, the remainders of the
+    /// accounts go first and the proposal itself last, its final figures are kept in the event
+    pub(crate) fn inner_archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32 {
+        let removed = ProposalAccountInvalidRemainder::<T>::drain_prefix(proposal_id)
+            .take(limit as usize)
+            .count() as u32;
+        if removed >= limit {
+            return removed;
+        }
+        if let Some(currency_id) = ProposalCurrencyId::<T>::take(proposal_id) {
+            let volume = ProposalTotalVolume::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+            let fee = ProposalFinallyMarketFee::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+            let liquid =
+                ProposalFinallyMarketLiquid::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+            let reward =
+                ProposalTotalAutonomyReward::<T>::take(proposal_id).unwrap_or_else(Zero::zero);
+            Proposals::<T>::remove(proposal_id);
+            PoolOptions::<T>::remove(proposal_id);
+            ProposalLiquidity::<T>::remove(proposal_id);
+            ProposalOutstanding::<T>::remove(proposal_id);
+            ProposalLiquidateCurrencyId::<T>::remove(proposal_id);
+            ProposalTotalEarnTradingFee::<T>::remove(proposal_id);
+            ProposalResult::<T>::remove(proposal_id);
+            ProposalInvalid::<T>::remove(proposal_id);
+            ProposalTotalInvalidRemainder::<T>::remove(proposal_id);
+            ProposalTotalMarket::<T>::remove(proposal_id);
+            ProposalTotalMarketFee::<T>::remove(proposal_id);
+            ProposalTotalMarketLiquid::<T>::remove(proposal_id);
+            ProposalCurrentAutonomyReward::<T>::remove(proposal_id);
+            Self::deposit_event(Event::ProposalArchived(
+                proposal_id,
+                currency_id,
+                volume,
+                fee,
+                liquid,
+                reward,
+            ));
+        }
+        removed + 1
+    }
+}
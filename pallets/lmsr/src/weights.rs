@@ -0,0 +1,114 @@
+//! Weights for xpmrl_lmsr
+//!
+//! PROVISIONAL: the figures below are estimated by hand from the storage accesses of each call
+//! on top of a fixed base cost, they have not been measured by the benchmarks of this pallet
+//! yet and must be replaced by the output of the benchmark CLI before a release.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for xpmrl_lmsr.
+pub trait WeightInfo {
+    fn new_proposal(o: u32) -> Weight;
+    fn remove_liquidity() -> Weight;
+    fn buy(o: u32) -> Weight;
+    fn sell(o: u32) -> Weight;
+    fn retrieval() -> Weight;
+    fn set_result() -> Weight;
+    fn set_result_when_end() -> Weight;
+}
+
+/// Provisional weights for xpmrl_lmsr, estimated by hand until they are benchmarked.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn new_proposal(o: u32) -> Weight {
+        (120_000_000 as Weight)
+            .saturating_add((30_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(o as Weight)))
+    }
+    fn remove_liquidity() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn buy(o: u32) -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add((16_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn sell(o: u32) -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add((16_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn retrieval() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn set_result() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn set_result_when_end() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn new_proposal(o: u32) -> Weight {
+        (120_000_000 as Weight)
+            .saturating_add((30_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(o as Weight)))
+    }
+    fn remove_liquidity() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn buy(o: u32) -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add((16_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn sell(o: u32) -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add((16_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn retrieval() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn set_result() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn set_result_when_end() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+}
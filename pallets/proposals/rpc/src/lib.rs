@@ -1,7 +1,10 @@
 use codec::Codec;
 use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
 use jsonrpc_derive::rpc;
-use proposals_info_runtime_api::types::{PersonalProposalInfo, ProposalInfo};
+use proposals_info_runtime_api::types::{
+    AccountPortfolio, ExactOutQuote, LiquidityQuote, PersonalProposalInfo, ProposalFilter,
+    ProposalInfo, ProposalPage, SpotPrices, TradeQuote,
+};
 pub use proposals_info_runtime_api::CoupleInfoApi as CoupleInfoRuntimeApi;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
@@ -40,6 +43,76 @@ pub trait CoupleInfoApi<
         account_id: AccountId,
         at: Option<BlockHash>,
     ) -> Result<PersonalProposalInfo<Balance, Moment, CurrencyId>>;
+
+    #[rpc(name = "proposal_getBuyExactOutQuote")]
+    fn get_buy_exact_out_quote(
+        &self,
+        proposal_id: ProposalId,
+        currency_id: CurrencyId,
+        number: Balance,
+        at: Option<BlockHash>,
+    ) -> Result<Option<ExactOutQuote<Balance>>>;
+
+    #[rpc(name = "proposal_getSellExactOutQuote")]
+    fn get_sell_exact_out_quote(
+        &self,
+        proposal_id: ProposalId,
+        currency_id: CurrencyId,
+        number: Balance,
+        at: Option<BlockHash>,
+    ) -> Result<Option<ExactOutQuote<Balance>>>;
+
+    #[rpc(name = "proposal_getSpotPrices")]
+    fn get_spot_prices(
+        &self,
+        proposal_id: ProposalId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<SpotPrices<Balance>>>;
+
+    #[rpc(name = "proposal_getBuyQuote")]
+    fn get_buy_quote(
+        &self,
+        proposal_id: ProposalId,
+        currency_id: CurrencyId,
+        number: Balance,
+        at: Option<BlockHash>,
+    ) -> Result<Option<TradeQuote<Balance>>>;
+
+    #[rpc(name = "proposal_getSellQuote")]
+    fn get_sell_quote(
+        &self,
+        proposal_id: ProposalId,
+        currency_id: CurrencyId,
+        number: Balance,
+        at: Option<BlockHash>,
+    ) -> Result<Option<TradeQuote<Balance>>>;
+
+    #[rpc(name = "proposal_getRemoveLiquidityQuote")]
+    fn get_remove_liquidity_quote(
+        &self,
+        proposal_id: ProposalId,
+        account_id: AccountId,
+        number: Balance,
+        at: Option<BlockHash>,
+    ) -> Result<Option<LiquidityQuote<Balance>>>;
+
+    #[rpc(name = "proposal_listProposals")]
+    fn list_proposals(
+        &self,
+        cursor: Option<ProposalId>,
+        limit: u32,
+        filter: ProposalFilter<CategoryId, AccountId, CurrencyId, Moment>,
+        at: Option<BlockHash>,
+    ) -> Result<ProposalPage<ProposalId, CategoryId, Balance, Moment, CurrencyId>>;
+
+    #[rpc(name = "proposal_getAccountPortfolio")]
+    fn get_account_portfolio(
+        &self,
+        account_id: AccountId,
+        cursor: Option<ProposalId>,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> Result<AccountPortfolio<ProposalId, CurrencyId, Balance>>;
 }
 
 pub struct CoupleInfo<C, M> {
@@ -129,4 +202,164 @@ where
             data: Some(format!("{:?}", e).into()),
         })
     }
+
+    fn get_buy_exact_out_quote(
+        &self,
+        proposal_id: ProposalId,
+        currency_id: CurrencyId,
+        number: Balance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<ExactOutQuote<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+        let runtime_api_result = api.get_buy_exact_out_quote(&at, proposal_id, currency_id, number);
+        runtime_api_result.map_err(|e| RpcError {
+            code: ErrorCode::ServerError(9876), // No real reason for this value
+            message: "Something wrong".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+
+    fn get_sell_exact_out_quote(
+        &self,
+        proposal_id: ProposalId,
+        currency_id: CurrencyId,
+        number: Balance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<ExactOutQuote<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+        let runtime_api_result =
+            api.get_sell_exact_out_quote(&at, proposal_id, currency_id, number);
+        runtime_api_result.map_err(|e| RpcError {
+            code: ErrorCode::ServerError(9876), // No real reason for this value
+            message: "Something wrong".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+
+    fn get_spot_prices(
+        &self,
+        proposal_id: ProposalId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<SpotPrices<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+        let runtime_api_result = api.get_spot_prices(&at, proposal_id);
+        runtime_api_result.map_err(|e| RpcError {
+            code: ErrorCode::ServerError(9876), // No real reason for this value
+            message: "Something wrong".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+
+    fn get_buy_quote(
+        &self,
+        proposal_id: ProposalId,
+        currency_id: CurrencyId,
+        number: Balance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<TradeQuote<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+        let runtime_api_result = api.get_buy_quote(&at, proposal_id, currency_id, number);
+        runtime_api_result.map_err(|e| RpcError {
+            code: ErrorCode::ServerError(9876), // No real reason for this value
+            message: "Something wrong".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+
+    fn get_sell_quote(
+        &self,
+        proposal_id: ProposalId,
+        currency_id: CurrencyId,
+        number: Balance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<TradeQuote<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+        let runtime_api_result = api.get_sell_quote(&at, proposal_id, currency_id, number);
+        runtime_api_result.map_err(|e| RpcError {
+            code: ErrorCode::ServerError(9876), // No real reason for this value
+            message: "Something wrong".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+
+    fn get_remove_liquidity_quote(
+        &self,
+        proposal_id: ProposalId,
+        account_id: AccountId,
+        number: Balance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<LiquidityQuote<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+        let runtime_api_result =
+            api.get_remove_liquidity_quote(&at, proposal_id, account_id, number);
+        runtime_api_result.map_err(|e| RpcError {
+            code: ErrorCode::ServerError(9876), // No real reason for this value
+            message: "Something wrong".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+
+    fn list_proposals(
+        &self,
+        cursor: Option<ProposalId>,
+        limit: u32,
+        filter: ProposalFilter<CategoryId, AccountId, CurrencyId, Moment>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<ProposalPage<ProposalId, CategoryId, Balance, Moment, CurrencyId>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+        let runtime_api_result = api.list_proposals(&at, cursor, limit, filter);
+        runtime_api_result.map_err(|e| RpcError {
+            code: ErrorCode::ServerError(9876), // No real reason for this value
+            message: "Something wrong".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+
+    fn get_account_portfolio(
+        &self,
+        account_id: AccountId,
+        cursor: Option<ProposalId>,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<AccountPortfolio<ProposalId, CurrencyId, Balance>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+        let runtime_api_result = api.get_account_portfolio(&at, account_id, cursor, limit);
+        runtime_api_result.map_err(|e| RpcError {
+            code: ErrorCode::ServerError(9876), // No real reason for this value
+            message: "Something wrong".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
 }
@@ -8,6 +8,9 @@ use sp_runtime::traits::{MaybeDisplay, MaybeFromStr};
 pub mod types;
 
 sp_api::decl_runtime_apis! {
+	/// Version 2 added the quotes, `list_proposals`, `get_account_portfolio` and the `options`
+	/// of the proposal infos
+	#[api_version(2)]
 	pub trait CoupleInfoApi<VersionId, ProposalId, CategoryId, Balance, Moment, CurrencyId, AccountId> where
 		VersionId: Codec,
 		ProposalId: Codec,
@@ -19,5 +22,14 @@ sp_api::decl_runtime_apis! {
 	{
 		fn get_proposal_info(version_id: VersionId, proposal_id: ProposalId) -> types::ProposalInfo<CategoryId, Balance, Moment, CurrencyId>;
 		fn get_personal_proposal_info(version_id: VersionId, proposal_id: ProposalId, account_id: AccountId) -> types::PersonalProposalInfo<Balance, Moment, CurrencyId>;
+		/// The quotes below are those of the couple pool, they are `None` for proposals of other engines
+		fn get_buy_exact_out_quote(proposal_id: ProposalId, currency_id: CurrencyId, number: Balance) -> Option<types::ExactOutQuote<Balance>>;
+		fn get_sell_exact_out_quote(proposal_id: ProposalId, currency_id: CurrencyId, number: Balance) -> Option<types::ExactOutQuote<Balance>>;
+		fn get_spot_prices(proposal_id: ProposalId) -> Option<types::SpotPrices<Balance>>;
+		fn get_buy_quote(proposal_id: ProposalId, currency_id: CurrencyId, number: Balance) -> Option<types::TradeQuote<Balance>>;
+		fn get_sell_quote(proposal_id: ProposalId, currency_id: CurrencyId, number: Balance) -> Option<types::TradeQuote<Balance>>;
+		fn get_remove_liquidity_quote(proposal_id: ProposalId, account_id: AccountId, number: Balance) -> Option<types::LiquidityQuote<Balance>>;
+		fn list_proposals(cursor: Option<ProposalId>, limit: u32, filter: types::ProposalFilter<CategoryId, AccountId, CurrencyId, Moment>) -> types::ProposalPage<ProposalId, CategoryId, Balance, Moment, CurrencyId>;
+		fn get_account_portfolio(account_id: AccountId, cursor: Option<ProposalId>, limit: u32) -> types::AccountPortfolio<ProposalId, CurrencyId, Balance>;
 	}
 }
@@ -25,6 +25,25 @@ pub struct ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
         serde(deserialize_with = "vec_u8_deserialize_from_string")
     )]
     pub detail: Vec<u8>,
+    #[cfg_attr(feature = "std", serde(serialize_with = "vec_u8_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "vec_u8_deserialize_from_string")
+    )]
+    pub resolution_source: Vec<u8>,
+    #[cfg_attr(feature = "std", serde(serialize_with = "vec_u8_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "vec_u8_deserialize_from_string")
+    )]
+    pub resolution_rules: Vec<u8>,
+    pub resolution_time: Moment,
+    #[cfg_attr(feature = "std", serde(serialize_with = "vec_u8_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "vec_u8_deserialize_from_string")
+    )]
+    pub invalid_condition: Vec<u8>,
     #[cfg_attr(
         feature = "std",
         serde(bound(serialize = "Balance: std::fmt::Display"))
@@ -65,6 +84,8 @@ pub struct ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
         serde(deserialize_with = "vec_u8_deserialize_from_string")
     )]
     pub no_name: Vec<u8>,
+    /// Every option of the proposal, `yes` and `no` only hold the first two of them
+    pub options: Vec<ProposalOptionInfo<CurrencyId, Balance>>,
     pub close_time: Moment,
     #[cfg_attr(
         feature = "std",
@@ -85,6 +106,36 @@ pub struct ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
     pub token_id: CurrencyId,
 }
 
+/// One option of a proposal and the amount of it held by the market
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ProposalOptionInfo<CurrencyId, Balance> {
+    pub currency_id: CurrencyId,
+    #[cfg_attr(feature = "std", serde(serialize_with = "vec_u8_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "vec_u8_deserialize_from_string")
+    )]
+    pub name: Vec<u8>,
+    pub decimals: u8,
+    /// The reserve of the option in the pool, or its outstanding tokens for LMSR proposals
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub amount: Balance,
+}
+
 #[derive(PartialEq, Eq, Clone, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
@@ -201,10 +252,407 @@ pub struct PersonalProposalInfo<Balance, Moment, CurrencyId> {
         serde(deserialize_with = "balance_deserialize_from_string")
     )]
     pub balance: Balance,
+    /// Every option of the proposal, `yes` and `no` only hold the first two of them
+    pub options: Vec<ProposalOptionInfo<CurrencyId, Balance>>,
     pub close_time: Moment,
     pub status: ProposalStatus,
 }
 
+/// What has to be paid for an exact trade output
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ExactOutQuote<Balance> {
+    /// Settlement currency paid when buying, option currency sold when selling
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub amount: Balance,
+    /// The trading fee, in settlement currency
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub fee: Balance,
+}
+
+/// The price of one whole token of each option, in settlement currency
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SpotPrices<Balance> {
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub yes: Balance,
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub no: Balance,
+}
+
+/// The expected result of a buy or a sell
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TradeQuote<Balance> {
+    /// Option currency received when buying, settlement currency received when selling
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub amount: Balance,
+    /// The trading fee, in settlement currency
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub fee: Balance,
+    /// How far the price of the trade, without the fee, is from the spot price, with the
+    /// decimals of the fee rate
+    pub price_impact: u32,
+}
+
+/// The expected result of removing liquidity
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct LiquidityQuote<Balance> {
+    /// Settlement currency received, including the fee
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub amount: Balance,
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub yes: Balance,
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub no: Balance,
+    /// The share of the trading fees, in settlement currency
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub fee: Balance,
+}
+
+/// Filters of the proposal listing, a filter that is not set matches every proposal
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ProposalFilter<CategoryId, AccountId, CurrencyId, Moment> {
+    pub status: Option<ProposalStatus>,
+    pub category_id: Option<CategoryId>,
+    pub owner: Option<AccountId>,
+    /// The settlement currency of the proposal
+    pub currency_id: Option<CurrencyId>,
+    /// Only proposals closing at or after this time
+    pub close_after: Option<Moment>,
+    /// Only proposals closing at or before this time
+    pub close_before: Option<Moment>,
+}
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ProposalListItem<ProposalId, CategoryId, Balance, Moment, CurrencyId> {
+    pub proposal_id: ProposalId,
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(
+            serialize = "Balance: std::fmt::Display, CategoryId: Serialize, Moment: Serialize, CurrencyId: Serialize"
+        ))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(
+            deserialize = "Balance: std::str::FromStr, CategoryId: Deserialize<'de>, Moment: Deserialize<'de>, CurrencyId: Deserialize<'de>"
+        ))
+    )]
+    pub info: ProposalInfo<CategoryId, Balance, Moment, CurrencyId>,
+}
+
+/// A page of the proposal listing
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ProposalPage<ProposalId, CategoryId, Balance, Moment, CurrencyId> {
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(
+            serialize = "ProposalId: Serialize, Balance: std::fmt::Display, CategoryId: Serialize, Moment: Serialize, CurrencyId: Serialize"
+        ))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(
+            deserialize = "ProposalId: Deserialize<'de>, Balance: std::str::FromStr, CategoryId: Deserialize<'de>, Moment: Deserialize<'de>, CurrencyId: Deserialize<'de>"
+        ))
+    )]
+    pub proposals: Vec<ProposalListItem<ProposalId, CategoryId, Balance, Moment, CurrencyId>>,
+    /// The cursor of the next page, `None` when there are no more proposals
+    ///
+    /// The number of proposal ids looked at by one call is bounded, so a page can hold fewer
+    /// proposals than asked for, or none, and still have a next page
+    pub next: Option<ProposalId>,
+}
+
+/// The balance of one option currency of a proposal
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct OptionBalance<CurrencyId, Balance> {
+    pub currency_id: CurrencyId,
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub balance: Balance,
+}
+
+/// The position of an account in one proposal
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PortfolioItem<ProposalId, CurrencyId, Balance> {
+    pub proposal_id: ProposalId,
+    pub status: ProposalStatus,
+    /// The settlement currency of the proposal
+    pub currency_id: CurrencyId,
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "CurrencyId: Serialize, Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "CurrencyId: Deserialize<'de>, Balance: std::str::FromStr"))
+    )]
+    pub options: Vec<OptionBalance<CurrencyId, Balance>>,
+    /// Liquidity currency held
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub liquidity: Balance,
+    /// Liquidity currency held in reserve, which still earns its share of the trading fee
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub reserved_liquidity: Balance,
+    /// Governance currency staked on the proposal vote
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub vote_stake: Balance,
+    /// Votes locked by the node in the autonomy of the proposal
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub autonomy_lock: Balance,
+    /// Settlement currency, net of the withdrawal fee, the option currencies can be retrieved for
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub retrievable: Balance,
+    /// Trading fee earned as liquidity provider or owner of the proposal and not claimed yet
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(serialize = "Balance: std::fmt::Display"))
+    )]
+    #[cfg_attr(feature = "std", serde(serialize_with = "balance_serialize_as_string"))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(deserialize = "Balance: std::str::FromStr"))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(deserialize_with = "balance_deserialize_from_string")
+    )]
+    pub unclaimed_fee: Balance,
+}
+
+/// A page of the positions of an account across the proposals
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AccountPortfolio<ProposalId, CurrencyId, Balance> {
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(
+            serialize = "ProposalId: Serialize, CurrencyId: Serialize, Balance: std::fmt::Display"
+        ))
+    )]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound(
+            deserialize = "ProposalId: Deserialize<'de>, CurrencyId: Deserialize<'de>, Balance: std::str::FromStr"
+        ))
+    )]
+    pub items: Vec<PortfolioItem<ProposalId, CurrencyId, Balance>>,
+    /// The cursor of the next page, `None` when there are no more proposals
+    ///
+    /// As for `ProposalPage`, a page can hold fewer positions than asked for and still have a
+    /// next page
+    pub next: Option<ProposalId>,
+}
+
 #[cfg(feature = "std")]
 fn balance_serialize_as_string<S: Serializer, T: std::fmt::Display>(
     t: &T,
@@ -0,0 +1,190 @@
+//! Benchmarks for the proposals pallet
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+
+fn create_proposal<T: Config>(owner: &T::AccountId) -> ProposalIdOf<T> {
+    let proposal_id =
+        <Pallet<T> as LiquidityPool<T>>::get_next_proposal_id().expect("proposal id overflow");
+    let now = TimeOf::<T>::now();
+    let close_time = now.saturating_add(1_000_000u32.into());
+    <Pallet<T> as LiquidityPool<T>>::init_proposal(
+        proposal_id,
+        owner,
+        Status::OriginalPrediction,
+        now,
+        close_time,
+        One::one(),
+    );
+    proposal_id
+}
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+    let who: T::AccountId = account(name, index, SEED);
+    let currency_id = T::GovernanceCurrencyId::get();
+    <TokensOf<T> as Tokens<T::AccountId>>::mint(currency_id, &who, 1_000_000u32.into())
+        .expect("mint failed");
+    who
+}
+
+benchmarks! {
+    set_status {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let proposal_id = create_proposal::<T>(&owner);
+    }: _(RawOrigin::Root, proposal_id, Status::FormalPrediction)
+    verify {
+        assert_eq!(ProposalStatus::<T>::get(proposal_id), Some(Status::FormalPrediction));
+    }
+
+    stake_to {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let proposal_id = create_proposal::<T>(&owner);
+        let caller: T::AccountId = whitelisted_caller();
+        let currency_id = T::GovernanceCurrencyId::get();
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(currency_id, &caller, 1_000_000u32.into())?;
+        let number: BalanceOf<T> = 1_000u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id, number, true)
+    verify {
+        assert_eq!(ProposalVoteStake::<T>::get(proposal_id, &caller), Some((number, true)));
+    }
+
+    stake_to_from {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let proposal_id = create_proposal::<T>(&owner);
+        let voter = funded_account::<T>("voter", 0);
+        let caller: T::AccountId = whitelisted_caller();
+        let number: BalanceOf<T> = 1_000u32.into();
+        <TokensOf<T> as Tokens<T::AccountId>>::approve(
+            T::GovernanceCurrencyId::get(),
+            &voter,
+            &caller,
+            number,
+        )?;
+    }: _(RawOrigin::Signed(caller), voter.clone(), proposal_id, number, true)
+    verify {
+        assert_eq!(ProposalVoteStake::<T>::get(proposal_id, &voter), Some((number, true)));
+    }
+
+    unstake_from {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let proposal_id = create_proposal::<T>(&owner);
+        let caller: T::AccountId = whitelisted_caller();
+        let currency_id = T::GovernanceCurrencyId::get();
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(currency_id, &caller, 1_000_000u32.into())?;
+        Pallet::<T>::inner_stake_to(&caller, proposal_id, 1_000u32.into(), true)?;
+        ProposalStatus::<T>::insert(proposal_id, Status::FormalPrediction);
+    }: _(RawOrigin::Signed(caller.clone()), proposal_id)
+    verify {
+        assert_eq!(ProposalVoteStake::<T>::get(proposal_id, &caller), None);
+    }
+
+    withdrawal_reward {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let proposal_id = create_proposal::<T>(&owner);
+        let caller: T::AccountId = whitelisted_caller();
+        let currency_id = T::GovernanceCurrencyId::get();
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(currency_id, &caller, 1_000_000u32.into())?;
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(
+            currency_id,
+            &Pallet::<T>::module_account(),
+            1_000_000u32.into(),
+        )?;
+        MinimumVote::<T>::put(BalanceOf::<T>::zero());
+        ProposalReward::<T>::insert(proposal_id, BalanceOf::<T>::from(1_000u32));
+        Pallet::<T>::inner_stake_to(&caller, proposal_id, 1_000u32.into(), true)?;
+        ProposalStatus::<T>::insert(proposal_id, Status::FormalPrediction);
+    }: _(RawOrigin::Signed(caller), proposal_id)
+
+    deposit_reward {
+        let caller = funded_account::<T>("caller", 0);
+        let number: BalanceOf<T> = 1_000u32.into();
+    }: _(RawOrigin::Signed(caller), number)
+    verify {
+        let currency_id = T::GovernanceCurrencyId::get();
+        let reward_account = Pallet::<T>::module_account();
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(currency_id, &reward_account);
+        assert!(balance >= number);
+    }
+
+    reclaim_reward {
+        let currency_id = T::GovernanceCurrencyId::get();
+        let reward_account = Pallet::<T>::module_account();
+        <TokensOf<T> as Tokens<T::AccountId>>::mint(currency_id, &reward_account, 1_000u32.into())?;
+        let to: T::AccountId = account("to", 0, SEED);
+    }: _(RawOrigin::Root, to)
+    verify {
+        let balance = <TokensOf<T> as Tokens<T::AccountId>>::balance(currency_id, &reward_account);
+        assert_eq!(balance, Zero::zero());
+    }
+
+    set_proposal_minimum_interval_time {
+        let time: MomentOf<T> = 60_000u32.into();
+    }: _(RawOrigin::Root, time)
+    verify {
+        assert_eq!(ProposalMinimumIntervalTime::<T>::get(), Some(time));
+    }
+
+    set_default_reward {
+        let value: BalanceOf<T> = 1_000u32.into();
+    }: _(RawOrigin::Root, value)
+    verify {
+        assert_eq!(DefaultReward::<T>::get(), Some(value));
+    }
+
+    change_state {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let proposal_id = create_proposal::<T>(&owner);
+        ProposalStatus::<T>::insert(proposal_id, Status::FormalPrediction);
+        let close_time = ProposalCloseTime::<T>::get(proposal_id).ok_or("proposal not created")?;
+        let now = close_time.saturating_add(One::one());
+    }: {
+        <Pallet<T> as DeadlineScheduler>::change_state(proposal_id, now)?;
+    }
+    verify {
+        assert_eq!(ProposalStatus::<T>::get(proposal_id), Some(Status::WaitingForResults));
+    }
+
+    archive_proposal {
+        let l in 1 .. 1_000;
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let proposal_id = create_proposal::<T>(&owner);
+        for index in 0..l {
+            let voter = funded_account::<T>("voter", index);
+            Pallet::<T>::inner_stake_to(&voter, proposal_id, 1_000u32.into(), true)?;
+        }
+        ProposalStatus::<T>::insert(proposal_id, Status::End);
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller), proposal_id, l + 2)
+    verify {
+        assert_eq!(ProposalStatus::<T>::get(proposal_id), None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_set_status::<Test>());
+            assert_ok!(test_benchmark_stake_to::<Test>());
+            assert_ok!(test_benchmark_stake_to_from::<Test>());
+            assert_ok!(test_benchmark_unstake_from::<Test>());
+            assert_ok!(test_benchmark_withdrawal_reward::<Test>());
+            assert_ok!(test_benchmark_deposit_reward::<Test>());
+            assert_ok!(test_benchmark_reclaim_reward::<Test>());
+            assert_ok!(test_benchmark_set_proposal_minimum_interval_time::<Test>());
+            assert_ok!(test_benchmark_set_default_reward::<Test>());
+            assert_ok!(test_benchmark_change_state::<Test>());
+            assert_ok!(test_benchmark_archive_proposal::<Test>());
+        });
+    }
+}
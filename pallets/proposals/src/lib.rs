@@ -26,6 +26,10 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+
 #[cfg(feature = "std")]
 use frame_support::traits::GenesisBuild;
 
@@ -35,27 +39,45 @@ use frame_support::{
     traits::{Get, Time},
 };
 use sp_runtime::traits::{
-    AccountIdConversion, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Zero,
+    AccountIdConversion, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Saturating, Zero,
 };
+use sp_std::{cmp, vec::Vec};
 use xpmrl_traits::{
-    pool::{LiquidityPool, LiquiditySubPool},
+    deadline::DeadlineScheduler,
+    pool::{LiquidityPool, ProposalArchive},
+    registry::LiquidityRegistry,
     tokens::Tokens,
-    ProposalStatus as Status,
+    ProposalStatus as Status, Resolution, MAX_RESOLUTION_LENGTH,
 };
+use xpmrl_utils::with_transaction_result;
+
+pub use weights::WeightInfo;
 
 #[frame_support::pallet]
 pub mod pallet {
-    use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, traits::Time};
+    use frame_support::{
+        dispatch::{DispatchResultWithPostInfo, PostDispatchInfo},
+        pallet_prelude::*,
+        traits::Time,
+        weights::Pays,
+    };
     use frame_system::pallet_prelude::*;
-    use sp_runtime::{traits::Zero, ModuleId};
+    use sp_runtime::{
+        traits::{One, Saturating, Zero},
+        ModuleId,
+    };
+    use sp_std::vec::Vec;
     use xpmrl_traits::{
-        pool::{LiquidityPool, LiquiditySubPool},
+        pool::{LiquidityPool, ProposalArchive},
+        registry::LiquidityRegistry,
         system::ProposalSystem,
         tokens::Tokens,
-        ProposalStatus as Status,
+        ProposalStatus as Status, Resolution,
     };
     use xpmrl_utils::with_transaction_result;
 
+    use crate::weights::WeightInfo;
+
     pub(crate) type TimeOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::Time;
     pub(crate) type MomentOf<T> = <TimeOf<T> as Time>::Moment;
 
@@ -74,13 +96,24 @@ pub mod pallet {
     #[pallet::config]
     pub trait Config: frame_system::Config + ProposalSystem<Self::AccountId> {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
-        type SubPool: LiquiditySubPool<Self>;
+        /// The market engines, looked up by the version id the proposal was created with
+        type LiquidityEngines: LiquidityRegistry<Self>;
+        /// The other modules keeping storage about the proposals, archived along with the engine
+        /// of the proposal
+        type Archive: ProposalArchive<Self>;
 
         #[pallet::constant]
         type GovernanceCurrencyId: Get<CurrencyIdOf<Self>>;
 
         #[pallet::constant]
         type RewardId: Get<ModuleId>;
+
+        /// The length of time covered by one bucket of the deadline queue, usually the block time
+        #[pallet::constant]
+        type DeadlineBucket: Get<MomentOf<Self>>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
@@ -94,8 +127,6 @@ pub mod pallet {
     pub type CurrentProposalId<T: Config> = StorageValue<_, ProposalIdOf<T>>;
 
     /// Version id, forwarded to different processing modules through different versions
-    ///
-    /// This storage is not currently used
     #[pallet::storage]
     #[pallet::getter(fn proposal_liquidate_version_id)]
     pub type ProposalLiquidateVersionId<T: Config> =
@@ -133,6 +164,47 @@ pub mod pallet {
     pub type ProposalCreateTime<T: Config> =
         StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, MomentOf<T>, OptionQuery>;
 
+    /// The next time the state of the proposal can change on its own, the proposal is queued in
+    /// the bucket of the deadline queue holding this time
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_deadline)]
+    pub type ProposalDeadline<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, MomentOf<T>, OptionQuery>;
+
+    /// The deadline queue, the deadlines of the proposals grouped by bucket
+    ///
+    /// An entry whose deadline no longer matches `ProposalDeadline` was rescheduled and is skipped
+    #[pallet::storage]
+    #[pallet::getter(fn deadline_queue)]
+    pub type DeadlineQueue<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        MomentOf<T>,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        MomentOf<T>,
+        OptionQuery,
+    >;
+
+    /// The first bucket of the deadline queue that has not been processed yet
+    #[pallet::storage]
+    #[pallet::getter(fn next_deadline_bucket)]
+    pub type NextDeadlineBucket<T: Config> = StorageValue<_, MomentOf<T>>;
+
+    /// How many times in a row `change_state` has failed on the proposal, the time before it is
+    /// retried doubles with each failure
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_retries)]
+    pub type ProposalRetries<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, u32, OptionQuery>;
+
+    /// The proposals created before the deadline queue existed that have not been queued yet,
+    /// from the first one to the end of the range, a batch of them is queued every block
+    #[pallet::storage]
+    #[pallet::getter(fn unscheduled_proposals)]
+    pub type UnscheduledProposals<T: Config> =
+        StorageValue<_, (ProposalIdOf<T>, ProposalIdOf<T>), OptionQuery>;
+
     /// Time when the proposal enters the announcement
     #[pallet::storage]
     #[pallet::getter(fn proposal_announcement_time)]
@@ -202,6 +274,25 @@ pub mod pallet {
     pub type ProposalReward<T: Config> =
         StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, BalanceOf<T>, OptionQuery>;
 
+    /// Stored the voters that have withdrawn their reward of a proposal
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_reward_withdrawn)]
+    pub type ProposalRewardWithdrawn<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProposalIdOf<T>,
+        Twox64Concat,
+        T::AccountId,
+        bool,
+        OptionQuery,
+    >;
+
+    /// How the question of the proposal resolves, given when the proposal is created
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_resolution)]
+    pub type ProposalResolution<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalIdOf<T>, Resolution<MomentOf<T>>, OptionQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub expiration_time: u32,
@@ -239,10 +330,15 @@ pub mod pallet {
     pub enum Event<T: Config> {
         ProposalStatusChanged(ProposalIdOf<T>, Status),
         StakeTo(T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
+        /// A spender staked for an owner out of the allowance of the owner, with the spender
+        /// first
+        StakeToFrom(T::AccountId, T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
         UnStakeFrom(T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
         DepositReward(T::AccountId, T::AccountId, BalanceOf<T>),
         ReclaimReward(T::AccountId, T::AccountId, BalanceOf<T>),
         WithdrawalReward(T::AccountId, ProposalIdOf<T>, BalanceOf<T>),
+        /// The last storage of the proposal was removed by the account
+        ProposalArchived(T::AccountId, ProposalIdOf<T>),
     }
 
     #[pallet::error]
@@ -262,6 +358,14 @@ pub mod pallet {
         OwnerNotAllowedVote,
         ProposalAbnormalVote,
         AccountNotStake,
+        /// Some assets of the proposal have not been withdrawn yet, it can not be archived
+        ProposalNotDrained,
+        /// A text of the resolution of the proposal is too long
+        ResolutionTooLong,
+        /// The result of the proposal can not be expected before the proposal closes
+        ResolutionBeforeClose,
+        /// The reward of the proposal has already been withdrawn by the account
+        RewardAlreadyWithdrawn,
     }
 
     #[pallet::hooks]
@@ -275,6 +379,17 @@ pub mod pallet {
                 0
             })
         }
+
+        /// Mark the proposals created before the deadline queue existed to be queued, they are
+        /// queued in batches by the following blocks
+        fn on_runtime_upgrade() -> Weight {
+            if NextDeadlineBucket::<T>::exists() || UnscheduledProposals::<T>::exists() {
+                return T::DbWeight::get().reads(2);
+            }
+            let max_id = <Self as LiquidityPool<T>>::max_proposal_id();
+            UnscheduledProposals::<T>::put((Zero::zero(), max_id));
+            T::DbWeight::get().reads_writes(3, 1)
+        }
     }
 
     #[pallet::call]
@@ -282,7 +397,7 @@ pub mod pallet {
         /// Set new state for proposal
         ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::set_status())]
         pub fn set_status(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
@@ -309,7 +424,7 @@ pub mod pallet {
         /// amount of governance tokens to vote
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::stake_to())]
         pub fn stake_to(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
@@ -331,11 +446,46 @@ pub mod pallet {
             Ok(().into())
         }
 
+        /// Stake governance tokens of `owner` to vote on a proposal, out of the governance tokens
+        /// `owner` approved to the transactor
+        ///
+        /// The stake and the vote belong to `owner`, who withdraws them with `unstake_from`.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::stake_to_from())]
+        pub fn stake_to_from(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            proposal_id: ProposalIdOf<T>,
+            number: BalanceOf<T>,
+            opinion: bool,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let state = <Self as LiquidityPool<T>>::get_proposal_state(proposal_id)?;
+            ensure!(
+                state == Status::OriginalPrediction,
+                Error::<T>::ProposalAbnormalState
+            );
+            let proposal_owner = <Self as LiquidityPool<T>>::proposal_owner(proposal_id)?;
+            ensure!(owner != proposal_owner, Error::<T>::OwnerNotAllowedVote);
+            let number = with_transaction_result(|| -> Result<BalanceOf<T>, DispatchError> {
+                Self::record_stake(&owner, proposal_id, number, opinion)?;
+                <TokensOf<T> as Tokens<T::AccountId>>::reserve_from_allowance(
+                    T::GovernanceCurrencyId::get(),
+                    &who,
+                    &owner,
+                    number,
+                )
+            })?;
+            Self::deposit_event(Event::<T>::StakeToFrom(who, owner, proposal_id, number));
+            Ok(().into())
+        }
+
         /// Withdraw the stake, after the proposal status changes, the staked coins can be
         /// withdrawn
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::unstake_from())]
         pub fn unstake_from(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
@@ -361,6 +511,7 @@ pub mod pallet {
                         }
                     },
                 )?;
+                ProposalRewardWithdrawn::<T>::remove(proposal_id, &who);
                 <TokensOf<T> as Tokens<T::AccountId>>::unreserve(currency_id, &who, number)?;
                 Ok(number)
             })?;
@@ -372,7 +523,7 @@ pub mod pallet {
         /// corresponding reward
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::withdrawal_reward())]
         pub fn withdrawal_reward(
             origin: OriginFor<T>,
             proposal_id: ProposalIdOf<T>,
@@ -383,15 +534,8 @@ pub mod pallet {
                 state != Status::OriginalPrediction,
                 Error::<T>::ProposalAbnormalState
             );
-            let minimum_vote = MinimumVote::<T>::get().unwrap_or_else(Zero::zero);
             let approval =
-                ProposalCountVote::<T>::get(proposal_id, true).unwrap_or_else(Zero::zero);
-            let disapproval =
-                ProposalCountVote::<T>::get(proposal_id, false).unwrap_or_else(Zero::zero);
-            ensure!(
-                approval > disapproval && approval > minimum_vote,
-                Error::<T>::ProposalAbnormalVote
-            );
+                Self::proposal_approval(proposal_id).ok_or(Error::<T>::ProposalAbnormalVote)?;
             let number = with_transaction_result(|| -> Result<BalanceOf<T>, DispatchError> {
                 Self::inner_withdrawal_reward(&who, proposal_id, approval)
             })?;
@@ -403,7 +547,7 @@ pub mod pallet {
         /// will be used to issue the corresponding reward
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::deposit_reward())]
         pub fn deposit_reward(
             origin: OriginFor<T>,
             number: BalanceOf<T>,
@@ -426,7 +570,7 @@ pub mod pallet {
         /// Reclaim unused rewards
         ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::reclaim_reward())]
         pub fn reclaim_reward(
             origin: OriginFor<T>,
             to: T::AccountId,
@@ -448,7 +592,7 @@ pub mod pallet {
             Ok(().into())
         }
 
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::set_proposal_minimum_interval_time())]
         pub fn set_proposal_minimum_interval_time(
             origin: OriginFor<T>,
             time: MomentOf<T>,
@@ -458,7 +602,7 @@ pub mod pallet {
             Ok(().into())
         }
 
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::set_default_reward())]
         pub fn set_default_reward(
             origin: OriginFor<T>,
             value: BalanceOf<T>,
@@ -467,6 +611,46 @@ pub mod pallet {
             DefaultReward::<T>::set(Some(value));
             Ok(().into())
         }
+
+        /// Remove the storage of a proposal that is over and whose assets have all been
+        /// withdrawn, with at most `limit` entries removed by this call
+        ///
+        /// The governance tokens still staked on the proposal are returned to the voters, with
+        /// the rewards they did not withdraw. The call can be repeated until
+        /// `ProposalArchived` is emitted, and its fee is refunded as long as it removes storage.
+        /// It fails without removing anything when a stake or a reward can not be paid.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        #[pallet::weight(T::WeightInfo::archive_proposal(*limit))]
+        pub fn archive_proposal(
+            origin: OriginFor<T>,
+            proposal_id: ProposalIdOf<T>,
+            limit: u32,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let state = <Self as LiquidityPool<T>>::get_proposal_state(proposal_id)?;
+            ensure!(state == Status::End, Error::<T>::ProposalAbnormalState);
+            let version_id =
+                <Self as LiquidityPool<T>>::proposal_liquidate_version_id(proposal_id)?;
+            ensure!(
+                T::LiquidityEngines::is_drained(version_id, proposal_id)
+                    && T::Archive::is_drained(proposal_id),
+                Error::<T>::ProposalNotDrained
+            );
+            let (removed, archived) =
+                with_transaction_result(|| Self::inner_archive(version_id, proposal_id, limit))?;
+            if archived {
+                Self::deposit_event(Event::<T>::ProposalArchived(who, proposal_id));
+            }
+            Ok(PostDispatchInfo {
+                actual_weight: Some(T::WeightInfo::archive_proposal(removed)),
+                pays_fee: if removed > 0 || archived {
+                    Pays::No
+                } else {
+                    Pays::Yes
+                },
+            })
+        }
     }
 }
 
@@ -487,6 +671,9 @@ impl<T: Config> GenesisConfig<T> {
     }
 }
 
+/// The most proposals created before the deadline queue existed queued in one block
+const MAX_UNSCHEDULED_PROPOSALS_PER_BLOCK: u32 = 64;
+
 impl<T: Config> Pallet<T> {
     fn module_account() -> T::AccountId {
         T::RewardId::get().into_account()
@@ -494,48 +681,35 @@ impl<T: Config> Pallet<T> {
 
     fn begin_block(_: T::BlockNumber) -> Result<Weight, DispatchError> {
         let now = <TimeOf<T> as Time>::now();
-        let expiration_time = <Self as LiquidityPool<T>>::proposal_automatic_expiration_time();
-        let max_id = <Self as LiquidityPool<T>>::max_proposal_id();
-        let mut index: ProposalIdOf<T> = Zero::zero();
-        let minimum_vote = MinimumVote::<T>::get().unwrap_or_else(Zero::zero);
-        loop {
-            if index >= max_id {
-                break;
-            }
-            let start =
-                ProposalCreateTime::<T>::get(index).ok_or(Error::<T>::ProposalIdNotExist)?;
-            let end = ProposalCloseTime::<T>::get(index).ok_or(Error::<T>::ProposalIdNotExist)?;
-            let diff = now.checked_sub(&start).unwrap_or_else(Zero::zero);
-            let state = <Self as LiquidityPool<T>>::get_proposal_state(index)
-                .unwrap_or(Status::OriginalPrediction);
-            if diff > expiration_time && state == Status::OriginalPrediction {
-                let approval = ProposalCountVote::<T>::get(index, true).unwrap_or_else(Zero::zero);
-                let disapproval =
-                    ProposalCountVote::<T>::get(index, false).unwrap_or_else(Zero::zero);
-                if approval > disapproval && approval > minimum_vote {
-                    <Self as LiquidityPool<T>>::set_proposal_state(
-                        index,
-                        Status::FormalPrediction,
-                    )?;
-                } else {
-                    <Self as LiquidityPool<T>>::set_proposal_state(index, Status::End)?;
-                }
-            } else if now > end {
-                if state == Status::OriginalPrediction {
-                    <Self as LiquidityPool<T>>::set_proposal_state(index, Status::End)?;
-                } else if state == Status::FormalPrediction {
-                    <Self as LiquidityPool<T>>::set_proposal_state(
-                        index,
-                        Status::WaitingForResults,
-                    )?;
-                    ProposalAnnouncementTime::<T>::insert(index, now);
-                }
+        let weight = Self::schedule_unscheduled_proposals();
+        let progress = <Self as DeadlineScheduler>::process_deadlines(now);
+        let change = T::WeightInfo::change_state()
+            .saturating_add(T::LiquidityEngines::state_changed_weight());
+        Ok(weight.saturating_add(progress.weight(T::DbWeight::get(), change)))
+    }
+
+    /// Queue a batch of the proposals created before the deadline queue existed
+    fn schedule_unscheduled_proposals() -> Weight {
+        let (mut index, end) = match UnscheduledProposals::<T>::get() {
+            Some(range) => range,
+            None => return T::DbWeight::get().reads(1),
+        };
+        let mut weight = T::DbWeight::get().reads_writes(1, 1);
+        let mut scheduled: u32 = 0;
+        while index < end && scheduled < MAX_UNSCHEDULED_PROPOSALS_PER_BLOCK {
+            if let Err(e) = Self::schedule_deadline(index) {
+                sp_runtime::print(e);
             }
-            index = index
-                .checked_add(&One::one())
-                .ok_or(Error::<T>::ProposalIdOverflow)?;
+            weight = weight.saturating_add(T::DbWeight::get().reads_writes(5, 2));
+            index = index.saturating_add(One::one());
+            scheduled += 1;
+        }
+        if index < end {
+            UnscheduledProposals::<T>::put((index, end));
+        } else {
+            UnscheduledProposals::<T>::kill();
         }
-        Ok(0)
+        weight
     }
 
     fn inner_stake_to(
@@ -545,6 +719,17 @@ impl<T: Config> Pallet<T> {
         opinion: bool,
     ) -> Result<BalanceOf<T>, DispatchError> {
         let currency_id = T::GovernanceCurrencyId::get();
+        Self::record_stake(who, proposal_id, number, opinion)?;
+        <TokensOf<T> as Tokens<T::AccountId>>::reserve(currency_id, who, number)
+    }
+
+    /// Record the vote of `who` on a proposal, the staked tokens are reserved by the caller
+    fn record_stake(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+        opinion: bool,
+    ) -> Result<(), DispatchError> {
         ProposalVoteStake::<T>::try_mutate(
             proposal_id,
             &who,
@@ -567,9 +752,21 @@ impl<T: Config> Pallet<T> {
                 *optional = Some(new);
                 Ok(())
             },
-        )?;
-        let number = <TokensOf<T> as Tokens<T::AccountId>>::reserve(currency_id, who, number)?;
-        Ok(number)
+        )
+    }
+
+    /// The votes that approved the proposal, if it was approved by enough of them for the
+    /// voters to be rewarded
+    fn proposal_approval(proposal_id: ProposalIdOf<T>) -> Option<BalanceOf<T>> {
+        let minimum_vote = MinimumVote::<T>::get().unwrap_or_else(Zero::zero);
+        let approval = ProposalCountVote::<T>::get(proposal_id, true).unwrap_or_else(Zero::zero);
+        let disapproval =
+            ProposalCountVote::<T>::get(proposal_id, false).unwrap_or_else(Zero::zero);
+        if approval > disapproval && approval > minimum_vote {
+            Some(approval)
+        } else {
+            None
+        }
     }
 
     fn inner_withdrawal_reward(
@@ -579,6 +776,22 @@ impl<T: Config> Pallet<T> {
     ) -> Result<BalanceOf<T>, DispatchError> {
         let (number, opinion) =
             ProposalVoteStake::<T>::get(proposal_id, &who).ok_or(Error::<T>::AccountNotStake)?;
+        ensure!(
+            !ProposalRewardWithdrawn::<T>::contains_key(proposal_id, &who),
+            Error::<T>::RewardAlreadyWithdrawn
+        );
+        ProposalRewardWithdrawn::<T>::insert(proposal_id, &who, true);
+        Self::pay_reward(who, proposal_id, number, opinion, approval)
+    }
+
+    /// Pay the reward of the proposal to a voter who staked `number` with `opinion`
+    fn pay_reward(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+        opinion: bool,
+        approval: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
         let total = ProposalReward::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
         let base: BalanceOf<T> = 100u32.into();
         let currency_id = T::GovernanceCurrencyId::get();
@@ -604,15 +817,94 @@ impl<T: Config> Pallet<T> {
         proposal_id: ProposalIdOf<T>,
         new_status: Status,
     ) -> Result<Status, DispatchError> {
+        let version_id = ProposalLiquidateVersionId::<T>::get(proposal_id)
+            .ok_or(Error::<T>::ProposalIdNotExist)?;
         if new_status == Status::End {
-            T::SubPool::finally_locked(proposal_id)?;
+            T::LiquidityEngines::finally_locked(version_id, proposal_id)?;
         }
         ProposalStatus::<T>::try_mutate(proposal_id, |status| -> Result<Status, DispatchError> {
             let old_status = status.ok_or(Error::<T>::ProposalIdNotExist)?;
             ensure!(old_status != new_status, Error::<T>::StatusMustDiff);
             *status = Some(new_status);
             Ok(new_status)
-        })
+        })?;
+        T::LiquidityEngines::state_changed(version_id, proposal_id, new_status)?;
+        Self::schedule_deadline(proposal_id)?;
+        Ok(new_status)
+    }
+
+    /// Remove at most `limit` storage entries of the proposal, in the engine of the proposal
+    /// first, then in the other modules and in this one, and return how many were removed and
+    /// whether the proposal is gone
+    ///
+    /// Fails when the stake or the reward of a voter can not be paid, the stake is then kept
+    fn inner_archive(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+        limit: u32,
+    ) -> Result<(u32, bool), DispatchError> {
+        let mut removed = T::LiquidityEngines::archive(version_id, proposal_id, limit);
+        if removed < limit {
+            removed += T::Archive::archive(proposal_id, limit - removed);
+        }
+        if removed < limit {
+            let approval = Self::proposal_approval(proposal_id);
+            let stakes: Vec<(T::AccountId, (BalanceOf<T>, bool))> =
+                ProposalVoteStake::<T>::iter_prefix(proposal_id)
+                    .take((limit - removed) as usize)
+                    .collect();
+            for (who, (number, opinion)) in stakes {
+                with_transaction_result(|| {
+                    Self::release_vote_stake(&who, proposal_id, number, opinion, approval)
+                })?;
+                removed += 1;
+            }
+        }
+        if removed < limit {
+            removed += ProposalCountVote::<T>::drain_prefix(proposal_id)
+                .take((limit - removed) as usize)
+                .count() as u32;
+        }
+        if removed < limit {
+            ProposalOwner::<T>::remove(proposal_id);
+            ProposalCloseTime::<T>::remove(proposal_id);
+            ProposalCreateTime::<T>::remove(proposal_id);
+            ProposalDeadline::<T>::remove(proposal_id);
+            ProposalRetries::<T>::remove(proposal_id);
+            ProposalAnnouncementTime::<T>::remove(proposal_id);
+            ProposalReward::<T>::remove(proposal_id);
+            ProposalResolution::<T>::remove(proposal_id);
+            ProposalLiquidateVersionId::<T>::remove(proposal_id);
+            ProposalStatus::<T>::remove(proposal_id);
+            return Ok((removed, true));
+        }
+        Ok((removed, false))
+    }
+
+    /// Return the stake of the voter, pay the reward the voter did not withdraw and remove the
+    /// stake from the proposal
+    fn release_vote_stake(
+        who: &T::AccountId,
+        proposal_id: ProposalIdOf<T>,
+        number: BalanceOf<T>,
+        opinion: bool,
+        approval: Option<BalanceOf<T>>,
+    ) -> Result<(), DispatchError> {
+        let currency_id = T::GovernanceCurrencyId::get();
+        <TokensOf<T> as Tokens<T::AccountId>>::unreserve(currency_id, who, number)?;
+        let withdrawn = ProposalRewardWithdrawn::<T>::take(proposal_id, who).is_some();
+        if let (false, Some(approval)) = (withdrawn, approval) {
+            let reward = Self::pay_reward(who, proposal_id, number, opinion, approval)?;
+            if !reward.is_zero() {
+                Self::deposit_event(Event::<T>::WithdrawalReward(
+                    who.clone(),
+                    proposal_id,
+                    reward,
+                ));
+            }
+        }
+        ProposalVoteStake::<T>::remove(proposal_id, who);
+        Ok(())
     }
 
     fn get_next_proposal_id() -> Result<ProposalIdOf<T>, DispatchError> {
@@ -628,6 +920,67 @@ impl<T: Config> Pallet<T> {
     }
 }
 
+impl<T: Config> DeadlineScheduler for Pallet<T> {
+    type ProposalId = ProposalIdOf<T>;
+    type Moment = MomentOf<T>;
+    type Time = TimeOf<T>;
+    type BucketSize = T::DeadlineBucket;
+    type Deadlines = ProposalDeadline<T>;
+    type Buckets = DeadlineQueue<T>;
+    type NextBucket = NextDeadlineBucket<T>;
+    type Retries = ProposalRetries<T>;
+
+    /// The next time `change_state` can change the state of the proposal, `None` if it never
+    /// will
+    fn next_deadline(proposal_id: ProposalIdOf<T>) -> Result<Option<MomentOf<T>>, DispatchError> {
+        let state = <Self as LiquidityPool<T>>::get_proposal_state(proposal_id)?;
+        let close_time =
+            ProposalCloseTime::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let close_deadline = close_time.saturating_add(One::one());
+        match state {
+            Status::OriginalPrediction => {
+                let create_time = ProposalCreateTime::<T>::get(proposal_id)
+                    .ok_or(Error::<T>::ProposalIdNotExist)?;
+                let expiration_time =
+                    <Self as LiquidityPool<T>>::proposal_automatic_expiration_time();
+                let expiration_deadline = create_time
+                    .saturating_add(expiration_time)
+                    .saturating_add(One::one());
+                Ok(Some(cmp::min(expiration_deadline, close_deadline)))
+            }
+            Status::FormalPrediction => Ok(Some(close_deadline)),
+            _ => Ok(None),
+        }
+    }
+
+    fn change_state(index: ProposalIdOf<T>, now: MomentOf<T>) -> Result<(), DispatchError> {
+        let expiration_time = <Self as LiquidityPool<T>>::proposal_automatic_expiration_time();
+        let minimum_vote = MinimumVote::<T>::get().unwrap_or_else(Zero::zero);
+        let start = ProposalCreateTime::<T>::get(index).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let end = ProposalCloseTime::<T>::get(index).ok_or(Error::<T>::ProposalIdNotExist)?;
+        let diff = now.checked_sub(&start).unwrap_or_else(Zero::zero);
+        let state = <Self as LiquidityPool<T>>::get_proposal_state(index)
+            .unwrap_or(Status::OriginalPrediction);
+        if diff > expiration_time && state == Status::OriginalPrediction {
+            let approval = ProposalCountVote::<T>::get(index, true).unwrap_or_else(Zero::zero);
+            let disapproval = ProposalCountVote::<T>::get(index, false).unwrap_or_else(Zero::zero);
+            if approval > disapproval && approval > minimum_vote {
+                <Self as LiquidityPool<T>>::set_proposal_state(index, Status::FormalPrediction)?;
+            } else {
+                <Self as LiquidityPool<T>>::set_proposal_state(index, Status::End)?;
+            }
+        } else if now > end {
+            if state == Status::OriginalPrediction {
+                <Self as LiquidityPool<T>>::set_proposal_state(index, Status::End)?;
+            } else if state == Status::FormalPrediction {
+                <Self as LiquidityPool<T>>::set_proposal_state(index, Status::WaitingForResults)?;
+                ProposalAnnouncementTime::<T>::insert(index, now);
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<T: Config> LiquidityPool<T> for Pallet<T> {
     fn get_proposal_minimum_interval_time() -> MomentOf<T> {
         ProposalMinimumIntervalTime::<T>::get().unwrap_or_else(Zero::zero)
@@ -656,6 +1009,9 @@ impl<T: Config> LiquidityPool<T> for Pallet<T> {
         ProposalCloseTime::<T>::insert(proposal_id, close_time);
         let default_reward = DefaultReward::<T>::get().unwrap_or_else(Zero::zero);
         ProposalReward::<T>::insert(proposal_id, default_reward);
+        if let Err(e) = Self::schedule_deadline(proposal_id) {
+            sp_runtime::print(e);
+        }
     }
 
     fn append_used_currency(currency_id: CurrencyIdOf<T>) {
@@ -694,6 +1050,10 @@ impl<T: Config> LiquidityPool<T> for Pallet<T> {
         Self::set_new_status(proposal_id, new_state)
     }
 
+    fn state_changed_weight() -> Weight {
+        T::LiquidityEngines::state_changed_weight()
+    }
+
     fn proposal_owner(proposal_id: ProposalIdOf<T>) -> Result<T::AccountId, DispatchError> {
         match ProposalOwner::<T>::get(proposal_id) {
             Some(owner) => Ok(owner),
@@ -701,6 +1061,15 @@ impl<T: Config> LiquidityPool<T> for Pallet<T> {
         }
     }
 
+    fn proposal_liquidate_version_id(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<VersionIdOf<T>, DispatchError> {
+        match ProposalLiquidateVersionId::<T>::get(proposal_id) {
+            Some(version_id) => Ok(version_id),
+            None => Err(Error::<T>::ProposalIdNotExist.into()),
+        }
+    }
+
     fn proposal_announcement_time(
         proposal_id: ProposalIdOf<T>,
     ) -> Result<MomentOf<T>, DispatchError> {
@@ -709,4 +1078,37 @@ impl<T: Config> LiquidityPool<T> for Pallet<T> {
             None => Err(Error::<T>::ProposalIdNotExist.into()),
         }
     }
+
+    fn set_proposal_resolution(
+        proposal_id: ProposalIdOf<T>,
+        resolution: Resolution<MomentOf<T>>,
+    ) -> Result<(), DispatchError> {
+        ensure!(
+            [
+                &resolution.source,
+                &resolution.rules,
+                &resolution.invalid_condition
+            ]
+            .iter()
+            .all(|text| text.len() <= MAX_RESOLUTION_LENGTH),
+            Error::<T>::ResolutionTooLong
+        );
+        let close_time =
+            ProposalCloseTime::<T>::get(proposal_id).ok_or(Error::<T>::ProposalIdNotExist)?;
+        ensure!(
+            resolution.time >= close_time,
+            Error::<T>::ResolutionBeforeClose
+        );
+        ProposalResolution::<T>::insert(proposal_id, resolution);
+        Ok(())
+    }
+
+    fn proposal_resolution(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<Resolution<MomentOf<T>>, DispatchError> {
+        match ProposalResolution::<T>::get(proposal_id) {
+            Some(resolution) => Ok(resolution),
+            None => Err(Error::<T>::ProposalIdNotExist.into()),
+        }
+    }
 }
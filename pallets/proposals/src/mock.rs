@@ -8,14 +8,15 @@ use frame_support::{
 };
 use sp_core::H256;
 use sp_runtime::{
-    testing::Header,
+    testing::{Header, TestSignature, UintAuthorityId},
     traits::{BlakeTwo256, IdentityLookup},
     ModuleId,
 };
 use std::{cell::RefCell, collections::HashMap};
 use xpmrl_traits::{
     couple::LiquidityCouple,
-    pool::{LiquidityPool, LiquiditySubPool},
+    pool::{LiquidityPool, LiquiditySubPool, ProposalArchive},
+    registry::LiquidityEngine,
     system::ProposalSystem,
     tokens::Tokens,
     ProposalStatus,
@@ -89,6 +90,7 @@ type TimeOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::
 pub type MomentOf<T> = <TimeOf<T> as Time>::Moment;
 
 type ProposalIdOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::ProposalId;
+type VersionIdOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::VersionId;
 
 pub struct CoupleWrapper {
     pub announcement_time: HashMap<ProposalIdOf<Test>, MomentOf<Test>>,
@@ -161,6 +163,40 @@ impl LiquiditySubPool<Test> for Couple {
     }
 }
 
+impl LiquidityEngine<Test> for Couple {
+    fn version_id() -> VersionIdOf<Test> {
+        1
+    }
+}
+
+impl ProposalArchive<Test> for Couple {
+    fn is_drained(proposal_id: ProposalIdOf<Test>) -> bool {
+        COUPLE_WRAPPER.with(|wrapper| -> bool {
+            let wrapper = wrapper.borrow();
+            match (
+                wrapper.proposal_pair.get(&proposal_id),
+                wrapper.proposal_lp.get(&proposal_id),
+            ) {
+                (Some((yes_id, no_id)), Some(lp_id)) => [yes_id, no_id, lp_id]
+                    .iter()
+                    .all(|id| <XPMRLTokens as Tokens<AccountId>>::total_supply(**id) == 0),
+                _ => true,
+            }
+        })
+    }
+
+    fn archive(proposal_id: ProposalIdOf<Test>, _limit: u32) -> u32 {
+        COUPLE_WRAPPER.with(|wrapper| {
+            let mut wrapper = wrapper.borrow_mut();
+            wrapper.announcement_time.remove(&proposal_id);
+            wrapper.proposal_pair.remove(&proposal_id);
+            wrapper.proposal_result.remove(&proposal_id);
+            wrapper.proposal_lp.remove(&proposal_id);
+        });
+        0
+    }
+}
+
 impl LiquidityCouple<Test> for Couple {
     fn proposal_pair(
         proposal_id: ProposalIdOf<Test>,
@@ -272,18 +308,26 @@ impl xpmrl_tokens::Config for Test {
     type Currency = Balances;
     type NativeCurrencyId = NativeCurrencyId;
     type ModuleId = TokensModuleId;
+    type BeforeTransfer = ();
+    type PermitSignature = TestSignature;
+    type PermitSigner = UintAuthorityId;
+    type WeightInfo = ();
 }
 
 parameter_types! {
     pub const GovernanceCurrencyId: CurrencyIdOf<Test> = 1;
     pub const RewardId: ModuleId = ModuleId(*b"xpreward");
+    pub const DeadlineBucket: MomentOf<Test> = 1;
 }
 
 impl proposals::Config for Test {
     type Event = Event;
-    type SubPool = Couple;
+    type LiquidityEngines = (Couple,);
+    type Archive = ();
     type GovernanceCurrencyId = GovernanceCurrencyId;
     type RewardId = RewardId;
+    type DeadlineBucket = DeadlineBucket;
+    type WeightInfo = ();
 }
 
 pub fn run_to_block<Module: Hooks<BlockNumber>>(n: BlockNumber) {
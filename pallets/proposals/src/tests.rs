@@ -1,7 +1,14 @@
 use crate::{mock::*, Error};
 
-use frame_support::{assert_noop, assert_ok, traits::Time};
-use xpmrl_traits::{pool::LiquidityPool, tokens::Tokens, ProposalStatus};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{OnRuntimeUpgrade, Time},
+    weights::Pays,
+};
+use xpmrl_traits::{
+    couple::LiquidityCouple, deadline::MAX_DEADLINES_PER_BLOCK, pool::LiquidityPool,
+    registry::ENGINE_NOT_FOUND, tokens::Tokens, ProposalStatus, Resolution, MAX_RESOLUTION_LENGTH,
+};
 
 #[test]
 fn test_set_status() {
@@ -39,6 +46,28 @@ fn test_set_status() {
     });
 }
 
+#[test]
+fn test_liquidate_version_routing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Couple::new_couple_proposal(1, 1, 10));
+        assert_eq!(
+            <ProposalsModule as LiquidityPool<Test>>::proposal_liquidate_version_id(0),
+            Ok(1)
+        );
+        assert_ok!(Couple::new_couple_proposal(1, 1, 10));
+        crate::ProposalLiquidateVersionId::<Test>::insert(1, 2);
+        assert_noop!(
+            ProposalsModule::set_status(Origin::root(), 1, ProposalStatus::End),
+            ENGINE_NOT_FOUND
+        );
+        assert_ok!(ProposalsModule::set_status(
+            Origin::root(),
+            0,
+            ProposalStatus::End
+        ));
+    });
+}
+
 #[test]
 fn test_stake_to() {
     new_test_ext().execute_with(|| {
@@ -73,6 +102,57 @@ fn test_stake_to() {
     })
 }
 
+#[test]
+fn test_stake_to_from() {
+    new_test_ext().execute_with(|| {
+        let owner = 2;
+        let spender = 3;
+        let number = 100;
+        assert_ok!(Couple::new_couple_proposal(1, 1, 10));
+        assert_noop!(
+            ProposalsModule::stake_to_from(Origin::signed(spender), 1, 0, number, true),
+            Error::<Test>::OwnerNotAllowedVote
+        );
+        assert_noop!(
+            ProposalsModule::stake_to_from(Origin::signed(spender), owner, 0, number, true),
+            xpmrl_tokens::Error::<Test>::OriginNotAllowed
+        );
+        assert_ok!(XPMRLTokens::approve(
+            Origin::signed(owner),
+            1,
+            spender,
+            number
+        ));
+        let owner_before = XPMRLTokens::balance(1, &owner);
+        let spender_before = XPMRLTokens::balance(1, &spender);
+        assert_ok!(ProposalsModule::stake_to_from(
+            Origin::signed(spender),
+            owner,
+            0,
+            number,
+            true
+        ));
+        assert_eq!(owner_before - XPMRLTokens::balance(1, &owner), number);
+        assert_eq!(XPMRLTokens::reserved_balance(1, &owner), number);
+        assert_eq!(XPMRLTokens::balance(1, &spender), spender_before);
+        assert_eq!(
+            ProposalsModule::proposal_vote_stake(0, owner),
+            Some((number, true))
+        );
+        let event = Event::proposals(crate::Event::StakeToFrom(spender, owner, 0, number));
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        assert_ok!(
+            <ProposalsModule as LiquidityPool<Test>>::set_proposal_state(
+                0,
+                ProposalStatus::FormalPrediction
+            )
+        );
+        assert_ok!(ProposalsModule::unstake_from(Origin::signed(owner), 0));
+        assert_eq!(XPMRLTokens::balance(1, &owner), owner_before);
+    })
+}
+
 #[test]
 fn test_unstake_from() {
     new_test_ext().execute_with(|| {
@@ -158,6 +238,205 @@ fn test_withdrawal_reward() {
         assert_ok!(ProposalsModule::withdrawal_reward(Origin::signed(2), 0));
         let after = <TokensOf<Test> as Tokens<AccountId>>::balance(currency_id, &2);
         assert_eq!(after - before, default_reward / 2);
+        assert_eq!(ProposalsModule::proposal_reward_withdrawn(0, 2), Some(true));
+        assert_noop!(
+            ProposalsModule::withdrawal_reward(Origin::signed(2), 0),
+            Error::<Test>::RewardAlreadyWithdrawn
+        );
+    })
+}
+
+#[test]
+fn test_archive_proposal_pays_reward() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Couple::new_couple_proposal(1, 1, 102));
+        assert_ok!(ProposalsModule::stake_to(Origin::signed(2), 0, 600, true));
+        assert_ok!(ProposalsModule::stake_to(Origin::signed(3), 0, 600, true));
+        assert_ok!(ProposalsModule::stake_to(Origin::signed(4), 0, 600, false));
+        let now = <Timestamp as Time>::now();
+        run_to_block::<ProposalsModule>(now + 101);
+        let default_reward = ProposalsModule::default_reward().unwrap();
+        assert_ok!(ProposalsModule::deposit_reward(
+            Origin::signed(1),
+            default_reward
+        ));
+        assert_ok!(ProposalsModule::withdrawal_reward(Origin::signed(2), 0));
+        assert_ok!(ProposalsModule::set_status(
+            Origin::root(),
+            0,
+            ProposalStatus::End
+        ));
+
+        // the reward that was not withdrawn is paid to its voter on archival
+        let currency_id = <Test as crate::Config>::GovernanceCurrencyId::get();
+        let before: Vec<u128> = (2..=4)
+            .map(|account| XPMRLTokens::balance(currency_id, &account))
+            .collect();
+        assert_ok!(ProposalsModule::archive_proposal(Origin::signed(1), 0, 10));
+        let after: Vec<u128> = (2..=4)
+            .map(|account| XPMRLTokens::balance(currency_id, &account))
+            .collect();
+        assert_eq!(after[0] - before[0], 600);
+        assert_eq!(after[1] - before[1], 600 + default_reward / 2);
+        assert_eq!(after[2] - before[2], 600);
+        let event = Event::proposals(crate::Event::WithdrawalReward(3, 0, default_reward / 2));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(ProposalsModule::proposal_reward_withdrawn(0, 2), None);
+        assert_eq!(ProposalsModule::proposal_status(0), None);
+    })
+}
+
+#[test]
+fn test_archive_proposal_keeps_unpaid_stake() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Couple::new_couple_proposal(1, 1, 102));
+        assert_ok!(ProposalsModule::stake_to(Origin::signed(2), 0, 600, true));
+        assert_ok!(ProposalsModule::stake_to(Origin::signed(3), 0, 600, true));
+        let now = <Timestamp as Time>::now();
+        run_to_block::<ProposalsModule>(now + 101);
+        assert_ok!(ProposalsModule::set_status(
+            Origin::root(),
+            0,
+            ProposalStatus::End
+        ));
+
+        // the reward account holds nothing, so the rewards can not be paid
+        assert!(ProposalsModule::archive_proposal(Origin::signed(1), 0, 10).is_err());
+        assert_eq!(
+            ProposalsModule::proposal_vote_stake(0, 2),
+            Some((600, true))
+        );
+        assert_eq!(
+            ProposalsModule::proposal_vote_stake(0, 3),
+            Some((600, true))
+        );
+        assert_eq!(XPMRLTokens::reserved_balance(1, &2), 600);
+        assert_eq!(
+            ProposalsModule::proposal_status(0),
+            Some(ProposalStatus::End)
+        );
+
+        let default_reward = ProposalsModule::default_reward().unwrap();
+        assert_ok!(ProposalsModule::deposit_reward(
+            Origin::signed(1),
+            default_reward
+        ));
+        assert_ok!(ProposalsModule::archive_proposal(Origin::signed(1), 0, 10));
+        assert_eq!(ProposalsModule::proposal_vote_stake(0, 2), None);
+        assert_eq!(XPMRLTokens::reserved_balance(1, &2), 0);
+        assert_eq!(ProposalsModule::proposal_status(0), None);
+    })
+}
+
+#[test]
+fn test_archive_proposal() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Couple::new_couple_proposal(1, 1, 10));
+        assert_ok!(
+            <ProposalsModule as LiquidityPool<Test>>::set_proposal_resolution(
+                0,
+                Resolution {
+                    time: 10,
+                    ..Default::default()
+                }
+            )
+        );
+        let before: Vec<u128> = (2..=4)
+            .map(|account| XPMRLTokens::balance(1, &account))
+            .collect();
+        assert_ok!(ProposalsModule::stake_to(Origin::signed(2), 0, 600, true));
+        assert_ok!(ProposalsModule::stake_to(Origin::signed(3), 0, 600, true));
+        assert_ok!(ProposalsModule::stake_to(Origin::signed(4), 0, 600, false));
+        assert_noop!(
+            ProposalsModule::archive_proposal(Origin::signed(1), 0, 10),
+            Error::<Test>::ProposalAbnormalState
+        );
+        assert_ok!(ProposalsModule::set_status(
+            Origin::root(),
+            0,
+            ProposalStatus::End
+        ));
+        let (yes_id, _) = Couple::proposal_pair(0).unwrap();
+        assert_ok!(<XPMRLTokens as Tokens<AccountId>>::mint(yes_id, &1, 100));
+        assert_noop!(
+            ProposalsModule::archive_proposal(Origin::signed(1), 0, 10),
+            Error::<Test>::ProposalNotDrained
+        );
+        assert_ok!(<XPMRLTokens as Tokens<AccountId>>::burn(yes_id, &1, 100));
+
+        let info = ProposalsModule::archive_proposal(Origin::signed(1), 0, 2).unwrap();
+        assert_eq!(info.pays_fee, Pays::No);
+        assert_eq!(
+            ProposalsModule::proposal_status(0),
+            Some(ProposalStatus::End)
+        );
+        let reserved: u128 = (2..=4)
+            .map(|account| XPMRLTokens::reserved_balance(1, &account))
+            .sum();
+        assert_eq!(reserved, 600);
+        assert_ok!(ProposalsModule::archive_proposal(Origin::signed(1), 0, 10));
+        for (account, balance) in (2..=4).zip(before) {
+            assert_eq!(XPMRLTokens::reserved_balance(1, &account), 0);
+            assert_eq!(XPMRLTokens::balance(1, &account), balance);
+        }
+        assert_eq!(ProposalsModule::proposal_vote_stake(0, 4), None);
+        assert_eq!(ProposalsModule::proposal_count_vote(0, true), None);
+        assert_eq!(ProposalsModule::proposal_status(0), None);
+        assert_eq!(ProposalsModule::proposal_owner(0), None);
+        assert_eq!(ProposalsModule::proposal_resolution(0), None);
+        let event = Event::proposals(crate::Event::ProposalArchived(1, 0));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_noop!(
+            ProposalsModule::archive_proposal(Origin::signed(1), 0, 10),
+            Error::<Test>::ProposalIdNotExist
+        );
+    })
+}
+
+#[test]
+fn test_proposal_resolution() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Couple::new_couple_proposal(1, 1, 10));
+        let resolution = Resolution {
+            source: b"ipfs://QmResolutionSource".to_vec(),
+            rules: b"YES if the home team wins in regular time".to_vec(),
+            time: 20,
+            invalid_condition: b"the match is cancelled".to_vec(),
+        };
+        assert_noop!(
+            <ProposalsModule as LiquidityPool<Test>>::set_proposal_resolution(
+                0,
+                Resolution {
+                    rules: vec![b'r'; MAX_RESOLUTION_LENGTH + 1],
+                    ..resolution.clone()
+                }
+            ),
+            Error::<Test>::ResolutionTooLong
+        );
+        assert_noop!(
+            <ProposalsModule as LiquidityPool<Test>>::set_proposal_resolution(
+                0,
+                Resolution {
+                    time: 9,
+                    ..resolution.clone()
+                }
+            ),
+            Error::<Test>::ResolutionBeforeClose
+        );
+        assert_noop!(
+            <ProposalsModule as LiquidityPool<Test>>::set_proposal_resolution(
+                1,
+                resolution.clone()
+            ),
+            Error::<Test>::ProposalIdNotExist
+        );
+        assert_ok!(
+            <ProposalsModule as LiquidityPool<Test>>::set_proposal_resolution(
+                0,
+                resolution.clone()
+            )
+        );
+        assert_eq!(ProposalsModule::proposal_resolution(0), Some(resolution));
     })
 }
 
@@ -273,3 +552,133 @@ fn test_hooks() {
         ));
     });
 }
+
+#[test]
+fn test_deadline_queue() {
+    new_test_ext().execute_with(|| {
+        let now = <Timestamp as Time>::now();
+        assert_ok!(Couple::new_couple_proposal(1, 1, now + 10));
+        assert_eq!(ProposalsModule::proposal_deadline(0), Some(now + 11));
+        assert_eq!(ProposalsModule::deadline_queue(now + 11, 0), Some(now + 11));
+        assert_ok!(ProposalsModule::set_status(
+            Origin::root(),
+            0,
+            ProposalStatus::FormalPrediction
+        ));
+        assert_eq!(ProposalsModule::deadline_queue(now + 11, 0), Some(now + 11));
+
+        assert_ok!(Couple::new_couple_proposal(1, 1, now + 20));
+        assert_eq!(ProposalsModule::proposal_deadline(1), Some(now + 21));
+        assert_ok!(ProposalsModule::set_status(
+            Origin::root(),
+            1,
+            ProposalStatus::End
+        ));
+        assert_eq!(ProposalsModule::proposal_deadline(1), None);
+
+        run_to_block::<ProposalsModule>(now + 10);
+        assert_eq!(
+            ProposalsModule::proposal_status(0),
+            Some(ProposalStatus::FormalPrediction)
+        );
+        assert_eq!(ProposalsModule::next_deadline_bucket(), Some(now + 10));
+        run_to_block::<ProposalsModule>(now + 11);
+        assert_eq!(
+            ProposalsModule::proposal_status(0),
+            Some(ProposalStatus::WaitingForResults)
+        );
+        assert_eq!(
+            ProposalsModule::proposal_announcement_time(0),
+            Some(now + 11)
+        );
+        assert_eq!(ProposalsModule::proposal_deadline(0), None);
+        assert_eq!(ProposalsModule::deadline_queue(now + 11, 0), None);
+
+        run_to_block::<ProposalsModule>(now + 21);
+        assert_eq!(
+            ProposalsModule::proposal_status(1),
+            Some(ProposalStatus::End)
+        );
+        assert_eq!(ProposalsModule::deadline_queue(now + 21, 1), None);
+    });
+}
+
+#[test]
+fn test_deadline_queue_carries_over() {
+    new_test_ext().execute_with(|| {
+        let now = <Timestamp as Time>::now();
+        let count = MAX_DEADLINES_PER_BLOCK + 8;
+        for _ in 0..count {
+            assert_ok!(Couple::new_couple_proposal(1, 1, now + 10));
+        }
+        run_to_block::<ProposalsModule>(now + 11);
+        let ended = (0..count)
+            .filter(|id| ProposalsModule::proposal_status(id) == Some(ProposalStatus::End))
+            .count();
+        assert_eq!(ended, MAX_DEADLINES_PER_BLOCK as usize);
+        assert_eq!(ProposalsModule::next_deadline_bucket(), Some(now + 11));
+
+        run_to_block::<ProposalsModule>(now + 12);
+        for id in 0..count {
+            assert_eq!(
+                ProposalsModule::proposal_status(id),
+                Some(ProposalStatus::End)
+            );
+        }
+        assert_eq!(ProposalsModule::next_deadline_bucket(), Some(now + 12));
+    });
+}
+
+#[test]
+fn test_deadline_queue_upgrade() {
+    new_test_ext().execute_with(|| {
+        let now = <Timestamp as Time>::now();
+        assert_ok!(Couple::new_couple_proposal(1, 1, now + 10));
+        assert_ok!(Couple::new_couple_proposal(1, 1, now + 20));
+        // the proposals were created before the deadline queue existed
+        crate::ProposalDeadline::<Test>::remove_all();
+        crate::DeadlineQueue::<Test>::remove_all();
+
+        ProposalsModule::on_runtime_upgrade();
+        assert_eq!(ProposalsModule::unscheduled_proposals(), Some((0, 2)));
+        assert_eq!(ProposalsModule::proposal_deadline(0), None);
+        run_to_block::<ProposalsModule>(now + 1);
+        assert_eq!(ProposalsModule::unscheduled_proposals(), None);
+        assert_eq!(ProposalsModule::proposal_deadline(0), Some(now + 11));
+        assert_eq!(ProposalsModule::proposal_deadline(1), Some(now + 21));
+
+        // a later upgrade leaves the queue alone
+        ProposalsModule::on_runtime_upgrade();
+        assert_eq!(ProposalsModule::unscheduled_proposals(), None);
+    });
+}
+
+#[test]
+fn test_failed_state_change_backs_off() {
+    new_test_ext().execute_with(|| {
+        let now = <Timestamp as Time>::now();
+        assert_ok!(Couple::new_couple_proposal(1, 1, now + 10));
+        assert_ok!(ProposalsModule::set_status(
+            Origin::root(),
+            0,
+            ProposalStatus::FormalPrediction
+        ));
+        // the state of the proposal can not change without its creation time
+        crate::ProposalCreateTime::<Test>::remove(0);
+        run_to_block::<ProposalsModule>(now + 11);
+        assert_eq!(ProposalsModule::proposal_retries(0), Some(1));
+        assert_eq!(ProposalsModule::proposal_deadline(0), Some(now + 12));
+        run_to_block::<ProposalsModule>(now + 12);
+        assert_eq!(ProposalsModule::proposal_retries(0), Some(2));
+        assert_eq!(ProposalsModule::proposal_deadline(0), Some(now + 14));
+
+        crate::ProposalCreateTime::<Test>::insert(0, now);
+        run_to_block::<ProposalsModule>(now + 14);
+        assert_eq!(
+            ProposalsModule::proposal_status(0),
+            Some(ProposalStatus::WaitingForResults)
+        );
+        assert_eq!(ProposalsModule::proposal_retries(0), None);
+        assert_eq!(ProposalsModule::proposal_deadline(0), None);
+    });
+}
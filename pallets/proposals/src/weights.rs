@@ -0,0 +1,146 @@
+//! Weights for xpmrl_proposals
+//!
+//! PROVISIONAL: the figures below are estimated by hand from the storage accesses of each call
+//! on top of a fixed base cost, they have not been measured by the benchmarks of this pallet
+//! yet and must be replaced by the output of the benchmark CLI before a release.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for xpmrl_proposals.
+pub trait WeightInfo {
+    fn set_status() -> Weight;
+    fn stake_to() -> Weight;
+    fn stake_to_from() -> Weight;
+    fn unstake_from() -> Weight;
+    fn withdrawal_reward() -> Weight;
+    fn deposit_reward() -> Weight;
+    fn reclaim_reward() -> Weight;
+    fn set_proposal_minimum_interval_time() -> Weight;
+    fn set_default_reward() -> Weight;
+    fn archive_proposal(l: u32) -> Weight;
+    fn change_state() -> Weight;
+}
+
+/// Provisional weights for xpmrl_proposals, estimated by hand until they are benchmarked.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn set_status() -> Weight {
+        (38_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn stake_to() -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn stake_to_from() -> Weight {
+        (61_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn unstake_from() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn withdrawal_reward() -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn deposit_reward() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn reclaim_reward() -> Weight {
+        (42_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_proposal_minimum_interval_time() -> Weight {
+        (12_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_default_reward() -> Weight {
+        (12_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn archive_proposal(l: u32) -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add((14_000_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(T::DbWeight::get().writes(45 as Weight))
+            .saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(l as Weight)))
+    }
+    fn change_state() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(13 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn set_status() -> Weight {
+        (38_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn stake_to() -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn stake_to_from() -> Weight {
+        (61_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn unstake_from() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn withdrawal_reward() -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn deposit_reward() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn reclaim_reward() -> Weight {
+        (42_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_proposal_minimum_interval_time() -> Weight {
+        (12_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_default_reward() -> Weight {
+        (12_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn archive_proposal(l: u32) -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add((14_000_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(45 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(l as Weight)))
+    }
+    fn change_state() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(13 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+}
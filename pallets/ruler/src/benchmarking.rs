@@ -0,0 +1,45 @@
+//! Benchmarks for the ruler pallet
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+
+benchmarks! {
+    transfer_ruler_address {
+        let caller: T::AccountId = whitelisted_caller();
+        let address: T::AccountId = account("address", 0, SEED);
+        RulerAddress::<T>::insert(RulerModule::PlatformDividend, caller.clone());
+    }: _(RawOrigin::Signed(caller), RulerModule::PlatformDividend, address.clone())
+    verify {
+        assert_eq!(PendingRulerAddress::<T>::get(RulerModule::PlatformDividend), Some(address));
+    }
+
+    accept_ruler_address {
+        let caller: T::AccountId = whitelisted_caller();
+        let old: T::AccountId = account("old", 0, SEED);
+        RulerAddress::<T>::insert(RulerModule::PlatformDividend, old);
+        PendingRulerAddress::<T>::insert(RulerModule::PlatformDividend, caller.clone());
+    }: _(RawOrigin::Signed(caller.clone()), RulerModule::PlatformDividend)
+    verify {
+        assert_eq!(RulerAddress::<T>::get(RulerModule::PlatformDividend), Some(caller));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_transfer_ruler_address::<Test>());
+            assert_ok!(test_benchmark_accept_ruler_address::<Test>());
+        });
+    }
+}
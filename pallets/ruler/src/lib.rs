@@ -23,7 +23,12 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+
 pub use pallet::*;
+pub use weights::WeightInfo;
 
 use frame_support::dispatch::DispatchError;
 use xpmrl_traits::{ruler::RulerAccounts, RulerModule};
@@ -36,10 +41,14 @@ pub mod pallet {
     use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
     use frame_system::pallet_prelude::*;
     use xpmrl_traits::RulerModule;
+    use crate::weights::WeightInfo;
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
@@ -101,7 +110,7 @@ pub mod pallet {
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Transfer control authority
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::transfer_ruler_address())]
         pub fn transfer_ruler_address(
             origin: OriginFor<T>,
             module: RulerModule,
@@ -117,7 +126,7 @@ pub mod pallet {
         }
 
         /// Accept control authority
-        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::WeightInfo::accept_ruler_address())]
         pub fn accept_ruler_address(
             origin: OriginFor<T>,
             module: RulerModule,
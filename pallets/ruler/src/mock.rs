@@ -59,6 +59,7 @@ impl frame_system::Config for Test {
 
 impl ruler::Config for Test {
     type Event = Event;
+    type WeightInfo = ();
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -0,0 +1,411 @@
+//! <!-- markdown-link-check-disable -->
+//! # Test Support
+//!
+//! Run `cargo doc --package xpmrl-test-support --open` to view this crate's documentation.
+//!
+//! The mock runtime and fixtures shared by the tests of the market engines, couple,
+//! categorical and lmsr. Only meant as a dev-dependency.
+//!
+
+/// Declares the mock runtime `Test` of a market engine in the calling module
+///
+/// `$module: $pallet` is the name of the engine in `construct_runtime!` and the path of its
+/// pallet, `$before_transfer` is the `BeforeTransfer` hook of the tokens. Besides the runtime,
+/// it declares the `ProposalsWrapper` standing in for the proposals pallet, which tells the
+/// engine about state changes like the proposals pallet does, the `RulerWrapper`, the type
+/// aliases of the tests and the fixtures `resolution`, `create_proposal_with` and `set_state`.
+///
+/// The engine keeps its own `Config`, genesis and `new_test_ext` next to the macro call, the
+/// calling crate needs the same dependencies as a hand-written mock.
+#[macro_export]
+macro_rules! mock_runtime {
+    ($module:ident: $pallet:ident, BeforeTransfer = $before_transfer:ty) => {
+        type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+        type Block = frame_system::mocking::MockBlock<Test>;
+        pub type AccountId = u64;
+        type Balance = u128;
+        pub type BlockNumber = u64;
+
+        thread_local! {
+            static PROPOSALS_WRAPPER: std::cell::RefCell<ProposalsWrapper> =
+                std::cell::RefCell::new(ProposalsWrapper::new());
+        }
+
+        pub struct Timestamp;
+        impl frame_support::traits::Time for Timestamp {
+            type Moment = BlockNumber;
+            fn now() -> Self::Moment {
+                System::block_number()
+            }
+        }
+
+        frame_support::construct_runtime!(
+            pub enum Test where
+                Block = Block,
+                NodeBlock = Block,
+                UncheckedExtrinsic = UncheckedExtrinsic,
+            {
+                System: frame_system::{Module, Call, Config, Storage, Event<T>},
+                $module: $pallet::{Module, Call, Config, Storage, Event<T>},
+                XPMRLTokens: xpmrl_tokens::{Module, Call, Config<T>, Storage, Event<T>},
+                PalletBalances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+            }
+        );
+
+        frame_support::parameter_types! {
+            pub const BlockHashCount: u64 = 250;
+            pub const SS58Prefix: u8 = 42;
+        }
+
+        impl frame_system::Config for Test {
+            type BaseCallFilter = ();
+            type BlockWeights = ();
+            type BlockLength = ();
+            type DbWeight = ();
+            type Origin = Origin;
+            type Call = Call;
+            type Index = u64;
+            type BlockNumber = BlockNumber;
+            type Hash = sp_core::H256;
+            type Hashing = sp_runtime::traits::BlakeTwo256;
+            type AccountId = AccountId;
+            type Lookup = sp_runtime::traits::IdentityLookup<Self::AccountId>;
+            type Header = sp_runtime::testing::Header;
+            type Event = Event;
+            type BlockHashCount = BlockHashCount;
+            type Version = ();
+            type PalletInfo = PalletInfo;
+            type AccountData = pallet_balances::AccountData<Balance>;
+            type OnNewAccount = ();
+            type OnKilledAccount = ();
+            type SystemWeightInfo = ();
+            type SS58Prefix = SS58Prefix;
+        }
+
+        frame_support::parameter_types! {
+            pub const ExistentialDeposit: u64 = 1;
+        }
+
+        impl pallet_balances::Config for Test {
+            type Balance = Balance;
+            type DustRemoval = ();
+            type Event = Event;
+            type ExistentialDeposit = ExistentialDeposit;
+            type AccountStore = frame_system::Pallet<Test>;
+            type MaxLocks = ();
+            type WeightInfo = ();
+        }
+
+        type CurrencyId = u32;
+
+        frame_support::parameter_types! {
+            pub const NativeCurrencyId: CurrencyId = 0;
+            pub const TokensModuleId: sp_runtime::ModuleId = sp_runtime::ModuleId(*b"xptokens");
+        }
+
+        impl xpmrl_tokens::Config for Test {
+            type Event = Event;
+            type CurrencyId = CurrencyId;
+            type Currency = PalletBalances;
+            type NativeCurrencyId = NativeCurrencyId;
+            type ModuleId = TokensModuleId;
+            type BeforeTransfer = $before_transfer;
+            type PermitSignature = sp_runtime::testing::TestSignature;
+            type PermitSigner = sp_runtime::testing::UintAuthorityId;
+            type WeightInfo = ();
+        }
+
+        pub type TokensOf<T> = <T as xpmrl_traits::system::ProposalSystem<
+            <T as frame_system::Config>::AccountId,
+        >>::Tokens;
+        pub type CurrencyIdOf<T> = <TokensOf<T> as xpmrl_traits::tokens::Tokens<
+            <T as frame_system::Config>::AccountId,
+        >>::CurrencyId;
+        pub type BalanceOf<T> = <TokensOf<T> as xpmrl_traits::tokens::Tokens<
+            <T as frame_system::Config>::AccountId,
+        >>::Balance;
+
+        pub type TimeOf<T> = <T as xpmrl_traits::system::ProposalSystem<
+            <T as frame_system::Config>::AccountId,
+        >>::Time;
+        pub type MomentOf<T> = <TimeOf<T> as frame_support::traits::Time>::Moment;
+
+        pub type ProposalIdOf<T> = <T as xpmrl_traits::system::ProposalSystem<
+            <T as frame_system::Config>::AccountId,
+        >>::ProposalId;
+        type VersionIdOf<T> = <T as xpmrl_traits::system::ProposalSystem<
+            <T as frame_system::Config>::AccountId,
+        >>::VersionId;
+
+        pub struct ProposalsWrapper {
+            pub next_proposal_id: ProposalIdOf<Test>,
+            pub interval_time: MomentOf<Test>,
+            pub create_time: std::collections::HashMap<ProposalIdOf<Test>, MomentOf<Test>>,
+            pub close_time: std::collections::HashMap<ProposalIdOf<Test>, MomentOf<Test>>,
+            pub used_currency_id: std::collections::HashMap<CurrencyIdOf<Test>, ()>,
+            pub proposal_state:
+                std::collections::HashMap<ProposalIdOf<Test>, xpmrl_traits::ProposalStatus>,
+            pub proposal_owner: std::collections::HashMap<ProposalIdOf<Test>, AccountId>,
+            pub version_id: std::collections::HashMap<ProposalIdOf<Test>, VersionIdOf<Test>>,
+            pub announcement_time: std::collections::HashMap<ProposalIdOf<Test>, MomentOf<Test>>,
+            pub resolution: std::collections::HashMap<
+                ProposalIdOf<Test>,
+                xpmrl_traits::Resolution<MomentOf<Test>>,
+            >,
+        }
+
+        impl ProposalsWrapper {
+            fn new() -> ProposalsWrapper {
+                ProposalsWrapper {
+                    next_proposal_id: 0,
+                    interval_time: 5,
+                    announcement_time: std::collections::HashMap::new(),
+                    resolution: std::collections::HashMap::new(),
+                    create_time: std::collections::HashMap::new(),
+                    close_time: std::collections::HashMap::new(),
+                    used_currency_id: std::collections::HashMap::new(),
+                    proposal_state: std::collections::HashMap::new(),
+                    proposal_owner: std::collections::HashMap::new(),
+                    version_id: std::collections::HashMap::new(),
+                }
+            }
+        }
+
+        impl xpmrl_traits::pool::LiquidityPool<Test> for ProposalsWrapper {
+            fn get_proposal_minimum_interval_time() -> MomentOf<Test> {
+                PROPOSALS_WRAPPER
+                    .with(|wrapper| -> MomentOf<Test> { wrapper.borrow().interval_time })
+            }
+
+            fn is_currency_id_used(currency_id: CurrencyIdOf<Test>) -> bool {
+                PROPOSALS_WRAPPER.with(|wrapper| -> bool {
+                    wrapper.borrow().used_currency_id.contains_key(&currency_id)
+                })
+            }
+
+            fn get_next_proposal_id() -> Result<ProposalIdOf<Test>, sp_runtime::DispatchError> {
+                PROPOSALS_WRAPPER.with(
+                    |wrapper| -> Result<ProposalIdOf<Test>, sp_runtime::DispatchError> {
+                        let id = wrapper.borrow().next_proposal_id;
+                        wrapper.borrow_mut().next_proposal_id = id
+                            .checked_add(1)
+                            .ok_or(sp_runtime::DispatchError::Other("ProposalIdOverflow"))?;
+                        Ok(id)
+                    },
+                )
+            }
+
+            fn proposal_create_time(
+                proposal_id: ProposalIdOf<Test>,
+            ) -> Result<MomentOf<Test>, sp_runtime::DispatchError> {
+                PROPOSALS_WRAPPER.with(
+                    |wrapper| -> Result<MomentOf<Test>, sp_runtime::DispatchError> {
+                        match wrapper.borrow().create_time.get(&proposal_id) {
+                            Some(v) => Ok(*v),
+                            None => Err("ProposalIdNotExist".into()),
+                        }
+                    },
+                )
+            }
+
+            fn proposal_close_time(
+                proposal_id: ProposalIdOf<Test>,
+            ) -> Result<MomentOf<Test>, sp_runtime::DispatchError> {
+                PROPOSALS_WRAPPER.with(
+                    |wrapper| -> Result<MomentOf<Test>, sp_runtime::DispatchError> {
+                        match wrapper.borrow().close_time.get(&proposal_id) {
+                            Some(v) => Ok(*v),
+                            None => Err("ProposalIdNotExist".into()),
+                        }
+                    },
+                )
+            }
+
+            fn init_proposal(
+                proposal_id: ProposalIdOf<Test>,
+                owner: &AccountId,
+                state: xpmrl_traits::ProposalStatus,
+                _create_time: MomentOf<Test>,
+                _close_time: MomentOf<Test>,
+                version: VersionIdOf<Test>,
+            ) {
+                PROPOSALS_WRAPPER.with(|wrapper| -> () {
+                    wrapper.borrow_mut().version_id.insert(proposal_id, version);
+                    wrapper
+                        .borrow_mut()
+                        .proposal_owner
+                        .insert(proposal_id, *owner);
+                    wrapper
+                        .borrow_mut()
+                        .proposal_state
+                        .insert(proposal_id, state);
+                })
+            }
+
+            fn append_used_currency(currency_id: CurrencyIdOf<Test>) {
+                PROPOSALS_WRAPPER.with(|wrapper| -> () {
+                    wrapper
+                        .borrow_mut()
+                        .used_currency_id
+                        .insert(currency_id, ());
+                })
+            }
+
+            fn max_proposal_id() -> ProposalIdOf<Test> {
+                PROPOSALS_WRAPPER
+                    .with(|wrapper| -> ProposalIdOf<Test> { wrapper.borrow().next_proposal_id })
+            }
+
+            fn proposal_automatic_expiration_time() -> MomentOf<Test> {
+                0
+            }
+
+            fn get_proposal_state(
+                proposal_id: ProposalIdOf<Test>,
+            ) -> Result<xpmrl_traits::ProposalStatus, sp_runtime::DispatchError> {
+                PROPOSALS_WRAPPER.with(
+                    |wrapper| -> Result<xpmrl_traits::ProposalStatus, sp_runtime::DispatchError> {
+                        match wrapper.borrow().proposal_state.get(&proposal_id) {
+                            Some(v) => Ok(*v),
+                            None => Err($pallet::Error::<Test>::ProposalIdNotExist.into()),
+                        }
+                    },
+                )
+            }
+
+            fn set_proposal_state(
+                proposal_id: ProposalIdOf<Test>,
+                new_state: xpmrl_traits::ProposalStatus,
+            ) -> Result<xpmrl_traits::ProposalStatus, sp_runtime::DispatchError> {
+                PROPOSALS_WRAPPER.with(|wrapper| {
+                    wrapper
+                        .borrow_mut()
+                        .proposal_state
+                        .insert(proposal_id, new_state);
+                });
+                <$module as xpmrl_traits::pool::LiquiditySubPool<Test>>::state_changed(
+                    proposal_id,
+                    new_state,
+                )?;
+                Ok(new_state)
+            }
+
+            fn proposal_owner(
+                proposal_id: ProposalIdOf<Test>,
+            ) -> Result<AccountId, sp_runtime::DispatchError> {
+                PROPOSALS_WRAPPER.with(|wrapper| -> Result<AccountId, sp_runtime::DispatchError> {
+                    match wrapper.borrow().proposal_owner.get(&proposal_id) {
+                        Some(v) => Ok(*v),
+                        None => Err($pallet::Error::<Test>::ProposalIdNotExist.into()),
+                    }
+                })
+            }
+
+            fn proposal_liquidate_version_id(
+                proposal_id: ProposalIdOf<Test>,
+            ) -> Result<VersionIdOf<Test>, sp_runtime::DispatchError> {
+                PROPOSALS_WRAPPER.with(
+                    |wrapper| -> Result<VersionIdOf<Test>, sp_runtime::DispatchError> {
+                        match wrapper.borrow().version_id.get(&proposal_id) {
+                            Some(v) => Ok(*v),
+                            None => Err($pallet::Error::<Test>::ProposalIdNotExist.into()),
+                        }
+                    },
+                )
+            }
+
+            fn proposal_announcement_time(
+                proposal_id: ProposalIdOf<Test>,
+            ) -> Result<MomentOf<Test>, sp_runtime::DispatchError> {
+                PROPOSALS_WRAPPER.with(
+                    |wrapper| -> Result<MomentOf<Test>, sp_runtime::DispatchError> {
+                        match wrapper.borrow().announcement_time.get(&proposal_id) {
+                            Some(v) => Ok(*v),
+                            None => Err("ProposalIdNotExist".into()),
+                        }
+                    },
+                )
+            }
+
+            fn set_proposal_resolution(
+                proposal_id: ProposalIdOf<Test>,
+                resolution: xpmrl_traits::Resolution<MomentOf<Test>>,
+            ) -> Result<(), sp_runtime::DispatchError> {
+                PROPOSALS_WRAPPER.with(|wrapper| -> Result<(), sp_runtime::DispatchError> {
+                    wrapper
+                        .borrow_mut()
+                        .resolution
+                        .insert(proposal_id, resolution);
+                    Ok(())
+                })
+            }
+
+            fn proposal_resolution(
+                proposal_id: ProposalIdOf<Test>,
+            ) -> Result<xpmrl_traits::Resolution<MomentOf<Test>>, sp_runtime::DispatchError> {
+                PROPOSALS_WRAPPER.with(
+                    |wrapper| -> Result<
+                        xpmrl_traits::Resolution<MomentOf<Test>>,
+                        sp_runtime::DispatchError,
+                    > {
+                        match wrapper.borrow().resolution.get(&proposal_id) {
+                            Some(v) => Ok(v.clone()),
+                            None => Err("ProposalIdNotExist".into()),
+                        }
+                    },
+                )
+            }
+        }
+
+        pub struct RulerWrapper;
+        impl xpmrl_traits::ruler::RulerAccounts<Test> for RulerWrapper {
+            fn get_account(
+                _module: xpmrl_traits::RulerModule,
+            ) -> Result<AccountId, sp_runtime::DispatchError> {
+                Ok(3)
+            }
+        }
+
+        type ProposalId = u32;
+        type VersionId = u32;
+        type CategoryId = u32;
+
+        impl xpmrl_traits::system::ProposalSystem<AccountId> for Test {
+            type ProposalId = ProposalId;
+            type CategoryId = CategoryId;
+            type Tokens = XPMRLTokens;
+            type Time = Timestamp;
+            type VersionId = VersionId;
+        }
+
+        /// How every proposal of the tests resolves
+        pub fn resolution(time: MomentOf<Test>) -> xpmrl_traits::Resolution<MomentOf<Test>> {
+            xpmrl_traits::Resolution {
+                source: "https://example.com/result".as_bytes().to_vec(),
+                rules: "the first option wins if it happens in time".as_bytes().to_vec(),
+                time,
+                invalid_condition: "the event is cancelled".as_bytes().to_vec(),
+            }
+        }
+
+        /// Creates a proposal closing `step` from now with `new_proposal`, which is given the
+        /// close time, and returns the id of the proposal
+        pub fn create_proposal_with<F>(step: MomentOf<Test>, new_proposal: F) -> ProposalIdOf<Test>
+        where
+            F: FnOnce(MomentOf<Test>) -> frame_support::dispatch::DispatchResultWithPostInfo,
+        {
+            let now = <Timestamp as frame_support::traits::Time>::now();
+            frame_support::assert_ok!(new_proposal(now + step));
+            <ProposalsWrapper as xpmrl_traits::pool::LiquidityPool<Test>>::max_proposal_id() - 1
+        }
+
+        pub fn set_state(proposal_id: ProposalIdOf<Test>, state: xpmrl_traits::ProposalStatus) {
+            frame_support::assert_ok!(
+                <ProposalsWrapper as xpmrl_traits::pool::LiquidityPool<Test>>::set_proposal_state(
+                    proposal_id,
+                    state
+                )
+            );
+        }
+    };
+}
@@ -26,6 +26,11 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+
+use codec::Encode;
 use frame_support::{
     dispatch::DispatchError,
     ensure,
@@ -34,7 +39,13 @@ use frame_support::{
 };
 use sp_runtime::traits::{AccountIdConversion, CheckedAdd, CheckedSub, One, Zero};
 use sp_std::vec::Vec;
-use xpmrl_traits::tokens::Tokens;
+use xpmrl_traits::{
+    couple::INVALID_RESULT_ID,
+    tokens::{BeforeTransfer, Tokens},
+};
+use xpmrl_utils::with_transaction_result;
+
+pub use weights::WeightInfo;
 
 #[cfg(feature = "std")]
 use frame_support::traits::GenesisBuild;
@@ -50,21 +61,46 @@ pub mod pallet {
     use frame_system::pallet_prelude::*;
     use sp_runtime::{traits::*, ModuleId};
     use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, vec::Vec};
+    use xpmrl_traits::tokens::BeforeTransfer;
     use xpmrl_utils::with_transaction_result;
 
+    use crate::weights::WeightInfo;
+
     pub(crate) type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
     /// Basic attributes of the tokens
     #[derive(PartialEq, Eq, Clone, RuntimeDebug, Encode, Decode, Default)]
-    pub struct PRC20 <T: Default>{
+    pub struct PRC20 <T: Default, AccountId>{
         pub name: Vec<u8>,
         pub symbol: Vec<u8>,
         pub decimals: u8,
         pub total_supply: T,
+        /// Besides root, the account allowed to manage the asset
+        pub owner: Option<AccountId>,
+        /// Whether balances of the asset can leave any account
+        pub is_frozen: bool,
+    }
+
+    /// An off-chain signed approval, submitted on behalf of `owner` by any relayer
+    #[derive(PartialEq, Eq, Clone, RuntimeDebug, Encode, Decode)]
+    pub struct Permit<AccountId, CurrencyId, Balance, Index, BlockNumber> {
+        pub owner: AccountId,
+        pub spender: AccountId,
+        pub currency_id: CurrencyId,
+        pub amount: Balance,
+        pub nonce: Index,
+        pub deadline: BlockNumber,
     }
 
 
     pub(crate) type CurrencyIdOf<T> = <T as Config>::CurrencyId;
+    pub(crate) type PermitOf<T> = Permit<
+        <T as frame_system::Config>::AccountId,
+        CurrencyIdOf<T>,
+        BalanceOf<T>,
+        <T as frame_system::Config>::Index,
+        <T as frame_system::Config>::BlockNumber,
+    >;
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -85,6 +121,19 @@ pub mod pallet {
         /// Similar to the contract address of ETH, here is used to manage treasury funds
         #[pallet::constant]
         type ModuleId: Get<ModuleId>;
+
+        /// Called before tokens other than the native currency move from one account to another,
+        /// or are minted or burned
+        type BeforeTransfer: BeforeTransfer<Self::AccountId, Self::CurrencyId, BalanceOf<Self>>;
+
+        /// Signature the owner signs a permit with
+        type PermitSignature: Parameter + Verify<Signer = Self::PermitSigner>;
+
+        /// Public key that identifies the owner of a permit
+        type PermitSigner: IdentifyAccount<AccountId = Self::AccountId>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
@@ -136,8 +185,13 @@ pub mod pallet {
     /// store the basic attributes of all tokens.
     #[pallet::storage ]
     #[pallet::getter(fn currencies)]
-    pub type Currencies<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::CurrencyId, PRC20<BalanceOf<T>>, OptionQuery>;
+    pub type Currencies<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::CurrencyId,
+        PRC20<BalanceOf<T>, T::AccountId>,
+        OptionQuery,
+    >;
 
     /// Total amount of tokens
     // #[pallet::storage]
@@ -184,6 +238,26 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Accounts whose balance of an asset is frozen by the asset owner, keyed by the asset
+    /// first so that they are removed with it in `destroy_asset`
+    #[pallet::storage]
+    #[pallet::getter(fn frozen_account)]
+    pub type FrozenAccount<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::CurrencyId,
+        Blake2_128Concat,
+        T::AccountId,
+        bool,
+        ValueQuery,
+    >;
+
+    /// The nonce the next permit of the account must carry
+    #[pallet::storage]
+    #[pallet::getter(fn permit_nonce)]
+    pub type PermitNonce<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::Index, ValueQuery>;
+
     #[pallet::event]
     #[pallet::metadata(T::AccountId = "AccountId")]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -201,6 +275,13 @@ pub mod pallet {
             BalanceOf<T>,
         ),
         Approval(T::CurrencyId, T::AccountId, T::AccountId, BalanceOf<T>),
+        MetadataSet(T::CurrencyId),
+        OwnerChanged(T::CurrencyId, Option<T::AccountId>),
+        AssetFrozen(T::CurrencyId),
+        AssetThawed(T::CurrencyId),
+        AccountFrozen(T::CurrencyId, T::AccountId),
+        AccountThawed(T::CurrencyId, T::AccountId),
+        AssetDestroyed(T::CurrencyId),
     }
 
     #[pallet::error]
@@ -213,6 +294,12 @@ pub mod pallet {
         BurnFromSelf,
         ApproveSelf,
         OriginNotAllowed,
+        PermitExpired,
+        InvalidPermitNonce,
+        InvalidPermitSignature,
+        AssetIsFrozen,
+        AccountIsFrozen,
+        AssetNotEmpty,
     }
 
     #[pallet::hooks]
@@ -223,7 +310,7 @@ pub mod pallet {
         /// create new tokens
         ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(2, 2))]
+        #[pallet::weight(T::WeightInfo::new_asset())]
         pub fn new_asset(
             origin: OriginFor<T>,
             name: Vec<u8>,
@@ -239,7 +326,7 @@ pub mod pallet {
         /// mint a tokens
         ///
         /// The dispatch origin for this call is `root`.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(2, 1))]
+        #[pallet::weight(T::WeightInfo::mint())]
         pub fn mint(
             origin: OriginFor<T>,
             currency_id: T::CurrencyId,
@@ -256,7 +343,7 @@ pub mod pallet {
         /// burn tokens
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(2, 1))]
+        #[pallet::weight(T::WeightInfo::burn())]
         pub fn burn(
             origin: OriginFor<T>,
             currency_id: T::CurrencyId,
@@ -272,7 +359,7 @@ pub mod pallet {
         /// burn tokens
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(6, 2))]
+        #[pallet::weight(T::WeightInfo::burn_from())]
         pub fn burn_from(
             origin: OriginFor<T>,
             currency_id: T::CurrencyId,
@@ -281,19 +368,9 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
             ensure!(who != from, Error::<T>::BurnFromSelf);
-            let alloweds = Allowance::<T>::try_get(&from, currency_id)
-                .map_err(|_| Error::<T>::OriginNotAllowed)?;
-            let allow = *(alloweds.get(&who).ok_or(Error::<T>::OriginNotAllowed)?);
-            ensure!(allow >= number, Error::<T>::OriginNotAllowed);
             let actual_number = with_transaction_result(|| {
-                let actual_number = Self::inner_burn_from(currency_id, &from, number)?;
-                Self::inner_approve(
-                    currency_id,
-                    &from,
-                    &who,
-                    allow.checked_sub(&number).unwrap_or_else(Zero::zero),
-                )?;
-                Ok(actual_number)
+                Self::inner_spend_allowance(currency_id, &from, &who, number)?;
+                Self::inner_burn_from(currency_id, &from, number)
             })?;
             Self::deposit_event(Event::BurnFrom(currency_id, who, from, actual_number));
             Ok(().into())
@@ -302,7 +379,7 @@ pub mod pallet {
         /// transfer tokens
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(4, 2))]
+        #[pallet::weight(T::WeightInfo::transfer())]
         pub fn transfer(
             origin: OriginFor<T>,
             currency_id: T::CurrencyId,
@@ -321,7 +398,7 @@ pub mod pallet {
         /// transfer tokens
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(7, 3))]
+        #[pallet::weight(T::WeightInfo::transfer_from())]
         pub fn transfer_from(
             origin: OriginFor<T>,
             currency_id: T::CurrencyId,
@@ -332,19 +409,9 @@ pub mod pallet {
             let who = ensure_signed(origin)?;
             ensure!(from != to, Error::<T>::TransferFromSelf);
             ensure!(who != from, Error::<T>::TransferFromSelf);
-            let alloweds = Allowance::<T>::try_get(&from, currency_id)
-                .map_err(|_| Error::<T>::OriginNotAllowed)?;
-            let allow = *(alloweds.get(&who).ok_or(Error::<T>::OriginNotAllowed)?);
-            ensure!(allow >= number, Error::<T>::OriginNotAllowed);
             let actual_number = with_transaction_result(|| {
-                let actual_number = Self::inner_transfer_from(currency_id, &from, &to, number)?;
-                Self::inner_approve(
-                    currency_id,
-                    &from,
-                    &who,
-                    allow.checked_sub(&number).unwrap_or_else(Zero::zero),
-                )?;
-                Ok(actual_number)
+                Self::inner_spend_allowance(currency_id, &from, &who, number)?;
+                Self::inner_transfer_from(currency_id, &from, &to, number)
             })?;
             Self::deposit_event(Event::TransferFrom(
                 currency_id,
@@ -359,7 +426,7 @@ pub mod pallet {
         /// authorize tokens
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(2, 1))]
+        #[pallet::weight(T::WeightInfo::approve())]
         pub fn approve(
             origin: OriginFor<T>,
             currency_id: T::CurrencyId,
@@ -374,7 +441,7 @@ pub mod pallet {
             Ok(().into())
         }
 
-        #[pallet::weight(1_000 + T::DbWeight::get().reads_writes(2, 1))]
+        #[pallet::weight(T::WeightInfo::add_approve())]
         pub fn add_approve(
             origin: OriginFor<T>,
             currency_id: T::CurrencyId,
@@ -388,6 +455,156 @@ pub mod pallet {
             Self::deposit_event(Event::Approval(currency_id, who, spender, acutal_number));
             Ok(().into())
         }
+
+        /// authorize tokens with an off-chain signed permit of the owner
+        ///
+        /// The owner signs the SCALE encoding of `(b"xpmrl/permit", genesis_hash, permit)`.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the relayer.
+        #[pallet::weight(T::WeightInfo::permit())]
+        pub fn permit(
+            origin: OriginFor<T>,
+            permit: PermitOf<T>,
+            signature: T::PermitSignature,
+        ) -> DispatchResultWithPostInfo {
+            let _ = ensure_signed(origin)?;
+            ensure!(
+                <frame_system::Module<T>>::block_number() <= permit.deadline,
+                Error::<T>::PermitExpired
+            );
+            ensure!(
+                PermitNonce::<T>::get(&permit.owner) == permit.nonce,
+                Error::<T>::InvalidPermitNonce
+            );
+            ensure!(
+                signature.verify(&Self::permit_payload(&permit)[..], &permit.owner),
+                Error::<T>::InvalidPermitSignature
+            );
+            let acutal_number = with_transaction_result(|| {
+                PermitNonce::<T>::mutate(&permit.owner, |nonce| {
+                    *nonce = nonce.saturating_add(One::one())
+                });
+                Self::inner_approve(
+                    permit.currency_id,
+                    &permit.owner,
+                    &permit.spender,
+                    permit.amount,
+                )
+            })?;
+            Self::deposit_event(Event::Approval(
+                permit.currency_id,
+                permit.owner,
+                permit.spender,
+                acutal_number,
+            ));
+            Ok(().into())
+        }
+
+        /// change the name and symbol of an asset
+        ///
+        /// The dispatch origin for this call is `root` or `Signed` by the asset owner.
+        #[pallet::weight(T::WeightInfo::set_metadata())]
+        pub fn set_metadata(
+            origin: OriginFor<T>,
+            currency_id: T::CurrencyId,
+            name: Vec<u8>,
+            symbol: Vec<u8>,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_asset_owner(origin, currency_id)?;
+            Self::mutate_asset(currency_id, |asset| {
+                asset.name = name;
+                asset.symbol = symbol;
+            })?;
+            Self::deposit_event(Event::MetadataSet(currency_id));
+            Ok(().into())
+        }
+
+        /// hand the management of an asset over to another account, or to root only
+        ///
+        /// The dispatch origin for this call is `root` or `Signed` by the asset owner.
+        #[pallet::weight(T::WeightInfo::set_owner())]
+        pub fn set_owner(
+            origin: OriginFor<T>,
+            currency_id: T::CurrencyId,
+            owner: Option<T::AccountId>,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_asset_owner(origin, currency_id)?;
+            Self::mutate_asset(currency_id, |asset| asset.owner = owner.clone())?;
+            Self::deposit_event(Event::OwnerChanged(currency_id, owner));
+            Ok(().into())
+        }
+
+        /// stop balances of an asset from leaving any account
+        ///
+        /// The dispatch origin for this call is `root` or `Signed` by the asset owner.
+        #[pallet::weight(T::WeightInfo::freeze_asset())]
+        pub fn freeze_asset(
+            origin: OriginFor<T>,
+            currency_id: T::CurrencyId,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_asset_owner(origin, currency_id)?;
+            Self::mutate_asset(currency_id, |asset| asset.is_frozen = true)?;
+            Self::deposit_event(Event::AssetFrozen(currency_id));
+            Ok(().into())
+        }
+
+        /// undo `freeze_asset`
+        ///
+        /// The dispatch origin for this call is `root` or `Signed` by the asset owner.
+        #[pallet::weight(T::WeightInfo::thaw_asset())]
+        pub fn thaw_asset(
+            origin: OriginFor<T>,
+            currency_id: T::CurrencyId,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_asset_owner(origin, currency_id)?;
+            Self::mutate_asset(currency_id, |asset| asset.is_frozen = false)?;
+            Self::deposit_event(Event::AssetThawed(currency_id));
+            Ok(().into())
+        }
+
+        /// stop the balance of an asset from leaving `who`
+        ///
+        /// The dispatch origin for this call is `root` or `Signed` by the asset owner.
+        #[pallet::weight(T::WeightInfo::freeze_account())]
+        pub fn freeze_account(
+            origin: OriginFor<T>,
+            currency_id: T::CurrencyId,
+            who: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_asset_owner(origin, currency_id)?;
+            FrozenAccount::<T>::insert(currency_id, &who, true);
+            Self::deposit_event(Event::AccountFrozen(currency_id, who));
+            Ok(().into())
+        }
+
+        /// undo `freeze_account`
+        ///
+        /// The dispatch origin for this call is `root` or `Signed` by the asset owner.
+        #[pallet::weight(T::WeightInfo::thaw_account())]
+        pub fn thaw_account(
+            origin: OriginFor<T>,
+            currency_id: T::CurrencyId,
+            who: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_asset_owner(origin, currency_id)?;
+            FrozenAccount::<T>::remove(currency_id, &who);
+            Self::deposit_event(Event::AccountThawed(currency_id, who));
+            Ok(().into())
+        }
+
+        /// remove an asset whose total supply is zero
+        ///
+        /// The dispatch origin for this call is `root` or `Signed` by the asset owner.
+        #[pallet::weight(T::WeightInfo::destroy_asset())]
+        pub fn destroy_asset(
+            origin: OriginFor<T>,
+            currency_id: T::CurrencyId,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_asset_owner(origin, currency_id)?;
+            Self::inner_destroy_asset(currency_id)?;
+            Self::deposit_event(Event::AssetDestroyed(currency_id));
+            Ok(().into())
+        }
     }
 }
 
@@ -410,7 +627,8 @@ impl<T: Config> Pallet<T> {
     fn get_next_currency_id() -> Result<T::CurrencyId, DispatchError> {
         CurrentCurrencyId::<T>::try_mutate(|value| -> Result<T::CurrencyId, DispatchError> {
             let mut currency_id = value.unwrap_or_else(Zero::zero);
-            if currency_id == T::NativeCurrencyId::get() {
+            if currency_id == T::NativeCurrencyId::get() || currency_id == INVALID_RESULT_ID.into()
+            {
                 currency_id = currency_id
                     .checked_add(&One::one())
                     .ok_or(Error::<T>::CurrencyIdOverflow)?;
@@ -434,6 +652,47 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Root may manage every asset, a signed origin only the assets it owns
+    fn ensure_asset_owner(
+        origin: T::Origin,
+        currency_id: T::CurrencyId,
+    ) -> Result<(), DispatchError> {
+        let asset = Currencies::<T>::get(currency_id).ok_or(Error::<T>::CurrencyIdNotExist)?;
+        if frame_system::ensure_root(origin.clone()).is_ok() {
+            return Ok(());
+        }
+        let who = frame_system::ensure_signed(origin)?;
+        ensure!(asset.owner == Some(who), Error::<T>::OriginNotAllowed);
+        Ok(())
+    }
+
+    /// Balances can only leave `who` if neither the asset nor the account is frozen
+    fn ensure_not_frozen(
+        currency_id: T::CurrencyId,
+        who: &T::AccountId,
+    ) -> Result<(), DispatchError> {
+        if currency_id != T::NativeCurrencyId::get() {
+            let asset = Currencies::<T>::get(currency_id).ok_or(Error::<T>::CurrencyIdNotExist)?;
+            ensure!(!asset.is_frozen, Error::<T>::AssetIsFrozen);
+            ensure!(
+                !FrozenAccount::<T>::get(currency_id, who),
+                Error::<T>::AccountIsFrozen
+            );
+        }
+        Ok(())
+    }
+
+    fn mutate_asset<F>(currency_id: T::CurrencyId, f: F) -> Result<(), DispatchError>
+    where
+        F: FnOnce(&mut PRC20<BalanceOf<T>, T::AccountId>),
+    {
+        Currencies::<T>::try_mutate(currency_id, |asset| -> Result<(), DispatchError> {
+            let asset = asset.as_mut().ok_or(Error::<T>::CurrencyIdNotExist)?;
+            f(asset);
+            Ok(())
+        })
+    }
+
     fn inner_decimals(currency_id: T::CurrencyId) -> Result<u8, DispatchError> {
         let xrc = Currencies::<T>::get(currency_id).ok_or(Error::<T>::CurrencyIdNotExist)?;
         Ok(xrc.decimals)
@@ -450,11 +709,36 @@ impl<T: Config> Pallet<T> {
             symbol,
             decimals,
             total_supply: Zero::zero(),
+            owner: None,
+            is_frozen: false,
         };
         Currencies::<T>::insert(currency_id, asset);
         Ok(currency_id)
     }
 
+    /// Remove the asset and its frozen accounts
+    ///
+    /// The allowances of the asset are keyed by their owner first so they are left in place,
+    /// but as currency ids are never reused and `approve` needs the asset to exist, they can no
+    /// longer be spent or raised.
+    fn inner_destroy_asset(currency_id: T::CurrencyId) -> Result<(), DispatchError> {
+        let asset = Currencies::<T>::get(currency_id).ok_or(Error::<T>::CurrencyIdNotExist)?;
+        ensure!(asset.total_supply.is_zero(), Error::<T>::AssetNotEmpty);
+        Currencies::<T>::remove(currency_id);
+        FrozenAccount::<T>::remove_prefix(currency_id);
+        Ok(())
+    }
+
+    fn inner_total_supply(currency_id: T::CurrencyId) -> BalanceOf<T> {
+        if currency_id == T::NativeCurrencyId::get() {
+            T::Currency::total_issuance()
+        } else {
+            Currencies::<T>::get(currency_id)
+                .map(|asset| asset.total_supply)
+                .unwrap_or_else(Zero::zero)
+        }
+    }
+
     fn inner_mint_to(
         currency_id: T::CurrencyId,
         to: &T::AccountId,
@@ -469,6 +753,7 @@ impl<T: Config> Pallet<T> {
             T::Currency::make_free_balance_be(to, new_balance);
             Ok(number)
         } else {
+            T::BeforeTransfer::before_mint_or_burn(currency_id, to, number)?;
             let actual_number = FreeBalanceOf::<T>::try_mutate(
                 &to,
                 currency_id,
@@ -506,12 +791,14 @@ impl<T: Config> Pallet<T> {
         number: BalanceOf<T>,
     ) -> Result<BalanceOf<T>, DispatchError> {
         Self::ensure_currency_id(currency_id)?;
+        Self::ensure_not_frozen(currency_id, from)?;
         if currency_id == T::NativeCurrencyId::get() {
             let old_balance = T::Currency::free_balance(from);
             let new_balance = old_balance.checked_sub(&number).unwrap_or_else(Zero::zero);
             T::Currency::make_free_balance_be(from, new_balance);
             Ok(number)
         } else {
+            T::BeforeTransfer::before_mint_or_burn(currency_id, from, number)?;
             let actual_number = FreeBalanceOf::<T>::try_mutate(
                 &from,
                 currency_id,
@@ -548,6 +835,7 @@ impl<T: Config> Pallet<T> {
         number: BalanceOf<T>,
     ) -> Result<BalanceOf<T>, DispatchError> {
         Self::ensure_currency_id(currency_id)?;
+        Self::ensure_not_frozen(currency_id, from)?;
         if currency_id == T::NativeCurrencyId::get() {
             ensure!(
                 T::Currency::free_balance(from) >= number,
@@ -560,6 +848,7 @@ impl<T: Config> Pallet<T> {
                 FreeBalanceOf::<T>::get(&from, currency_id).unwrap_or_else(Zero::zero) >= number,
                 Error::<T>::InsufficientBalance
             );
+            T::BeforeTransfer::before_transfer(currency_id, from, to, number)?;
             let actual_number = FreeBalanceOf::<T>::try_mutate(
                 &from,
                 currency_id,
@@ -606,6 +895,13 @@ impl<T: Config> Pallet<T> {
         )
     }
 
+    /// The message the owner signs for a permit, the permit is bound to this pallet and to the
+    /// chain by its genesis hash so that the signature is not valid anywhere else
+    pub(crate) fn permit_payload(permit: &PermitOf<T>) -> Vec<u8> {
+        let genesis_hash = <frame_system::Module<T>>::block_hash(T::BlockNumber::zero());
+        (b"xpmrl/permit", genesis_hash, permit).encode()
+    }
+
     fn inner_add_approve(
         currency_id: T::CurrencyId,
         owner: &T::AccountId,
@@ -633,11 +929,31 @@ impl<T: Config> Pallet<T> {
         )
     }
 
+    /// Take `number` out of the allowance `owner` granted to `spender`
+    fn inner_spend_allowance(
+        currency_id: T::CurrencyId,
+        owner: &T::AccountId,
+        spender: &T::AccountId,
+        number: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let alloweds = Allowance::<T>::try_get(owner, currency_id)
+            .map_err(|_| Error::<T>::OriginNotAllowed)?;
+        let allow = *(alloweds.get(spender).ok_or(Error::<T>::OriginNotAllowed)?);
+        ensure!(allow >= number, Error::<T>::OriginNotAllowed);
+        Self::inner_approve(
+            currency_id,
+            owner,
+            spender,
+            allow.checked_sub(&number).unwrap_or_else(Zero::zero),
+        )
+    }
+
     fn inner_reserve(
         currency_id: T::CurrencyId,
         from: &T::AccountId,
         number: BalanceOf<T>,
     ) -> Result<BalanceOf<T>, DispatchError> {
+        Self::ensure_not_frozen(currency_id, from)?;
         if currency_id == T::NativeCurrencyId::get() {
             ensure!(
                 T::Currency::free_balance(from) >= number,
@@ -684,6 +1000,7 @@ impl<T: Config> Pallet<T> {
             );
             Ok(actual_number)
         } else {
+            T::BeforeTransfer::before_mint_or_burn(currency_id, from, number)?;
             let actual_number = ReserveOf::<T>::try_mutate(
                 &from,
                 currency_id,
@@ -773,10 +1090,18 @@ impl<T: Config> Tokens<T::AccountId> for Pallet<T> {
         Self::inner_new_asset(name, symbol, decimals)
     }
 
+    fn destroy_asset(currency_id: Self::CurrencyId) -> Result<(), DispatchError> {
+        Self::inner_destroy_asset(currency_id)
+    }
+
     fn decimals(currency_id: Self::CurrencyId) -> Result<u8, DispatchError> {
         Self::inner_decimals(currency_id)
     }
 
+    fn total_supply(currency_id: Self::CurrencyId) -> Self::Balance {
+        Self::inner_total_supply(currency_id)
+    }
+
     fn balance(currency_id: Self::CurrencyId, account: &T::AccountId) -> Self::Balance {
         Self::inner_free_balance_of(currency_id, account)
     }
@@ -867,4 +1192,41 @@ impl<T: Config> Tokens<T::AccountId> for Pallet<T> {
         let module_account = Self::module_account();
         Self::inner_transfer_from(currency_id, &module_account, to, value)
     }
+
+    fn approve(
+        currency_id: Self::CurrencyId,
+        owner: &T::AccountId,
+        spender: &T::AccountId,
+        number: Self::Balance,
+    ) -> Result<Self::Balance, DispatchError> {
+        Self::inner_approve(currency_id, owner, spender, number)
+    }
+
+    fn transfer_from_allowance(
+        currency_id: Self::CurrencyId,
+        spender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        number: Self::Balance,
+    ) -> Result<Self::Balance, DispatchError> {
+        ensure!(from != to, Error::<T>::TransferFromSelf);
+        ensure!(spender != from, Error::<T>::TransferFromSelf);
+        with_transaction_result(|| {
+            Self::inner_spend_allowance(currency_id, from, spender, number)?;
+            Self::inner_transfer_from(currency_id, from, to, number)
+        })
+    }
+
+    fn reserve_from_allowance(
+        currency_id: Self::CurrencyId,
+        spender: &T::AccountId,
+        who: &T::AccountId,
+        value: Self::Balance,
+    ) -> Result<Self::Balance, DispatchError> {
+        ensure!(spender != who, Error::<T>::TransferFromSelf);
+        with_transaction_result(|| {
+            Self::inner_spend_allowance(currency_id, who, spender, value)?;
+            Self::inner_reserve(currency_id, who, value)
+        })
+    }
 }
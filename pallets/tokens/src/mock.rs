@@ -5,7 +5,7 @@ use frame_support::{parameter_types};
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
+	testing::{Header, TestSignature, UintAuthorityId},
 	traits::{BlakeTwo256, IdentityLookup},
 	ModuleId,
 };
@@ -82,6 +82,10 @@ impl tokens::Config for Test {
 	type Currency = PalletBalances;
 	type NativeCurrencyId = NativeCurrencyId;
 	type ModuleId = TokensModuleId;
+	type BeforeTransfer = ();
+	type PermitSignature = TestSignature;
+	type PermitSigner = UintAuthorityId;
+	type WeightInfo = ();
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -1,6 +1,9 @@
-use crate::pallet::PRC20;
+use crate::pallet::{Permit, PRC20};
 use crate::{mock::*, Error};
+use codec::Encode;
 use frame_support::{assert_noop, assert_ok};
+use sp_runtime::testing::TestSignature;
+use xpmrl_traits::tokens::Tokens;
 
 #[test]
 fn test_new_asset() {
@@ -14,6 +17,8 @@ fn test_new_asset() {
             symbol: "USD".as_bytes().to_vec(),
             decimals: 6,
             total_supply: 0,
+            owner: None,
+            is_frozen: false,
         };
 
         // new a asset
@@ -46,11 +51,13 @@ fn test_mint() {
         assert_ok!(TokensModule::mint(Origin::root(), 0, 1, 100));
         assert_eq!(PalletBalances::free_balance(1), 200);
 
-        let asset = PRC20 {
+        let asset: PRC20<u128, u64> = PRC20 {
             name: "Tether USD".as_bytes().to_vec(),
             symbol: "USD".as_bytes().to_vec(),
             decimals: 6,
             total_supply: 0,
+            owner: None,
+            is_frozen: false,
         };
         assert_ok!(TokensModule::new_asset(
             Origin::root(),
@@ -84,11 +91,13 @@ fn test_burn() {
             Error::<Test>::CurrencyIdNotExist
         );
         assert_ok!(TokensModule::burn(Origin::signed(1), 0, 100));
-        let asset = PRC20 {
+        let asset: PRC20<u128, u64> = PRC20 {
             name: "Tether USD".as_bytes().to_vec(),
             symbol: "USD".as_bytes().to_vec(),
             decimals: 6,
             total_supply: 0,
+            owner: None,
+            is_frozen: false,
         };
         assert_ok!(TokensModule::new_asset(
             Origin::root(),
@@ -122,11 +131,13 @@ fn test_transfer() {
         assert_ok!(TokensModule::transfer(Origin::signed(3), 0, 1, 50));
         assert_eq!(TokensModule::inner_free_balance_of(0, &1), 100);
 
-        let asset = PRC20 {
+        let asset: PRC20<u128, u64> = PRC20 {
             name: "Tether USD".as_bytes().to_vec(),
             symbol: "USD".as_bytes().to_vec(),
             decimals: 6,
             total_supply: 0,
+            owner: None,
+            is_frozen: false,
         };
         assert_ok!(TokensModule::new_asset(
             Origin::root(),
@@ -158,11 +169,13 @@ fn test_transfer() {
 #[test]
 fn test_approve() {
     new_test_ext().execute_with(|| {
-        let asset = PRC20 {
+        let asset: PRC20<u128, u64> = PRC20 {
             name: "Tether USD".as_bytes().to_vec(),
             symbol: "USD".as_bytes().to_vec(),
             decimals: 6,
             total_supply: 0,
+            owner: None,
+            is_frozen: false,
         };
         assert_ok!(TokensModule::new_asset(
             Origin::root(),
@@ -191,11 +204,13 @@ fn test_approve() {
 #[test]
 fn test_burn_from() {
     new_test_ext().execute_with(|| {
-        let asset = PRC20 {
+        let asset: PRC20<u128, u64> = PRC20 {
             name: "Tether USD".as_bytes().to_vec(),
             symbol: "USD".as_bytes().to_vec(),
             decimals: 6,
             total_supply: 0,
+            owner: None,
+            is_frozen: false,
         };
         assert_ok!(TokensModule::new_asset(
             Origin::root(),
@@ -227,11 +242,13 @@ fn test_burn_from() {
 #[test]
 fn test_transfer_from() {
     new_test_ext().execute_with(|| {
-        let asset = PRC20 {
+        let asset: PRC20<u128, u64> = PRC20 {
             name: "Tether USD".as_bytes().to_vec(),
             symbol: "USD".as_bytes().to_vec(),
             decimals: 6,
             total_supply:  0,
+            owner: None,
+            is_frozen: false,
         };
         assert_ok!(TokensModule::new_asset(
             Origin::root(),
@@ -262,3 +279,287 @@ fn test_transfer_from() {
             .any(|record| record.event == transfer_event));
     });
 }
+
+#[test]
+fn test_transfer_from_allowance() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TokensModule::new_asset(
+            Origin::root(),
+            "Tether USD".as_bytes().to_vec(),
+            "USD".as_bytes().to_vec(),
+            6
+        ));
+        assert_ok!(TokensModule::mint(Origin::root(), 1, 1, 200));
+        assert_noop!(
+            <TokensModule as Tokens<u64>>::transfer_from_allowance(1, &2, &1, &3, 100),
+            Error::<Test>::OriginNotAllowed
+        );
+        assert_ok!(TokensModule::approve(Origin::signed(1), 1, 2, 100));
+        assert_noop!(
+            <TokensModule as Tokens<u64>>::transfer_from_allowance(1, &1, &1, &3, 100),
+            Error::<Test>::TransferFromSelf
+        );
+        assert_noop!(
+            <TokensModule as Tokens<u64>>::transfer_from_allowance(1, &2, &1, &3, 101),
+            Error::<Test>::OriginNotAllowed
+        );
+        assert_ok!(
+            <TokensModule as Tokens<u64>>::transfer_from_allowance(1, &2, &1, &3, 60),
+            60
+        );
+        assert_eq!(TokensModule::free_balance_of(1, 1), Some(140));
+        assert_eq!(TokensModule::free_balance_of(3, 1), Some(60));
+        assert_eq!(
+            TokensModule::allowance(1, 1).unwrap().get(&2u64),
+            Some(&40u128)
+        );
+    });
+}
+
+#[test]
+fn test_reserve_from_allowance() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TokensModule::new_asset(
+            Origin::root(),
+            "Tether USD".as_bytes().to_vec(),
+            "USD".as_bytes().to_vec(),
+            6
+        ));
+        assert_ok!(TokensModule::mint(Origin::root(), 1, 1, 200));
+        assert_ok!(TokensModule::approve(Origin::signed(1), 1, 2, 300));
+        assert_noop!(
+            <TokensModule as Tokens<u64>>::reserve_from_allowance(1, &1, &1, 100),
+            Error::<Test>::TransferFromSelf
+        );
+        assert_noop!(
+            <TokensModule as Tokens<u64>>::reserve_from_allowance(1, &2, &1, 250),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_ok!(
+            <TokensModule as Tokens<u64>>::reserve_from_allowance(1, &2, &1, 150),
+            150
+        );
+        assert_eq!(TokensModule::free_balance_of(1, 1), Some(50));
+        assert_eq!(TokensModule::reserve_of(1, 1), Some(150));
+        assert_eq!(
+            TokensModule::allowance(1, 1).unwrap().get(&2u64),
+            Some(&150u128)
+        );
+    });
+}
+
+#[test]
+fn test_permit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TokensModule::new_asset(
+            Origin::root(),
+            "Tether USD".as_bytes().to_vec(),
+            "USD".as_bytes().to_vec(),
+            6
+        ));
+        let permit = Permit {
+            owner: 1,
+            spender: 2,
+            currency_id: 1,
+            amount: 100,
+            nonce: 0,
+            deadline: 10,
+        };
+        let signature = TestSignature(1, TokensModule::permit_payload(&permit));
+
+        // the bare permit is signed, without the domain and the genesis hash
+        assert_noop!(
+            TokensModule::permit(
+                Origin::signed(3),
+                permit.clone(),
+                TestSignature(1, permit.encode())
+            ),
+            Error::<Test>::InvalidPermitSignature
+        );
+        // signed by someone other than the owner
+        assert_noop!(
+            TokensModule::permit(
+                Origin::signed(3),
+                permit.clone(),
+                TestSignature(2, TokensModule::permit_payload(&permit))
+            ),
+            Error::<Test>::InvalidPermitSignature
+        );
+        assert_noop!(
+            TokensModule::permit(
+                Origin::signed(3),
+                Permit {
+                    nonce: 1,
+                    ..permit.clone()
+                },
+                signature.clone()
+            ),
+            Error::<Test>::InvalidPermitNonce
+        );
+
+        assert_ok!(TokensModule::permit(
+            Origin::signed(3),
+            permit.clone(),
+            signature.clone()
+        ));
+        assert_eq!(
+            TokensModule::allowance(1, 1).unwrap().get(&2u64),
+            Some(&100u128)
+        );
+        assert_eq!(TokensModule::permit_nonce(1), 1);
+        let approval_event = Event::tokens(crate::Event::Approval(1, 1, 2, 100));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == approval_event));
+
+        // the same permit can not be replayed
+        assert_noop!(
+            TokensModule::permit(Origin::signed(3), permit.clone(), signature),
+            Error::<Test>::InvalidPermitNonce
+        );
+
+        let permit = Permit { nonce: 1, ..permit };
+        System::set_block_number(11);
+        assert_noop!(
+            TokensModule::permit(
+                Origin::signed(3),
+                permit.clone(),
+                TestSignature(1, TokensModule::permit_payload(&permit))
+            ),
+            Error::<Test>::PermitExpired
+        );
+    });
+}
+
+#[test]
+fn test_set_metadata_and_owner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TokensModule::new_asset(
+            Origin::root(),
+            "Tether USD".as_bytes().to_vec(),
+            "USD".as_bytes().to_vec(),
+            6
+        ));
+        assert_noop!(
+            TokensModule::set_metadata(Origin::signed(1), 1, b"A".to_vec(), b"A".to_vec()),
+            Error::<Test>::OriginNotAllowed
+        );
+        assert_noop!(
+            TokensModule::set_owner(Origin::root(), 2, Some(1)),
+            Error::<Test>::CurrencyIdNotExist
+        );
+        assert_ok!(TokensModule::set_owner(Origin::root(), 1, Some(1)));
+        let owner_event = Event::tokens(crate::Event::OwnerChanged(1, Some(1)));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == owner_event));
+
+        assert_ok!(TokensModule::set_metadata(
+            Origin::signed(1),
+            1,
+            "Tether".as_bytes().to_vec(),
+            "USDT".as_bytes().to_vec()
+        ));
+        let asset = TokensModule::currencies(1).unwrap();
+        assert_eq!(asset.name, "Tether".as_bytes().to_vec());
+        assert_eq!(asset.symbol, "USDT".as_bytes().to_vec());
+        assert_eq!(asset.decimals, 6);
+
+        // the owner can hand the asset over, after which it no longer manages it
+        assert_ok!(TokensModule::set_owner(Origin::signed(1), 1, Some(2)));
+        assert_noop!(
+            TokensModule::set_metadata(Origin::signed(1), 1, b"A".to_vec(), b"A".to_vec()),
+            Error::<Test>::OriginNotAllowed
+        );
+        assert_ok!(TokensModule::set_metadata(
+            Origin::signed(2),
+            1,
+            b"A".to_vec(),
+            b"A".to_vec()
+        ));
+    });
+}
+
+#[test]
+fn test_freeze() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TokensModule::new_asset(
+            Origin::root(),
+            "Tether USD".as_bytes().to_vec(),
+            "USD".as_bytes().to_vec(),
+            6
+        ));
+        assert_ok!(TokensModule::mint(Origin::root(), 1, 1, 200));
+        assert_ok!(TokensModule::set_owner(Origin::root(), 1, Some(3)));
+        assert_noop!(
+            TokensModule::freeze_asset(Origin::signed(1), 1),
+            Error::<Test>::OriginNotAllowed
+        );
+
+        assert_ok!(TokensModule::freeze_asset(Origin::signed(3), 1));
+        assert_noop!(
+            TokensModule::transfer(Origin::signed(1), 1, 2, 100),
+            Error::<Test>::AssetIsFrozen
+        );
+        assert_noop!(
+            TokensModule::burn(Origin::signed(1), 1, 100),
+            Error::<Test>::AssetIsFrozen
+        );
+        assert_ok!(TokensModule::thaw_asset(Origin::signed(3), 1));
+
+        assert_ok!(TokensModule::freeze_account(Origin::signed(3), 1, 1));
+        assert!(TokensModule::frozen_account(1, 1));
+        assert_noop!(
+            TokensModule::transfer(Origin::signed(1), 1, 2, 100),
+            Error::<Test>::AccountIsFrozen
+        );
+        assert_noop!(
+            <TokensModule as Tokens<u64>>::reserve(1, &1, 100),
+            Error::<Test>::AccountIsFrozen
+        );
+        // a frozen account can still receive
+        assert_ok!(TokensModule::mint(Origin::root(), 1, 2, 100));
+        assert_ok!(TokensModule::transfer(Origin::signed(2), 1, 1, 50));
+
+        assert_ok!(TokensModule::thaw_account(Origin::signed(3), 1, 1));
+        assert!(!TokensModule::frozen_account(1, 1));
+        assert_ok!(TokensModule::transfer(Origin::signed(1), 1, 2, 100));
+    });
+}
+
+#[test]
+fn test_destroy_asset() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TokensModule::new_asset(
+            Origin::root(),
+            "Tether USD".as_bytes().to_vec(),
+            "USD".as_bytes().to_vec(),
+            6
+        ));
+        assert_ok!(TokensModule::mint(Origin::root(), 1, 1, 200));
+        assert_noop!(
+            TokensModule::destroy_asset(Origin::root(), 1),
+            Error::<Test>::AssetNotEmpty
+        );
+        assert_ok!(TokensModule::burn(Origin::signed(1), 1, 200));
+        assert_eq!(<TokensModule as Tokens<u64>>::total_supply(1), 0);
+        assert_ok!(TokensModule::freeze_account(Origin::root(), 1, 2));
+
+        assert_ok!(TokensModule::destroy_asset(Origin::root(), 1));
+        assert_eq!(TokensModule::currencies(1), None);
+        assert!(!TokensModule::frozen_account(1, 2));
+        let destroy_event = Event::tokens(crate::Event::AssetDestroyed(1));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == destroy_event));
+        assert_noop!(
+            TokensModule::mint(Origin::root(), 1, 1, 200),
+            Error::<Test>::CurrencyIdNotExist
+        );
+        // the native currency is not managed by this module
+        assert_noop!(
+            TokensModule::destroy_asset(Origin::root(), 0),
+            Error::<Test>::CurrencyIdNotExist
+        );
+    });
+}
@@ -1,11 +1,17 @@
 use crate::{system::ProposalSystem, tokens::Tokens};
 use frame_support::dispatch::DispatchError;
+use sp_std::{vec, vec::Vec};
 
 type TokensOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::Tokens;
 type CurrencyIdOf<T> = <TokensOf<T> as Tokens<<T as frame_system::Config>::AccountId>>::CurrencyId;
+type BalanceOf<T> = <TokensOf<T> as Tokens<<T as frame_system::Config>::AccountId>>::Balance;
 
 type ProposalIdOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::ProposalId;
 
+/// The currency id voted for to settle a proposal as invalid, the tokens module never gives it
+/// to an asset so it can not be mistaken for an option or a liquidity token
+pub const INVALID_RESULT_ID: u32 = u32::MAX;
+
 pub trait LiquidityCouple<T>
 where
     T: ProposalSystem<T::AccountId> + frame_system::Config,
@@ -14,6 +20,34 @@ where
         proposal_id: ProposalIdOf<T>,
     ) -> Result<(CurrencyIdOf<T>, CurrencyIdOf<T>), DispatchError>;
 
+    /// All option tokens of the proposal, in the order they were created
+    ///
+    /// Two option proposals only need to implement `proposal_pair`
+    fn proposal_options(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<Vec<CurrencyIdOf<T>>, DispatchError> {
+        let (id1, id2) = Self::proposal_pair(proposal_id)?;
+        Ok(vec![id1, id2])
+    }
+
+    /// The currency id standing for the invalid result of the proposal, `INVALID_RESULT_ID`
+    /// for every proposal that can be voided and `None` for the others
+    ///
+    /// Setting it as the result voids the proposal, every option token then redeems an equal
+    /// share of the collateral
+    fn proposal_invalid_result(_proposal_id: ProposalIdOf<T>) -> Option<CurrencyIdOf<T>> {
+        None
+    }
+
+    /// Every result the proposal can be settled to, its options followed by its invalid result
+    fn proposal_results(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<Vec<CurrencyIdOf<T>>, DispatchError> {
+        let mut results = Self::proposal_options(proposal_id)?;
+        results.extend(Self::proposal_invalid_result(proposal_id));
+        Ok(results)
+    }
+
     fn set_proposal_result(
         proposal_id: ProposalIdOf<T>,
         result: CurrencyIdOf<T>,
@@ -29,4 +63,36 @@ where
     fn proposal_liquidate_currency_id(
         proposal_id: ProposalIdOf<T>,
     ) -> Result<CurrencyIdOf<T>, DispatchError>;
+
+    /// The `[min, max]` range of a scalar proposal, `None` if the proposal settles on one of
+    /// its options
+    fn proposal_scalar_range(
+        _proposal_id: ProposalIdOf<T>,
+    ) -> Option<(BalanceOf<T>, BalanceOf<T>)> {
+        None
+    }
+
+    fn set_proposal_scalar_result(
+        _proposal_id: ProposalIdOf<T>,
+        _value: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        Err(DispatchError::Other("ProposalNotScalar"))
+    }
+
+    fn get_proposal_scalar_result(
+        _proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Err(DispatchError::Other("ProposalNotScalar"))
+    }
+
+    /// Create a proposal owned by `who` in the `OriginalPrediction` state, a scalar one if
+    /// `scalar` is set, so that the benchmarks of the modules settling proposals have a market
+    /// to work on
+    #[cfg(feature = "runtime-benchmarks")]
+    fn new_benchmark_proposal(
+        _who: &T::AccountId,
+        _scalar: bool,
+    ) -> Result<ProposalIdOf<T>, DispatchError> {
+        Err(DispatchError::Other("NotSupported"))
+    }
 }
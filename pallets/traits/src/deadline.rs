@@ -0,0 +1,181 @@
+use codec::FullCodec;
+use frame_support::{
+    dispatch::DispatchError,
+    storage::{with_transaction, IterableStorageDoubleMap, StorageMap, StorageValue},
+    traits::{Get, Time},
+    weights::{RuntimeDbWeight, Weight},
+};
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, One, Saturating},
+    RuntimeDebug, TransactionOutcome,
+};
+use sp_std::{cmp, vec::Vec};
+
+/// The most buckets of the deadline queue processed in one block, the remaining ones are
+/// processed in the following blocks
+pub const MAX_DEADLINE_BUCKETS_PER_BLOCK: u32 = 64;
+
+/// The most entries of the deadline queue handled in one block, the rest of the bucket is
+/// carried to the following blocks
+pub const MAX_DEADLINES_PER_BLOCK: u32 = 32;
+
+/// A failed state change is retried at most `2^MAX_RETRY_BACKOFF_SHIFT` buckets later
+pub const MAX_RETRY_BACKOFF_SHIFT: u32 = 16;
+
+/// What one block did to the deadline queue
+#[derive(Default, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct DeadlineProgress {
+    /// The buckets taken from the queue
+    pub buckets: u32,
+    /// The entries read from those buckets
+    pub entries: u32,
+    /// The entries put back because their deadline is later in the bucket
+    pub postponed: u32,
+    /// The proposals on which `change_state` was called
+    pub changes: u32,
+}
+
+impl DeadlineProgress {
+    /// The weight of the block, `change` being the most weight of one call to `change_state`
+    pub fn weight(&self, db: RuntimeDbWeight, change: Weight) -> Weight {
+        db.reads_writes(1, 1)
+            .saturating_add(db.reads((2 * self.buckets) as Weight))
+            .saturating_add(db.reads_writes((2 * self.entries) as Weight, self.entries as Weight))
+            .saturating_add(db.writes(self.postponed as Weight))
+            .saturating_add(change.saturating_mul(self.changes as Weight))
+    }
+}
+
+/// A queue of the next time the state of each proposal can change on its own, grouped in
+/// buckets of time, so that a block only reads the proposals whose time has come
+///
+/// The module implementing it provides the storage and the rules of its proposals, the queue
+/// itself is handled by the provided functions
+pub trait DeadlineScheduler {
+    type ProposalId: FullCodec + Copy;
+    type Moment: AtLeast32BitUnsigned + FullCodec + Copy;
+    type Time: Time<Moment = Self::Moment>;
+    /// The length of time covered by one bucket, usually the block time
+    type BucketSize: Get<Self::Moment>;
+    /// The deadline each proposal is queued at
+    type Deadlines: StorageMap<Self::ProposalId, Self::Moment, Query = Option<Self::Moment>>;
+    /// The deadlines of the proposals grouped by bucket, an entry whose deadline no longer
+    /// matches `Deadlines` was rescheduled and is skipped
+    type Buckets: IterableStorageDoubleMap<
+        Self::Moment,
+        Self::ProposalId,
+        Self::Moment,
+        Query = Option<Self::Moment>,
+    >;
+    /// The first bucket that has not been processed yet
+    type NextBucket: StorageValue<Self::Moment, Query = Option<Self::Moment>>;
+    /// How many times in a row `change_state` has failed on the proposal
+    type Retries: StorageMap<Self::ProposalId, u32, Query = Option<u32>>;
+
+    /// The next time `change_state` can act on the proposal, `None` if it never will
+    fn next_deadline(proposal_id: Self::ProposalId) -> Result<Option<Self::Moment>, DispatchError>;
+
+    /// Change the state of the proposal whose deadline has been reached
+    fn change_state(proposal_id: Self::ProposalId, now: Self::Moment) -> Result<(), DispatchError>;
+
+    /// The bucket holding `deadline`, a deadline that has already passed goes to the bucket of
+    /// `now`
+    fn deadline_bucket(deadline: Self::Moment, now: Self::Moment) -> Self::Moment {
+        let size = cmp::max(Self::BucketSize::get(), One::one());
+        cmp::max(deadline, now) / size
+    }
+
+    /// Queue the proposal at its next deadline, the entry of its previous deadline is left in
+    /// the queue and skipped
+    fn schedule_deadline(proposal_id: Self::ProposalId) -> Result<(), DispatchError> {
+        match Self::next_deadline(proposal_id)? {
+            Some(deadline) => {
+                if Self::Deadlines::get(proposal_id) != Some(deadline) {
+                    let now = Self::Time::now();
+                    Self::Deadlines::insert(proposal_id, deadline);
+                    Self::Buckets::insert(
+                        Self::deadline_bucket(deadline, now),
+                        proposal_id,
+                        deadline,
+                    );
+                }
+            }
+            None => Self::Deadlines::remove(proposal_id),
+        }
+        Ok(())
+    }
+
+    /// Queue the proposal again after `change_state` failed on it, one bucket later after the
+    /// first failure and twice as late after each following one, a proposal that no longer
+    /// exists is dropped from the queue
+    fn retry_deadline(proposal_id: Self::ProposalId, now: Self::Moment) {
+        if !matches!(Self::next_deadline(proposal_id), Ok(Some(_))) {
+            Self::Retries::remove(proposal_id);
+            return;
+        }
+        let retries = Self::Retries::get(proposal_id).unwrap_or(0);
+        Self::Retries::insert(proposal_id, retries.saturating_add(1));
+        let size = cmp::max(Self::BucketSize::get(), One::one());
+        let backoff: Self::Moment = (1u32 << cmp::min(retries, MAX_RETRY_BACKOFF_SHIFT)).into();
+        let deadline = now.saturating_add(size.saturating_mul(backoff));
+        Self::Deadlines::insert(proposal_id, deadline);
+        Self::Buckets::insert(Self::deadline_bucket(deadline, now), proposal_id, deadline);
+    }
+
+    /// Change the state of the proposals whose deadline has been reached, bucket by bucket, a
+    /// failed change is rolled back and retried later
+    ///
+    /// At most `MAX_DEADLINES_PER_BLOCK` entries are handled, a bucket that is not empty
+    /// afterwards is carried to the next block
+    fn process_deadlines(now: Self::Moment) -> DeadlineProgress {
+        let current = Self::deadline_bucket(now, now);
+        let mut bucket = Self::NextBucket::get().unwrap_or(current);
+        let mut progress = DeadlineProgress::default();
+        while bucket <= current && progress.buckets < MAX_DEADLINE_BUCKETS_PER_BLOCK {
+            let remaining = MAX_DEADLINES_PER_BLOCK.saturating_sub(progress.entries);
+            let entries: Vec<_> = Self::Buckets::drain_prefix(bucket)
+                .take(remaining as usize)
+                .collect();
+            progress.buckets += 1;
+            for (proposal_id, deadline) in entries {
+                progress.entries += 1;
+                if Self::Deadlines::get(proposal_id) != Some(deadline) {
+                    continue;
+                }
+                if deadline > now {
+                    Self::Buckets::insert(bucket, proposal_id, deadline);
+                    progress.postponed += 1;
+                    continue;
+                }
+                Self::Deadlines::remove(proposal_id);
+                let result = with_transaction(|| {
+                    let res = Self::change_state(proposal_id, now);
+                    if res.is_ok() {
+                        TransactionOutcome::Commit(res)
+                    } else {
+                        TransactionOutcome::Rollback(res)
+                    }
+                });
+                match result {
+                    Ok(_) => {
+                        Self::Retries::remove(proposal_id);
+                        if let Err(e) = Self::schedule_deadline(proposal_id) {
+                            sp_runtime::print(e);
+                        }
+                    }
+                    Err(e) => {
+                        sp_runtime::print(e);
+                        Self::retry_deadline(proposal_id, now);
+                    }
+                }
+                progress.changes += 1;
+            }
+            if Self::Buckets::iter_prefix(bucket).next().is_some() {
+                break;
+            }
+            bucket = bucket.saturating_add(One::one());
+        }
+        Self::NextBucket::put(cmp::min(bucket, current));
+        progress
+    }
+}
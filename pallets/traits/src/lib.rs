@@ -10,13 +10,16 @@
 
 pub mod autonomy;
 pub mod couple;
+pub mod deadline;
 pub mod pool;
+pub mod registry;
 pub mod ruler;
 pub mod system;
 pub mod tokens;
 
 use codec::{Decode, Encode};
 use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
 
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
@@ -37,3 +40,19 @@ pub enum RulerModule {
     PlatformDividend,
     NotUsed,
 }
+
+/// The maximum length of each text of the resolution of a proposal
+pub const MAX_RESOLUTION_LENGTH: usize = 1024;
+
+/// How the question of a proposal resolves, given by its owner when the proposal is created
+#[derive(PartialEq, Eq, Clone, RuntimeDebug, Encode, Decode, Default)]
+pub struct Resolution<Moment> {
+    /// The url or the ipfs cid of the source the result is read from
+    pub source: Vec<u8>,
+    /// The rules the result is decided by
+    pub rules: Vec<u8>,
+    /// When the result is expected to be known
+    pub time: Moment,
+    /// When the proposal resolves as invalid, none of its options wins
+    pub invalid_condition: Vec<u8>,
+}
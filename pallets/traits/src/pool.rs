@@ -1,5 +1,5 @@
-use crate::{tokens::Tokens, ProposalStatus};
-use frame_support::{dispatch::DispatchError, traits::Time};
+use crate::{tokens::Tokens, ProposalStatus, Resolution};
+use frame_support::{dispatch::DispatchError, traits::Time, weights::Weight};
 
 use crate::system::ProposalSystem;
 
@@ -38,11 +38,28 @@ where
         proposal_id: ProposalIdOf<T>,
         new_state: ProposalStatus,
     ) -> Result<ProposalStatus, DispatchError>;
+    /// The most weight the engines take on top of `set_proposal_state` for one proposal
+    fn state_changed_weight() -> Weight {
+        0
+    }
 
     fn proposal_owner(proposal_id: ProposalIdOf<T>) -> Result<T::AccountId, DispatchError>;
+    fn proposal_liquidate_version_id(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<VersionIdOf<T>, DispatchError>;
     fn proposal_announcement_time(
         proposal_id: ProposalIdOf<T>,
     ) -> Result<MomentOf<T>, DispatchError>;
+
+    /// Store how the question of the proposal resolves, after checking its lengths and that the
+    /// result is not expected before the proposal closes
+    fn set_proposal_resolution(
+        proposal_id: ProposalIdOf<T>,
+        resolution: Resolution<MomentOf<T>>,
+    ) -> Result<(), DispatchError>;
+    fn proposal_resolution(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<Resolution<MomentOf<T>>, DispatchError>;
 }
 
 pub trait LiquiditySubPool<T>
@@ -50,4 +67,80 @@ where
     T: ProposalSystem<T::AccountId> + frame_system::Config,
 {
     fn finally_locked(proposal_id: ProposalIdOf<T>) -> Result<(), DispatchError>;
+
+    /// Called by the proposals module once the state of a proposal changed, so that the engine
+    /// can close what is only open in the old state
+    fn state_changed(
+        _proposal_id: ProposalIdOf<T>,
+        _new_state: ProposalStatus,
+    ) -> Result<(), DispatchError> {
+        Ok(())
+    }
+
+    /// The most weight `finally_locked` and `state_changed` take together on one proposal,
+    /// charged by the modules that change the state of proposals in their hooks
+    fn state_changed_weight() -> Weight {
+        0
+    }
+}
+
+/// Removes the storage a module keeps about a proposal once the proposal is settled
+///
+/// Implemented for tuples of modules, the budget left over by a module goes to the next one
+pub trait ProposalArchive<T>
+where
+    T: ProposalSystem<T::AccountId> + frame_system::Config,
+{
+    /// Whether everything the module holds for the proposal has been withdrawn by its owners, so
+    /// that its storage can be removed
+    fn is_drained(proposal_id: ProposalIdOf<T>) -> bool;
+
+    /// Remove at most `limit` storage entries of the proposal and return how many were removed
+    ///
+    /// Less than `limit` means that nothing is left. The entries keyed by the proposal id alone
+    /// are removed last, within the same call, and are not counted
+    fn archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32;
 }
+
+impl<T> ProposalArchive<T> for ()
+where
+    T: ProposalSystem<T::AccountId> + frame_system::Config,
+{
+    fn is_drained(_proposal_id: ProposalIdOf<T>) -> bool {
+        true
+    }
+
+    fn archive(_proposal_id: ProposalIdOf<T>, _limit: u32) -> u32 {
+        0
+    }
+}
+
+macro_rules! impl_proposal_archive {
+    ($($module:ident),+) => {
+        impl<T, $($module),+> ProposalArchive<T> for ($($module,)+)
+        where
+            T: ProposalSystem<T::AccountId> + frame_system::Config,
+            $($module: ProposalArchive<T>,)+
+        {
+            fn is_drained(proposal_id: ProposalIdOf<T>) -> bool {
+                $($module::is_drained(proposal_id))&&+
+            }
+
+            fn archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32 {
+                let mut removed: u32 = 0;
+                $(
+                    if removed < limit {
+                        removed = removed
+                            .saturating_add($module::archive(proposal_id, limit - removed));
+                    }
+                )+
+                removed
+            }
+        }
+    };
+}
+
+impl_proposal_archive!(A);
+impl_proposal_archive!(A, B);
+impl_proposal_archive!(A, B, C);
+impl_proposal_archive!(A, B, C, D);
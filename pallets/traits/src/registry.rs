@@ -0,0 +1,490 @@
+use crate::{
+    couple::LiquidityCouple,
+    pool::{LiquidityPool, LiquiditySubPool, ProposalArchive},
+    system::ProposalSystem,
+    tokens::Tokens,
+    ProposalStatus,
+};
+use frame_support::{dispatch::DispatchError, weights::Weight};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+type TokensOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::Tokens;
+type CurrencyIdOf<T> = <TokensOf<T> as Tokens<<T as frame_system::Config>::AccountId>>::CurrencyId;
+type BalanceOf<T> = <TokensOf<T> as Tokens<<T as frame_system::Config>::AccountId>>::Balance;
+
+type ProposalIdOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::ProposalId;
+type VersionIdOf<T> = <T as ProposalSystem<<T as frame_system::Config>::AccountId>>::VersionId;
+
+/// Returned when no engine is registered for the version id of a proposal
+pub const ENGINE_NOT_FOUND: DispatchError = DispatchError::Other("LiquidityEngineNotFound");
+
+/// A market engine, which creates proposals under a fixed version id and handles them until
+/// they are settled and archived
+pub trait LiquidityEngine<T>:
+    LiquiditySubPool<T> + LiquidityCouple<T> + ProposalArchive<T>
+where
+    T: ProposalSystem<T::AccountId> + frame_system::Config,
+{
+    /// The version id recorded in the proposals module for the proposals created by this engine
+    fn version_id() -> VersionIdOf<T>;
+}
+
+/// Maps a version id to the market engine that handles the proposals of that version
+///
+/// Implemented for tuples of engines, e.g. `(Couple, Categorical)`, so that a new engine can be
+/// added without touching the proposals already living under the old ones
+pub trait LiquidityRegistry<T>
+where
+    T: ProposalSystem<T::AccountId> + frame_system::Config,
+{
+    fn is_registered(version_id: VersionIdOf<T>) -> bool;
+
+    fn finally_locked(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<(), DispatchError>;
+
+    fn state_changed(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+        new_state: ProposalStatus,
+    ) -> Result<(), DispatchError>;
+
+    /// The largest `state_changed_weight` of the registered engines
+    fn state_changed_weight() -> Weight;
+
+    fn proposal_pair(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<(CurrencyIdOf<T>, CurrencyIdOf<T>), DispatchError>;
+
+    fn proposal_options(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<Vec<CurrencyIdOf<T>>, DispatchError>;
+
+    fn proposal_invalid_result(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Option<CurrencyIdOf<T>>;
+
+    fn set_proposal_result(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+        result: CurrencyIdOf<T>,
+    ) -> Result<(), DispatchError>;
+
+    fn set_proposal_result_when_end(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+        result: CurrencyIdOf<T>,
+    ) -> Result<(), DispatchError>;
+
+    fn get_proposal_result(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<CurrencyIdOf<T>, DispatchError>;
+
+    fn proposal_liquidate_currency_id(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<CurrencyIdOf<T>, DispatchError>;
+
+    fn proposal_scalar_range(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Option<(BalanceOf<T>, BalanceOf<T>)>;
+
+    fn set_proposal_scalar_result(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+        value: BalanceOf<T>,
+    ) -> Result<(), DispatchError>;
+
+    fn get_proposal_scalar_result(
+        version_id: VersionIdOf<T>,
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError>;
+
+    /// `false` when no engine is registered for the version id
+    fn is_drained(version_id: VersionIdOf<T>, proposal_id: ProposalIdOf<T>) -> bool;
+
+    fn archive(version_id: VersionIdOf<T>, proposal_id: ProposalIdOf<T>, limit: u32) -> u32;
+
+    /// Create a benchmark proposal with the first engine that supports it
+    #[cfg(feature = "runtime-benchmarks")]
+    fn new_benchmark_proposal(
+        who: &T::AccountId,
+        scalar: bool,
+    ) -> Result<ProposalIdOf<T>, DispatchError>;
+}
+
+macro_rules! impl_liquidity_registry {
+    ($($engine:ident),+) => {
+        impl<T, $($engine),+> LiquidityRegistry<T> for ($($engine,)+)
+        where
+            T: ProposalSystem<T::AccountId> + frame_system::Config,
+            $($engine: LiquidityEngine<T>,)+
+        {
+            fn is_registered(version_id: VersionIdOf<T>) -> bool {
+                $(
+                    if $engine::version_id() == version_id {
+                        return true;
+                    }
+                )+
+                false
+            }
+
+            fn finally_locked(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+            ) -> Result<(), DispatchError> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquiditySubPool<T>>::finally_locked(proposal_id);
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+
+            fn state_changed(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+                new_state: ProposalStatus,
+            ) -> Result<(), DispatchError> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquiditySubPool<T>>::state_changed(
+                            proposal_id,
+                            new_state,
+                        );
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+
+            fn state_changed_weight() -> Weight {
+                let mut weight: Weight = 0;
+                $(
+                    weight = weight.max(<$engine as LiquiditySubPool<T>>::state_changed_weight());
+                )+
+                weight
+            }
+
+            fn proposal_pair(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+            ) -> Result<(CurrencyIdOf<T>, CurrencyIdOf<T>), DispatchError> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquidityCouple<T>>::proposal_pair(proposal_id);
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+
+            fn proposal_options(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+            ) -> Result<Vec<CurrencyIdOf<T>>, DispatchError> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquidityCouple<T>>::proposal_options(proposal_id);
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+
+            fn proposal_invalid_result(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+            ) -> Option<CurrencyIdOf<T>> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquidityCouple<T>>::proposal_invalid_result(
+                            proposal_id,
+                        );
+                    }
+                )+
+                None
+            }
+
+            fn set_proposal_result(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+                result: CurrencyIdOf<T>,
+            ) -> Result<(), DispatchError> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquidityCouple<T>>::set_proposal_result(
+                            proposal_id,
+                            result,
+                        );
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+
+            fn set_proposal_result_when_end(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+                result: CurrencyIdOf<T>,
+            ) -> Result<(), DispatchError> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquidityCouple<T>>::set_proposal_result_when_end(
+                            proposal_id,
+                            result,
+                        );
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+
+            fn get_proposal_result(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+            ) -> Result<CurrencyIdOf<T>, DispatchError> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquidityCouple<T>>::get_proposal_result(proposal_id);
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+
+            fn proposal_liquidate_currency_id(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+            ) -> Result<CurrencyIdOf<T>, DispatchError> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquidityCouple<T>>::proposal_liquidate_currency_id(
+                            proposal_id,
+                        );
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+
+            fn proposal_scalar_range(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+            ) -> Option<(BalanceOf<T>, BalanceOf<T>)> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquidityCouple<T>>::proposal_scalar_range(proposal_id);
+                    }
+                )+
+                None
+            }
+
+            fn set_proposal_scalar_result(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+                value: BalanceOf<T>,
+            ) -> Result<(), DispatchError> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquidityCouple<T>>::set_proposal_scalar_result(
+                            proposal_id,
+                            value,
+                        );
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+
+            fn get_proposal_scalar_result(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+            ) -> Result<BalanceOf<T>, DispatchError> {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as LiquidityCouple<T>>::get_proposal_scalar_result(
+                            proposal_id,
+                        );
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+
+            fn is_drained(version_id: VersionIdOf<T>, proposal_id: ProposalIdOf<T>) -> bool {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as ProposalArchive<T>>::is_drained(proposal_id);
+                    }
+                )+
+                false
+            }
+
+            fn archive(
+                version_id: VersionIdOf<T>,
+                proposal_id: ProposalIdOf<T>,
+                limit: u32,
+            ) -> u32 {
+                $(
+                    if $engine::version_id() == version_id {
+                        return <$engine as ProposalArchive<T>>::archive(proposal_id, limit);
+                    }
+                )+
+                0
+            }
+
+            #[cfg(feature = "runtime-benchmarks")]
+            fn new_benchmark_proposal(
+                who: &T::AccountId,
+                scalar: bool,
+            ) -> Result<ProposalIdOf<T>, DispatchError> {
+                $(
+                    if let Ok(proposal_id) =
+                        <$engine as LiquidityCouple<T>>::new_benchmark_proposal(who, scalar)
+                    {
+                        return Ok(proposal_id);
+                    }
+                )+
+                Err(ENGINE_NOT_FOUND)
+            }
+        }
+    };
+}
+
+impl_liquidity_registry!(A);
+impl_liquidity_registry!(A, B);
+impl_liquidity_registry!(A, B, C);
+impl_liquidity_registry!(A, B, C, D);
+
+/// Forwards every call about a proposal to the engine of `Registry` it was created with, the
+/// version id being read from `Pool`
+///
+/// Modules that only know about `LiquiditySubPool`/`LiquidityCouple` can be configured with it
+pub struct LiquidityRouter<Pool, Registry>(PhantomData<(Pool, Registry)>);
+
+impl<T, Pool, Registry> LiquiditySubPool<T> for LiquidityRouter<Pool, Registry>
+where
+    T: ProposalSystem<T::AccountId> + frame_system::Config,
+    Pool: LiquidityPool<T>,
+    Registry: LiquidityRegistry<T>,
+{
+    fn finally_locked(proposal_id: ProposalIdOf<T>) -> Result<(), DispatchError> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id)?;
+        Registry::finally_locked(version_id, proposal_id)
+    }
+
+    fn state_changed(
+        proposal_id: ProposalIdOf<T>,
+        new_state: ProposalStatus,
+    ) -> Result<(), DispatchError> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id)?;
+        Registry::state_changed(version_id, proposal_id, new_state)
+    }
+
+    fn state_changed_weight() -> Weight {
+        Registry::state_changed_weight()
+    }
+}
+
+impl<T, Pool, Registry> LiquidityCouple<T> for LiquidityRouter<Pool, Registry>
+where
+    T: ProposalSystem<T::AccountId> + frame_system::Config,
+    Pool: LiquidityPool<T>,
+    Registry: LiquidityRegistry<T>,
+{
+    fn proposal_pair(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<(CurrencyIdOf<T>, CurrencyIdOf<T>), DispatchError> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id)?;
+        Registry::proposal_pair(version_id, proposal_id)
+    }
+
+    fn proposal_options(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<Vec<CurrencyIdOf<T>>, DispatchError> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id)?;
+        Registry::proposal_options(version_id, proposal_id)
+    }
+
+    fn proposal_invalid_result(proposal_id: ProposalIdOf<T>) -> Option<CurrencyIdOf<T>> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id).ok()?;
+        Registry::proposal_invalid_result(version_id, proposal_id)
+    }
+
+    fn set_proposal_result(
+        proposal_id: ProposalIdOf<T>,
+        result: CurrencyIdOf<T>,
+    ) -> Result<(), DispatchError> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id)?;
+        Registry::set_proposal_result(version_id, proposal_id, result)
+    }
+
+    fn set_proposal_result_when_end(
+        proposal_id: ProposalIdOf<T>,
+        result: CurrencyIdOf<T>,
+    ) -> Result<(), DispatchError> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id)?;
+        Registry::set_proposal_result_when_end(version_id, proposal_id, result)
+    }
+
+    fn get_proposal_result(proposal_id: ProposalIdOf<T>) -> Result<CurrencyIdOf<T>, DispatchError> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id)?;
+        Registry::get_proposal_result(version_id, proposal_id)
+    }
+
+    fn proposal_liquidate_currency_id(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<CurrencyIdOf<T>, DispatchError> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id)?;
+        Registry::proposal_liquidate_currency_id(version_id, proposal_id)
+    }
+
+    fn proposal_scalar_range(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Option<(BalanceOf<T>, BalanceOf<T>)> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id).ok()?;
+        Registry::proposal_scalar_range(version_id, proposal_id)
+    }
+
+    fn set_proposal_scalar_result(
+        proposal_id: ProposalIdOf<T>,
+        value: BalanceOf<T>,
+    ) -> Result<(), DispatchError> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id)?;
+        Registry::set_proposal_scalar_result(version_id, proposal_id, value)
+    }
+
+    fn get_proposal_scalar_result(
+        proposal_id: ProposalIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let version_id = Pool::proposal_liquidate_version_id(proposal_id)?;
+        Registry::get_proposal_scalar_result(version_id, proposal_id)
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn new_benchmark_proposal(
+        who: &T::AccountId,
+        scalar: bool,
+    ) -> Result<ProposalIdOf<T>, DispatchError> {
+        Registry::new_benchmark_proposal(who, scalar)
+    }
+}
+
+impl<T, Pool, Registry> ProposalArchive<T> for LiquidityRouter<Pool, Registry>
+where
+    T: ProposalSystem<T::AccountId> + frame_system::Config,
+    Pool: LiquidityPool<T>,
+    Registry: LiquidityRegistry<T>,
+{
+    fn is_drained(proposal_id: ProposalIdOf<T>) -> bool {
+        match Pool::proposal_liquidate_version_id(proposal_id) {
+            Ok(version_id) => Registry::is_drained(version_id, proposal_id),
+            Err(_) => false,
+        }
+    }
+
+    fn archive(proposal_id: ProposalIdOf<T>, limit: u32) -> u32 {
+        match Pool::proposal_liquidate_version_id(proposal_id) {
+            Ok(version_id) => Registry::archive(version_id, proposal_id, limit),
+            Err(_) => 0,
+        }
+    }
+}
@@ -23,6 +23,8 @@ pub trait Tokens<AccountId> {
         symbol: Vec<u8>,
         decimals: u8,
     ) -> Result<Self::CurrencyId, DispatchError>;
+    /// Remove an asset whose total supply is zero
+    fn destroy_asset(currency_id: Self::CurrencyId) -> Result<(), DispatchError>;
     fn transfer(
         currency_id: Self::CurrencyId,
         from: &AccountId,
@@ -30,6 +32,7 @@ pub trait Tokens<AccountId> {
         number: Self::Balance,
     ) -> Result<Self::Balance, DispatchError>;
     fn decimals(currency_id: Self::CurrencyId) -> Result<u8, DispatchError>;
+    fn total_supply(currency_id: Self::CurrencyId) -> Self::Balance;
     fn balance(currency_id: Self::CurrencyId, account: &AccountId) -> Self::Balance;
     fn mint(
         currency_id: Self::CurrencyId,
@@ -75,4 +78,102 @@ pub trait Tokens<AccountId> {
         to: &AccountId,
         value: Self::Balance,
     ) -> Result<Self::Balance, DispatchError>;
+    /// Set the number of tokens of `owner` that `spender` is allowed to spend
+    fn approve(
+        currency_id: Self::CurrencyId,
+        owner: &AccountId,
+        spender: &AccountId,
+        number: Self::Balance,
+    ) -> Result<Self::Balance, DispatchError>;
+    /// Transfer `number` tokens of `from` to `to`, paid out of the allowance `from` granted to
+    /// `spender`
+    fn transfer_from_allowance(
+        currency_id: Self::CurrencyId,
+        spender: &AccountId,
+        from: &AccountId,
+        to: &AccountId,
+        number: Self::Balance,
+    ) -> Result<Self::Balance, DispatchError>;
+    /// Reserve `value` tokens of `who`, paid out of the allowance `who` granted to `spender`
+    fn reserve_from_allowance(
+        currency_id: Self::CurrencyId,
+        spender: &AccountId,
+        who: &AccountId,
+        value: Self::Balance,
+    ) -> Result<Self::Balance, DispatchError>;
 }
+
+/// Called by the tokens module before it moves tokens from one account to another, or mints or
+/// burns them
+///
+/// Lets a module that pays out to the holders of a token bring what they earned up to date with
+/// the balances they held so far. Implemented for tuples of modules, which are called in order.
+pub trait BeforeTransfer<AccountId, CurrencyId, Balance> {
+    fn before_transfer(
+        currency_id: CurrencyId,
+        from: &AccountId,
+        to: &AccountId,
+        number: Balance,
+    ) -> Result<(), DispatchError>;
+
+    /// Called before `number` tokens of `who` are minted or burned
+    fn before_mint_or_burn(
+        currency_id: CurrencyId,
+        who: &AccountId,
+        number: Balance,
+    ) -> Result<(), DispatchError>;
+}
+
+impl<AccountId, CurrencyId, Balance> BeforeTransfer<AccountId, CurrencyId, Balance> for () {
+    fn before_transfer(
+        _currency_id: CurrencyId,
+        _from: &AccountId,
+        _to: &AccountId,
+        _number: Balance,
+    ) -> Result<(), DispatchError> {
+        Ok(())
+    }
+
+    fn before_mint_or_burn(
+        _currency_id: CurrencyId,
+        _who: &AccountId,
+        _number: Balance,
+    ) -> Result<(), DispatchError> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_before_transfer {
+    ($($module:ident),+) => {
+        impl<AccountId, CurrencyId, Balance, $($module),+>
+            BeforeTransfer<AccountId, CurrencyId, Balance> for ($($module,)+)
+        where
+            CurrencyId: Copy,
+            Balance: Copy,
+            $($module: BeforeTransfer<AccountId, CurrencyId, Balance>,)+
+        {
+            fn before_transfer(
+                currency_id: CurrencyId,
+                from: &AccountId,
+                to: &AccountId,
+                number: Balance,
+            ) -> Result<(), DispatchError> {
+                $($module::before_transfer(currency_id, from, to, number)?;)+
+                Ok(())
+            }
+
+            fn before_mint_or_burn(
+                currency_id: CurrencyId,
+                who: &AccountId,
+                number: Balance,
+            ) -> Result<(), DispatchError> {
+                $($module::before_mint_or_burn(currency_id, who, number)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_before_transfer!(A);
+impl_before_transfer!(A, B);
+impl_before_transfer!(A, B, C);
@@ -24,6 +24,12 @@ pub fn with_transaction_result<R>(
     })
 }
 
+/// Run `f` in a storage transaction that is always rolled back, so that a call can be simulated
+/// without changing the storage
+pub fn with_transaction_rollback<R>(f: impl FnOnce() -> R) -> R {
+    with_transaction(|| TransactionOutcome::Rollback(f()))
+}
+
 #[macro_export]
 macro_rules! runtime_format {
 	($($args:tt)*) => {{
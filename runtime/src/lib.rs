@@ -13,7 +13,8 @@ use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::traits::{
-    AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, Verify,
+    AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor,
+    SaturatedConversion, Verify,
 };
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
@@ -43,13 +44,23 @@ pub use sp_runtime::BuildStorage;
 pub use sp_runtime::{Perbill, Permill};
 
 pub use autonomy;
+pub use categorical;
 pub use couple;
 pub use couple::pallet::Proposal;
+pub use lmsr;
 /// Import the template pallet.
 pub use proposals;
-use proposals_info_runtime_api::types::{PersonalProposalInfo, ProposalInfo};
+use proposals_info_runtime_api::types::{
+    AccountPortfolio, ExactOutQuote, LiquidityQuote, OptionBalance, PersonalProposalInfo,
+    PortfolioItem, ProposalFilter, ProposalInfo, ProposalListItem, ProposalOptionInfo,
+    ProposalPage, SpotPrices, TradeQuote,
+};
 pub use tokens;
-use traits::{system::ProposalSystem, ProposalStatus};
+use traits::{
+    registry::{LiquidityEngine, LiquidityRouter},
+    system::ProposalSystem,
+    ProposalStatus,
+};
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -105,10 +116,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("node-predict"),
     impl_name: create_runtime_str!("node-predict"),
     authoring_version: 1,
-    spec_version: 1,
+    spec_version: 2,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 2,
 };
 
 /// This determines the average expected block time that we are targetting.
@@ -285,22 +296,38 @@ impl ProposalSystem<AccountId> for Runtime {
 parameter_types! {
     pub const GovernanceCurrencyId: CurrencyId = 1;
     pub const RewardId: ModuleId = ModuleId(*b"xpreward");
+    pub const DeadlineBucket: Moment = MILLISECS_PER_BLOCK;
 }
 
 impl proposals::Config for Runtime {
     type Event = Event;
-    type SubPool = Couple;
+    type LiquidityEngines = LiquidityEngines;
+    type Archive = Autonomy;
     type GovernanceCurrencyId = GovernanceCurrencyId;
     type RewardId = RewardId;
+    type DeadlineBucket = DeadlineBucket;
+    type WeightInfo = proposals::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
     pub const CurrentLiquidateVersionId: VersionId = 1;
+    pub const CategoricalLiquidateVersionId: VersionId = 2;
+    pub const LmsrLiquidateVersionId: VersionId = 3;
     pub const EarnTradingFeeDecimals: u8 = FEE_DECIMALS;
+    pub const MaxOptions: u32 = 16;
+    pub const MaxOpenOrders: u32 = 64;
+    pub const MaxAccountOpenOrders: u32 = 8;
+    pub const MaxTradeMatches: u32 = 4;
+    pub const MinOrderNumber: Balance = 100_000;
 }
 
+/// Market engines, looked up by the version id a proposal was created with. New engines must be
+/// appended with a new version id, so that the live proposals keep their engine
+pub type LiquidityEngines = (Couple, Categorical, Lmsr);
+
 impl ruler::Config for Runtime {
     type Event = Event;
+    type WeightInfo = ruler::weights::SubstrateWeight<Runtime>;
 }
 
 impl couple::Config for Runtime {
@@ -310,11 +337,37 @@ impl couple::Config for Runtime {
     type Autonomy = Autonomy;
     type CurrentLiquidateVersionId = CurrentLiquidateVersionId;
     type EarnTradingFeeDecimals = EarnTradingFeeDecimals;
+    type MaxOpenOrders = MaxOpenOrders;
+    type MaxAccountOpenOrders = MaxAccountOpenOrders;
+    type MaxTradeMatches = MaxTradeMatches;
+    type MinOrderNumber = MinOrderNumber;
+    type WeightInfo = couple::weights::SubstrateWeight<Runtime>;
+}
+
+impl categorical::Config for Runtime {
+    type Event = Event;
+    type Pool = Proposals;
+    type Ruler = Ruler;
+    type EarnTradingFeeDecimals = EarnTradingFeeDecimals;
+    type CurrentLiquidateVersionId = CategoricalLiquidateVersionId;
+    type MaxOptions = MaxOptions;
+    type WeightInfo = categorical::weights::SubstrateWeight<Runtime>;
+}
+
+impl lmsr::Config for Runtime {
+    type Event = Event;
+    type Pool = Proposals;
+    type Ruler = Ruler;
+    type EarnTradingFeeDecimals = EarnTradingFeeDecimals;
+    type CurrentLiquidateVersionId = LmsrLiquidateVersionId;
+    type MaxOptions = MaxOptions;
+    type WeightInfo = lmsr::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
     pub const StakeCurrencyId: CurrencyId = 1;
     pub const AutonomyId: ModuleId = ModuleId(*b"xpgovern");
+    pub const MaxDisputeRounds: u32 = 3;
 }
 
 impl autonomy::Config for Runtime {
@@ -323,8 +376,12 @@ impl autonomy::Config for Runtime {
     type AuthorityId = autonomy::crypto::OcwAuthId;
     type StakeCurrencyId = StakeCurrencyId;
     type Pool = Proposals;
-    type CouplePool = Couple;
+    type CouplePool = LiquidityRouter<Proposals, LiquidityEngines>;
     type AutonomyId = AutonomyId;
+    type DeadlineBucket = DeadlineBucket;
+    type DisputeOrigin = frame_system::EnsureRoot<AccountId>;
+    type MaxDisputeRounds = MaxDisputeRounds;
+    type WeightInfo = autonomy::weights::SubstrateWeight<Runtime>;
 }
 
 impl frame_system::offchain::SigningTypes for Runtime {
@@ -353,6 +410,10 @@ impl tokens::Config for Runtime {
     type Currency = Balances;
     type NativeCurrencyId = NativeCurrencyId;
     type ModuleId = TokensModuleId;
+    type BeforeTransfer = Couple;
+    type PermitSignature = Signature;
+    type PermitSigner = <Signature as Verify>::Signer;
+    type WeightInfo = tokens::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -374,6 +435,8 @@ construct_runtime!(
         Ruler: ruler::{Module, Call, Config<T>, Storage, Event<T>},
         Proposals: proposals::{Module, Call, Config<T>, Storage, Event<T>},
         Couple: couple::{Module, Call, Config, Storage, Event<T>},
+        Categorical: categorical::{Module, Call, Config, Storage, Event<T>},
+        Lmsr: lmsr::{Module, Call, Config, Storage, Event<T>},
         Autonomy: autonomy::{Module, Call, Config<T>, Storage, Event<T>, ValidateUnsigned},
         Tokens: tokens::{Module, Call, Config<T>, Storage, Event<T>},
     }
@@ -412,6 +475,564 @@ pub type Executive = frame_executive::Executive<
     AllModules,
 >;
 
+/// The version id the proposal was created with, the one given by the caller is only used for
+/// the proposals that do not exist yet
+fn liquidate_version_id(version_id: VersionId, proposal_id: ProposalId) -> VersionId {
+    Proposals::proposal_liquidate_version_id(proposal_id).unwrap_or(version_id)
+}
+
+/// The holdings of an account in a proposal as seen by the engine of the proposal
+struct EnginePosition {
+    /// The settlement currency of the proposal
+    currency_id: CurrencyId,
+    option_ids: Vec<CurrencyId>,
+    liquidity_currency_id: CurrencyId,
+    retrievable: Balance,
+    unclaimed_fee: Balance,
+}
+
+/// What the runtime APIs read from a market engine of `LiquidityEngines`
+trait EngineInfo: LiquidityEngine<Runtime> {
+    fn proposal_info(
+        proposal_id: ProposalId,
+    ) -> ProposalInfo<CategoryId, Balance, Moment, CurrencyId>;
+
+    fn personal_proposal_info(
+        proposal_id: ProposalId,
+        account_id: AccountId,
+    ) -> PersonalProposalInfo<Balance, Moment, CurrencyId>;
+
+    fn position(proposal_id: ProposalId, account_id: &AccountId) -> EnginePosition;
+}
+
+/// Looks up the `EngineInfo` of a version id among a tuple of engines, the same way
+/// `LiquidityRegistry` does for the pallets, `None` when no engine has the version id
+trait EngineInfoRegistry {
+    fn proposal_info(
+        version_id: VersionId,
+        proposal_id: ProposalId,
+    ) -> Option<ProposalInfo<CategoryId, Balance, Moment, CurrencyId>>;
+
+    fn personal_proposal_info(
+        version_id: VersionId,
+        proposal_id: ProposalId,
+        account_id: AccountId,
+    ) -> Option<PersonalProposalInfo<Balance, Moment, CurrencyId>>;
+
+    fn position(
+        version_id: VersionId,
+        proposal_id: ProposalId,
+        account_id: &AccountId,
+    ) -> Option<EnginePosition>;
+}
+
+macro_rules! impl_engine_info_registry {
+    ($($engine:ident),+) => {
+        impl<$($engine: EngineInfo),+> EngineInfoRegistry for ($($engine,)+) {
+            fn proposal_info(version_id: VersionId, proposal_id: ProposalId) -> Option<ProposalInfo<CategoryId, Balance, Moment, CurrencyId>> {
+                $(
+                    if <$engine as LiquidityEngine<Runtime>>::version_id() == version_id {
+                        return Some(<$engine as EngineInfo>::proposal_info(proposal_id));
+                    }
+                )+
+                None
+            }
+
+            fn personal_proposal_info(version_id: VersionId, proposal_id: ProposalId, account_id: AccountId) -> Option<PersonalProposalInfo<Balance, Moment, CurrencyId>> {
+                $(
+                    if <$engine as LiquidityEngine<Runtime>>::version_id() == version_id {
+                        return Some(<$engine as EngineInfo>::personal_proposal_info(proposal_id, account_id));
+                    }
+                )+
+                None
+            }
+
+            fn position(version_id: VersionId, proposal_id: ProposalId, account_id: &AccountId) -> Option<EnginePosition> {
+                $(
+                    if <$engine as LiquidityEngine<Runtime>>::version_id() == version_id {
+                        return Some(<$engine as EngineInfo>::position(proposal_id, account_id));
+                    }
+                )+
+                None
+            }
+        }
+    };
+}
+
+impl_engine_info_registry!(A);
+impl_engine_info_registry!(A, B);
+impl_engine_info_registry!(A, B, C);
+
+impl EngineInfo for Couple {
+    fn proposal_info(
+        proposal_id: ProposalId,
+    ) -> ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
+        couple_proposal_info(proposal_id)
+    }
+
+    fn personal_proposal_info(
+        proposal_id: ProposalId,
+        account_id: AccountId,
+    ) -> PersonalProposalInfo<Balance, Moment, CurrencyId> {
+        couple_personal_proposal_info(proposal_id, account_id)
+    }
+
+    fn position(proposal_id: ProposalId, account_id: &AccountId) -> EnginePosition {
+        let (yes_currency_id, no_currency_id) = Couple::pool_pairs(proposal_id).unwrap_or_default();
+        EnginePosition {
+            currency_id: Couple::proposal_currency_id(proposal_id).unwrap_or_default(),
+            option_ids: [yes_currency_id, no_currency_id].to_vec(),
+            liquidity_currency_id: Couple::proposal_liquidate_currency_id(proposal_id)
+                .unwrap_or_default(),
+            retrievable: Couple::retrievable(account_id, proposal_id).unwrap_or_default(),
+            unclaimed_fee: Couple::claimable_fee(account_id, proposal_id).unwrap_or_default(),
+        }
+    }
+}
+
+impl EngineInfo for Categorical {
+    fn proposal_info(
+        proposal_id: ProposalId,
+    ) -> ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
+        categorical_proposal_info(proposal_id)
+    }
+
+    fn personal_proposal_info(
+        proposal_id: ProposalId,
+        account_id: AccountId,
+    ) -> PersonalProposalInfo<Balance, Moment, CurrencyId> {
+        categorical_personal_proposal_info(proposal_id, account_id)
+    }
+
+    fn position(proposal_id: ProposalId, account_id: &AccountId) -> EnginePosition {
+        EnginePosition {
+            currency_id: Categorical::proposal_currency_id(proposal_id).unwrap_or_default(),
+            option_ids: Categorical::pool_options(proposal_id).unwrap_or_default(),
+            liquidity_currency_id: Categorical::proposal_liquidate_currency_id(proposal_id)
+                .unwrap_or_default(),
+            retrievable: Categorical::retrievable(account_id, proposal_id).unwrap_or_default(),
+            unclaimed_fee: 0,
+        }
+    }
+}
+
+impl EngineInfo for Lmsr {
+    fn proposal_info(
+        proposal_id: ProposalId,
+    ) -> ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
+        lmsr_proposal_info(proposal_id)
+    }
+
+    fn personal_proposal_info(
+        proposal_id: ProposalId,
+        account_id: AccountId,
+    ) -> PersonalProposalInfo<Balance, Moment, CurrencyId> {
+        lmsr_personal_proposal_info(proposal_id, account_id)
+    }
+
+    fn position(proposal_id: ProposalId, account_id: &AccountId) -> EnginePosition {
+        EnginePosition {
+            currency_id: Lmsr::proposal_currency_id(proposal_id).unwrap_or_default(),
+            option_ids: Lmsr::pool_options(proposal_id).unwrap_or_default(),
+            liquidity_currency_id: Lmsr::proposal_liquidate_currency_id(proposal_id)
+                .unwrap_or_default(),
+            retrievable: Lmsr::retrievable(account_id, proposal_id).unwrap_or_default(),
+            unclaimed_fee: 0,
+        }
+    }
+}
+
+fn couple_proposal_info(proposal_id: ProposalId) -> ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
+    let proposal = Couple::proposals(proposal_id).unwrap_or_default();
+    let resolution = Proposals::proposal_resolution(proposal_id).unwrap_or_default();
+    let (yes, no) = Couple::proposal_total_optional_market(proposal_id).unwrap_or_default();
+    let close_time = Proposals::proposal_close_time(proposal_id).unwrap_or_default();
+    let liquidity = Couple::proposal_total_market_liquid(proposal_id).unwrap_or_default();
+    let pairs = Couple::pool_pairs(proposal_id).unwrap_or_default();
+    let yes_name = Tokens::currencies(pairs.0).unwrap_or_default().name;
+    let no_name = Tokens::currencies(pairs.1).unwrap_or_default().name;
+    let status = Proposals::proposal_status(proposal_id).unwrap_or(ProposalStatus::OriginalPrediction);
+    let token_id = Couple::proposal_currency_id(proposal_id).unwrap_or_default();
+    let decimals = Tokens::currencies(token_id).unwrap_or_default().decimals;
+
+    ProposalInfo {
+        title: proposal.title,
+        category_id: proposal.category_id,
+        detail: proposal.detail,
+        resolution_source: resolution.source,
+        resolution_rules: resolution.rules,
+        resolution_time: resolution.time,
+        invalid_condition: resolution.invalid_condition,
+        yes,
+        yes_name,
+        no,
+        no_name,
+        options: option_infos(&[pairs.0, pairs.1], &[yes, no]),
+        close_time,
+        liquidity,
+        status,
+        token_id,
+        decimals
+    }
+}
+
+/// Categorical proposals only fill the first two options into `yes` and `no`, `options` holds all
+/// of them
+fn categorical_proposal_info(proposal_id: ProposalId) -> ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
+    let proposal = Categorical::proposals(proposal_id).unwrap_or_default();
+    let resolution = Proposals::proposal_resolution(proposal_id).unwrap_or_default();
+    let market = Categorical::proposal_total_optional_market(proposal_id).unwrap_or_default();
+    let yes = market.get(0).copied().unwrap_or_default();
+    let no = market.get(1).copied().unwrap_or_default();
+    let close_time = Proposals::proposal_close_time(proposal_id).unwrap_or_default();
+    let liquidity = Categorical::proposal_total_market_liquid(proposal_id).unwrap_or_default();
+    let options = Categorical::pool_options(proposal_id).unwrap_or_default();
+    let yes_name = Tokens::currencies(options.get(0).copied().unwrap_or_default()).unwrap_or_default().name;
+    let no_name = Tokens::currencies(options.get(1).copied().unwrap_or_default()).unwrap_or_default().name;
+    let status = Proposals::proposal_status(proposal_id).unwrap_or(ProposalStatus::OriginalPrediction);
+    let token_id = Categorical::proposal_currency_id(proposal_id).unwrap_or_default();
+    let decimals = Tokens::currencies(token_id).unwrap_or_default().decimals;
+
+    ProposalInfo {
+        title: proposal.title,
+        category_id: proposal.category_id,
+        detail: proposal.detail,
+        resolution_source: resolution.source,
+        resolution_rules: resolution.rules,
+        resolution_time: resolution.time,
+        invalid_condition: resolution.invalid_condition,
+        yes,
+        yes_name,
+        no,
+        no_name,
+        options: option_infos(&options, &market),
+        close_time,
+        liquidity,
+        status,
+        token_id,
+        decimals
+    }
+}
+
+/// LMSR proposals fill the outstanding tokens of the first two options into `yes` and `no`,
+/// `options` holds all of them
+fn lmsr_proposal_info(proposal_id: ProposalId) -> ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
+    let proposal = Lmsr::proposals(proposal_id).unwrap_or_default();
+    let resolution = Proposals::proposal_resolution(proposal_id).unwrap_or_default();
+    let outstanding = Lmsr::proposal_outstanding(proposal_id).unwrap_or_default();
+    let yes = outstanding.get(0).copied().unwrap_or_default();
+    let no = outstanding.get(1).copied().unwrap_or_default();
+    let close_time = Proposals::proposal_close_time(proposal_id).unwrap_or_default();
+    let liquidity = Lmsr::proposal_total_market_liquid(proposal_id).unwrap_or_default();
+    let options = Lmsr::pool_options(proposal_id).unwrap_or_default();
+    let yes_name = Tokens::currencies(options.get(0).copied().unwrap_or_default()).unwrap_or_default().name;
+    let no_name = Tokens::currencies(options.get(1).copied().unwrap_or_default()).unwrap_or_default().name;
+    let status = Proposals::proposal_status(proposal_id).unwrap_or(ProposalStatus::OriginalPrediction);
+    let token_id = Lmsr::proposal_currency_id(proposal_id).unwrap_or_default();
+    let decimals = Tokens::currencies(token_id).unwrap_or_default().decimals;
+
+    ProposalInfo {
+        title: proposal.title,
+        category_id: proposal.category_id,
+        detail: proposal.detail,
+        resolution_source: resolution.source,
+        resolution_rules: resolution.rules,
+        resolution_time: resolution.time,
+        invalid_condition: resolution.invalid_condition,
+        yes,
+        yes_name,
+        no,
+        no_name,
+        options: option_infos(&options, &outstanding),
+        close_time,
+        liquidity,
+        status,
+        token_id,
+        decimals
+    }
+}
+
+/// The options of a proposal with the amount of each held by the market
+fn option_infos(
+    option_ids: &[CurrencyId],
+    amounts: &[Balance],
+) -> Vec<ProposalOptionInfo<CurrencyId, Balance>> {
+    option_ids
+        .iter()
+        .enumerate()
+        .map(|(index, &currency_id)| {
+            let coin = Tokens::currencies(currency_id).unwrap_or_default();
+            ProposalOptionInfo {
+                currency_id,
+                name: coin.name,
+                decimals: coin.decimals,
+                amount: amounts.get(index).copied().unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+fn couple_personal_proposal_info(proposal_id: ProposalId, account_id: AccountId) -> PersonalProposalInfo<Balance, Moment, CurrencyId> {
+    let title = Couple::proposals(proposal_id).unwrap_or_default().title;
+    let currency_id = Couple::proposal_currency_id(proposal_id).unwrap_or_default();
+    let (yes_currency_id, no_currency_id) = Couple::pool_pairs(proposal_id).unwrap_or_default();
+    let liquidity_currency_id = Couple::proposal_liquidate_currency_id(proposal_id).unwrap_or_default();
+    let (yes, no) = Couple::proposal_total_optional_market(proposal_id).unwrap_or_default();
+    let fee_rate = Couple::proposal_total_earn_trading_fee(proposal_id).unwrap_or_default();
+    let fee = Couple::proposal_total_market_fee(proposal_id).unwrap_or_default();
+    let total = Couple::proposal_total_market(proposal_id).unwrap_or_default();
+    let liquidity = Couple::proposal_total_market_liquid(proposal_id).unwrap_or_default();
+
+    personal_proposal_info(
+        account_id,
+        proposal_id,
+        title,
+        currency_id,
+        (yes_currency_id, no_currency_id),
+        liquidity_currency_id,
+        (yes, no),
+        option_infos(&[yes_currency_id, no_currency_id], &[yes, no]),
+        fee_rate,
+        fee,
+        total,
+        liquidity,
+    )
+}
+
+/// The most proposals returned by one call of `list_proposals`
+const MAX_PROPOSAL_PAGE_SIZE: u32 = 100;
+
+/// The most proposal ids looked at by one call of `list_proposals` or `account_portfolio`
+const MAX_PROPOSALS_SCANNED: ProposalId = 1000;
+
+/// Proposals from `cursor` on that match `filter`, the page is full after `limit` proposals or
+/// `MAX_PROPOSALS_SCANNED` proposal ids
+fn list_proposals(
+    cursor: Option<ProposalId>,
+    limit: u32,
+    filter: ProposalFilter<CategoryId, AccountId, CurrencyId, Moment>,
+) -> ProposalPage<ProposalId, CategoryId, Balance, Moment, CurrencyId> {
+    let end = Proposals::current_proposal_id().unwrap_or_default();
+    let limit = limit.min(MAX_PROPOSAL_PAGE_SIZE) as usize;
+    let mut proposals = Vec::new();
+    let mut proposal_id = cursor.unwrap_or_default();
+    let scan_end = proposal_id.saturating_add(MAX_PROPOSALS_SCANNED);
+    while proposal_id < end {
+        if proposals.len() >= limit || proposal_id >= scan_end {
+            return ProposalPage { proposals, next: Some(proposal_id) };
+        }
+        let owner_matches = match &filter.owner {
+            Some(owner) => Proposals::proposal_owner(proposal_id).as_ref() == Some(owner),
+            None => true,
+        };
+        let version_id = liquidate_version_id(CurrentLiquidateVersionId::get(), proposal_id);
+        let info = if owner_matches {
+            <LiquidityEngines as EngineInfoRegistry>::proposal_info(version_id, proposal_id)
+        } else {
+            None
+        };
+        if let Some(info) = info {
+            if filter.status.map_or(true, |status| status == info.status)
+                && filter.category_id.map_or(true, |category_id| category_id == info.category_id)
+                && filter.currency_id.map_or(true, |currency_id| currency_id == info.token_id)
+                && filter.close_after.map_or(true, |time| info.close_time >= time)
+                && filter.close_before.map_or(true, |time| info.close_time <= time)
+            {
+                proposals.push(ProposalListItem { proposal_id, info });
+            }
+        }
+        proposal_id += 1;
+    }
+    ProposalPage { proposals, next: None }
+}
+
+/// The positions of `account_id` in the proposals from `cursor` on it holds something in, the
+/// page is full after `limit` positions or `MAX_PROPOSALS_SCANNED` proposal ids
+fn account_portfolio(
+    account_id: AccountId,
+    cursor: Option<ProposalId>,
+    limit: u32,
+) -> AccountPortfolio<ProposalId, CurrencyId, Balance> {
+    let end = Proposals::current_proposal_id().unwrap_or_default();
+    let limit = limit.min(MAX_PROPOSAL_PAGE_SIZE) as usize;
+    let mut items = Vec::new();
+    let mut proposal_id = cursor.unwrap_or_default();
+    let scan_end = proposal_id.saturating_add(MAX_PROPOSALS_SCANNED);
+    while proposal_id < end {
+        if items.len() >= limit || proposal_id >= scan_end {
+            return AccountPortfolio { items, next: Some(proposal_id) };
+        }
+        let version_id = liquidate_version_id(CurrentLiquidateVersionId::get(), proposal_id);
+        let position = match <LiquidityEngines as EngineInfoRegistry>::position(
+            version_id,
+            proposal_id,
+            &account_id,
+        ) {
+            Some(position) => position,
+            None => {
+                proposal_id += 1;
+                continue;
+            }
+        };
+        let EnginePosition {
+            currency_id,
+            option_ids,
+            liquidity_currency_id,
+            retrievable,
+            unclaimed_fee,
+        } = position;
+        let options: Vec<OptionBalance<CurrencyId, Balance>> = option_ids
+            .into_iter()
+            .map(|currency_id| OptionBalance {
+                currency_id,
+                balance: Tokens::free_balance_of(account_id.clone(), currency_id).unwrap_or_default(),
+            })
+            .collect();
+        let liquidity = Tokens::free_balance_of(account_id.clone(), liquidity_currency_id).unwrap_or_default();
+        let reserved_liquidity = Tokens::reserve_of(account_id.clone(), liquidity_currency_id).unwrap_or_default();
+        let vote_stake = Proposals::proposal_vote_stake(proposal_id, &account_id).map(|(number, _)| number).unwrap_or_default();
+        let autonomy_lock = Autonomy::staked_node_lock_num(proposal_id, &account_id).unwrap_or_default();
+        let holds_options = options.iter().any(|option| option.balance > 0);
+        if holds_options
+            || liquidity > 0
+            || reserved_liquidity > 0
+            || vote_stake > 0
+            || autonomy_lock > 0
+            || unclaimed_fee > 0
+        {
+            items.push(PortfolioItem {
+                proposal_id,
+                status: Proposals::proposal_status(proposal_id).unwrap_or(ProposalStatus::OriginalPrediction),
+                currency_id,
+                options,
+                liquidity,
+                reserved_liquidity,
+                vote_stake,
+                autonomy_lock,
+                retrievable,
+                unclaimed_fee,
+            });
+        }
+        proposal_id += 1;
+    }
+    AccountPortfolio { items, next: None }
+}
+
+/// Categorical proposals only fill the first two options into `yes` and `no`, `options` holds all
+/// of them
+fn categorical_personal_proposal_info(proposal_id: ProposalId, account_id: AccountId) -> PersonalProposalInfo<Balance, Moment, CurrencyId> {
+    let title = Categorical::proposals(proposal_id).unwrap_or_default().title;
+    let currency_id = Categorical::proposal_currency_id(proposal_id).unwrap_or_default();
+    let options = Categorical::pool_options(proposal_id).unwrap_or_default();
+    let yes_currency_id = options.get(0).copied().unwrap_or_default();
+    let no_currency_id = options.get(1).copied().unwrap_or_default();
+    let liquidity_currency_id = Categorical::proposal_liquidate_currency_id(proposal_id).unwrap_or_default();
+    let market = Categorical::proposal_total_optional_market(proposal_id).unwrap_or_default();
+    let yes = market.get(0).copied().unwrap_or_default();
+    let no = market.get(1).copied().unwrap_or_default();
+    let fee_rate = Categorical::proposal_total_earn_trading_fee(proposal_id).unwrap_or_default();
+    let fee = Categorical::proposal_total_market_fee(proposal_id).unwrap_or_default();
+    let total = Categorical::proposal_total_market(proposal_id).unwrap_or_default();
+    let liquidity = Categorical::proposal_total_market_liquid(proposal_id).unwrap_or_default();
+
+    personal_proposal_info(
+        account_id,
+        proposal_id,
+        title,
+        currency_id,
+        (yes_currency_id, no_currency_id),
+        liquidity_currency_id,
+        (yes, no),
+        option_infos(&options, &market),
+        fee_rate,
+        fee,
+        total,
+        liquidity,
+    )
+}
+
+/// LMSR proposals fill the outstanding tokens of the first two options into `yes` and `no`,
+/// `options` holds all of them
+fn lmsr_personal_proposal_info(proposal_id: ProposalId, account_id: AccountId) -> PersonalProposalInfo<Balance, Moment, CurrencyId> {
+    let title = Lmsr::proposals(proposal_id).unwrap_or_default().title;
+    let currency_id = Lmsr::proposal_currency_id(proposal_id).unwrap_or_default();
+    let options = Lmsr::pool_options(proposal_id).unwrap_or_default();
+    let yes_currency_id = options.get(0).copied().unwrap_or_default();
+    let no_currency_id = options.get(1).copied().unwrap_or_default();
+    let liquidity_currency_id = Lmsr::proposal_liquidate_currency_id(proposal_id).unwrap_or_default();
+    let outstanding = Lmsr::proposal_outstanding(proposal_id).unwrap_or_default();
+    let yes = outstanding.get(0).copied().unwrap_or_default();
+    let no = outstanding.get(1).copied().unwrap_or_default();
+    let fee_rate = Lmsr::proposal_total_earn_trading_fee(proposal_id).unwrap_or_default();
+    let fee = Lmsr::proposal_total_market_fee(proposal_id).unwrap_or_default();
+    let total = Lmsr::proposal_total_market(proposal_id).unwrap_or_default();
+    let liquidity = Lmsr::proposal_total_market_liquid(proposal_id).unwrap_or_default();
+
+    personal_proposal_info(
+        account_id,
+        proposal_id,
+        title,
+        currency_id,
+        (yes_currency_id, no_currency_id),
+        liquidity_currency_id,
+        (yes, no),
+        option_infos(&options, &outstanding),
+        fee_rate,
+        fee,
+        total,
+        liquidity,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn personal_proposal_info(
+    account_id: AccountId,
+    proposal_id: ProposalId,
+    title: Vec<u8>,
+    currency_id: CurrencyId,
+    (yes_currency_id, no_currency_id): (CurrencyId, CurrencyId),
+    liquidity_currency_id: CurrencyId,
+    (yes, no): (Balance, Balance),
+    options: Vec<ProposalOptionInfo<CurrencyId, Balance>>,
+    fee_rate: u32,
+    fee: Balance,
+    total: Balance,
+    liquidity: Balance,
+) -> PersonalProposalInfo<Balance, Moment, CurrencyId> {
+    let decimals = Tokens::currencies(currency_id).unwrap_or_default().decimals;
+    let yes_coin = Tokens::currencies(yes_currency_id).unwrap_or_default();
+    let no_coin = Tokens::currencies(no_currency_id).unwrap_or_default();
+    let liquidity_decimals = Tokens::currencies(liquidity_currency_id).unwrap_or_default().decimals;
+    let balance = Tokens::free_balance_of(account_id, currency_id).unwrap_or_default();
+    let close_time = Proposals::proposal_close_time(proposal_id).unwrap_or_default();
+    let status = Proposals::proposal_status(proposal_id).unwrap_or(ProposalStatus::OriginalPrediction);
+
+    PersonalProposalInfo {
+        title,
+        yes_name: yes_coin.name,
+        no_name: no_coin.name,
+        currency_id,
+        yes_currency_id,
+        no_currency_id,
+        options,
+        liquidity_currency_id,
+        decimals,
+        yes_decimals: yes_coin.decimals,
+        no_decimals: no_coin.decimals,
+        liquidity_decimals,
+        fee_rate_decimals: FEE_DECIMALS,
+        fee_rate,
+        fee,
+        no,
+        yes,
+        total,
+        liquidity,
+        balance,
+        close_time,
+        status,
+    }
+}
+
 impl_runtime_apis! {
     impl sp_api::Core<Block> for Runtime {
         fn version() -> RuntimeVersion {
@@ -543,79 +1164,68 @@ impl_runtime_apis! {
     }
 
     impl proposals_info_runtime_api::CoupleInfoApi<Block, VersionId, ProposalId, CategoryId, Balance, Moment, CurrencyId, AccountId> for Runtime {
-        fn get_proposal_info(_: VersionId, proposal_id: ProposalId) -> ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
-            let proposal = Couple::proposals(proposal_id).unwrap_or_default();
-            let (yes, no) = Couple::proposal_total_optional_market(proposal_id).unwrap_or_default();
-            let close_time = Proposals::proposal_close_time(proposal_id).unwrap_or_default();
-            let liquidity = Couple::proposal_total_market_liquid(proposal_id).unwrap_or_default();
-            let pairs = Couple::pool_pairs(proposal_id).unwrap_or_default();
-            let yes_name = Tokens::currencies(pairs.0).unwrap_or_default().name;
-            let no_name = Tokens::currencies(pairs.1).unwrap_or_default().name;
-            let status = Proposals::proposal_status(proposal_id).unwrap_or(ProposalStatus::OriginalPrediction);
-            let token_id = Couple::proposal_currency_id(proposal_id).unwrap_or_default();
-            let decimals = Tokens::currencies(token_id).unwrap_or_default().decimals;
-
-            ProposalInfo {
-                title: proposal.title,
-                category_id: proposal.category_id,
-                detail: proposal.detail,
-                yes,
-                yes_name,
-                no,
-                no_name,
-                close_time,
-                liquidity,
-                status,
-                token_id,
-                decimals
-            }
+        fn get_proposal_info(version_id: VersionId, proposal_id: ProposalId) -> ProposalInfo<CategoryId, Balance, Moment, CurrencyId> {
+            let version_id = liquidate_version_id(version_id, proposal_id);
+            <LiquidityEngines as EngineInfoRegistry>::proposal_info(version_id, proposal_id)
+                .unwrap_or_else(|| couple_proposal_info(proposal_id))
         }
 
-        fn get_personal_proposal_info(_: VersionId, proposal_id: ProposalId, account_id: AccountId) -> PersonalProposalInfo<Balance, Moment, CurrencyId> {
-            let title = Couple::proposals(proposal_id).unwrap_or_default().title;
-            let currency_id = Couple::proposal_currency_id(proposal_id).unwrap_or_default();
-            let (yes_currency_id, no_currency_id) = Couple::pool_pairs(proposal_id).unwrap_or_default();
-            let liquidity_currency_id = Couple::proposal_liquidate_currency_id(proposal_id).unwrap_or_default();
-            let decimals = Tokens::currencies(currency_id).unwrap_or_default().decimals;
-            let yes_coin = Tokens::currencies(yes_currency_id).unwrap_or_default();
-            let no_coin = Tokens::currencies(no_currency_id).unwrap_or_default();
-            let yes_decimals = yes_coin.decimals;
-            let no_decimals = no_coin.decimals;
-            let yes_name = yes_coin.name;
-            let no_name = no_coin.name;
-            let liquidity_decimals = Tokens::currencies(liquidity_currency_id).unwrap_or_default().decimals;
-            let (yes, no) = Couple::proposal_total_optional_market(proposal_id).unwrap_or_default();
-            let fee_rate = Couple::proposal_total_earn_trading_fee(proposal_id).unwrap_or_default();
-            let fee = Couple::proposal_total_market_fee(proposal_id).unwrap_or_default();
-            let total = Couple::proposal_total_market(proposal_id).unwrap_or_default();
-            let liquidity = Couple::proposal_total_market_liquid(proposal_id).unwrap_or_default();
-            let balance = Tokens::free_balance_of(account_id, currency_id).unwrap_or_default();
-            let close_time = Proposals::proposal_close_time(proposal_id).unwrap_or_default();
-            let status = Proposals::proposal_status(proposal_id).unwrap_or(ProposalStatus::OriginalPrediction);
-
-            PersonalProposalInfo {
-                title,
-                yes_name,
-                no_name,
-                currency_id,
-                yes_currency_id,
-                no_currency_id,
-                liquidity_currency_id,
-                decimals,
-                yes_decimals,
-                no_decimals,
-                liquidity_decimals,
-                fee_rate_decimals: FEE_DECIMALS,
-                fee_rate,
-                fee,
-                no,
-                yes,
-                total,
-                liquidity,
-                balance,
-                close_time,
-                status,
-            }
+        fn get_personal_proposal_info(version_id: VersionId, proposal_id: ProposalId, account_id: AccountId) -> PersonalProposalInfo<Balance, Moment, CurrencyId> {
+            let version_id = liquidate_version_id(version_id, proposal_id);
+            <LiquidityEngines as EngineInfoRegistry>::personal_proposal_info(version_id, proposal_id, account_id.clone())
+                .unwrap_or_else(|| couple_personal_proposal_info(proposal_id, account_id))
+        }
+
+        fn get_buy_exact_out_quote(proposal_id: ProposalId, currency_id: CurrencyId, number: Balance) -> Option<ExactOutQuote<Balance>> {
+            Couple::buy_exact_out_quote(proposal_id, currency_id, number)
+                .ok()
+                .map(|(amount, fee)| ExactOutQuote { amount, fee })
+        }
+
+        fn get_sell_exact_out_quote(proposal_id: ProposalId, currency_id: CurrencyId, number: Balance) -> Option<ExactOutQuote<Balance>> {
+            Couple::sell_exact_out_quote(proposal_id, currency_id, number)
+                .ok()
+                .map(|(amount, fee)| ExactOutQuote { amount, fee })
+        }
+
+        fn get_spot_prices(proposal_id: ProposalId) -> Option<SpotPrices<Balance>> {
+            Couple::spot_prices(proposal_id)
+                .ok()
+                .map(|(yes, no)| SpotPrices { yes, no })
+        }
+
+        fn get_buy_quote(proposal_id: ProposalId, currency_id: CurrencyId, number: Balance) -> Option<TradeQuote<Balance>> {
+            Couple::buy_quote(proposal_id, currency_id, number)
+                .ok()
+                .map(|(amount, fee, price_impact)| TradeQuote { amount, fee, price_impact: price_impact.saturated_into() })
+        }
+
+        fn get_sell_quote(proposal_id: ProposalId, currency_id: CurrencyId, number: Balance) -> Option<TradeQuote<Balance>> {
+            Couple::sell_quote(proposal_id, currency_id, number)
+                .ok()
+                .map(|(amount, fee, price_impact)| TradeQuote { amount, fee, price_impact: price_impact.saturated_into() })
+        }
+
+        fn get_remove_liquidity_quote(proposal_id: ProposalId, account_id: AccountId, number: Balance) -> Option<LiquidityQuote<Balance>> {
+            Couple::remove_liquidity_quote(&account_id, proposal_id, number)
+                .ok()
+                .map(|(amount, yes, no, fee)| LiquidityQuote { amount, yes, no, fee })
+        }
+
+        fn list_proposals(
+            cursor: Option<ProposalId>,
+            limit: u32,
+            filter: ProposalFilter<CategoryId, AccountId, CurrencyId, Moment>,
+        ) -> ProposalPage<ProposalId, CategoryId, Balance, Moment, CurrencyId> {
+            list_proposals(cursor, limit, filter)
+        }
+
+        fn get_account_portfolio(
+            account_id: AccountId,
+            cursor: Option<ProposalId>,
+            limit: u32,
+        ) -> AccountPortfolio<ProposalId, CurrencyId, Balance> {
+            account_portfolio(account_id, cursor, limit)
         }
     }
 
@@ -648,6 +1258,13 @@ impl_runtime_apis! {
             add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
             add_benchmark!(params, batches, pallet_balances, Balances);
             add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+            add_benchmark!(params, batches, xpmrl_tokens, Tokens);
+            add_benchmark!(params, batches, xpmrl_proposals, Proposals);
+            add_benchmark!(params, batches, xpmrl_couple, Couple);
+            add_benchmark!(params, batches, xpmrl_categorical, Categorical);
+            add_benchmark!(params, batches, xpmrl_lmsr, Lmsr);
+            add_benchmark!(params, batches, xpmrl_autonomy, Autonomy);
+            add_benchmark!(params, batches, xpmrl_ruler, Ruler);
 
             if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
             Ok(batches)